@@ -0,0 +1,55 @@
+//! ホストテスト用のembassy-stm32スタブ
+//!
+//! `config`モジュールが参照する型だけを、実機と同じ名前・振る舞いで提供します。
+
+#![no_std]
+
+/// 周波数型
+pub mod time {
+    /// 周波数 [Hz]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct Hertz(pub u32);
+}
+
+/// CRCユニット
+pub mod crc {
+    /// CRC-32計算（STM32のCRCユニットのデフォルト設定と同じ結果）
+    ///
+    /// 多項式0x04C11DB7、初期値0xFFFFFFFF、入出力の反転なし、ワード単位でMSBから処理。
+    pub struct Crc {
+        value: u32,
+    }
+
+    impl Crc {
+        /// 初期値で作成
+        pub const fn new() -> Self {
+            Self { value: 0xFFFF_FFFF }
+        }
+
+        /// 初期値に戻す
+        pub fn reset(&mut self) {
+            self.value = 0xFFFF_FFFF;
+        }
+
+        /// ワード列を入力して現在のCRCを返す
+        pub fn feed_words(&mut self, words: &[u32]) -> u32 {
+            for &word in words {
+                self.value ^= word;
+                for _ in 0..32 {
+                    self.value = if self.value & 0x8000_0000 != 0 {
+                        (self.value << 1) ^ 0x04C1_1DB7
+                    } else {
+                        self.value << 1
+                    };
+                }
+            }
+            self.value
+        }
+    }
+
+    impl Default for Crc {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+}
@@ -43,10 +43,12 @@ pub fn run_inverse_park_benchmark(iterations: u32) {
     // ベンチマーク実行
     let (result_idsp, result_libm, ticks_idsp, ticks_libm) =
         foc::benchmark_inverse_park(iterations);
+    let (result_fixed, ticks_fixed) = foc::benchmark_inverse_park_fixed_point(iterations);
 
     // サイクル/呼び出し を計算（整数に変換してdefmtで表示）
     let cycles_per_call_idsp = ticks_idsp / iterations;
     let cycles_per_call_libm = ticks_libm / iterations;
+    let cycles_per_call_fixed = ticks_fixed / iterations;
 
     info!("Benchmark results ({} iterations):", iterations);
     info!(
@@ -57,6 +59,10 @@ pub fn run_inverse_park_benchmark(iterations: u32) {
         "  libm::cosf/sinf: {} cycles total, {} cycles/call",
         ticks_libm, cycles_per_call_libm
     );
+    info!(
+        "  Q15 fixed-point: {} cycles total, {} cycles/call",
+        ticks_fixed, cycles_per_call_fixed
+    );
 
     // スピードアップを計算（ゼロ除算を回避）
     if cycles_per_call_idsp > 0 {
@@ -77,9 +83,18 @@ pub fn run_inverse_park_benchmark(iterations: u32) {
         "  Result libm:  alpha={}, beta={}",
         result_libm.0, result_libm.1
     );
+    info!(
+        "  Result Q15:   alpha={}, beta={}",
+        result_fixed.0, result_fixed.1
+    );
     info!(
         "  Error: alpha={}, beta={}",
         result_idsp.0 - result_libm.0,
         result_idsp.1 - result_libm.1
     );
+    info!(
+        "  Error Q15: alpha={}, beta={}",
+        result_fixed.0 - result_libm.0,
+        result_fixed.1 - result_libm.1
+    );
 }
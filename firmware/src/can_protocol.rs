@@ -1,6 +1,7 @@
 // CAN communication protocol definitions for motor control
 
 use crate::fmt::*;
+use crate::foc::HallDiagnosticsStatus;
 
 /// CAN message IDs
 pub mod can_ids {
@@ -25,6 +26,30 @@ pub mod can_ids {
     /// Reset config to defaults command (no data)
     pub const RESET_CONFIG: u32 = 0x105;
 
+    /// Torque command (q-axis current: f32 A, 4 bytes) - switches to torque mode
+    pub const TORQUE_CMD: u32 = 0x107;
+
+    /// Voltage command (vd: f32 V, vq: f32 V, 8 bytes) - switches to voltage mode
+    pub const VOLTAGE_CMD: u32 = 0x108;
+
+    /// Position command (multi-turn mechanical position: f32 rad, 4 bytes) - switches to position mode
+    /// Position zero is where the motor was enabled (1 turn = 2π rad)
+    pub const POSITION_CMD: u32 = 0x109;
+
+    /// Clear latched hall sensor fault command (no data)
+    pub const CLEAR_HALL_FAULT: u32 = 0x10A;
+
+    /// Start motor parameter identification command
+    /// (no data for defaults, or test_current: f32 A, test_speed: f32 RPM, 8 bytes)
+    pub const START_IDENTIFICATION: u32 = 0x10B;
+
+    /// Start speed PI auto-tune command (only in closed-loop speed mode)
+    /// (no data for defaults, or test_speed: f32 RPM, relay_ratio: f32 of output limit, 8 bytes)
+    pub const START_AUTOTUNE: u32 = 0x10C;
+
+    /// Apply auto-tuned speed PI gains command (no data)
+    pub const APPLY_AUTOTUNE: u32 = 0x10D;
+
     // === Motor Control Parameter Commands (0x110-0x113) ===
     /// Motor voltage params (max_voltage: f32, v_dc_bus: f32, 8 bytes)
     pub const MOTOR_VOLTAGE_PARAMS: u32 = 0x110;
@@ -38,25 +63,131 @@ pub mod can_ids {
     /// Angle interpolation (enable_angle_interpolation: bool, 1 byte)
     pub const ANGLE_INTERPOLATION: u32 = 0x113;
 
-    // === OpenLoop Parameter Commands (0x120-0x121) ===
+    // === Current Control Parameter Commands (0x114-0x116) ===
+    /// Current PI gains (Kp: f32, Ki: f32, 8 bytes)
+    pub const CURRENT_PI_GAINS: u32 = 0x114;
+
+    /// Current limit (max_current: f32, current_control_enabled: u8, 5 bytes)
+    pub const CURRENT_LIMIT: u32 = 0x115;
+
+    /// Current sense params (shunt_resistance: f32, amp_gain: f32, 8 bytes)
+    pub const CURRENT_SENSE_PARAMS: u32 = 0x116;
+
+    // === Position Control Parameter Commands (0x117) ===
+    /// Position params (kp: f32 RPM/rad, max_speed: f32 RPM, 8 bytes)
+    pub const POSITION_PARAMS: u32 = 0x117;
+
+    // === Motion Profile Parameter Commands (0x118-0x119) ===
+    /// Motion profile params (acceleration: f32 RPM/s, deceleration: f32 RPM/s, 8 bytes)
+    pub const MOTION_PROFILE_PARAMS: u32 = 0x118;
+
+    /// Motion profile jerk (jerk: f32 RPM/s², 0 = trapezoidal, 4 bytes)
+    pub const MOTION_PROFILE_JERK: u32 = 0x119;
+
+    // === Sensorless Parameter Commands (0x11A-0x11C) ===
+    /// Motor electrical params (resistance: f32 ohm, inductance: f32 H, 8 bytes)
+    pub const MOTOR_ELECTRICAL_PARAMS: u32 = 0x11A;
+
+    /// Sensorless observer params (flux_linkage: f32 Wb, pll_bandwidth: f32 rad/s, 8 bytes)
+    pub const SENSORLESS_PARAMS: u32 = 0x11B;
+
+    /// Angle source (min_speed: f32 RPM, source: u8 0=Hall/1=Sensorless/2=Hall+fallback, 5 bytes)
+    pub const ANGLE_SOURCE: u32 = 0x11C;
+
+    // === Hall Estimator Parameter Commands (0x11D) ===
+    /// Hall estimator (pll_bandwidth: f32 rad/s, estimator: u8 0=Filter/1=PLL, 5 bytes)
+    pub const HALL_ESTIMATOR_PARAMS: u32 = 0x11D;
+
+    // === Field Weakening Parameter Commands (0x11E-0x11F) ===
+    /// Field weakening params (gain: f32 1/s, voltage_ratio: f32 of max voltage, 8 bytes)
+    pub const FIELD_WEAKENING_PARAMS: u32 = 0x11E;
+
+    /// Field weakening limits (max_current: f32 A, max_voltage: f32 V, 0 = disabled, 8 bytes)
+    pub const FIELD_WEAKENING_LIMITS: u32 = 0x11F;
+
+    // === OpenLoop Parameter Commands (0x120-0x124) ===
     /// OpenLoop RPM params (initial_rpm: f32, target_rpm: f32, 8 bytes)
     pub const OPENLOOP_RPM_PARAMS: u32 = 0x120;
 
     /// OpenLoop accel/duty params (acceleration: f32, duty_ratio: u16, 6 bytes)
     pub const OPENLOOP_ACCEL_DUTY_PARAMS: u32 = 0x121;
 
-    // === PWM Configuration (0x130) ===
+    /// OpenLoop→FOC handover params (handover_time: f32 s, 0 = immediate switch, 4 bytes)
+    pub const OPENLOOP_HANDOVER_PARAMS: u32 = 0x122;
+
+    /// OpenLoop sine V/f params (boost_voltage: f32 V, vf_gain: f32 V/Hz, 8 bytes)
+    pub const OPENLOOP_VF_PARAMS: u32 = 0x123;
+
+    /// OpenLoop drive mode (u8, 1 byte: 0=six-step, 1=sine V/f)
+    pub const OPENLOOP_MODE: u32 = 0x124;
+
+    // === Speed Feedforward Parameter Commands (0x125-0x127) ===
+    /// Speed feedforward back-EMF (motor_kv: f32 RPM/V, 0 = from flux linkage, gain: f32, 8 bytes)
+    pub const SPEED_FF_BACK_EMF: u32 = 0x125;
+
+    /// Speed feedforward friction (gain: f32, band: f32 RPM, 8 bytes)
+    pub const SPEED_FF_FRICTION: u32 = 0x126;
+
+    /// Speed feedforward acceleration (gain: f32, 4 bytes)
+    pub const SPEED_FF_ACCELERATION: u32 = 0x127;
+
+    // === Speed Loop Filter Parameter Commands (0x128-0x12A) ===
+    /// Speed loop filter types (feedback_type: u8, feedback_length: u8, output_type: u8,
+    /// output_length: u8, 4 bytes; type 0=none/1=low-pass/2=notch/3=band-stop/4=moving average/5=median)
+    pub const SPEED_FILTER_TYPES: u32 = 0x128;
+
+    /// Speed feedback filter (frequency: f32 Hz, shape: f32 Q or bandwidth Hz, 8 bytes)
+    pub const SPEED_FEEDBACK_FILTER: u32 = 0x129;
+
+    /// Speed PI output filter (frequency: f32 Hz, shape: f32 Q or bandwidth Hz, 8 bytes)
+    pub const SPEED_OUTPUT_FILTER: u32 = 0x12A;
+
+    // === PI Controller Extension Commands (0x12B-0x12F) ===
+    /// PI back-calculation tracking gains (speed: f32 1/s, current: f32 1/s, 0 = disabled, 8 bytes)
+    pub const PI_TRACKING_GAINS: u32 = 0x12B;
+
+    /// Speed PI derivative term (kd: f32, filter_time: f32 s, 8 bytes)
+    pub const SPEED_PI_DERIVATIVE: u32 = 0x12C;
+
+    /// Speed PI setpoint weights (proportional: f32 b, derivative: f32 c, 8 bytes)
+    pub const SPEED_PI_SETPOINT_WEIGHTS: u32 = 0x12D;
+
+    /// Speed PI output rate limit (rate_limit: f32 A/s or V/s, 0 = unlimited, 4 bytes)
+    pub const SPEED_PI_RATE_LIMIT: u32 = 0x12E;
+
+    /// Position derivative term (kd: f32 RPM/(rad/s), filter_time: f32 s, 8 bytes)
+    pub const POSITION_DERIVATIVE: u32 = 0x12F;
+
+    // === PWM Configuration (0x130-0x132) ===
     /// PWM config (frequency: u32, dead_time: u16, 6 bytes)
     pub const PWM_CONFIG: u32 = 0x130;
 
+    /// Dead-time compensation (mode: u8 0=disabled/1=current sign/2=voltage sign, min_pulse: u16 timer ticks, 0 = no clamping, 3 bytes)
+    pub const DEAD_TIME_COMPENSATION: u32 = 0x131;
+
+    /// Overmodulation (enable: bool, 1 byte)
+    pub const OVERMODULATION: u32 = 0x132;
+
     // === CAN Configuration (0x140) ===
     /// CAN config (bitrate: u32, 4 bytes)
     pub const CAN_CONFIG: u32 = 0x140;
 
-    // === Control Timing (0x150) ===
+    // === Control Timing (0x150-0x151) ===
     /// Control timing (control_period_us: u64, 8 bytes)
+    /// The control period is the current/angle loop period, rounded to a multiple of the PWM period
     pub const CONTROL_TIMING: u32 = 0x150;
 
+    /// Loop rate dividers (speed_divider: u16 current loop cycles, position_divider: u16 speed loop cycles, 4 bytes)
+    pub const LOOP_DIVIDERS: u32 = 0x151;
+
+    // === Speed Gain Schedule (0x160-0x168) ===
+    /// Speed gain schedule point (index: u8 0-7, points: u8 points in use 0 = disabled, speed: u16 RPM, 4 bytes)
+    pub const SPEED_GAIN_SCHEDULE_POINT: u32 = 0x160;
+
+    /// Speed gain schedule gains (kp: f32, ki: f32, 8 bytes)
+    /// One ID per point: 0x161 + index (0x161-0x168)
+    pub const SPEED_GAIN_SCHEDULE_GAINS: u32 = 0x161;
+
     /// Motor status feedback (speed: f32, angle: f32, 8 bytes)
     pub const STATUS: u32 = 0x200;
 
@@ -69,6 +200,43 @@ pub mod can_ids {
     /// Calibration status feedback (electrical_offset: f32, direction_inversed: u8, success: u8, 6 bytes)
     pub const CALIBRATION_STATUS: u32 = 0x203;
 
+    /// Current status feedback (id: f32, iq: f32, 8 bytes)
+    pub const CURRENT_STATUS: u32 = 0x204;
+
+    /// Position status feedback (position: f32 rad, target: f32 rad, 8 bytes)
+    pub const POSITION_STATUS: u32 = 0x205;
+
+    /// Hall sector table feedback (page: u8 0=sectors 1-3/1=sectors 4-6, valid: u8, angles: 3 x u16 in 1/65536 turn, 8 bytes)
+    pub const HALL_SECTOR_TABLE_STATUS: u32 = 0x206;
+
+    /// Hall diagnostics feedback (fault_flags: u8, invalid_states: u16, skipped_sectors: u16, glitches: u16, wrong_direction_edges: u8, 8 bytes)
+    pub const HALL_DIAGNOSTICS_STATUS: u32 = 0x207;
+
+    /// Identification status feedback (state: u8 0=idle/1-5=running/6=completed/7=failed, electrical_valid: u8, mechanical_valid: u8, 3 bytes)
+    pub const IDENTIFICATION_STATUS: u32 = 0x208;
+
+    /// Identified electrical params (resistance: f32 ohm, inductance: f32 H, 8 bytes)
+    pub const IDENTIFIED_ELECTRICAL_PARAMS: u32 = 0x209;
+
+    /// Identified flux linkage and inertia (flux_linkage: f32 Wb, inertia: f32 kg·m², 8 bytes)
+    pub const IDENTIFIED_FLUX_INERTIA: u32 = 0x20A;
+
+    /// Identified friction (coulomb: f32 N·m, viscous: f32 N·m·s/rad, 8 bytes)
+    pub const IDENTIFIED_FRICTION: u32 = 0x20B;
+
+    /// Auto-tune status feedback (state: u8 0=idle/1-2=running/3=completed/4=failed, valid: u8, 2 bytes)
+    pub const AUTOTUNE_STATUS: u32 = 0x20C;
+
+    /// Auto-tuned speed PI gains, not applied until APPLY_AUTOTUNE (kp: f32, ki: f32, 8 bytes)
+    pub const AUTOTUNE_GAINS: u32 = 0x20D;
+
+    /// Control loop rates in use (current_loop_hz: u32, speed_divider: u16, position_divider: u16, 8 bytes)
+    pub const CONTROL_RATES_STATUS: u32 = 0x20E;
+
+    /// Drive state feedback (state: u8 0=init/1=idle/2=precharge/3=starting/4=running/5=stopping/6=fault/7=calibrating,
+    /// control_mode: u8, faults: u8 bit0=overvoltage/bit1=undervoltage/bit2=hall, 3 bytes)
+    pub const DRIVE_STATUS: u32 = 0x20F;
+
     /// Emergency stop (any data length)
     pub const EMERGENCY_STOP: u32 = 0x000;
 }
@@ -78,6 +246,12 @@ pub mod can_ids {
 pub struct MotorStatus {
     pub speed_rpm: f32,
     pub electrical_angle: f32,
+    /// Measured d-axis current [A]
+    pub id_current: f32,
+    /// Measured q-axis current [A]
+    pub iq_current: f32,
+    /// Multi-turn mechanical position [rad]
+    pub position: f32,
 }
 
 impl MotorStatus {
@@ -85,6 +259,9 @@ impl MotorStatus {
         Self {
             speed_rpm: 0.0,
             electrical_angle: 0.0,
+            id_current: 0.0,
+            iq_current: 0.0,
+            position: 0.0,
         }
     }
 }
@@ -95,6 +272,36 @@ impl Default for MotorStatus {
     }
 }
 
+/// Control loop rates in use
+///
+/// Divider changes are applied while the motor is stopped, so these may differ
+/// from the stored config until then.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ControlRates {
+    /// Current/angle loop frequency [Hz]
+    pub current_loop_hz: u32,
+    /// Current loop cycles per speed loop cycle
+    pub speed_divider: u16,
+    /// Speed loop cycles per position loop cycle
+    pub position_divider: u16,
+}
+
+impl ControlRates {
+    pub const fn new() -> Self {
+        Self {
+            current_loop_hz: 0,
+            speed_divider: 1,
+            position_divider: 1,
+        }
+    }
+}
+
+impl Default for ControlRates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Voltage status structure
 #[derive(Debug, Clone, Copy)]
 #[allow(dead_code)]
@@ -142,6 +349,93 @@ pub fn parse_speed_command(data: &[u8]) -> Option<f32> {
     Some(speed_rpm)
 }
 
+/// Parse torque command from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 4 bytes)
+///
+/// # Returns
+/// * `Some(iq_current)` if parsing successful
+/// * `None` if data length is incorrect
+pub fn parse_torque_command(data: &[u8]) -> Option<f32> {
+    if data.len() < 4 {
+        error!("Torque command: invalid data length {}", data.len());
+        return None;
+    }
+
+    let current_bytes = [data[0], data[1], data[2], data[3]];
+    let iq_current = f32::from_le_bytes(current_bytes);
+
+    info!("Torque command received: Iq={}A", iq_current);
+    Some(iq_current)
+}
+
+/// Encode torque command into CAN data
+#[allow(dead_code)]
+pub fn encode_torque_command(iq_current: f32) -> [u8; 4] {
+    iq_current.to_le_bytes()
+}
+
+/// Parse voltage command from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some((vd, vq))` if parsing successful
+/// * `None` if data length is incorrect
+pub fn parse_voltage_command(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        error!("Voltage command: invalid data length {}", data.len());
+        return None;
+    }
+
+    let vd_bytes = [data[0], data[1], data[2], data[3]];
+    let vq_bytes = [data[4], data[5], data[6], data[7]];
+
+    let vd = f32::from_le_bytes(vd_bytes);
+    let vq = f32::from_le_bytes(vq_bytes);
+
+    info!("Voltage command received: Vd={}V, Vq={}V", vd, vq);
+    Some((vd, vq))
+}
+
+/// Encode voltage command into CAN data
+#[allow(dead_code)]
+pub fn encode_voltage_command(vd: f32, vq: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&vd.to_le_bytes());
+    data[4..8].copy_from_slice(&vq.to_le_bytes());
+    data
+}
+
+/// Parse position command from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 4 bytes)
+///
+/// # Returns
+/// * `Some(position)` if parsing successful (multi-turn mechanical position in rad)
+/// * `None` if data length is incorrect
+pub fn parse_position_command(data: &[u8]) -> Option<f32> {
+    if data.len() < 4 {
+        error!("Position command: invalid data length {}", data.len());
+        return None;
+    }
+
+    let position_bytes = [data[0], data[1], data[2], data[3]];
+    let position = f32::from_le_bytes(position_bytes);
+
+    info!("Position command received: {} rad", position);
+    Some(position)
+}
+
+/// Encode position command into CAN data
+#[allow(dead_code)]
+pub fn encode_position_command(position: f32) -> [u8; 4] {
+    position.to_le_bytes()
+}
+
 /// Parse PI gains from CAN data
 ///
 /// # Arguments
@@ -232,6 +526,7 @@ pub fn decode_status(data: &[u8]) -> Option<MotorStatus> {
     Some(MotorStatus {
         speed_rpm,
         electrical_angle,
+        ..MotorStatus::new()
     })
 }
 
@@ -472,242 +767,1656 @@ pub fn encode_angle_interpolation(enable: bool) -> [u8; 1] {
 }
 
 // ============================================================================
-// OpenLoop Parameter Commands
+// Current Control Parameter Commands
 // ============================================================================
 
-/// Parse openloop RPM parameters from CAN data
+/// Parse current PI gains from CAN data
 ///
 /// # Arguments
 /// * `data` - CAN frame data (should be 8 bytes)
 ///
 /// # Returns
-/// * `Some((initial_rpm, target_rpm))` if parsing successful
+/// * `Some((kp, ki))` if parsing successful
 /// * `None` if data length is incorrect
-pub fn parse_openloop_rpm_params(data: &[u8]) -> Option<(f32, f32)> {
+pub fn parse_current_pi_gains(data: &[u8]) -> Option<(f32, f32)> {
     if data.len() < 8 {
-        error!("OpenLoop RPM params: invalid data length {}", data.len());
+        error!("Current PI gains: invalid data length {}", data.len());
         return None;
     }
 
-    let initial_bytes = [data[0], data[1], data[2], data[3]];
-    let target_bytes = [data[4], data[5], data[6], data[7]];
+    let kp_bytes = [data[0], data[1], data[2], data[3]];
+    let ki_bytes = [data[4], data[5], data[6], data[7]];
 
-    let initial_rpm = f32::from_le_bytes(initial_bytes);
-    let target_rpm = f32::from_le_bytes(target_bytes);
+    let kp = f32::from_le_bytes(kp_bytes);
+    let ki = f32::from_le_bytes(ki_bytes);
 
-    info!(
-        "OpenLoop RPM params received: initial={}, target={}",
-        initial_rpm, target_rpm
-    );
-    Some((initial_rpm, target_rpm))
+    info!("Current PI gains received: Kp={}, Ki={}", kp, ki);
+    Some((kp, ki))
 }
 
-/// Encode openloop RPM parameters into CAN data
+/// Encode current PI gains into CAN data
 #[allow(dead_code)]
-pub fn encode_openloop_rpm_params(initial_rpm: f32, target_rpm: f32) -> [u8; 8] {
+pub fn encode_current_pi_gains(kp: f32, ki: f32) -> [u8; 8] {
     let mut data = [0u8; 8];
-    data[0..4].copy_from_slice(&initial_rpm.to_le_bytes());
-    data[4..8].copy_from_slice(&target_rpm.to_le_bytes());
+    data[0..4].copy_from_slice(&kp.to_le_bytes());
+    data[4..8].copy_from_slice(&ki.to_le_bytes());
     data
 }
 
-/// Parse openloop acceleration/duty parameters from CAN data
+/// Parse current limit from CAN data
 ///
 /// # Arguments
-/// * `data` - CAN frame data (should be 6 bytes)
+/// * `data` - CAN frame data (should be 5 bytes)
 ///
 /// # Returns
-/// * `Some((acceleration, duty_ratio))` if parsing successful
+/// * `Some((max_current, current_control_enabled))` if parsing successful
 /// * `None` if data length is incorrect
-pub fn parse_openloop_accel_duty_params(data: &[u8]) -> Option<(f32, u16)> {
-    if data.len() < 6 {
-        error!(
-            "OpenLoop accel/duty params: invalid data length {}",
-            data.len()
-        );
+pub fn parse_current_limit(data: &[u8]) -> Option<(f32, bool)> {
+    if data.len() < 5 {
+        error!("Current limit: invalid data length {}", data.len());
         return None;
     }
 
-    let accel_bytes = [data[0], data[1], data[2], data[3]];
-    let duty_bytes = [data[4], data[5]];
-
-    let acceleration = f32::from_le_bytes(accel_bytes);
-    let duty_ratio = u16::from_le_bytes(duty_bytes);
+    let max_current_bytes = [data[0], data[1], data[2], data[3]];
+    let max_current = f32::from_le_bytes(max_current_bytes);
+    let enabled = data[4] != 0;
 
     info!(
-        "OpenLoop accel/duty params received: accel={}, duty={}",
-        acceleration, duty_ratio
+        "Current limit received: max_current={}A, enabled={}",
+        max_current, enabled
     );
-    Some((acceleration, duty_ratio))
+    Some((max_current, enabled))
 }
 
-/// Encode openloop acceleration/duty parameters into CAN data
+/// Encode current limit into CAN data
 #[allow(dead_code)]
-pub fn encode_openloop_accel_duty_params(acceleration: f32, duty_ratio: u16) -> [u8; 6] {
-    let mut data = [0u8; 6];
-    data[0..4].copy_from_slice(&acceleration.to_le_bytes());
-    data[4..6].copy_from_slice(&duty_ratio.to_le_bytes());
+pub fn encode_current_limit(max_current: f32, enabled: bool) -> [u8; 5] {
+    let mut data = [0u8; 5];
+    data[0..4].copy_from_slice(&max_current.to_le_bytes());
+    data[4] = if enabled { 1 } else { 0 };
     data
 }
 
-// ============================================================================
-// PWM Configuration Commands
-// ============================================================================
-
-/// Parse PWM configuration from CAN data
+/// Parse current sense parameters from CAN data
 ///
 /// # Arguments
-/// * `data` - CAN frame data (should be 6 bytes)
+/// * `data` - CAN frame data (should be 8 bytes)
 ///
 /// # Returns
-/// * `Some((frequency, dead_time))` if parsing successful
+/// * `Some((shunt_resistance, amp_gain))` if parsing successful
 /// * `None` if data length is incorrect
-pub fn parse_pwm_config(data: &[u8]) -> Option<(u32, u16)> {
-    if data.len() < 6 {
-        error!("PWM config: invalid data length {}", data.len());
+pub fn parse_current_sense_params(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        error!("Current sense params: invalid data length {}", data.len());
         return None;
     }
 
-    let freq_bytes = [data[0], data[1], data[2], data[3]];
-    let dead_time_bytes = [data[4], data[5]];
+    let shunt_bytes = [data[0], data[1], data[2], data[3]];
+    let gain_bytes = [data[4], data[5], data[6], data[7]];
 
-    let frequency = u32::from_le_bytes(freq_bytes);
-    let dead_time = u16::from_le_bytes(dead_time_bytes);
+    let shunt_resistance = f32::from_le_bytes(shunt_bytes);
+    let amp_gain = f32::from_le_bytes(gain_bytes);
 
     info!(
-        "PWM config received: freq={}Hz, dead_time={}",
-        frequency, dead_time
+        "Current sense params received: shunt={}ohm, gain={}",
+        shunt_resistance, amp_gain
     );
-    Some((frequency, dead_time))
+    Some((shunt_resistance, amp_gain))
 }
 
-/// Encode PWM configuration into CAN data
+/// Encode current sense parameters into CAN data
 #[allow(dead_code)]
-pub fn encode_pwm_config(frequency: u32, dead_time: u16) -> [u8; 6] {
-    let mut data = [0u8; 6];
-    data[0..4].copy_from_slice(&frequency.to_le_bytes());
-    data[4..6].copy_from_slice(&dead_time.to_le_bytes());
+pub fn encode_current_sense_params(shunt_resistance: f32, amp_gain: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&shunt_resistance.to_le_bytes());
+    data[4..8].copy_from_slice(&amp_gain.to_le_bytes());
     data
 }
 
-// ============================================================================
-// CAN Configuration Commands
-// ============================================================================
+/// Encode measured d/q currents into CAN data
+///
+/// # Arguments
+/// * `id_current` - Measured d-axis current [A]
+/// * `iq_current` - Measured q-axis current [A]
+///
+/// # Returns
+/// 8-byte array containing encoded current status
+pub fn encode_current_status(id_current: f32, iq_current: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&id_current.to_le_bytes());
+    data[4..8].copy_from_slice(&iq_current.to_le_bytes());
+    data
+}
 
-/// Parse CAN configuration from CAN data
+/// Decode measured d/q currents from CAN data
 ///
 /// # Arguments
-/// * `data` - CAN frame data (should be 4 bytes)
+/// * `data` - CAN frame data (should be 8 bytes)
 ///
 /// # Returns
-/// * `Some(bitrate)` if parsing successful
+/// * `Some((id_current, iq_current))` if parsing successful
 /// * `None` if data length is incorrect
-pub fn parse_can_config(data: &[u8]) -> Option<u32> {
-    if data.len() < 4 {
-        error!("CAN config: invalid data length {}", data.len());
+#[allow(dead_code)]
+pub fn decode_current_status(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
         return None;
     }
 
-    let bitrate_bytes = [data[0], data[1], data[2], data[3]];
-    let bitrate = u32::from_le_bytes(bitrate_bytes);
-
-    info!("CAN config received: bitrate={}", bitrate);
-    Some(bitrate)
-}
+    let id_bytes = [data[0], data[1], data[2], data[3]];
+    let iq_bytes = [data[4], data[5], data[6], data[7]];
 
-/// Encode CAN configuration into CAN data
-#[allow(dead_code)]
-pub fn encode_can_config(bitrate: u32) -> [u8; 4] {
-    bitrate.to_le_bytes()
+    Some((f32::from_le_bytes(id_bytes), f32::from_le_bytes(iq_bytes)))
 }
 
 // ============================================================================
-// Control Timing Commands
+// Position Control Parameter Commands
 // ============================================================================
 
-/// Parse control timing from CAN data
+/// Parse position control parameters from CAN data
 ///
 /// # Arguments
 /// * `data` - CAN frame data (should be 8 bytes)
 ///
 /// # Returns
-/// * `Some(control_period_us)` if parsing successful
+/// * `Some((kp, max_speed))` if parsing successful
 /// * `None` if data length is incorrect
-pub fn parse_control_timing(data: &[u8]) -> Option<u64> {
+pub fn parse_position_params(data: &[u8]) -> Option<(f32, f32)> {
     if data.len() < 8 {
-        error!("Control timing: invalid data length {}", data.len());
+        error!("Position params: invalid data length {}", data.len());
         return None;
     }
 
-    let period_bytes = [
-        data[0], data[1], data[2], data[3], data[4], data[5], data[6], data[7],
-    ];
-    let control_period_us = u64::from_le_bytes(period_bytes);
+    let kp_bytes = [data[0], data[1], data[2], data[3]];
+    let max_speed_bytes = [data[4], data[5], data[6], data[7]];
 
-    info!("Control timing received: {}us", control_period_us);
-    Some(control_period_us)
+    let kp = f32::from_le_bytes(kp_bytes);
+    let max_speed = f32::from_le_bytes(max_speed_bytes);
+
+    info!(
+        "Position params received: Kp={}, max_speed={} RPM",
+        kp, max_speed
+    );
+    Some((kp, max_speed))
 }
 
-/// Encode control timing into CAN data
+/// Encode position control parameters into CAN data
 #[allow(dead_code)]
-pub fn encode_control_timing(control_period_us: u64) -> [u8; 8] {
-    control_period_us.to_le_bytes()
+pub fn encode_position_params(kp: f32, max_speed: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&kp.to_le_bytes());
+    data[4..8].copy_from_slice(&max_speed.to_le_bytes());
+    data
 }
 
 // ============================================================================
-// Calibration Commands
+// Motion Profile Parameter Commands
 // ============================================================================
 
-/// Encode calibration status into CAN data
+/// Parse motion profile acceleration limits from CAN data
 ///
 /// # Arguments
-/// * `electrical_offset` - Electrical offset in radians (0～2π)
-/// * `direction_inversed` - Direction inversion flag
-/// * `success` - Calibration success flag
+/// * `data` - CAN frame data (should be 8 bytes)
 ///
 /// # Returns
-/// 6-byte array containing encoded calibration status
-pub fn encode_calibration_status(
-    electrical_offset: f32,
-    direction_inversed: bool,
-    success: bool,
-) -> [u8; 6] {
-    let mut data = [0u8; 6];
+/// * `Some((acceleration, deceleration))` if parsing successful (RPM/s)
+/// * `None` if data length is incorrect
+pub fn parse_motion_profile_params(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        error!("Motion profile params: invalid data length {}", data.len());
+        return None;
+    }
 
-    // Encode electrical offset as little-endian f32 (bytes 0-3)
+    let accel_bytes = [data[0], data[1], data[2], data[3]];
+    let decel_bytes = [data[4], data[5], data[6], data[7]];
+
+    let acceleration = f32::from_le_bytes(accel_bytes);
+    let deceleration = f32::from_le_bytes(decel_bytes);
+
+    info!(
+        "Motion profile params received: accel={} RPM/s, decel={} RPM/s",
+        acceleration, deceleration
+    );
+    Some((acceleration, deceleration))
+}
+
+/// Encode motion profile acceleration limits into CAN data
+#[allow(dead_code)]
+pub fn encode_motion_profile_params(acceleration: f32, deceleration: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&acceleration.to_le_bytes());
+    data[4..8].copy_from_slice(&deceleration.to_le_bytes());
+    data
+}
+
+/// Parse motion profile jerk limit from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 4 bytes)
+///
+/// # Returns
+/// * `Some(jerk)` if parsing successful (RPM/s², 0 = trapezoidal)
+/// * `None` if data length is incorrect
+pub fn parse_motion_profile_jerk(data: &[u8]) -> Option<f32> {
+    if data.len() < 4 {
+        error!("Motion profile jerk: invalid data length {}", data.len());
+        return None;
+    }
+
+    let jerk_bytes = [data[0], data[1], data[2], data[3]];
+    let jerk = f32::from_le_bytes(jerk_bytes);
+
+    info!("Motion profile jerk received: {} RPM/s^2", jerk);
+    Some(jerk)
+}
+
+/// Encode motion profile jerk limit into CAN data
+#[allow(dead_code)]
+pub fn encode_motion_profile_jerk(jerk: f32) -> [u8; 4] {
+    jerk.to_le_bytes()
+}
+
+// ============================================================================
+// Sensorless Parameter Commands
+// ============================================================================
+
+/// Parse motor electrical parameters from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some((resistance, inductance))` if parsing successful (ohm, H)
+/// * `None` if data length is incorrect
+pub fn parse_motor_electrical_params(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        error!(
+            "Motor electrical params: invalid data length {}",
+            data.len()
+        );
+        return None;
+    }
+
+    let resistance_bytes = [data[0], data[1], data[2], data[3]];
+    let inductance_bytes = [data[4], data[5], data[6], data[7]];
+
+    let resistance = f32::from_le_bytes(resistance_bytes);
+    let inductance = f32::from_le_bytes(inductance_bytes);
+
+    info!(
+        "Motor electrical params received: R={}ohm, L={}H",
+        resistance, inductance
+    );
+    Some((resistance, inductance))
+}
+
+/// Encode motor electrical parameters into CAN data
+#[allow(dead_code)]
+pub fn encode_motor_electrical_params(resistance: f32, inductance: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&resistance.to_le_bytes());
+    data[4..8].copy_from_slice(&inductance.to_le_bytes());
+    data
+}
+
+/// Parse sensorless observer parameters from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some((flux_linkage, pll_bandwidth))` if parsing successful (Wb, rad/s)
+/// * `None` if data length is incorrect
+pub fn parse_sensorless_params(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        error!("Sensorless params: invalid data length {}", data.len());
+        return None;
+    }
+
+    let flux_bytes = [data[0], data[1], data[2], data[3]];
+    let bandwidth_bytes = [data[4], data[5], data[6], data[7]];
+
+    let flux_linkage = f32::from_le_bytes(flux_bytes);
+    let pll_bandwidth = f32::from_le_bytes(bandwidth_bytes);
+
+    info!(
+        "Sensorless params received: flux_linkage={}Wb, pll_bandwidth={}rad/s",
+        flux_linkage, pll_bandwidth
+    );
+    Some((flux_linkage, pll_bandwidth))
+}
+
+/// Encode sensorless observer parameters into CAN data
+#[allow(dead_code)]
+pub fn encode_sensorless_params(flux_linkage: f32, pll_bandwidth: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&flux_linkage.to_le_bytes());
+    data[4..8].copy_from_slice(&pll_bandwidth.to_le_bytes());
+    data
+}
+
+/// Parse angle source selection from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 5 bytes)
+///
+/// # Returns
+/// * `Some((min_speed, source))` if parsing successful (RPM, raw source value)
+/// * `None` if data length is incorrect
+pub fn parse_angle_source(data: &[u8]) -> Option<(f32, u8)> {
+    if data.len() < 5 {
+        error!("Angle source: invalid data length {}", data.len());
+        return None;
+    }
+
+    let min_speed_bytes = [data[0], data[1], data[2], data[3]];
+    let min_speed = f32::from_le_bytes(min_speed_bytes);
+    let source = data[4];
+
+    info!(
+        "Angle source received: source={}, min_speed={} RPM",
+        source, min_speed
+    );
+    Some((min_speed, source))
+}
+
+/// Encode angle source selection into CAN data
+#[allow(dead_code)]
+pub fn encode_angle_source(min_speed: f32, source: u8) -> [u8; 5] {
+    let mut data = [0u8; 5];
+    data[0..4].copy_from_slice(&min_speed.to_le_bytes());
+    data[4] = source;
+    data
+}
+
+/// Parse hall estimator selection from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 5 bytes)
+///
+/// # Returns
+/// * `Some((pll_bandwidth, estimator))` if parsing successful (rad/s, raw estimator value)
+/// * `None` if data length is incorrect
+pub fn parse_hall_estimator_params(data: &[u8]) -> Option<(f32, u8)> {
+    if data.len() < 5 {
+        error!("Hall estimator params: invalid data length {}", data.len());
+        return None;
+    }
+
+    let bandwidth_bytes = [data[0], data[1], data[2], data[3]];
+    let pll_bandwidth = f32::from_le_bytes(bandwidth_bytes);
+    let estimator = data[4];
+
+    info!(
+        "Hall estimator params received: estimator={}, pll_bandwidth={}rad/s",
+        estimator, pll_bandwidth
+    );
+    Some((pll_bandwidth, estimator))
+}
+
+/// Encode hall estimator selection into CAN data
+#[allow(dead_code)]
+pub fn encode_hall_estimator_params(pll_bandwidth: f32, estimator: u8) -> [u8; 5] {
+    let mut data = [0u8; 5];
+    data[0..4].copy_from_slice(&pll_bandwidth.to_le_bytes());
+    data[4] = estimator;
+    data
+}
+
+/// Parse field weakening parameters from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some((gain, voltage_ratio))` if parsing successful (1/s, fraction of max voltage)
+/// * `None` if data length is incorrect
+pub fn parse_field_weakening_params(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        error!("Field weakening params: invalid data length {}", data.len());
+        return None;
+    }
+
+    let gain_bytes = [data[0], data[1], data[2], data[3]];
+    let ratio_bytes = [data[4], data[5], data[6], data[7]];
+
+    let gain = f32::from_le_bytes(gain_bytes);
+    let voltage_ratio = f32::from_le_bytes(ratio_bytes);
+
+    info!(
+        "Field weakening params received: gain={}, voltage_ratio={}",
+        gain, voltage_ratio
+    );
+    Some((gain, voltage_ratio))
+}
+
+/// Encode field weakening parameters into CAN data
+#[allow(dead_code)]
+pub fn encode_field_weakening_params(gain: f32, voltage_ratio: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&gain.to_le_bytes());
+    data[4..8].copy_from_slice(&voltage_ratio.to_le_bytes());
+    data
+}
+
+/// Parse field weakening limits from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some((max_current, max_voltage))` if parsing successful (A, V, 0 = disabled)
+/// * `None` if data length is incorrect
+pub fn parse_field_weakening_limits(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        error!("Field weakening limits: invalid data length {}", data.len());
+        return None;
+    }
+
+    let current_bytes = [data[0], data[1], data[2], data[3]];
+    let voltage_bytes = [data[4], data[5], data[6], data[7]];
+
+    let max_current = f32::from_le_bytes(current_bytes);
+    let max_voltage = f32::from_le_bytes(voltage_bytes);
+
+    info!(
+        "Field weakening limits received: max_current={}A, max_voltage={}V",
+        max_current, max_voltage
+    );
+    Some((max_current, max_voltage))
+}
+
+/// Encode field weakening limits into CAN data
+#[allow(dead_code)]
+pub fn encode_field_weakening_limits(max_current: f32, max_voltage: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&max_current.to_le_bytes());
+    data[4..8].copy_from_slice(&max_voltage.to_le_bytes());
+    data
+}
+
+/// Encode actual and target position into CAN data
+///
+/// # Arguments
+/// * `position` - Multi-turn mechanical position [rad]
+/// * `target_position` - Position command [rad]
+///
+/// # Returns
+/// 8-byte array containing encoded position status
+pub fn encode_position_status(position: f32, target_position: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&position.to_le_bytes());
+    data[4..8].copy_from_slice(&target_position.to_le_bytes());
+    data
+}
+
+/// Decode actual and target position from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some((position, target_position))` if parsing successful
+/// * `None` if data length is incorrect
+#[allow(dead_code)]
+pub fn decode_position_status(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        return None;
+    }
+
+    let position_bytes = [data[0], data[1], data[2], data[3]];
+    let target_bytes = [data[4], data[5], data[6], data[7]];
+
+    Some((
+        f32::from_le_bytes(position_bytes),
+        f32::from_le_bytes(target_bytes),
+    ))
+}
+
+// ============================================================================
+// OpenLoop Parameter Commands
+// ============================================================================
+
+/// Parse openloop RPM parameters from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some((initial_rpm, target_rpm))` if parsing successful
+/// * `None` if data length is incorrect
+pub fn parse_openloop_rpm_params(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        error!("OpenLoop RPM params: invalid data length {}", data.len());
+        return None;
+    }
+
+    let initial_bytes = [data[0], data[1], data[2], data[3]];
+    let target_bytes = [data[4], data[5], data[6], data[7]];
+
+    let initial_rpm = f32::from_le_bytes(initial_bytes);
+    let target_rpm = f32::from_le_bytes(target_bytes);
+
+    info!(
+        "OpenLoop RPM params received: initial={}, target={}",
+        initial_rpm, target_rpm
+    );
+    Some((initial_rpm, target_rpm))
+}
+
+/// Encode openloop RPM parameters into CAN data
+#[allow(dead_code)]
+pub fn encode_openloop_rpm_params(initial_rpm: f32, target_rpm: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&initial_rpm.to_le_bytes());
+    data[4..8].copy_from_slice(&target_rpm.to_le_bytes());
+    data
+}
+
+/// Parse openloop acceleration/duty parameters from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 6 bytes)
+///
+/// # Returns
+/// * `Some((acceleration, duty_ratio))` if parsing successful
+/// * `None` if data length is incorrect
+pub fn parse_openloop_accel_duty_params(data: &[u8]) -> Option<(f32, u16)> {
+    if data.len() < 6 {
+        error!(
+            "OpenLoop accel/duty params: invalid data length {}",
+            data.len()
+        );
+        return None;
+    }
+
+    let accel_bytes = [data[0], data[1], data[2], data[3]];
+    let duty_bytes = [data[4], data[5]];
+
+    let acceleration = f32::from_le_bytes(accel_bytes);
+    let duty_ratio = u16::from_le_bytes(duty_bytes);
+
+    info!(
+        "OpenLoop accel/duty params received: accel={}, duty={}",
+        acceleration, duty_ratio
+    );
+    Some((acceleration, duty_ratio))
+}
+
+/// Encode openloop acceleration/duty parameters into CAN data
+#[allow(dead_code)]
+pub fn encode_openloop_accel_duty_params(acceleration: f32, duty_ratio: u16) -> [u8; 6] {
+    let mut data = [0u8; 6];
+    data[0..4].copy_from_slice(&acceleration.to_le_bytes());
+    data[4..6].copy_from_slice(&duty_ratio.to_le_bytes());
+    data
+}
+
+/// Parse openloop→FOC handover parameters from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 4 bytes)
+///
+/// # Returns
+/// * `Some(handover_time)` if parsing successful (s, 0 = immediate switch)
+/// * `None` if data length is incorrect
+pub fn parse_openloop_handover_params(data: &[u8]) -> Option<f32> {
+    if data.len() < 4 {
+        error!(
+            "OpenLoop handover params: invalid data length {}",
+            data.len()
+        );
+        return None;
+    }
+
+    let time_bytes = [data[0], data[1], data[2], data[3]];
+    let handover_time = f32::from_le_bytes(time_bytes);
+
+    info!("OpenLoop handover params received: time={}s", handover_time);
+    Some(handover_time)
+}
+
+/// Encode openloop→FOC handover parameters into CAN data
+#[allow(dead_code)]
+pub fn encode_openloop_handover_params(handover_time: f32) -> [u8; 4] {
+    handover_time.to_le_bytes()
+}
+
+/// Parse openloop sine V/f parameters from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some((boost_voltage, vf_gain))` if parsing successful
+/// * `None` if data length is incorrect
+pub fn parse_openloop_vf_params(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        error!("OpenLoop V/f params: invalid data length {}", data.len());
+        return None;
+    }
+
+    let boost_bytes = [data[0], data[1], data[2], data[3]];
+    let gain_bytes = [data[4], data[5], data[6], data[7]];
+
+    let boost_voltage = f32::from_le_bytes(boost_bytes);
+    let vf_gain = f32::from_le_bytes(gain_bytes);
+
+    info!(
+        "OpenLoop V/f params received: boost={}V, gain={}V/Hz",
+        boost_voltage, vf_gain
+    );
+    Some((boost_voltage, vf_gain))
+}
+
+/// Encode openloop sine V/f parameters into CAN data
+#[allow(dead_code)]
+pub fn encode_openloop_vf_params(boost_voltage: f32, vf_gain: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&boost_voltage.to_le_bytes());
+    data[4..8].copy_from_slice(&vf_gain.to_le_bytes());
+    data
+}
+
+/// Parse openloop drive mode from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 1 byte)
+///
+/// # Returns
+/// * `Some(mode)` if parsing successful (0 = six-step, 1 = sine V/f)
+/// * `None` if data length is incorrect
+pub fn parse_openloop_mode(data: &[u8]) -> Option<u8> {
+    if data.is_empty() {
+        error!("OpenLoop mode: invalid data length {}", data.len());
+        return None;
+    }
+
+    info!("OpenLoop mode received: {}", data[0]);
+    Some(data[0])
+}
+
+/// Encode openloop drive mode into CAN data
+#[allow(dead_code)]
+pub fn encode_openloop_mode(mode: u8) -> [u8; 1] {
+    [mode]
+}
+
+// ============================================================================
+// Speed Feedforward Commands
+// ============================================================================
+
+/// Parse speed feedforward back-EMF parameters from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some((motor_kv, gain))` if parsing successful (RPM/V, 0 = from flux linkage)
+/// * `None` if data length is incorrect
+pub fn parse_speed_ff_back_emf(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        error!("Speed FF back-EMF: invalid data length {}", data.len());
+        return None;
+    }
+
+    let kv_bytes = [data[0], data[1], data[2], data[3]];
+    let gain_bytes = [data[4], data[5], data[6], data[7]];
+
+    let motor_kv = f32::from_le_bytes(kv_bytes);
+    let gain = f32::from_le_bytes(gain_bytes);
+
+    info!(
+        "Speed FF back-EMF received: kv={}RPM/V, gain={}",
+        motor_kv, gain
+    );
+    Some((motor_kv, gain))
+}
+
+/// Encode speed feedforward back-EMF parameters into CAN data
+#[allow(dead_code)]
+pub fn encode_speed_ff_back_emf(motor_kv: f32, gain: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&motor_kv.to_le_bytes());
+    data[4..8].copy_from_slice(&gain.to_le_bytes());
+    data
+}
+
+/// Parse speed feedforward friction parameters from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some((gain, band))` if parsing successful (band in RPM)
+/// * `None` if data length is incorrect
+pub fn parse_speed_ff_friction(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        error!("Speed FF friction: invalid data length {}", data.len());
+        return None;
+    }
+
+    let gain_bytes = [data[0], data[1], data[2], data[3]];
+    let band_bytes = [data[4], data[5], data[6], data[7]];
+
+    let gain = f32::from_le_bytes(gain_bytes);
+    let band = f32::from_le_bytes(band_bytes);
+
+    info!(
+        "Speed FF friction received: gain={}, band={}RPM",
+        gain, band
+    );
+    Some((gain, band))
+}
+
+/// Encode speed feedforward friction parameters into CAN data
+#[allow(dead_code)]
+pub fn encode_speed_ff_friction(gain: f32, band: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&gain.to_le_bytes());
+    data[4..8].copy_from_slice(&band.to_le_bytes());
+    data
+}
+
+/// Parse speed feedforward acceleration gain from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 4 bytes)
+///
+/// # Returns
+/// * `Some(gain)` if parsing successful
+/// * `None` if data length is incorrect
+pub fn parse_speed_ff_acceleration(data: &[u8]) -> Option<f32> {
+    if data.len() < 4 {
+        error!("Speed FF acceleration: invalid data length {}", data.len());
+        return None;
+    }
+
+    let gain_bytes = [data[0], data[1], data[2], data[3]];
+    let gain = f32::from_le_bytes(gain_bytes);
+
+    info!("Speed FF acceleration received: gain={}", gain);
+    Some(gain)
+}
+
+/// Encode speed feedforward acceleration gain into CAN data
+#[allow(dead_code)]
+pub fn encode_speed_ff_acceleration(gain: f32) -> [u8; 4] {
+    gain.to_le_bytes()
+}
+
+// ============================================================================
+// Speed Loop Filter Commands
+// ============================================================================
+
+/// Parse speed loop filter types from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 4 bytes)
+///
+/// # Returns
+/// * `Some(((feedback_type, feedback_length), (output_type, output_length)))` if parsing successful
+/// * `None` if data length is incorrect
+pub fn parse_speed_filter_types(data: &[u8]) -> Option<((u8, u8), (u8, u8))> {
+    if data.len() < 4 {
+        error!("Speed filter types: invalid data length {}", data.len());
+        return None;
+    }
+
+    let feedback = (data[0], data[1]);
+    let output = (data[2], data[3]);
+
+    info!(
+        "Speed filter types received: feedback={} (length {}), output={} (length {})",
+        feedback.0, feedback.1, output.0, output.1
+    );
+    Some((feedback, output))
+}
+
+/// Encode speed loop filter types into CAN data
+#[allow(dead_code)]
+pub fn encode_speed_filter_types(feedback: (u8, u8), output: (u8, u8)) -> [u8; 4] {
+    [feedback.0, feedback.1, output.0, output.1]
+}
+
+/// Parse speed loop filter parameters from CAN data
+///
+/// Shared by the speed feedback and speed PI output filter frames.
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some((frequency, shape))` if parsing successful (Hz, Q or bandwidth Hz)
+/// * `None` if data length is incorrect
+pub fn parse_speed_filter_params(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        error!("Speed filter params: invalid data length {}", data.len());
+        return None;
+    }
+
+    let frequency_bytes = [data[0], data[1], data[2], data[3]];
+    let shape_bytes = [data[4], data[5], data[6], data[7]];
+
+    let frequency = f32::from_le_bytes(frequency_bytes);
+    let shape = f32::from_le_bytes(shape_bytes);
+
+    info!(
+        "Speed filter params received: frequency={}Hz, shape={}",
+        frequency, shape
+    );
+    Some((frequency, shape))
+}
+
+/// Encode speed loop filter parameters into CAN data
+#[allow(dead_code)]
+pub fn encode_speed_filter_params(frequency: f32, shape: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&frequency.to_le_bytes());
+    data[4..8].copy_from_slice(&shape.to_le_bytes());
+    data
+}
+
+// ============================================================================
+// PI Controller Extension Commands
+// ============================================================================
+
+/// Parse PI back-calculation tracking gains from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some((speed, current))` if parsing successful (1/s)
+/// * `None` if data length is incorrect
+pub fn parse_pi_tracking_gains(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        error!("PI tracking gains: invalid data length {}", data.len());
+        return None;
+    }
+
+    let speed_bytes = [data[0], data[1], data[2], data[3]];
+    let current_bytes = [data[4], data[5], data[6], data[7]];
+
+    let speed = f32::from_le_bytes(speed_bytes);
+    let current = f32::from_le_bytes(current_bytes);
+
+    info!(
+        "PI tracking gains received: speed={}/s, current={}/s",
+        speed, current
+    );
+    Some((speed, current))
+}
+
+/// Encode PI back-calculation tracking gains into CAN data
+#[allow(dead_code)]
+pub fn encode_pi_tracking_gains(speed: f32, current: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&speed.to_le_bytes());
+    data[4..8].copy_from_slice(&current.to_le_bytes());
+    data
+}
+
+/// Parse a derivative term from CAN data
+///
+/// Shared by the speed PI and position derivative frames.
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some((kd, filter_time))` if parsing successful (filter time in s)
+/// * `None` if data length is incorrect
+pub fn parse_derivative_params(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        error!("Derivative params: invalid data length {}", data.len());
+        return None;
+    }
+
+    let kd_bytes = [data[0], data[1], data[2], data[3]];
+    let filter_time_bytes = [data[4], data[5], data[6], data[7]];
+
+    let kd = f32::from_le_bytes(kd_bytes);
+    let filter_time = f32::from_le_bytes(filter_time_bytes);
+
+    info!(
+        "Derivative params received: Kd={}, filter_time={}s",
+        kd, filter_time
+    );
+    Some((kd, filter_time))
+}
+
+/// Encode a derivative term into CAN data
+#[allow(dead_code)]
+pub fn encode_derivative_params(kd: f32, filter_time: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&kd.to_le_bytes());
+    data[4..8].copy_from_slice(&filter_time.to_le_bytes());
+    data
+}
+
+/// Parse speed PI setpoint weights from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some((proportional, derivative))` if parsing successful (b, c)
+/// * `None` if data length is incorrect
+pub fn parse_setpoint_weights(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        error!("Setpoint weights: invalid data length {}", data.len());
+        return None;
+    }
+
+    let proportional_bytes = [data[0], data[1], data[2], data[3]];
+    let derivative_bytes = [data[4], data[5], data[6], data[7]];
+
+    let proportional = f32::from_le_bytes(proportional_bytes);
+    let derivative = f32::from_le_bytes(derivative_bytes);
+
+    info!(
+        "Setpoint weights received: b={}, c={}",
+        proportional, derivative
+    );
+    Some((proportional, derivative))
+}
+
+/// Encode speed PI setpoint weights into CAN data
+#[allow(dead_code)]
+pub fn encode_setpoint_weights(proportional: f32, derivative: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&proportional.to_le_bytes());
+    data[4..8].copy_from_slice(&derivative.to_le_bytes());
+    data
+}
+
+/// Parse speed PI output rate limit from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 4 bytes)
+///
+/// # Returns
+/// * `Some(rate_limit)` if parsing successful (A/s or V/s, 0 = unlimited)
+/// * `None` if data length is incorrect
+pub fn parse_rate_limit(data: &[u8]) -> Option<f32> {
+    if data.len() < 4 {
+        error!("Rate limit: invalid data length {}", data.len());
+        return None;
+    }
+
+    let rate_limit = f32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+
+    info!("Rate limit received: {}/s", rate_limit);
+    Some(rate_limit)
+}
+
+/// Encode speed PI output rate limit into CAN data
+#[allow(dead_code)]
+pub fn encode_rate_limit(rate_limit: f32) -> [u8; 4] {
+    rate_limit.to_le_bytes()
+}
+
+// ============================================================================
+// Speed Gain Schedule Commands
+// ============================================================================
+
+/// Parse a speed gain schedule point from CAN data
+///
+/// The gains of the point are sent separately with `SPEED_GAIN_SCHEDULE_GAINS`
+/// in the PI gains format.
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 4 bytes)
+///
+/// # Returns
+/// * `Some((index, points, speed))` if parsing successful (speed in RPM)
+/// * `None` if data length is incorrect
+pub fn parse_gain_schedule_point(data: &[u8]) -> Option<(u8, u8, u16)> {
+    if data.len() < 4 {
+        error!("Gain schedule point: invalid data length {}", data.len());
+        return None;
+    }
+
+    let index = data[0];
+    let points = data[1];
+    let speed = u16::from_le_bytes([data[2], data[3]]);
+
+    info!(
+        "Gain schedule point received: index={}, points={}, speed={} RPM",
+        index, points, speed
+    );
+    Some((index, points, speed))
+}
+
+/// Encode a speed gain schedule point into CAN data
+#[allow(dead_code)]
+pub fn encode_gain_schedule_point(index: u8, points: u8, speed: u16) -> [u8; 4] {
+    let speed_bytes = speed.to_le_bytes();
+    [index, points, speed_bytes[0], speed_bytes[1]]
+}
+
+// ============================================================================
+// PWM Configuration Commands
+// ============================================================================
+
+/// Parse PWM configuration from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 6 bytes)
+///
+/// # Returns
+/// * `Some((frequency, dead_time))` if parsing successful
+/// * `None` if data length is incorrect
+pub fn parse_pwm_config(data: &[u8]) -> Option<(u32, u16)> {
+    if data.len() < 6 {
+        error!("PWM config: invalid data length {}", data.len());
+        return None;
+    }
+
+    let freq_bytes = [data[0], data[1], data[2], data[3]];
+    let dead_time_bytes = [data[4], data[5]];
+
+    let frequency = u32::from_le_bytes(freq_bytes);
+    let dead_time = u16::from_le_bytes(dead_time_bytes);
+
+    info!(
+        "PWM config received: freq={}Hz, dead_time={}",
+        frequency, dead_time
+    );
+    Some((frequency, dead_time))
+}
+
+/// Encode PWM configuration into CAN data
+#[allow(dead_code)]
+pub fn encode_pwm_config(frequency: u32, dead_time: u16) -> [u8; 6] {
+    let mut data = [0u8; 6];
+    data[0..4].copy_from_slice(&frequency.to_le_bytes());
+    data[4..6].copy_from_slice(&dead_time.to_le_bytes());
+    data
+}
+
+/// Parse dead-time compensation config from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 3 bytes)
+///
+/// # Returns
+/// * `Some((mode, min_pulse))` if parsing successful
+/// * `None` if data length is incorrect
+pub fn parse_dead_time_compensation(data: &[u8]) -> Option<(u8, u16)> {
+    if data.len() < 3 {
+        error!("Dead-time compensation: invalid data length {}", data.len());
+        return None;
+    }
+
+    let mode = data[0];
+    let min_pulse = u16::from_le_bytes([data[1], data[2]]);
+
+    info!(
+        "Dead-time compensation received: mode={}, min_pulse={}",
+        mode, min_pulse
+    );
+    Some((mode, min_pulse))
+}
+
+/// Encode dead-time compensation config into CAN data
+#[allow(dead_code)]
+pub fn encode_dead_time_compensation(mode: u8, min_pulse: u16) -> [u8; 3] {
+    let mut data = [0u8; 3];
+    data[0] = mode;
+    data[1..3].copy_from_slice(&min_pulse.to_le_bytes());
+    data
+}
+
+/// Parse overmodulation setting from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be at least 1 byte)
+///
+/// # Returns
+/// * `Some(enable)` if parsing successful
+/// * `None` if data length is incorrect
+pub fn parse_overmodulation(data: &[u8]) -> Option<bool> {
+    if data.is_empty() {
+        error!("Overmodulation: no data");
+        return None;
+    }
+
+    let enable = data[0] != 0;
+    info!("Overmodulation received: {}", enable);
+    Some(enable)
+}
+
+/// Encode overmodulation setting into CAN data
+#[allow(dead_code)]
+pub fn encode_overmodulation(enable: bool) -> [u8; 1] {
+    [if enable { 1 } else { 0 }]
+}
+
+// ============================================================================
+// CAN Configuration Commands
+// ============================================================================
+
+/// Parse CAN configuration from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 4 bytes)
+///
+/// # Returns
+/// * `Some(bitrate)` if parsing successful
+/// * `None` if data length is incorrect
+pub fn parse_can_config(data: &[u8]) -> Option<u32> {
+    if data.len() < 4 {
+        error!("CAN config: invalid data length {}", data.len());
+        return None;
+    }
+
+    let bitrate_bytes = [data[0], data[1], data[2], data[3]];
+    let bitrate = u32::from_le_bytes(bitrate_bytes);
+
+    info!("CAN config received: bitrate={}", bitrate);
+    Some(bitrate)
+}
+
+/// Encode CAN configuration into CAN data
+#[allow(dead_code)]
+pub fn encode_can_config(bitrate: u32) -> [u8; 4] {
+    bitrate.to_le_bytes()
+}
+
+// ============================================================================
+// Control Timing Commands
+// ============================================================================
+
+/// Parse control timing from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some(control_period_us)` if parsing successful
+/// * `None` if data length is incorrect
+pub fn parse_control_timing(data: &[u8]) -> Option<u64> {
+    if data.len() < 8 {
+        error!("Control timing: invalid data length {}", data.len());
+        return None;
+    }
+
+    let period_bytes = [
+        data[0], data[1], data[2], data[3], data[4], data[5], data[6], data[7],
+    ];
+    let control_period_us = u64::from_le_bytes(period_bytes);
+
+    info!("Control timing received: {}us", control_period_us);
+    Some(control_period_us)
+}
+
+/// Encode control timing into CAN data
+#[allow(dead_code)]
+pub fn encode_control_timing(control_period_us: u64) -> [u8; 8] {
+    control_period_us.to_le_bytes()
+}
+
+/// Parse loop rate dividers from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 4 bytes)
+///
+/// # Returns
+/// * `Some((speed_divider, position_divider))` if parsing successful
+/// * `None` if data length is incorrect
+pub fn parse_loop_dividers(data: &[u8]) -> Option<(u16, u16)> {
+    if data.len() < 4 {
+        error!("Loop dividers: invalid data length {}", data.len());
+        return None;
+    }
+
+    let speed_divider = u16::from_le_bytes([data[0], data[1]]);
+    let position_divider = u16::from_le_bytes([data[2], data[3]]);
+
+    info!(
+        "Loop dividers received: speed={}, position={}",
+        speed_divider, position_divider
+    );
+    Some((speed_divider, position_divider))
+}
+
+/// Encode loop rate dividers into CAN data
+#[allow(dead_code)]
+pub fn encode_loop_dividers(speed_divider: u16, position_divider: u16) -> [u8; 4] {
+    let mut data = [0u8; 4];
+    data[0..2].copy_from_slice(&speed_divider.to_le_bytes());
+    data[2..4].copy_from_slice(&position_divider.to_le_bytes());
+    data
+}
+
+/// Encode control loop rates status into CAN data
+///
+/// # Arguments
+/// * `rates` - Control loop rates in use
+///
+/// # Returns
+/// 8-byte array containing encoded rates
+pub fn encode_control_rates_status(rates: &ControlRates) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&rates.current_loop_hz.to_le_bytes());
+    data[4..6].copy_from_slice(&rates.speed_divider.to_le_bytes());
+    data[6..8].copy_from_slice(&rates.position_divider.to_le_bytes());
+    data
+}
+
+/// Decode control loop rates status from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some(rates)` if parsing successful
+/// * `None` if data length is incorrect
+#[allow(dead_code)]
+pub fn decode_control_rates_status(data: &[u8]) -> Option<ControlRates> {
+    if data.len() < 8 {
+        return None;
+    }
+
+    Some(ControlRates {
+        current_loop_hz: u32::from_le_bytes([data[0], data[1], data[2], data[3]]),
+        speed_divider: u16::from_le_bytes([data[4], data[5]]),
+        position_divider: u16::from_le_bytes([data[6], data[7]]),
+    })
+}
+
+// ============================================================================
+// Calibration Commands
+// ============================================================================
+
+/// Encode calibration status into CAN data
+///
+/// # Arguments
+/// * `electrical_offset` - Electrical offset in radians (0～2π)
+/// * `direction_inversed` - Direction inversion flag
+/// * `success` - Calibration success flag
+///
+/// # Returns
+/// 6-byte array containing encoded calibration status
+pub fn encode_calibration_status(
+    electrical_offset: f32,
+    direction_inversed: bool,
+    success: bool,
+) -> [u8; 6] {
+    let mut data = [0u8; 6];
+
+    // Encode electrical offset as little-endian f32 (bytes 0-3)
     let offset_bytes = electrical_offset.to_le_bytes();
     data[0..4].copy_from_slice(&offset_bytes);
 
-    // Encode direction inversed flag (byte 4)
-    data[4] = if direction_inversed { 1 } else { 0 };
+    // Encode direction inversed flag (byte 4)
+    data[4] = if direction_inversed { 1 } else { 0 };
+
+    // Encode success flag (byte 5)
+    data[5] = if success { 1 } else { 0 };
+
+    data
+}
+
+/// Decode calibration status from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be at least 6 bytes)
+///
+/// # Returns
+/// * `Some((electrical_offset, direction_inversed, success))` if parsing successful
+/// * `None` if data length is incorrect
+#[allow(dead_code)]
+pub fn decode_calibration_status(data: &[u8]) -> Option<(f32, bool, bool)> {
+    if data.len() < 6 {
+        return None;
+    }
+
+    let offset_bytes = [data[0], data[1], data[2], data[3]];
+    let electrical_offset = f32::from_le_bytes(offset_bytes);
+
+    let direction_inversed = data[4] != 0;
+    let success = data[5] != 0;
+
+    Some((electrical_offset, direction_inversed, success))
+}
+
+/// Encode half of the hall sector table into CAN data
+///
+/// The six sector start angles do not fit in one frame, so the table is sent
+/// as two pages of three sectors. Angles are encoded as fractions of one
+/// electrical turn (65536 = 2π).
+///
+/// # Arguments
+/// * `page` - 0 for hall states 1-3, 1 for hall states 4-6
+/// * `angles` - Electrical start angles of the three sectors [rad] (0～2π)
+/// * `valid` - Sector table valid flag
+///
+/// # Returns
+/// 8-byte array containing encoded sector table page
+pub fn encode_hall_sector_table_status(page: u8, angles: &[f32], valid: bool) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0] = page;
+    data[1] = if valid { 1 } else { 0 };
+
+    for (i, angle) in angles.iter().take(3).enumerate() {
+        let turns = (angle / core::f32::consts::TAU).clamp(0.0, 1.0);
+        let raw = (turns * 65536.0) as u32 as u16; // 2π wraps to 0
+        data[2 + i * 2..4 + i * 2].copy_from_slice(&raw.to_le_bytes());
+    }
+
+    data
+}
+
+/// Decode half of the hall sector table from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some((page, angles, valid))` if parsing successful (angles in rad)
+/// * `None` if data length is incorrect
+#[allow(dead_code)]
+pub fn decode_hall_sector_table_status(data: &[u8]) -> Option<(u8, [f32; 3], bool)> {
+    if data.len() < 8 {
+        return None;
+    }
+
+    let mut angles = [0.0f32; 3];
+    for (i, angle) in angles.iter_mut().enumerate() {
+        let raw = u16::from_le_bytes([data[2 + i * 2], data[3 + i * 2]]);
+        *angle = raw as f32 / 65536.0 * core::f32::consts::TAU;
+    }
+
+    Some((data[0], angles, data[1] != 0))
+}
+
+/// Encode hall diagnostics status into CAN data
+///
+/// # Arguments
+/// * `status` - Latched hall fault flags and event counters
+///
+/// # Returns
+/// 8-byte array containing encoded hall diagnostics status
+pub fn encode_hall_diagnostics_status(status: &HallDiagnosticsStatus) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0] = status.fault_flags;
+    data[1..3].copy_from_slice(&status.invalid_states.to_le_bytes());
+    data[3..5].copy_from_slice(&status.skipped_sectors.to_le_bytes());
+    data[5..7].copy_from_slice(&status.glitches.to_le_bytes());
+    data[7] = status.wrong_direction_edges;
+    data
+}
+
+/// Decode hall diagnostics status from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some(status)` if parsing successful
+/// * `None` if data length is incorrect
+#[allow(dead_code)]
+pub fn decode_hall_diagnostics_status(data: &[u8]) -> Option<HallDiagnosticsStatus> {
+    if data.len() < 8 {
+        return None;
+    }
+
+    Some(HallDiagnosticsStatus {
+        fault_flags: data[0],
+        invalid_states: u16::from_le_bytes([data[1], data[2]]),
+        skipped_sectors: u16::from_le_bytes([data[3], data[4]]),
+        glitches: u16::from_le_bytes([data[5], data[6]]),
+        wrong_direction_edges: data[7],
+    })
+}
+
+// ============================================================================
+// Motor Identification Commands
+// ============================================================================
+
+/// Parse motor parameter identification command from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some((test_current, test_speed))` if parsing successful (A, RPM)
+/// * `None` if data length is incorrect
+pub fn parse_identification_command(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        error!("Identification command: invalid data length {}", data.len());
+        return None;
+    }
+
+    let current_bytes = [data[0], data[1], data[2], data[3]];
+    let speed_bytes = [data[4], data[5], data[6], data[7]];
+
+    let test_current = f32::from_le_bytes(current_bytes);
+    let test_speed = f32::from_le_bytes(speed_bytes);
+
+    info!(
+        "Identification command received: current={}A, speed={}RPM",
+        test_current, test_speed
+    );
+    Some((test_current, test_speed))
+}
+
+/// Encode motor parameter identification command into CAN data
+#[allow(dead_code)]
+pub fn encode_identification_command(test_current: f32, test_speed: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&test_current.to_le_bytes());
+    data[4..8].copy_from_slice(&test_speed.to_le_bytes());
+    data
+}
+
+/// Encode identification status into CAN data
+///
+/// # Arguments
+/// * `state` - Identification state (`IdentificationState as u8`)
+/// * `electrical_valid` - Resistance, inductance and flux linkage valid flag
+/// * `mechanical_valid` - Inertia and friction valid flag
+///
+/// # Returns
+/// 3-byte array containing encoded identification status
+pub fn encode_identification_status(
+    state: u8,
+    electrical_valid: bool,
+    mechanical_valid: bool,
+) -> [u8; 3] {
+    [
+        state,
+        if electrical_valid { 1 } else { 0 },
+        if mechanical_valid { 1 } else { 0 },
+    ]
+}
 
-    // Encode success flag (byte 5)
-    data[5] = if success { 1 } else { 0 };
+/// Decode identification status from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 3 bytes)
+///
+/// # Returns
+/// * `Some((state, electrical_valid, mechanical_valid))` if parsing successful
+/// * `None` if data length is incorrect
+#[allow(dead_code)]
+pub fn decode_identification_status(data: &[u8]) -> Option<(u8, bool, bool)> {
+    if data.len() < 3 {
+        return None;
+    }
 
+    Some((data[0], data[1] != 0, data[2] != 0))
+}
+
+/// Encode a pair of identified parameters into CAN data
+///
+/// Shared by the identified electrical params, flux/inertia and friction frames.
+///
+/// # Arguments
+/// * `first` - First parameter (bytes 0-3)
+/// * `second` - Second parameter (bytes 4-7)
+///
+/// # Returns
+/// 8-byte array containing encoded parameters
+pub fn encode_identified_params(first: f32, second: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&first.to_le_bytes());
+    data[4..8].copy_from_slice(&second.to_le_bytes());
     data
 }
 
-/// Decode calibration status from CAN data
+/// Decode a pair of identified parameters from CAN data
 ///
 /// # Arguments
-/// * `data` - CAN frame data (should be at least 6 bytes)
+/// * `data` - CAN frame data (should be 8 bytes)
 ///
 /// # Returns
-/// * `Some((electrical_offset, direction_inversed, success))` if parsing successful
+/// * `Some((first, second))` if parsing successful
 /// * `None` if data length is incorrect
 #[allow(dead_code)]
-pub fn decode_calibration_status(data: &[u8]) -> Option<(f32, bool, bool)> {
-    if data.len() < 6 {
+pub fn decode_identified_params(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
         return None;
     }
 
-    let offset_bytes = [data[0], data[1], data[2], data[3]];
-    let electrical_offset = f32::from_le_bytes(offset_bytes);
+    let first = f32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+    let second = f32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+    Some((first, second))
+}
 
-    let direction_inversed = data[4] != 0;
-    let success = data[5] != 0;
+// ============================================================================
+// Speed PI Auto-Tune Commands
+// ============================================================================
 
-    Some((electrical_offset, direction_inversed, success))
+/// Parse speed PI auto-tune command from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some((test_speed, relay_ratio))` if parsing successful (RPM, ratio of output limit)
+/// * `None` if data length is incorrect
+pub fn parse_autotune_command(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        error!("Auto-tune command: invalid data length {}", data.len());
+        return None;
+    }
+
+    let speed_bytes = [data[0], data[1], data[2], data[3]];
+    let ratio_bytes = [data[4], data[5], data[6], data[7]];
+
+    let test_speed = f32::from_le_bytes(speed_bytes);
+    let relay_ratio = f32::from_le_bytes(ratio_bytes);
+
+    info!(
+        "Auto-tune command received: speed={}RPM, relay_ratio={}",
+        test_speed, relay_ratio
+    );
+    Some((test_speed, relay_ratio))
+}
+
+/// Encode speed PI auto-tune command into CAN data
+#[allow(dead_code)]
+pub fn encode_autotune_command(test_speed: f32, relay_ratio: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&test_speed.to_le_bytes());
+    data[4..8].copy_from_slice(&relay_ratio.to_le_bytes());
+    data
+}
+
+/// Encode auto-tune status into CAN data
+///
+/// # Arguments
+/// * `state` - Auto-tune state (`AutoTuneState as u8`)
+/// * `valid` - Tuned gains valid flag
+///
+/// # Returns
+/// 2-byte array containing encoded auto-tune status
+pub fn encode_autotune_status(state: u8, valid: bool) -> [u8; 2] {
+    [state, if valid { 1 } else { 0 }]
+}
+
+/// Decode auto-tune status from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 2 bytes)
+///
+/// # Returns
+/// * `Some((state, valid))` if parsing successful
+/// * `None` if data length is incorrect
+#[allow(dead_code)]
+pub fn decode_autotune_status(data: &[u8]) -> Option<(u8, bool)> {
+    if data.len() < 2 {
+        return None;
+    }
+
+    Some((data[0], data[1] != 0))
+}
+
+/// Encode drive state status into CAN data
+///
+/// # Arguments
+/// * `state` - Drive state (`DriveState as u8`)
+/// * `control_mode` - Control mode (`ControlMode as u8`)
+/// * `faults` - Active fault causes (bit flags)
+///
+/// # Returns
+/// 3-byte array containing encoded drive status
+pub fn encode_drive_status(state: u8, control_mode: u8, faults: u8) -> [u8; 3] {
+    [state, control_mode, faults]
+}
+
+/// Decode drive state status from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 3 bytes)
+///
+/// # Returns
+/// * `Some((state, control_mode, faults))` if parsing successful
+/// * `None` if data length is incorrect
+#[allow(dead_code)]
+pub fn decode_drive_status(data: &[u8]) -> Option<(u8, u8, u8)> {
+    if data.len() < 3 {
+        return None;
+    }
+
+    Some((data[0], data[1], data[2]))
+}
+
+/// Encode auto-tuned speed PI gains into CAN data
+///
+/// # Arguments
+/// * `kp` - Tuned proportional gain
+/// * `ki` - Tuned integral gain
+///
+/// # Returns
+/// 8-byte array containing encoded gains
+pub fn encode_autotune_gains(kp: f32, ki: f32) -> [u8; 8] {
+    let mut data = [0u8; 8];
+    data[0..4].copy_from_slice(&kp.to_le_bytes());
+    data[4..8].copy_from_slice(&ki.to_le_bytes());
+    data
+}
+
+/// Decode auto-tuned speed PI gains from CAN data
+///
+/// # Arguments
+/// * `data` - CAN frame data (should be 8 bytes)
+///
+/// # Returns
+/// * `Some((kp, ki))` if parsing successful
+/// * `None` if data length is incorrect
+#[allow(dead_code)]
+pub fn decode_autotune_gains(data: &[u8]) -> Option<(f32, f32)> {
+    if data.len() < 8 {
+        return None;
+    }
+
+    let kp = f32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+    let ki = f32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+    Some((kp, ki))
 }
 
 #[cfg(test)]
@@ -734,6 +2443,38 @@ mod tests {
         assert_eq!(parsed, Some((kp, ki)));
     }
 
+    #[test]
+    fn test_encode_decode_torque_command() {
+        let iq = -2.5f32;
+
+        let encoded = encode_torque_command(iq);
+        let decoded = parse_torque_command(&encoded).unwrap();
+
+        assert_eq!(decoded, iq);
+    }
+
+    #[test]
+    fn test_encode_decode_voltage_command() {
+        let vd = 0.5f32;
+        let vq = 6.0f32;
+
+        let encoded = encode_voltage_command(vd, vq);
+        let decoded = parse_voltage_command(&encoded).unwrap();
+
+        assert_eq!(decoded, (vd, vq));
+    }
+
+    #[test]
+    fn test_encode_decode_position_command() {
+        // 2.5回転
+        let position = 2.5 * core::f32::consts::TAU;
+
+        let encoded = encode_position_command(position);
+        let decoded = parse_position_command(&encoded).unwrap();
+
+        assert_eq!(decoded, position);
+    }
+
     #[test]
     fn test_encode_decode_status() {
         let speed = 1500.0f32;
@@ -818,6 +2559,188 @@ mod tests {
         assert_eq!(decoded, enable);
     }
 
+    #[test]
+    fn test_encode_decode_current_pi_gains() {
+        let kp = 0.5f32;
+        let ki = 200.0f32;
+
+        let encoded = encode_current_pi_gains(kp, ki);
+        let decoded = parse_current_pi_gains(&encoded).unwrap();
+
+        assert_eq!(decoded.0, kp);
+        assert_eq!(decoded.1, ki);
+    }
+
+    #[test]
+    fn test_encode_decode_current_limit() {
+        let max_current = 5.0f32;
+        let enabled = true;
+
+        let encoded = encode_current_limit(max_current, enabled);
+        let decoded = parse_current_limit(&encoded).unwrap();
+
+        assert_eq!(decoded.0, max_current);
+        assert_eq!(decoded.1, enabled);
+    }
+
+    #[test]
+    fn test_encode_decode_current_sense_params() {
+        let shunt = 0.01f32;
+        let gain = 4.0f32;
+
+        let encoded = encode_current_sense_params(shunt, gain);
+        let decoded = parse_current_sense_params(&encoded).unwrap();
+
+        assert_eq!(decoded.0, shunt);
+        assert_eq!(decoded.1, gain);
+    }
+
+    #[test]
+    fn test_encode_decode_current_status() {
+        let id = -0.25f32;
+        let iq = 3.5f32;
+
+        let encoded = encode_current_status(id, iq);
+        let decoded = decode_current_status(&encoded).unwrap();
+
+        assert_eq!(decoded, (id, iq));
+    }
+
+    #[test]
+    fn test_encode_decode_position_params() {
+        let kp = 50.0f32;
+        let max_speed = 500.0f32;
+
+        let encoded = encode_position_params(kp, max_speed);
+        let decoded = parse_position_params(&encoded).unwrap();
+
+        assert_eq!(decoded, (kp, max_speed));
+    }
+
+    #[test]
+    fn test_encode_decode_motion_profile_params() {
+        let acceleration = 2000.0f32;
+        let deceleration = 500.0f32;
+
+        let encoded = encode_motion_profile_params(acceleration, deceleration);
+        let decoded = parse_motion_profile_params(&encoded).unwrap();
+
+        assert_eq!(decoded, (acceleration, deceleration));
+        assert!(parse_motion_profile_params(&encoded[..4]).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_motion_profile_jerk() {
+        let jerk = 20000.0f32;
+
+        let encoded = encode_motion_profile_jerk(jerk);
+        let decoded = parse_motion_profile_jerk(&encoded).unwrap();
+
+        assert_eq!(decoded, jerk);
+        assert!(parse_motion_profile_jerk(&encoded[..2]).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_motor_electrical_params() {
+        let resistance = 0.3f32;
+        let inductance = 0.0003f32;
+
+        let encoded = encode_motor_electrical_params(resistance, inductance);
+        let decoded = parse_motor_electrical_params(&encoded).unwrap();
+
+        assert_eq!(decoded, (resistance, inductance));
+    }
+
+    #[test]
+    fn test_encode_decode_sensorless_params() {
+        let flux_linkage = 0.005f32;
+        let pll_bandwidth = 300.0f32;
+
+        let encoded = encode_sensorless_params(flux_linkage, pll_bandwidth);
+        let decoded = parse_sensorless_params(&encoded).unwrap();
+
+        assert_eq!(decoded, (flux_linkage, pll_bandwidth));
+    }
+
+    #[test]
+    fn test_encode_decode_angle_source() {
+        let min_speed = 300.0f32;
+        let source = 2u8;
+
+        let encoded = encode_angle_source(min_speed, source);
+        let decoded = parse_angle_source(&encoded).unwrap();
+
+        assert_eq!(decoded, (min_speed, source));
+        assert!(parse_angle_source(&encoded[..4]).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_hall_sector_table_status() {
+        let angles = [0.1f32, 3.0, 6.2];
+
+        let encoded = encode_hall_sector_table_status(1, &angles, true);
+        let (page, decoded, valid) = decode_hall_sector_table_status(&encoded).unwrap();
+
+        assert_eq!(page, 1);
+        assert!(valid);
+        for (expected, actual) in angles.iter().zip(decoded.iter()) {
+            assert!((expected - actual).abs() < 1e-4);
+        }
+        assert!(decode_hall_sector_table_status(&encoded[..7]).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_hall_diagnostics_status() {
+        let status = HallDiagnosticsStatus {
+            fault_flags: 0x0A,
+            invalid_states: 3,
+            skipped_sectors: 517,
+            glitches: 65535,
+            wrong_direction_edges: 12,
+        };
+
+        let encoded = encode_hall_diagnostics_status(&status);
+        let decoded = decode_hall_diagnostics_status(&encoded).unwrap();
+
+        assert_eq!(decoded, status);
+        assert!(decode_hall_diagnostics_status(&encoded[..7]).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_hall_estimator_params() {
+        let pll_bandwidth = 200.0f32;
+        let estimator = 1u8;
+
+        let encoded = encode_hall_estimator_params(pll_bandwidth, estimator);
+        let decoded = parse_hall_estimator_params(&encoded).unwrap();
+
+        assert_eq!(decoded, (pll_bandwidth, estimator));
+        assert!(parse_hall_estimator_params(&encoded[..4]).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_field_weakening() {
+        let encoded = encode_field_weakening_params(50.0, 0.95);
+        assert_eq!(parse_field_weakening_params(&encoded), Some((50.0, 0.95)));
+        assert!(parse_field_weakening_params(&encoded[..7]).is_none());
+
+        let encoded = encode_field_weakening_limits(8.0, 0.0);
+        assert_eq!(parse_field_weakening_limits(&encoded), Some((8.0, 0.0)));
+        assert!(parse_field_weakening_limits(&encoded[..7]).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_position_status() {
+        let position = -12.5f32;
+        let target = 31.4f32;
+
+        let encoded = encode_position_status(position, target);
+        let decoded = decode_position_status(&encoded).unwrap();
+
+        assert_eq!(decoded, (position, target));
+        assert!(decode_position_status(&encoded[..4]).is_none());
+    }
+
     #[test]
     fn test_encode_decode_openloop_rpm_params() {
         let initial = 100.0f32;
@@ -842,6 +2765,89 @@ mod tests {
         assert_eq!(decoded.1, duty);
     }
 
+    #[test]
+    fn test_encode_decode_openloop_handover_params() {
+        let handover_time = 0.25f32;
+
+        let encoded = encode_openloop_handover_params(handover_time);
+        let decoded = parse_openloop_handover_params(&encoded).unwrap();
+
+        assert_eq!(decoded, handover_time);
+        assert!(parse_openloop_handover_params(&encoded[..3]).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_openloop_vf_params() {
+        let boost = 1.5f32;
+        let gain = 0.04f32;
+
+        let encoded = encode_openloop_vf_params(boost, gain);
+        let decoded = parse_openloop_vf_params(&encoded).unwrap();
+
+        assert_eq!(decoded.0, boost);
+        assert_eq!(decoded.1, gain);
+        assert!(parse_openloop_vf_params(&encoded[..7]).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_openloop_mode() {
+        let encoded = encode_openloop_mode(1);
+        assert_eq!(parse_openloop_mode(&encoded), Some(1));
+        assert!(parse_openloop_mode(&[]).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_speed_feedforward() {
+        let encoded = encode_speed_ff_back_emf(120.0, 0.9);
+        assert_eq!(parse_speed_ff_back_emf(&encoded), Some((120.0, 0.9)));
+        assert!(parse_speed_ff_back_emf(&encoded[..7]).is_none());
+
+        let encoded = encode_speed_ff_friction(1.0, 15.0);
+        assert_eq!(parse_speed_ff_friction(&encoded), Some((1.0, 15.0)));
+        assert!(parse_speed_ff_friction(&encoded[..7]).is_none());
+
+        let encoded = encode_speed_ff_acceleration(0.5);
+        assert_eq!(parse_speed_ff_acceleration(&encoded), Some(0.5));
+        assert!(parse_speed_ff_acceleration(&encoded[..3]).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_speed_filters() {
+        let encoded = encode_speed_filter_types((2, 4), (5, 3));
+        assert_eq!(parse_speed_filter_types(&encoded), Some(((2, 4), (5, 3))));
+        assert!(parse_speed_filter_types(&encoded[..3]).is_none());
+
+        let encoded = encode_speed_filter_params(180.0, 4.5);
+        assert_eq!(parse_speed_filter_params(&encoded), Some((180.0, 4.5)));
+        assert!(parse_speed_filter_params(&encoded[..7]).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_pi_extensions() {
+        let encoded = encode_pi_tracking_gains(20.0, 500.0);
+        assert_eq!(parse_pi_tracking_gains(&encoded), Some((20.0, 500.0)));
+        assert!(parse_pi_tracking_gains(&encoded[..7]).is_none());
+
+        let encoded = encode_derivative_params(0.01, 0.002);
+        assert_eq!(parse_derivative_params(&encoded), Some((0.01, 0.002)));
+        assert!(parse_derivative_params(&encoded[..7]).is_none());
+
+        let encoded = encode_setpoint_weights(0.5, 0.0);
+        assert_eq!(parse_setpoint_weights(&encoded), Some((0.5, 0.0)));
+        assert!(parse_setpoint_weights(&encoded[..7]).is_none());
+
+        let encoded = encode_rate_limit(100.0);
+        assert_eq!(parse_rate_limit(&encoded), Some(100.0));
+        assert!(parse_rate_limit(&encoded[..3]).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_gain_schedule_point() {
+        let encoded = encode_gain_schedule_point(2, 4, 3000);
+        assert_eq!(parse_gain_schedule_point(&encoded), Some((2, 4, 3000)));
+        assert!(parse_gain_schedule_point(&encoded[..3]).is_none());
+    }
+
     #[test]
     fn test_encode_decode_pwm_config() {
         let freq = 50000u32;
@@ -854,6 +2860,26 @@ mod tests {
         assert_eq!(decoded.1, dead_time);
     }
 
+    #[test]
+    fn test_encode_decode_dead_time_compensation() {
+        let encoded = encode_dead_time_compensation(1, 340);
+        assert_eq!(parse_dead_time_compensation(&encoded), Some((1, 340)));
+        assert!(parse_dead_time_compensation(&encoded[..2]).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_overmodulation() {
+        assert_eq!(
+            parse_overmodulation(&encode_overmodulation(true)),
+            Some(true)
+        );
+        assert_eq!(
+            parse_overmodulation(&encode_overmodulation(false)),
+            Some(false)
+        );
+        assert!(parse_overmodulation(&[]).is_none());
+    }
+
     #[test]
     fn test_encode_decode_can_config() {
         let bitrate = 250000u32;
@@ -873,4 +2899,59 @@ mod tests {
 
         assert_eq!(decoded, period);
     }
+
+    #[test]
+    fn test_encode_decode_loop_rates() {
+        let encoded = encode_loop_dividers(10, 5);
+        assert_eq!(parse_loop_dividers(&encoded), Some((10, 5)));
+        assert!(parse_loop_dividers(&encoded[..3]).is_none());
+
+        let rates = ControlRates {
+            current_loop_hz: 25_000,
+            speed_divider: 10,
+            position_divider: 5,
+        };
+        let encoded = encode_control_rates_status(&rates);
+        assert_eq!(decode_control_rates_status(&encoded), Some(rates));
+        assert!(decode_control_rates_status(&encoded[..7]).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_identification() {
+        let encoded = encode_identification_command(2.0, 1000.0);
+        assert_eq!(parse_identification_command(&encoded), Some((2.0, 1000.0)));
+        assert!(parse_identification_command(&encoded[..4]).is_none());
+
+        let encoded = encode_identification_status(6, true, false);
+        assert_eq!(
+            decode_identification_status(&encoded),
+            Some((6, true, false))
+        );
+
+        let encoded = encode_identified_params(0.3, 0.0003);
+        assert_eq!(decode_identified_params(&encoded), Some((0.3, 0.0003)));
+        assert!(decode_identified_params(&encoded[..7]).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_autotune() {
+        let encoded = encode_autotune_command(-1000.0, 0.2);
+        assert_eq!(parse_autotune_command(&encoded), Some((-1000.0, 0.2)));
+        assert!(parse_autotune_command(&encoded[..4]).is_none());
+
+        let encoded = encode_autotune_status(3, true);
+        assert_eq!(decode_autotune_status(&encoded), Some((3, true)));
+        assert!(decode_autotune_status(&encoded[..1]).is_none());
+
+        let encoded = encode_autotune_gains(0.027, 0.38);
+        assert_eq!(decode_autotune_gains(&encoded), Some((0.027, 0.38)));
+        assert!(decode_autotune_gains(&encoded[..7]).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_drive_status() {
+        let encoded = encode_drive_status(6, 1, 0x03);
+        assert_eq!(decode_drive_status(&encoded), Some((6, 1, 0x03)));
+        assert!(decode_drive_status(&encoded[..2]).is_none());
+    }
 }
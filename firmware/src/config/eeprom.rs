@@ -80,6 +80,12 @@ pub fn read_config(
     // バイト列から構造体に変換
     let config = unsafe { StoredConfig::from_bytes(&buffer) }.ok_or(EepromError::InvalidSize)?;
 
+    // v1の設定はデフォルト値に戻さず、引き継げる項目を移行する（保存すると現在のバージョンになる）
+    if let Some(config) = StoredConfig::migrate_from_v1(&buffer) {
+        info!("Migrated config from version 1 to {}", config.version);
+        return Ok(config);
+    }
+
     // マジックナンバーとバージョンを検証
     if !config.validate_header() {
         error!(
@@ -8,14 +8,17 @@ pub const DEFAULT_SPEED_KI: f32 = 0.05; // 積分ゲイン（定常偏差低減
 /// 最大電圧 [V]（デフォルト値）
 pub const DEFAULT_MAX_VOLTAGE: f32 = 24.0;
 
-/// DCバス電圧 [V]（デフォルト値）
+/// DCバス電圧 [V]（デフォルト値、電圧監視の実測値が得られるまでの公称値）
 pub const DEFAULT_V_DC_BUS: f32 = 24.0;
 
+/// DCバス電圧の実測値として扱う下限 [V]（これ未満は未測定とみなし公称値を使用）
+pub const MIN_MEASURED_V_DC_BUS: f32 = 1.0;
+
 /// モーターの極対数（ポール数12 / 2 = 6）（デフォルト値）
 pub const DEFAULT_POLE_PAIRS: u8 = 6;
 
-/// 制御周期 [μs]（2.5kHz = 400μs）（デフォルト値）
-pub const DEFAULT_CONTROL_PERIOD_US: u64 = 400;
+/// 制御周期 [μs]（電流・角度ループ、25kHz = 40μs = PWM周期 × 2）（デフォルト値）
+pub const DEFAULT_CONTROL_PERIOD_US: u64 = 40;
 
 /// ホールセンサ速度フィルタ係数（foc-simple互換: α=0.05でより滑らかな速度推定）（デフォルト値）
 pub const DEFAULT_SPEED_FILTER_ALPHA: f32 = 0.05;
@@ -23,17 +26,36 @@ pub const DEFAULT_SPEED_FILTER_ALPHA: f32 = 0.05;
 /// Hall角度オフセット [度]（ハードウェアに応じて調整、モーターが正しく回転しない場合は調整が必要）
 pub const DEFAULT_HALL_ANGLE_OFFSET_DEG: f32 = 0.0;
 
-/// 最小出力電圧 [V]（静止摩擦を克服するための最小電圧）
-pub const MIN_VOLTAGE: f32 = 2.0;
+/// 速度PIゲインが有効かチェック（負値・NaNは不可）
+pub fn is_valid_speed_gain(value: f32) -> bool {
+    value.is_finite() && value >= 0.0
+}
 
-/// 最小電圧適用のしきい値 [RPM]（速度誤差がこの値を超える場合に最小電圧を適用）
-pub const MIN_VOLTAGE_ERROR_THRESHOLD: f32 = 2.0;
+/// 最大電圧・DCバス電圧が有効かチェック（正の有限値のみ、速度PIの出力制限・変調の基準になる）
+pub fn is_valid_voltage(value: f32) -> bool {
+    value.is_finite() && value > 0.0
+}
 
-/// 速度指令の最大加速度 [RPM/s]（急激な速度変化を抑制してPI制御を安定化）
-pub const MAX_SPEED_ACCELERATION: f32 = 100.0;
+/// 極対数が有効かチェック（0は不可）
+pub fn is_valid_pole_pairs(pole_pairs: u8) -> bool {
+    pole_pairs > 0
+}
 
-/// オープンループ始動パラメータ（6ステップ駆動）
+/// ホールセンサ速度フィルタ係数が有効かチェック（0より大きく1以下）
+pub fn is_valid_speed_filter_alpha(value: f32) -> bool {
+    value > 0.0 && value <= 1.0
+}
+
+/// Hall角度オフセットが有効かチェック（有限値のみ）
+pub fn is_valid_angle_offset(value: f32) -> bool {
+    value.is_finite()
+}
+
+/// オープンループ始動パラメータ（6ステップ駆動 / 正弦波V/f駆動）
 pub mod openloop {
+    /// 駆動方式（0 = 6ステップ、1 = 正弦波V/f）（デフォルト値）
+    pub const DEFAULT_MODE: u8 = 0;
+
     /// 初期回転数 [RPM]（デバッグ用：非常に低速）
     pub const DEFAULT_INITIAL_RPM: f32 = 10.0;
 
@@ -45,6 +67,370 @@ pub mod openloop {
 
     /// デューティ比 (0-100)（デバッグ用：最大トルク）
     pub const DEFAULT_DUTY_RATIO: u16 = 10;
+
+    /// デューティ比の上限
+    pub const MAX_DUTY_RATIO: u16 = 100;
+
+    /// 初期回転数・FOC切替回転数が有効かチェック
+    /// （0以下の初期回転数はステップ周期が発散するため不可、切替回転数は初期回転数以上）
+    pub fn is_valid_rpm_range(initial_rpm: f32, target_rpm: f32) -> bool {
+        initial_rpm.is_finite()
+            && target_rpm.is_finite()
+            && initial_rpm > 0.0
+            && target_rpm >= initial_rpm
+    }
+
+    /// 加速度が有効かチェック（正の有限値のみ）
+    pub fn is_valid_acceleration(value: f32) -> bool {
+        value.is_finite() && value > 0.0
+    }
+
+    /// デューティ比が有効かチェック（0～上限）
+    pub fn is_valid_duty_ratio(value: u16) -> bool {
+        value <= MAX_DUTY_RATIO
+    }
+
+    /// 正弦波V/f駆動の0Hzでのブースト電圧 [V]（デフォルト値）
+    pub const DEFAULT_VF_BOOST_VOLTAGE: f32 = 1.0;
+
+    /// 正弦波V/f駆動のV/fゲイン [V/Hz]（電気周波数あたりの電圧、逆起電力定数より少し大きめ）（デフォルト値）
+    pub const DEFAULT_VF_GAIN: f32 = 0.05;
+
+    /// V/f駆動のブースト電圧・ゲインが有効かチェック（負値・NaNは不可）
+    pub fn is_valid_vf_param(value: f32) -> bool {
+        value.is_finite() && value >= 0.0
+    }
+
+    /// FOC切替時のハンドオーバー時間 [s]（強制転流の電圧ベクトルからFOC出力へ徐々に移行、0 = 即時切替）（デフォルト値）
+    pub const DEFAULT_HANDOVER_TIME: f32 = 0.2;
+
+    /// ハンドオーバー時間の上限 [s]
+    pub const MAX_HANDOVER_TIME: f32 = 5.0;
+
+    /// ハンドオーバー時間が有効かチェック（0～上限の有限値）
+    pub fn is_valid_handover_time(value: f32) -> bool {
+        value.is_finite() && (0.0..=MAX_HANDOVER_TIME).contains(&value)
+    }
+
+    /// キャリブレーション済みの場合のFOC直接始動のタイムアウト [s]（回転指令後この時間内に回転しなければオープンループ始動に切替）
+    pub const DIRECT_START_TIMEOUT: f32 = 1.0;
+
+    /// FOC直接始動の成功判定に必要なHallエッジ数（6 = 1電気回転）
+    pub const DIRECT_START_EDGES: u32 = 6;
+}
+
+/// 電流制御パラメータ（d/q軸電流PI + 相電流検出）
+pub mod current {
+    /// 電流PI制御の比例ゲイン [V/A]（デフォルト値）
+    pub const DEFAULT_KP: f32 = 0.5;
+
+    /// 電流PI制御の積分ゲイン [V/(A·s)]（デフォルト値）
+    pub const DEFAULT_KI: f32 = 200.0;
+
+    /// 最大電流 [A]（電流制御時の速度PI出力 = q軸電流指令の制限）（デフォルト値）
+    pub const DEFAULT_MAX_CURRENT: f32 = 5.0;
+
+    /// シャント抵抗 [Ω]（デフォルト値）
+    pub const DEFAULT_SHUNT_RESISTANCE: f32 = 0.01;
+
+    /// 電流検出アンプのゲイン（OPAMP1のPGA ×4に合わせる）（デフォルト値）
+    pub const DEFAULT_AMP_GAIN: f32 = 4.0;
+
+    /// 電流制御有効フラグ（デフォルト値）
+    /// 既存の速度PIゲインは電圧出力で調整されているため、電流ゲイン調整後にCANで有効化する
+    pub const DEFAULT_ENABLED: bool = false;
+
+    /// ゼロ電流オフセット校正のサンプル数（制御周期ごとに1サンプル）
+    pub const OFFSET_CALIBRATION_SAMPLES: u16 = 256;
+
+    /// PIゲインが有効かチェック（負値・NaNは不可）
+    pub fn is_valid_gain(value: f32) -> bool {
+        value.is_finite() && value >= 0.0
+    }
+
+    /// 最大電流・シャント抵抗・アンプゲインが有効かチェック（正の有限値のみ）
+    pub fn is_valid_positive(value: f32) -> bool {
+        value.is_finite() && value > 0.0
+    }
+}
+
+/// 位置制御パラメータ（位置P → 速度PIのカスケード制御）
+pub mod position {
+    /// 位置P制御の比例ゲイン [RPM/rad]（デフォルト値）
+    pub const DEFAULT_KP: f32 = 50.0;
+
+    /// 位置制御時の最大速度指令 [RPM]（デフォルト値）
+    pub const DEFAULT_MAX_SPEED: f32 = 500.0;
+
+    /// 比例ゲインが有効かチェック（負値・NaNは不可）
+    pub fn is_valid_gain(value: f32) -> bool {
+        value.is_finite() && value >= 0.0
+    }
+
+    /// 最大速度指令が有効かチェック（正の有限値のみ）
+    pub fn is_valid_max_speed(value: f32) -> bool {
+        value.is_finite() && value > 0.0
+    }
+}
+
+/// 加減速プロファイルパラメータ（速度・位置指令の台形/Sカーブ整形）
+pub mod profile {
+    /// 加速度 [RPM/s]（速度の絶対値が増加する方向）（デフォルト値）
+    pub const DEFAULT_ACCELERATION: f32 = 100.0;
+
+    /// 減速度 [RPM/s]（速度の絶対値が減少する方向）（デフォルト値）
+    pub const DEFAULT_DECELERATION: f32 = 100.0;
+
+    /// 加加速度（ジャーク） [RPM/s²]（0 = 台形プロファイル）（デフォルト値）
+    pub const DEFAULT_JERK: f32 = 0.0;
+
+    /// 加速度・減速度が有効かチェック（正の有限値のみ）
+    pub fn is_valid_rate(value: f32) -> bool {
+        value.is_finite() && value > 0.0
+    }
+
+    /// ジャークが有効かチェック（負値・NaNは不可、0は台形プロファイル）
+    pub fn is_valid_jerk(value: f32) -> bool {
+        value.is_finite() && value >= 0.0
+    }
+}
+
+/// センサレス角度推定パラメータ（磁束オブザーバ + PLL）
+pub mod sensorless {
+    /// 角度の取得元（0 = Hall、1 = センサレス、2 = Hall＋センサレスフォールバック）（デフォルト値）
+    pub const DEFAULT_ANGLE_SOURCE: u8 = 0;
+
+    /// 相抵抗 [Ω]（デフォルト値、モーター実測値に合わせて調整）
+    pub const DEFAULT_RESISTANCE: f32 = 0.3;
+
+    /// 相インダクタンス [H]（デフォルト値、モーター実測値に合わせて調整）
+    pub const DEFAULT_INDUCTANCE: f32 = 0.0003;
+
+    /// 永久磁石の鎖交磁束 [Wb]（相ピーク値）（デフォルト値、モーター実測値に合わせて調整）
+    pub const DEFAULT_FLUX_LINKAGE: f32 = 0.005;
+
+    /// PLL帯域 [rad/s]（デフォルト値）
+    pub const DEFAULT_PLL_BANDWIDTH: f32 = 300.0;
+
+    /// オブザーバ角度を使用する最低速度 [RPM]（逆起電力が小さい低速域では推定できない）（デフォルト値）
+    pub const DEFAULT_MIN_SPEED: f32 = 300.0;
+
+    /// モーター定数・PLL帯域が有効かチェック（正の有限値のみ）
+    pub fn is_valid_positive(value: f32) -> bool {
+        value.is_finite() && value > 0.0
+    }
+
+    /// 最低速度が有効かチェック（負値・NaNは不可）
+    pub fn is_valid_min_speed(value: f32) -> bool {
+        value.is_finite() && value >= 0.0
+    }
+}
+
+/// Hallセンサー角度・速度推定パラメータ
+pub mod hall {
+    /// 推定方式（0 = 速度フィルタ＋外挿、1 = エッジタイムスタンプPLL）（デフォルト値）
+    pub const DEFAULT_ESTIMATOR: u8 = 0;
+
+    /// PLL帯域 [rad/s]（デフォルト値）
+    pub const DEFAULT_PLL_BANDWIDTH: f32 = 200.0;
+
+    /// PLL帯域が有効かチェック（正の有限値のみ）
+    pub fn is_valid_pll_bandwidth(value: f32) -> bool {
+        value.is_finite() && value > 0.0
+    }
+}
+
+/// 駆動状態マシン（停止処理）
+pub mod drive {
+    /// 回転停止とみなすHallエッジの途切れ時間 [s]
+    pub const STOP_SETTLE_TIME: f32 = 0.1;
+
+    /// 停止処理の最大時間 [s]（Hallエッジが途切れなくても待機に戻る）
+    pub const STOP_TIMEOUT: f32 = 3.0;
+
+    /// 駆動要求チャネルの容量（制御割り込みが毎周期すべて受信する）
+    pub const REQUEST_QUEUE_LEN: usize = 8;
+
+    /// 起動時の自動キャリブレーションのトルク値 (0-100)
+    pub const AUTO_CALIBRATION_TORQUE: u8 = 10;
+}
+
+/// モーターパラメータ同定（抵抗・インダクタンス・鎖交磁束・慣性モーメント・摩擦の自動測定）
+pub mod identification {
+    /// 試験電流 [A]（直流注入・I/f駆動・加速試験の電流）（デフォルト値）
+    pub const DEFAULT_TEST_CURRENT: f32 = 2.0;
+
+    /// 試験速度 [RPM]（I/f駆動の回転数、逆起電力が十分に大きくなる速度）（デフォルト値）
+    pub const DEFAULT_TEST_SPEED: f32 = 1000.0;
+
+    /// 試験電流・試験速度が有効かチェック（正の有限値のみ）
+    pub fn is_valid_positive(value: f32) -> bool {
+        value.is_finite() && value > 0.0
+    }
+}
+
+/// 速度PIゲイン自動調整（リレーフィードバック法）
+pub mod autotune {
+    /// 試験速度 [RPM]（この速度を中心に速度を振動させる）（デフォルト値）
+    pub const DEFAULT_TEST_SPEED: f32 = 1000.0;
+
+    /// リレー振幅（速度PIの出力制限に対する比）（デフォルト値）
+    pub const DEFAULT_RELAY_RATIO: f32 = 0.2;
+
+    /// リレー振幅の上限（速度PIの出力制限に対する比）
+    pub const MAX_RELAY_RATIO: f32 = 0.5;
+
+    /// 試験中の速度上限（試験速度に対する比、超えたら中断）
+    pub const MAX_SPEED_RATIO: f32 = 1.5;
+
+    /// 試験速度が有効かチェック（0以外の有限値、符号は回転方向）
+    pub fn is_valid_test_speed(value: f32) -> bool {
+        value.is_finite() && value != 0.0
+    }
+
+    /// リレー振幅の比が有効かチェック（0より大きく上限以下）
+    pub fn is_valid_relay_ratio(value: f32) -> bool {
+        value.is_finite() && value > 0.0 && value <= MAX_RELAY_RATIO
+    }
+}
+
+/// 弱め界磁パラメータ（電圧ベクトル飽和時に負のd軸指令を注入）
+pub mod field_weakening {
+    /// 積分ゲイン [1/s]（しきい値を超えた電圧（最大電圧比）あたりの弱め界磁レベルの変化率）（デフォルト値）
+    pub const DEFAULT_GAIN: f32 = 50.0;
+
+    /// 弱め界磁を開始する電圧ベクトルの大きさ（最大電圧に対する比）（デフォルト値）
+    pub const DEFAULT_VOLTAGE_RATIO: f32 = 0.95;
+
+    /// 電流制御時の最大d軸電流 [A]（負方向の大きさ、0 = 無効）（デフォルト値）
+    pub const DEFAULT_MAX_CURRENT: f32 = 0.0;
+
+    /// 電圧制御時の最大d軸電圧 [V]（負方向の大きさ、0 = 無効）（デフォルト値）
+    pub const DEFAULT_MAX_VOLTAGE: f32 = 0.0;
+
+    /// 積分ゲイン・最大d軸電流/電圧が有効かチェック（負値・NaNは不可、最大値0は無効化）
+    pub fn is_valid_non_negative(value: f32) -> bool {
+        value.is_finite() && value >= 0.0
+    }
+
+    /// 電圧比が有効かチェック（0より大きく1以下）
+    pub fn is_valid_voltage_ratio(value: f32) -> bool {
+        value.is_finite() && value > 0.0 && value <= 1.0
+    }
+}
+
+/// 速度フィードフォワード（逆起電力・摩擦・加速トルクを速度指令から計算して速度PIの出力に加算）
+pub mod feedforward {
+    /// Kv [RPM/V]（q軸電圧1Vあたりの無負荷回転数、0 = 鎖交磁束から換算）（デフォルト値）
+    pub const DEFAULT_MOTOR_KV: f32 = 0.0;
+
+    /// 逆起電力項のゲイン（0 = 無効、1 = モデルどおり）（デフォルト値）
+    ///
+    /// 鎖交磁束のデフォルト値は仮の値のため、同定またはKvの設定後に有効化する。
+    pub const DEFAULT_BACK_EMF_GAIN: f32 = 0.0;
+
+    /// 摩擦項のゲイン（同定したクーロン摩擦・粘性摩擦に掛ける、未同定の間は0と同じ）（デフォルト値）
+    pub const DEFAULT_FRICTION_GAIN: f32 = 1.0;
+
+    /// 摩擦補償の符号を線形に切り替える速度指令の幅 [RPM]（0 = 符号を即座に切り替え）（デフォルト値）
+    pub const DEFAULT_FRICTION_BAND: f32 = 10.0;
+
+    /// 加速項のゲイン（同定した慣性モーメントに掛ける、未同定の間は0と同じ）（デフォルト値）
+    pub const DEFAULT_ACCELERATION_GAIN: f32 = 1.0;
+
+    /// 各項のゲインの上限（モデル誤差の過補償による発振を防ぐ）
+    pub const MAX_GAIN: f32 = 2.0;
+
+    /// ゲインが有効かチェック（0以上上限以下）
+    pub fn is_valid_gain(value: f32) -> bool {
+        value.is_finite() && (0.0..=MAX_GAIN).contains(&value)
+    }
+
+    /// Kv・摩擦補償の幅が有効かチェック（負値・NaNは不可、Kvの0は鎖交磁束から換算）
+    pub fn is_valid_non_negative(value: f32) -> bool {
+        value.is_finite() && value >= 0.0
+    }
+}
+
+/// PIコントローラーの拡張（逆算アンチワインドアップ・D項・セットポイント重み・出力レート制限・ゲインスケジュール）
+pub mod pid {
+    /// 速度PIの逆算アンチワインドアップの追従ゲイン [1/s]（0 = 無効）（デフォルト値）
+    pub const DEFAULT_SPEED_TRACKING_GAIN: f32 = 0.0;
+
+    /// d/q軸電流PIの逆算アンチワインドアップの追従ゲイン [1/s]（0 = 飽和中は積分を停止）（デフォルト値）
+    pub const DEFAULT_CURRENT_TRACKING_GAIN: f32 = 0.0;
+
+    /// 速度PIの微分ゲイン（0 = D項なし）（デフォルト値）
+    pub const DEFAULT_SPEED_KD: f32 = 0.0;
+
+    /// 位置制御の微分ゲイン [RPM/(rad/s)]（0 = D項なし）（デフォルト値）
+    pub const DEFAULT_POSITION_KD: f32 = 0.0;
+
+    /// D項のローパスフィルタの時定数 [s]（デフォルト値）
+    pub const DEFAULT_DERIVATIVE_FILTER_TIME: f32 = 0.002;
+
+    /// 速度PIのP項のセットポイント重み b（1 = 偏差に比例）（デフォルト値）
+    pub const DEFAULT_PROPORTIONAL_WEIGHT: f32 = 1.0;
+
+    /// 速度PIのD項のセットポイント重み c（0 = 測定値のみを微分）（デフォルト値）
+    pub const DEFAULT_DERIVATIVE_WEIGHT: f32 = 0.0;
+
+    /// 速度PI出力の変化率の上限 [A/s または V/s]（0 = 制限なし）（デフォルト値）
+    pub const DEFAULT_SPEED_RATE_LIMIT: f32 = 0.0;
+
+    /// 速度PIのゲインスケジュールの使用点数（0 = 無効、速度PIゲインを使用）（デフォルト値）
+    pub const DEFAULT_SCHEDULE_POINTS: u8 = 0;
+
+    /// ゲインスケジュールの点数の上限（`foc::pi_controller::MAX_SCHEDULE_POINTS`と同じ）
+    pub const MAX_SCHEDULE_POINTS: usize = 8;
+
+    /// 追従ゲイン・微分ゲイン・時定数・レート制限・ゲインが有効かチェック（負値・NaNは不可）
+    pub fn is_valid_non_negative(value: f32) -> bool {
+        value.is_finite() && value >= 0.0
+    }
+
+    /// セットポイント重みが有効かチェック（0以上1以下）
+    pub fn is_valid_weight(value: f32) -> bool {
+        value.is_finite() && (0.0..=1.0).contains(&value)
+    }
+
+    /// ゲインスケジュールの点番号が有効かチェック（上限未満）
+    pub fn is_valid_schedule_index(index: u8) -> bool {
+        (index as usize) < MAX_SCHEDULE_POINTS
+    }
+
+    /// ゲインスケジュールの使用点数が有効かチェック（上限以下、0は無効化）
+    pub fn is_valid_schedule_points(points: u8) -> bool {
+        (points as usize) <= MAX_SCHEDULE_POINTS
+    }
+}
+
+/// 速度ループのフィルタ（速度フィードバック・速度PI出力に挿入、機械共振の除去等）
+pub mod filter {
+    /// フィルタ種別（0 = なし、1 = ローパス、2 = ノッチ、3 = バンドストップ、4 = 移動平均、5 = メディアン）（デフォルト値）
+    pub const DEFAULT_TYPE: u8 = 0;
+
+    /// フィルタ長（移動平均・メディアンのサンプル数）（デフォルト値）
+    pub const DEFAULT_LENGTH: u8 = 4;
+
+    /// カットオフ/中心周波数 [Hz]（デフォルト値）
+    pub const DEFAULT_FREQUENCY: f32 = 100.0;
+
+    /// 形状（ローパス・ノッチはQ、バンドストップは帯域幅 [Hz]）（デフォルト値）
+    pub const DEFAULT_SHAPE: f32 = 0.707;
+
+    /// フィルタ長の上限（`foc::filter::MAX_WINDOW_LENGTH`と同じ）
+    pub const MAX_LENGTH: u8 = 16;
+
+    /// フィルタ長が有効かチェック（1以上上限以下）
+    pub fn is_valid_length(length: u8) -> bool {
+        (1..=MAX_LENGTH).contains(&length)
+    }
+
+    /// 周波数・形状が有効かチェック（正の有限値、ナイキスト周波数は適用時にチェック）
+    pub fn is_valid_positive(value: f32) -> bool {
+        value.is_finite() && value > 0.0
+    }
 }
 
 /// PWM設定
@@ -56,10 +442,112 @@ pub mod pwm {
 
     /// デッドタイム（デフォルト値）
     pub const DEFAULT_DEAD_TIME: u16 = 1;
+
+    /// PWM周波数の下限 [Hz]（可聴域・電流リップル増大を避ける）
+    pub const MIN_FREQUENCY_HZ: u32 = 5_000;
+
+    /// PWM周波数の上限 [Hz]（スイッチング損失・ADCサンプリング時間の制約）
+    pub const MAX_FREQUENCY_HZ: u32 = 100_000;
+
+    /// デッドタイムの下限（0はアーム短絡の危険があるため不可）
+    pub const MIN_DEAD_TIME: u16 = 1;
+
+    /// デッドタイムの上限（TIM1 BDTRで表現可能な範囲内）
+    pub const MAX_DEAD_TIME: u16 = 1000;
+
+    /// PWM周波数が有効範囲内かチェック
+    pub const fn is_valid_frequency(frequency_hz: u32) -> bool {
+        frequency_hz >= MIN_FREQUENCY_HZ && frequency_hz <= MAX_FREQUENCY_HZ
+    }
+
+    /// デッドタイムが有効範囲内かチェック
+    pub const fn is_valid_dead_time(dead_time: u16) -> bool {
+        dead_time >= MIN_DEAD_TIME && dead_time <= MAX_DEAD_TIME
+    }
+
+    /// タイマークロック [Hz]（デッドタイム・最小パルス幅のカウント単位）
+    pub const TIMER_CLOCK_HZ: u32 = 170_000_000;
+
+    /// デッドタイム補償方式（0 = 無効、1 = 電流符号、2 = 電圧符号）（デフォルト値）
+    pub const DEFAULT_DEAD_TIME_COMPENSATION: u8 = 0;
+
+    /// 最小パルス幅（タイマークロック数、0 = 制限なし）（デフォルト値）
+    pub const DEFAULT_MIN_PULSE: u16 = 0;
+
+    /// 最小パルス幅の上限（タイマークロック数）
+    pub const MAX_MIN_PULSE: u16 = 1000;
+
+    /// 電流符号の線形領域 [A]（ゼロクロス付近での補償のチャタリングを防ぐ）
+    pub const DEAD_TIME_CURRENT_BAND: f32 = 0.2;
+
+    /// 最小パルス幅が有効範囲内かチェック
+    pub const fn is_valid_min_pulse(min_pulse: u16) -> bool {
+        min_pulse <= MAX_MIN_PULSE
+    }
+
+    /// 過変調（線形変調Vdc/√3を超えて6ステップまで）を許可するか（デフォルト値）
+    pub const DEFAULT_OVERMODULATION: bool = false;
 }
 
 /// CAN設定
 pub mod can {
     /// CANビットレート（250kbps）（デフォルト値）
     pub const DEFAULT_BITRATE: u32 = 250_000;
+
+    /// サポートするCANビットレート [bps]
+    pub const SUPPORTED_BITRATES: [u32; 4] = [125_000, 250_000, 500_000, 1_000_000];
+
+    /// ビットレートがサポート対象かチェック
+    pub fn is_valid_bitrate(bitrate: u32) -> bool {
+        SUPPORTED_BITRATES.contains(&bitrate)
+    }
+}
+
+/// 制御周期設定
+pub mod timing {
+    /// 制御周期の下限 [μs]（50kHz、PWM周期より短い場合はPWM周期に丸める）
+    pub const MIN_CONTROL_PERIOD_US: u64 = 20;
+
+    /// 制御周期の上限 [μs]（100Hz）
+    pub const MAX_CONTROL_PERIOD_US: u64 = 10_000;
+
+    /// 制御周期が有効範囲内かチェック
+    pub const fn is_valid_control_period(period_us: u64) -> bool {
+        period_us >= MIN_CONTROL_PERIOD_US && period_us <= MAX_CONTROL_PERIOD_US
+    }
+
+    /// 速度ループの分周比（電流ループの周期数、25kHz / 10 = 2.5kHz）（デフォルト値）
+    pub const DEFAULT_SPEED_DIVIDER: u16 = 10;
+
+    /// 位置ループの分周比（速度ループの周期数、2.5kHz / 5 = 500Hz）（デフォルト値）
+    pub const DEFAULT_POSITION_DIVIDER: u16 = 5;
+
+    /// 速度・位置ループの分周比の上限
+    pub const MAX_LOOP_DIVIDER: u16 = 100;
+
+    /// 速度・位置ループの分周比が有効範囲内かチェック（1以上、上限以下）
+    pub const fn is_valid_loop_divider(divider: u16) -> bool {
+        divider >= 1 && divider <= MAX_LOOP_DIVIDER
+    }
+
+    /// モーター制御タスクが制御割り込みと指令・ステータスを受け渡す周期 [μs]（1kHz）
+    pub const EXCHANGE_PERIOD_US: u64 = 1_000;
+
+    /// CPUクロック [Hz]（制御割り込みの処理時間を計測するDWTサイクルカウンタの単位）
+    pub const CPU_CLOCK_HZ: u32 = 170_000_000;
+
+    /// モーター制御タスクが制御ループの状態をログ出力する間隔（受け渡し周期の回数、1秒）
+    pub const STATUS_LOG_INTERVAL: u32 = 1_000;
+
+    /// 制御周期の分周比（PWM周期数）を計算
+    ///
+    /// 制御割り込みはPWM周期ごとに発生するため、制御周期をPWM周期の整数倍（最小1）に丸める。
+    pub const fn control_divider(pwm_frequency: u32, control_period_us: u64) -> u32 {
+        let divider = (control_period_us * pwm_frequency as u64 + 500_000) / 1_000_000;
+        if divider == 0 {
+            1
+        } else {
+            divider as u32
+        }
+    }
 }
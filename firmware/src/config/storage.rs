@@ -8,7 +8,31 @@ use super::params;
 pub const CONFIG_MAGIC: u32 = 0x31474643;
 
 /// 現在の設定バージョン
-pub const CONFIG_VERSION: u16 = 1;
+pub const CONFIG_VERSION: u16 = 2;
+
+/// 移行元の旧設定バージョン
+///
+/// v1の構造体は現在の構造体の`control_period_us`までと同じレイアウト。
+const CONFIG_VERSION_V1: u16 = 1;
+
+/// v1の構造体のうち引き継ぐ範囲のバイト数（`crc32`の直前まで）
+const CONFIG_V1_DATA_SIZE: usize =
+    core::mem::offset_of!(StoredConfig, control_period_us) + core::mem::size_of::<u64>();
+
+/// `sanitize_hardware_params`の戻り値：PWM周波数が範囲外
+pub const INVALID_PWM_FREQUENCY: u8 = 0x01;
+
+/// `sanitize_hardware_params`の戻り値：デッドタイムが範囲外
+pub const INVALID_PWM_DEAD_TIME: u8 = 0x02;
+
+/// `sanitize_hardware_params`の戻り値：CANビットレートが非対応
+pub const INVALID_CAN_BITRATE: u8 = 0x04;
+
+/// `sanitize_hardware_params`の戻り値：制御周期が範囲外
+pub const INVALID_CONTROL_PERIOD: u8 = 0x08;
+
+/// `sanitize_hardware_params`の戻り値：速度・位置ループの分周比が範囲外
+pub const INVALID_LOOP_DIVIDER: u8 = 0x10;
 
 /// 永続化される設定構造体
 ///
@@ -36,7 +60,7 @@ pub struct StoredConfig {
     /// 最大電圧 [V]
     pub max_voltage: f32,
 
-    /// DCバス電圧 [V]
+    /// DCバス電圧 [V]（公称値、電圧監視の実測値が得られない間のみ使用）
     pub v_dc_bus: f32,
 
     /// モーターの極対数
@@ -101,9 +125,240 @@ pub struct StoredConfig {
     pub can_bitrate: u32,
 
     // === 制御タイミング ===
-    /// 制御周期 [μs]
+    /// 制御周期 [μs]（電流・角度ループ、PWM周期の整数倍に丸める）
     pub control_period_us: u64,
 
+    // === 電流制御 ===
+    /// 電流PI制御の比例ゲイン [V/A]
+    pub current_kp: f32,
+
+    /// 電流PI制御の積分ゲイン [V/(A·s)]
+    pub current_ki: f32,
+
+    /// 最大電流 [A]（q軸電流指令の制限）
+    pub max_current: f32,
+
+    /// シャント抵抗 [Ω]
+    pub current_shunt_resistance: f32,
+
+    /// 電流検出アンプのゲイン
+    pub current_amp_gain: f32,
+
+    /// 電流制御有効フラグ（falseの場合は速度PIが直接q軸電圧を出力）
+    pub current_control_enabled: bool,
+
+    /// パディング
+    _padding5: [u8; 3],
+
+    // === 位置制御 ===
+    /// 位置P制御の比例ゲイン [RPM/rad]
+    pub position_kp: f32,
+
+    /// 位置制御時の最大速度指令 [RPM]
+    pub position_max_speed: f32,
+
+    // === 加減速プロファイル ===
+    /// 加速度 [RPM/s]
+    pub profile_acceleration: f32,
+
+    /// 減速度 [RPM/s]
+    pub profile_deceleration: f32,
+
+    /// 加加速度（ジャーク） [RPM/s²]（0 = 台形プロファイル）
+    pub profile_jerk: f32,
+
+    // === センサレス角度推定 ===
+    /// 角度の取得元（0 = Hall、1 = センサレス、2 = Hall＋センサレスフォールバック）
+    pub angle_source: u8,
+
+    /// パディング
+    _padding6: [u8; 3],
+
+    /// 相抵抗 [Ω]
+    pub motor_resistance: f32,
+
+    /// 相インダクタンス [H]
+    pub motor_inductance: f32,
+
+    /// 永久磁石の鎖交磁束 [Wb]
+    pub motor_flux_linkage: f32,
+
+    /// オブザーバPLL帯域 [rad/s]
+    pub observer_pll_bandwidth: f32,
+
+    /// オブザーバ角度を使用する最低速度 [RPM]
+    pub sensorless_min_speed: f32,
+
+    // === Hall角度・速度推定 ===
+    /// 推定方式（0 = 速度フィルタ＋外挿、1 = エッジタイムスタンプPLL）
+    pub hall_estimator: u8,
+
+    /// パディング
+    _padding7: [u8; 3],
+
+    /// Hall PLL帯域 [rad/s]
+    pub hall_pll_bandwidth: f32,
+
+    // === キャリブレーション結果（Hallセクター境界） ===
+    /// 各Hallセクターの開始電気角 [rad]（インデックス = Hall状態 - 1）
+    pub calibration_sector_angles: [f32; 6],
+
+    /// セクター境界テーブル有効フラグ
+    pub calibration_sector_table_valid: bool,
+
+    /// パディング
+    _padding8: [u8; 3],
+
+    // === オープンループ→FOC切替 ===
+    /// ハンドオーバー時間 [s]（0 = 即時切替）
+    pub openloop_handover_time: f32,
+
+    // === オープンループ正弦波V/f駆動 ===
+    /// 駆動方式（0 = 6ステップ、1 = 正弦波V/f）
+    pub openloop_mode: u8,
+
+    /// パディング
+    _padding9: [u8; 3],
+
+    /// 0Hzでのブースト電圧 [V]
+    pub openloop_vf_boost_voltage: f32,
+
+    /// V/fゲイン [V/Hz]（電気周波数あたりの電圧）
+    pub openloop_vf_gain: f32,
+
+    // === 弱め界磁 ===
+    /// 積分ゲイン [1/s]
+    pub field_weakening_gain: f32,
+
+    /// 弱め界磁を開始する電圧ベクトルの大きさ（最大電圧に対する比）
+    pub field_weakening_voltage_ratio: f32,
+
+    /// 電流制御時の最大d軸電流 [A]（0 = 無効）
+    pub field_weakening_max_current: f32,
+
+    /// 電圧制御時の最大d軸電圧 [V]（0 = 無効）
+    pub field_weakening_max_voltage: f32,
+
+    // === モーター機械定数（パラメータ同定結果） ===
+    /// 慣性モーメント [kg·m²]（0 = 未同定）
+    pub motor_inertia: f32,
+
+    /// クーロン摩擦トルク [N·m]
+    pub motor_friction_torque: f32,
+
+    /// 粘性摩擦係数 [N·m·s/rad]
+    pub motor_viscous_friction: f32,
+
+    // === デッドタイム補償 ===
+    /// 補償方式（0 = 無効、1 = 電流符号、2 = 電圧符号）
+    pub pwm_dead_time_compensation: u8,
+
+    /// パディング
+    _padding10: u8,
+
+    /// 最小パルス幅（タイマークロック数、0 = 制限なし）
+    pub pwm_min_pulse: u16,
+
+    // === 変調 ===
+    /// 過変調を許可するか（電圧制限: false = Vdc/√3、true = 6ステップの基本波 2Vdc/π）
+    pub pwm_overmodulation: bool,
+
+    /// パディング
+    _padding11: [u8; 3],
+
+    // === 速度フィードフォワード ===
+    /// Kv [RPM/V]（q軸電圧1Vあたりの無負荷回転数、0 = 鎖交磁束から換算）
+    pub motor_kv: f32,
+
+    /// 逆起電力項のゲイン（0 = 無効）
+    pub ff_back_emf_gain: f32,
+
+    /// 摩擦項のゲイン（同定したクーロン摩擦・粘性摩擦に掛ける、0 = 無効）
+    pub ff_friction_gain: f32,
+
+    /// 摩擦補償の符号を線形に切り替える速度指令の幅 [RPM]
+    pub ff_friction_band: f32,
+
+    /// 加速項のゲイン（同定した慣性モーメントに掛ける、0 = 無効）
+    pub ff_acceleration_gain: f32,
+
+    // === PIコントローラーの拡張 ===
+    /// 速度PIの逆算アンチワインドアップの追従ゲイン [1/s]（0 = 無効）
+    pub speed_tracking_gain: f32,
+
+    /// d/q軸電流PIの逆算アンチワインドアップの追従ゲイン [1/s]（0 = 飽和中は積分を停止）
+    pub current_tracking_gain: f32,
+
+    /// 速度PIの微分ゲイン（0 = D項なし）
+    pub speed_kd: f32,
+
+    /// 速度PIのD項のローパスフィルタの時定数 [s]
+    pub speed_derivative_filter_time: f32,
+
+    /// 速度PIのP項のセットポイント重み b
+    pub speed_proportional_weight: f32,
+
+    /// 速度PIのD項のセットポイント重み c
+    pub speed_derivative_weight: f32,
+
+    /// 速度PI出力の変化率の上限（q軸電流指令 [A/s] またはq軸電圧指令 [V/s]、0 = 制限なし）
+    pub speed_rate_limit: f32,
+
+    /// 位置制御の微分ゲイン [RPM/(rad/s)]（0 = D項なし）
+    pub position_kd: f32,
+
+    /// 位置制御のD項のローパスフィルタの時定数 [s]
+    pub position_derivative_filter_time: f32,
+
+    // === 速度PIのゲインスケジュール ===
+    /// 使用点数（0 = 無効、速度PIゲインを使用）
+    pub speed_schedule_points: u8,
+
+    /// パディング
+    _padding12: [u8; 3],
+
+    /// 各点の速度 [RPM]（速度の絶対値、昇順）
+    pub speed_schedule_speeds: [f32; params::pid::MAX_SCHEDULE_POINTS],
+
+    /// 各点の速度PI比例ゲイン
+    pub speed_schedule_kp: [f32; params::pid::MAX_SCHEDULE_POINTS],
+
+    /// 各点の速度PI積分ゲイン
+    pub speed_schedule_ki: [f32; params::pid::MAX_SCHEDULE_POINTS],
+
+    // === 速度ループのフィルタ ===
+    /// 速度フィードバックのフィルタ種別（0 = なし、1 = ローパス、2 = ノッチ、3 = バンドストップ、
+    /// 4 = 移動平均、5 = メディアン）
+    pub speed_feedback_filter: u8,
+
+    /// 速度フィードバックのフィルタ長（移動平均・メディアンのサンプル数）
+    pub speed_feedback_filter_length: u8,
+
+    /// 速度PI出力のフィルタ種別（速度フィードバックと同じ）
+    pub speed_output_filter: u8,
+
+    /// 速度PI出力のフィルタ長（移動平均・メディアンのサンプル数）
+    pub speed_output_filter_length: u8,
+
+    /// 速度フィードバックのフィルタのカットオフ/中心周波数 [Hz]
+    pub speed_feedback_filter_frequency: f32,
+
+    /// 速度フィードバックのフィルタの形状（ローパス・ノッチはQ、バンドストップは帯域幅 [Hz]）
+    pub speed_feedback_filter_shape: f32,
+
+    /// 速度PI出力のフィルタのカットオフ/中心周波数 [Hz]
+    pub speed_output_filter_frequency: f32,
+
+    /// 速度PI出力のフィルタの形状（ローパス・ノッチはQ、バンドストップは帯域幅 [Hz]）
+    pub speed_output_filter_shape: f32,
+
+    // === 制御ループの周期 ===
+    /// 速度ループの分周比（電流ループの周期数）
+    pub speed_loop_divider: u16,
+
+    /// 位置ループの分周比（速度ループの周期数）
+    pub position_loop_divider: u16,
+
     /// CRC32チェックサム（最後に配置）
     pub crc32: u32,
 }
@@ -139,18 +394,91 @@ impl StoredConfig {
             _padding4: 0,
             can_bitrate: params::can::DEFAULT_BITRATE,
             control_period_us: params::DEFAULT_CONTROL_PERIOD_US,
+            current_kp: params::current::DEFAULT_KP,
+            current_ki: params::current::DEFAULT_KI,
+            max_current: params::current::DEFAULT_MAX_CURRENT,
+            current_shunt_resistance: params::current::DEFAULT_SHUNT_RESISTANCE,
+            current_amp_gain: params::current::DEFAULT_AMP_GAIN,
+            current_control_enabled: params::current::DEFAULT_ENABLED,
+            _padding5: [0; 3],
+            position_kp: params::position::DEFAULT_KP,
+            position_max_speed: params::position::DEFAULT_MAX_SPEED,
+            profile_acceleration: params::profile::DEFAULT_ACCELERATION,
+            profile_deceleration: params::profile::DEFAULT_DECELERATION,
+            profile_jerk: params::profile::DEFAULT_JERK,
+            angle_source: params::sensorless::DEFAULT_ANGLE_SOURCE,
+            _padding6: [0; 3],
+            motor_resistance: params::sensorless::DEFAULT_RESISTANCE,
+            motor_inductance: params::sensorless::DEFAULT_INDUCTANCE,
+            motor_flux_linkage: params::sensorless::DEFAULT_FLUX_LINKAGE,
+            observer_pll_bandwidth: params::sensorless::DEFAULT_PLL_BANDWIDTH,
+            sensorless_min_speed: params::sensorless::DEFAULT_MIN_SPEED,
+            hall_estimator: params::hall::DEFAULT_ESTIMATOR,
+            _padding7: [0; 3],
+            hall_pll_bandwidth: params::hall::DEFAULT_PLL_BANDWIDTH,
+            calibration_sector_angles: [0.0; 6], // キャリブレーション未実施
+            calibration_sector_table_valid: false,
+            _padding8: [0; 3],
+            openloop_handover_time: params::openloop::DEFAULT_HANDOVER_TIME,
+            openloop_mode: params::openloop::DEFAULT_MODE,
+            _padding9: [0; 3],
+            openloop_vf_boost_voltage: params::openloop::DEFAULT_VF_BOOST_VOLTAGE,
+            openloop_vf_gain: params::openloop::DEFAULT_VF_GAIN,
+            field_weakening_gain: params::field_weakening::DEFAULT_GAIN,
+            field_weakening_voltage_ratio: params::field_weakening::DEFAULT_VOLTAGE_RATIO,
+            field_weakening_max_current: params::field_weakening::DEFAULT_MAX_CURRENT,
+            field_weakening_max_voltage: params::field_weakening::DEFAULT_MAX_VOLTAGE,
+            motor_inertia: 0.0, // パラメータ同定未実施
+            motor_friction_torque: 0.0,
+            motor_viscous_friction: 0.0,
+            pwm_dead_time_compensation: params::pwm::DEFAULT_DEAD_TIME_COMPENSATION,
+            _padding10: 0,
+            pwm_min_pulse: params::pwm::DEFAULT_MIN_PULSE,
+            pwm_overmodulation: params::pwm::DEFAULT_OVERMODULATION,
+            _padding11: [0; 3],
+            motor_kv: params::feedforward::DEFAULT_MOTOR_KV,
+            ff_back_emf_gain: params::feedforward::DEFAULT_BACK_EMF_GAIN,
+            ff_friction_gain: params::feedforward::DEFAULT_FRICTION_GAIN,
+            ff_friction_band: params::feedforward::DEFAULT_FRICTION_BAND,
+            ff_acceleration_gain: params::feedforward::DEFAULT_ACCELERATION_GAIN,
+            speed_tracking_gain: params::pid::DEFAULT_SPEED_TRACKING_GAIN,
+            current_tracking_gain: params::pid::DEFAULT_CURRENT_TRACKING_GAIN,
+            speed_kd: params::pid::DEFAULT_SPEED_KD,
+            speed_derivative_filter_time: params::pid::DEFAULT_DERIVATIVE_FILTER_TIME,
+            speed_proportional_weight: params::pid::DEFAULT_PROPORTIONAL_WEIGHT,
+            speed_derivative_weight: params::pid::DEFAULT_DERIVATIVE_WEIGHT,
+            speed_rate_limit: params::pid::DEFAULT_SPEED_RATE_LIMIT,
+            position_kd: params::pid::DEFAULT_POSITION_KD,
+            position_derivative_filter_time: params::pid::DEFAULT_DERIVATIVE_FILTER_TIME,
+            speed_schedule_points: params::pid::DEFAULT_SCHEDULE_POINTS,
+            _padding12: [0; 3],
+            speed_schedule_speeds: [0.0; params::pid::MAX_SCHEDULE_POINTS],
+            speed_schedule_kp: [0.0; params::pid::MAX_SCHEDULE_POINTS],
+            speed_schedule_ki: [0.0; params::pid::MAX_SCHEDULE_POINTS],
+            speed_feedback_filter: params::filter::DEFAULT_TYPE,
+            speed_feedback_filter_length: params::filter::DEFAULT_LENGTH,
+            speed_output_filter: params::filter::DEFAULT_TYPE,
+            speed_output_filter_length: params::filter::DEFAULT_LENGTH,
+            speed_feedback_filter_frequency: params::filter::DEFAULT_FREQUENCY,
+            speed_feedback_filter_shape: params::filter::DEFAULT_SHAPE,
+            speed_output_filter_frequency: params::filter::DEFAULT_FREQUENCY,
+            speed_output_filter_shape: params::filter::DEFAULT_SHAPE,
+            speed_loop_divider: params::timing::DEFAULT_SPEED_DIVIDER,
+            position_loop_divider: params::timing::DEFAULT_POSITION_DIVIDER,
             crc32: 0, // CRC計算前は0
         }
     }
 
     /// バイト配列として参照を取得（CRC計算用）
     ///
-    /// CRC32フィールドを除くすべてのバイトを返す
+    /// CRC32フィールドより前のすべてのバイトを返す
+    ///
+    /// 注: u64フィールドのアライメントにより構造体末尾にパディングが入るため、
+    /// サイズからではなくcrc32のオフセットで範囲を決める
     pub fn as_bytes_for_crc(&self) -> &[u8] {
         let ptr = self as *const Self as *const u8;
-        let total_size = core::mem::size_of::<Self>();
-        let crc_size = core::mem::size_of::<u32>();
-        unsafe { core::slice::from_raw_parts(ptr, total_size - crc_size) }
+        let crc_offset = core::mem::offset_of!(Self, crc32);
+        unsafe { core::slice::from_raw_parts(ptr, crc_offset) }
     }
 
     /// バイト配列として可変参照を取得（シリアライズ用）
@@ -173,6 +501,188 @@ impl StoredConfig {
         Some(*ptr)
     }
 
+    /// v1の設定を現在のバージョンに移行
+    ///
+    /// v1から引き継げる項目（PIゲイン、極対数、キャリブレーション結果、オープンループ・PWM・CAN設定等）は
+    /// そのまま使い、以降のバージョンで追加された項目はデフォルト値にする。
+    ///
+    /// 注: v1のCRCはCRCフィールド自身を含む範囲で計算されていたため検証できない。
+    /// 値の範囲は読み込み後の検証（`sanitize_*`と設定の適用時の検証）で確認する。
+    ///
+    /// # 戻り値
+    /// v1の設定であれば移行した設定、それ以外は`None`
+    pub fn migrate_from_v1(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < CONFIG_V1_DATA_SIZE {
+            return None;
+        }
+        let magic = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if magic != CONFIG_MAGIC || version != CONFIG_VERSION_V1 {
+            return None;
+        }
+
+        let mut data = [0u8; CONFIG_V1_DATA_SIZE];
+        data.copy_from_slice(&bytes[..CONFIG_V1_DATA_SIZE]);
+        // boolフィールドは0/1以外の値を取れないため正規化してからコピー
+        for offset in [
+            core::mem::offset_of!(Self, enable_angle_interpolation),
+            core::mem::offset_of!(Self, calibration_direction_inversed),
+            core::mem::offset_of!(Self, calibration_success),
+        ] {
+            data[offset] = (data[offset] != 0) as u8;
+        }
+
+        let mut config = Self::default();
+        config.as_bytes_mut()[..CONFIG_V1_DATA_SIZE].copy_from_slice(&data);
+        config.version = CONFIG_VERSION;
+        config.crc32 = 0;
+        Some(config)
+    }
+
+    /// PWM・CAN・制御周期・制御ループの分周比の設定値を検証し、範囲外の項目をデフォルト値に戻す
+    ///
+    /// 不正なビットレート等でCANにアクセスできなくなるのを防ぐため、
+    /// ペリフェラルへ適用する前に呼び出す。
+    ///
+    /// # 戻り値
+    /// デフォルト値に戻した項目のビットマスク（`INVALID_*`）、すべて有効なら0
+    pub fn sanitize_hardware_params(&mut self) -> u8 {
+        let mut invalid = 0;
+
+        if !params::pwm::is_valid_frequency(self.pwm_frequency) {
+            self.pwm_frequency = params::pwm::DEFAULT_FREQUENCY.0;
+            invalid |= INVALID_PWM_FREQUENCY;
+        }
+        if !params::pwm::is_valid_dead_time(self.pwm_dead_time) {
+            self.pwm_dead_time = params::pwm::DEFAULT_DEAD_TIME;
+            invalid |= INVALID_PWM_DEAD_TIME;
+        }
+        if !params::can::is_valid_bitrate(self.can_bitrate) {
+            self.can_bitrate = params::can::DEFAULT_BITRATE;
+            invalid |= INVALID_CAN_BITRATE;
+        }
+        if !params::timing::is_valid_control_period(self.control_period_us) {
+            self.control_period_us = params::DEFAULT_CONTROL_PERIOD_US;
+            invalid |= INVALID_CONTROL_PERIOD;
+        }
+        if !params::timing::is_valid_loop_divider(self.speed_loop_divider)
+            || !params::timing::is_valid_loop_divider(self.position_loop_divider)
+        {
+            self.speed_loop_divider = params::timing::DEFAULT_SPEED_DIVIDER;
+            self.position_loop_divider = params::timing::DEFAULT_POSITION_DIVIDER;
+            invalid |= INVALID_LOOP_DIVIDER;
+        }
+
+        invalid
+    }
+
+    /// 浮動小数点の設定値を検証し、NaN・無限大の項目をデフォルト値に戻す
+    ///
+    /// フラッシュの破損やCANで受信した不正な値が制御に入るのを防ぐ。
+    /// 範囲の検証は各パラメータの適用時に行う。
+    ///
+    /// # 戻り値
+    /// デフォルト値に戻した値の数、すべて有限なら0
+    pub fn sanitize_float_params(&mut self) -> u32 {
+        let defaults = Self::default();
+        let mut reset = 0;
+
+        macro_rules! reset_non_finite {
+            ($($field:ident),* $(,)?) => {
+                $(
+                    if !self.$field.is_finite() {
+                        self.$field = defaults.$field;
+                        reset += 1;
+                    }
+                )*
+            };
+        }
+        reset_non_finite!(
+            speed_kp,
+            speed_ki,
+            max_voltage,
+            v_dc_bus,
+            speed_filter_alpha,
+            hall_angle_offset,
+            calibration_electrical_offset,
+            openloop_initial_rpm,
+            openloop_target_rpm,
+            openloop_acceleration,
+            current_kp,
+            current_ki,
+            max_current,
+            current_shunt_resistance,
+            current_amp_gain,
+            position_kp,
+            position_max_speed,
+            profile_acceleration,
+            profile_deceleration,
+            profile_jerk,
+            motor_resistance,
+            motor_inductance,
+            motor_flux_linkage,
+            observer_pll_bandwidth,
+            sensorless_min_speed,
+            hall_pll_bandwidth,
+            openloop_handover_time,
+            openloop_vf_boost_voltage,
+            openloop_vf_gain,
+            field_weakening_gain,
+            field_weakening_voltage_ratio,
+            field_weakening_max_current,
+            field_weakening_max_voltage,
+            motor_inertia,
+            motor_friction_torque,
+            motor_viscous_friction,
+            motor_kv,
+            ff_back_emf_gain,
+            ff_friction_gain,
+            ff_friction_band,
+            ff_acceleration_gain,
+            speed_tracking_gain,
+            current_tracking_gain,
+            speed_kd,
+            speed_derivative_filter_time,
+            speed_proportional_weight,
+            speed_derivative_weight,
+            speed_rate_limit,
+            position_kd,
+            position_derivative_filter_time,
+            speed_feedback_filter_frequency,
+            speed_feedback_filter_shape,
+            speed_output_filter_frequency,
+            speed_output_filter_shape,
+        );
+
+        for (values, default_values) in [
+            (
+                &mut self.calibration_sector_angles[..],
+                &defaults.calibration_sector_angles[..],
+            ),
+            (
+                &mut self.speed_schedule_speeds[..],
+                &defaults.speed_schedule_speeds[..],
+            ),
+            (
+                &mut self.speed_schedule_kp[..],
+                &defaults.speed_schedule_kp[..],
+            ),
+            (
+                &mut self.speed_schedule_ki[..],
+                &defaults.speed_schedule_ki[..],
+            ),
+        ] {
+            for (value, &default) in values.iter_mut().zip(default_values) {
+                if !value.is_finite() {
+                    *value = default;
+                    reset += 1;
+                }
+            }
+        }
+
+        reset
+    }
+
     /// マジックナンバーとバージョンを検証
     pub fn validate_header(&self) -> bool {
         self.magic == CONFIG_MAGIC && self.version == CONFIG_VERSION
@@ -186,7 +696,7 @@ impl StoredConfig {
         let data = self.as_bytes_for_crc();
 
         // 4バイト境界に合わせてデータを準備
-        let mut aligned_data = [0u32; 64]; // 最大256バイト分
+        let mut aligned_data = [0u32; core::mem::size_of::<Self>() / 4]; // 構造体全体を格納できるサイズ
         let word_count = data.len().div_ceil(4);
 
         for (i, aligned_word) in aligned_data.iter_mut().enumerate().take(word_count) {
@@ -218,6 +728,14 @@ impl StoredConfig {
     }
 }
 
+// v1のレイアウトとの互換性チェック（`migrate_from_v1`が前提とする範囲）
+const _: () = {
+    assert!(core::mem::offset_of!(StoredConfig, calibration_electrical_offset) == 40);
+    assert!(core::mem::offset_of!(StoredConfig, pwm_frequency) == 64);
+    assert!(core::mem::offset_of!(StoredConfig, control_period_us) == 80);
+    assert!(CONFIG_V1_DATA_SIZE == 88);
+};
+
 // コンパイル時サイズチェック（2KB以内であることを確認）
 const _: () = {
     const SIZE: usize = core::mem::size_of::<StoredConfig>();
@@ -237,8 +755,122 @@ mod tests {
         let config = StoredConfig::default();
         assert_eq!(config.magic, CONFIG_MAGIC);
         assert_eq!(config.version, CONFIG_VERSION);
-        assert_eq!(config.speed_kp, 0.5);
-        assert_eq!(config.speed_ki, 0.05);
+        assert_eq!(config.speed_kp, params::DEFAULT_SPEED_KP);
+        assert_eq!(config.speed_ki, params::DEFAULT_SPEED_KI);
+    }
+
+    #[test]
+    fn test_migrate_from_v1() {
+        let mut v1 = StoredConfig::default();
+        v1.version = CONFIG_VERSION_V1;
+        v1.speed_kp = 0.8;
+        v1.speed_ki = 0.2;
+        v1.pole_pairs = 7;
+        v1.calibration_electrical_offset = 1.25;
+        v1.calibration_direction_inversed = true;
+        v1.calibration_success = true;
+        v1.control_period_us = 200;
+        v1.current_kp = 9.0; // v1には存在しない項目
+        let mut bytes = [0u8; core::mem::size_of::<StoredConfig>()];
+        bytes.copy_from_slice(v1.as_bytes_mut());
+
+        let config = StoredConfig::migrate_from_v1(&bytes).unwrap();
+        let defaults = StoredConfig::default();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert!(config.validate_header());
+        assert_eq!(config.speed_kp, 0.8);
+        assert_eq!(config.speed_ki, 0.2);
+        assert_eq!(config.pole_pairs, 7);
+        assert_eq!(config.calibration_electrical_offset, 1.25);
+        assert!(config.calibration_direction_inversed);
+        assert!(config.calibration_success);
+        assert_eq!(config.control_period_us, 200);
+        assert_eq!(config.current_kp, defaults.current_kp);
+
+        // boolの不正な値は正規化する
+        bytes[core::mem::offset_of!(StoredConfig, calibration_success)] = 0xFF;
+        assert!(
+            StoredConfig::migrate_from_v1(&bytes)
+                .unwrap()
+                .calibration_success
+        );
+
+        // v1以外は移行しない
+        bytes[4] = CONFIG_VERSION as u8;
+        assert!(StoredConfig::migrate_from_v1(&bytes).is_none());
+        bytes[4] = CONFIG_VERSION_V1 as u8;
+        bytes[0] = 0;
+        assert!(StoredConfig::migrate_from_v1(&bytes).is_none());
+        assert!(StoredConfig::migrate_from_v1(&bytes[..16]).is_none());
+    }
+
+    #[test]
+    fn test_sanitize_hardware_params() {
+        let mut config = StoredConfig::default();
+        assert_eq!(config.sanitize_hardware_params(), 0);
+
+        config.pwm_frequency = 1_000_000;
+        config.can_bitrate = 123_456;
+        let invalid = config.sanitize_hardware_params();
+        assert_eq!(invalid, INVALID_PWM_FREQUENCY | INVALID_CAN_BITRATE);
+        assert_eq!(config.pwm_frequency, params::pwm::DEFAULT_FREQUENCY.0);
+        assert_eq!(config.can_bitrate, params::can::DEFAULT_BITRATE);
+
+        config.pwm_dead_time = 0;
+        config.control_period_us = 0;
+        let invalid = config.sanitize_hardware_params();
+        assert_eq!(invalid, INVALID_PWM_DEAD_TIME | INVALID_CONTROL_PERIOD);
+
+        config.position_loop_divider = 0;
+        assert_eq!(config.sanitize_hardware_params(), INVALID_LOOP_DIVIDER);
+        assert_eq!(
+            config.speed_loop_divider,
+            params::timing::DEFAULT_SPEED_DIVIDER
+        );
+        assert_eq!(
+            config.position_loop_divider,
+            params::timing::DEFAULT_POSITION_DIVIDER
+        );
+    }
+
+    #[test]
+    fn test_sanitize_float_params() {
+        let mut config = StoredConfig::default();
+        assert_eq!(config.sanitize_float_params(), 0);
+
+        config.speed_kp = f32::NAN;
+        config.max_voltage = f32::INFINITY;
+        config.speed_output_filter_shape = f32::NEG_INFINITY;
+        config.calibration_sector_angles[3] = f32::NAN;
+        config.speed_schedule_ki[0] = f32::NAN;
+        config.motor_inertia = 1.5e-4; // 有限値はそのまま
+        assert_eq!(config.sanitize_float_params(), 5);
+
+        let defaults = StoredConfig::default();
+        assert_eq!(config.speed_kp, defaults.speed_kp);
+        assert_eq!(config.max_voltage, defaults.max_voltage);
+        assert_eq!(
+            config.speed_output_filter_shape,
+            defaults.speed_output_filter_shape
+        );
+        assert_eq!(
+            config.calibration_sector_angles[3],
+            defaults.calibration_sector_angles[3]
+        );
+        assert_eq!(config.speed_schedule_ki[0], defaults.speed_schedule_ki[0]);
+        assert_eq!(config.motor_inertia, 1.5e-4);
+        assert_eq!(config.sanitize_float_params(), 0);
+    }
+
+    #[test]
+    fn test_crc_bytes_exclude_crc_field() {
+        let mut config = StoredConfig::default();
+        let mut before = [0u8; core::mem::size_of::<StoredConfig>()];
+        let len = config.as_bytes_for_crc().len();
+        before[..len].copy_from_slice(config.as_bytes_for_crc());
+
+        config.crc32 = 0xDEADBEEF;
+        assert_eq!(config.as_bytes_for_crc(), &before[..len]);
     }
 
     #[test]
@@ -0,0 +1,82 @@
+//! TIM1更新割り込みによる制御周期の生成
+//!
+//! PWM（TIM1、エッジアライン）の更新イベントごとに割り込みを発生させ、
+//! 固定の分周比ごとに制御周期を実行します。制御周期はPWM周期の整数倍になり、
+//! タスクのタイマー待ちのようなドリフト・ジッタがなく、相電流のサンプリング（TIM1 CH4）とも同期します。
+//!
+//! ## 動作原理
+//! 1. TIM1のUIE（更新割り込み）を有効化（ComplementaryPwmの周波数・デッドタイム設定は変更しない）
+//! 2. カウンタのオーバーフロー（PWM周期の先頭）ごとにTIM1_UP_TIM16割り込みが発生
+//! 3. 割り込みごとに分周カウンタを進め、分周比に達した割り込みで制御周期を実行
+//! 4. 相電流は直前のPWM周期末尾（CC4）でサンプリングされた注入変換結果を使用
+//!
+//! 注: レピティションカウンタ（RCR）で更新イベント自体を間引くと、デューティのプリロードの反映も
+//! 分周比ごとになり1制御周期遅れるため、更新イベントは毎PWM周期のまま割り込み側で分周する。
+
+use core::sync::atomic::{AtomicU32, Ordering};
+use embassy_stm32::pac;
+
+/// 制御周期の分周比（PWM周期数、1以上）
+pub static DIVIDER: AtomicU32 = AtomicU32::new(1);
+
+/// 分周カウンタ（前回の制御周期からのPWM周期数）
+pub static TICK_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+/// TIM1更新割り込みの初期化
+///
+/// ComplementaryPwmの初期化後、制御割り込みで使う状態を設定してから呼び出すこと。
+///
+/// # 引数
+/// * `divider` - 制御周期の分周比（PWM周期数、0は1として扱う）
+///
+/// # Safety
+/// PACを使用した直接的なレジスタ操作を含むため、unsafe
+pub unsafe fn init_control_timer(divider: u32) {
+    let tim1 = pac::TIM1;
+
+    DIVIDER.store(divider.max(1), Ordering::Relaxed);
+    TICK_COUNTER.store(0, Ordering::Relaxed);
+
+    // 1. Update Request Source: カウンタオーバーフローのみ（UGによる更新では割り込まない）
+    tim1.cr1()
+        .modify(|w| w.set_urs(pac::timer::vals::Urs::COUNTER_ONLY));
+
+    // 2. 更新割り込みを有効化（保留中のフラグはクリアしてから）
+    tim1.sr().modify(|w| w.set_uif(false));
+    tim1.dier().modify(|w| w.set_uie(true));
+
+    // 3. 割り込み有効化（NVIC）
+    // 優先度: Hall（TIM4、0x20）より低く、Embassyタスク（スレッドモード）より高くする
+    // Priority 3 = 0x30
+    // 優先度0（最高）で割り込まないよう、優先度を設定してから有効化する
+    unsafe {
+        let mut cp = cortex_m::Peripherals::steal();
+        cp.NVIC.set_priority(pac::Interrupt::TIM1_UP_TIM16, 0x30);
+        cortex_m::peripheral::NVIC::unmask(pac::Interrupt::TIM1_UP_TIM16);
+
+        // 制御割り込みの処理時間の計測用にDWTサイクルカウンタを有効化
+        cp.DCB.enable_trace();
+        cp.DWT.enable_cycle_counter();
+    }
+}
+
+/// TIM1更新割り込みの処理（フラグクリアと分周）
+///
+/// # 戻り値
+/// * `bool` - この割り込みで制御周期を実行するか
+///
+/// # Safety
+/// 割り込みコンテキストからのみ呼び出すこと
+#[inline(always)]
+pub unsafe fn on_update() -> bool {
+    pac::TIM1.sr().modify(|w| w.set_uif(false)); // フラグクリア
+
+    let tick = TICK_COUNTER.load(Ordering::Relaxed) + 1;
+    if tick >= DIVIDER.load(Ordering::Relaxed) {
+        TICK_COUNTER.store(0, Ordering::Relaxed);
+        true
+    } else {
+        TICK_COUNTER.store(tick, Ordering::Relaxed);
+        false
+    }
+}
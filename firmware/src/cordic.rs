@@ -0,0 +1,80 @@
+//! CORDICコプロセッサによる三角関数計算
+//!
+//! STM32G4のCORDICユニットでsin/cos（余弦関数）とatan2（位相関数）を計算します。
+//! 引数・結果はq1.31固定小数点で、角度は±1 = ±π（`idsp`の位相と同じ表現）です。
+//!
+//! ## 動作
+//! 1. CSRに関数・精度・引数/結果の数を設定
+//! 2. WDATAに引数を書き込むと計算を開始
+//! 3. RDATAの読み出しは計算が終わるまでバスをウェイトさせるため、RRDYのポーリングは不要
+//!
+//! 計算の途中で別のコンテキストから使用すると結果が壊れるため、制御割り込みからのみ使用すること
+//! （起動時のベンチマークは制御割り込みの有効化前に実行する）。
+
+use embassy_stm32::pac;
+
+/// CSR.FUNC: 余弦関数（結果1 = m·cos θ、結果2 = m·sin θ）
+const FUNC_COSINE: u32 = 0;
+
+/// CSR.FUNC: 位相関数（結果1 = atan2(y, x)）
+const FUNC_PHASE: u32 = 2;
+
+/// CSR.PRECISION: 反復回数/4（6 = 24回、q1.31で約2^-19の精度、約29サイクル）
+const PRECISION: u32 = 6 << 4;
+
+/// CSR.NRES: 結果を2個読み出す
+const NRES_TWO: u32 = 1 << 19;
+
+/// CSR.NARGS: 引数を2個書き込む
+const NARGS_TWO: u32 = 1 << 20;
+
+/// CORDICの初期化（クロック有効化）
+///
+/// # Safety
+/// PACを使用した直接的なレジスタ操作を含むため、unsafe
+pub unsafe fn init_cordic() {
+    let rcc = pac::RCC;
+    rcc.ahb1enr().modify(|w| w.set_cordicen(true));
+}
+
+/// sin/cosを計算
+///
+/// # 引数
+/// * `phase` - 角度（q1.31、i32::MIN = -π）
+///
+/// # 戻り値
+/// * `(cos, sin)` - q1.31
+pub fn cossin(phase: i32) -> (i32, i32) {
+    let cordic = pac::CORDIC;
+
+    cordic.csr().write_value(pac::cordic::regs::Csr(
+        FUNC_COSINE | PRECISION | NRES_TWO | NARGS_TWO,
+    ));
+    cordic.wdata().write_value(phase as u32);
+    cordic.wdata().write_value(i32::MAX as u32); // 振幅 m = 1
+
+    let cos = cordic.rdata().read() as i32;
+    let sin = cordic.rdata().read() as i32;
+    (cos, sin)
+}
+
+/// ベクトル (x, y) の位相を計算
+///
+/// # 引数
+/// * `y` - Y成分（`x`と共通の任意のスケール）
+/// * `x` - X成分
+///
+/// # 戻り値
+/// * 位相（q1.31、i32::MIN = -π）
+pub fn atan2(y: i32, x: i32) -> i32 {
+    let cordic = pac::CORDIC;
+
+    cordic
+        .csr()
+        .write_value(pac::cordic::regs::Csr(FUNC_PHASE | PRECISION | NARGS_TWO));
+    // 絶対値が1を超えないよう1/2に縮小（位相は変わらない）
+    cordic.wdata().write_value((x >> 1) as u32);
+    cordic.wdata().write_value((y >> 1) as u32);
+
+    cordic.rdata().read() as i32
+}
@@ -0,0 +1,165 @@
+//! TIM1同期の相電流サンプリング（ADC注入変換）
+//!
+//! PWM周期と同期して3相の電流検出アンプ出力を同時にサンプリングします。
+//! DCバス電圧もADC2の注入シーケンスでV相の後に変換します。
+//!
+//! ## ハードウェア構成
+//! - U相: OPAMP1（PGA ×4）→ PA2 → ADC1_IN3（注入ランク1）
+//! - V相: OPAMP2（外部抵抗）→ PA6 → ADC2_IN3（注入ランク1）
+//! - W相: OPAMP3（外部抵抗）→ PB1 → ADC1_IN12（注入ランク2）
+//! - DCバス電圧: 分圧回路 → PC1 → ADC2_IN7（注入ランク2）
+//!
+//! ## 動作原理
+//! 1. TIM1 CH4（ピン未接続）をPWM周期末尾のコンペア点に設定（CCR4 = ARR - SAMPLE_LEAD_TICKS）
+//! 2. ADC1/ADC2の注入変換をTIM1_CC4イベントで起動（JEXTSEL=1、立ち上がりエッジ）
+//! 3. エッジアラインPWM（PWMモード1）ではCNT >= CCRの区間がローサイドON＝シャント導通区間のため、
+//!    周期末尾でサンプリングすれば3相とも電流が流れている状態を計測できる
+//! 4. 変換結果はJDRレジスタに保持され、制御ループが最新値を読み出す
+//!
+//! 注: ローサイドはデューティの終わりからデッドタイム後にONになるため、デューティがCCR4からデッドタイムを
+//! 引いた値を超える相は計測できない。`max_sampled_duty`でデューティの上限を求めて制限すること。
+//! ADC1/ADC2はembassyの`Adc::new()`で有効化・キャリブレーション済みであることを前提とする。
+//!
+//! ## 通常変換を使わない理由
+//! embassyの通常変換（`Adc::blocking_read`）は変換のたびにSMPR・CFGRを書き換え、実行中の注入変換と
+//! 競合する。そのためADC1/ADC2では通常変換を使わず、電圧監視タスクもDCバス電圧を
+//! 注入変換の結果（`read_bus_voltage_raw`）から読む。注入シーケンスが設定どおり動作し続けているかは
+//! `is_injected_sequence_armed`で確認できる。
+
+mod injected;
+
+use embassy_stm32::pac;
+
+/// サンプリング点をPWM周期末尾から前倒しするタイマーカウント数
+///
+/// 170MHzで2μs。ADC1はU相・W相を順に変換するため、注入変換2回分
+/// （(サンプリング24.5サイクル + 変換12.5サイクル) × 2 = 74 ADCサイクル、42.5MHzで約1.74μs）が
+/// 周期末尾でローサイドがOFFになる前に完了するための余裕。
+const SAMPLE_LEAD_TICKS: u16 = 340;
+
+/// JEXTSEL: TIM1_CC4イベント（ADC12注入トリガー）
+const JEXTSEL_TIM1_CC4: u8 = 1;
+
+/// U相: ADC1チャネル（PA2 = ADC1_IN3）
+const ADC1_CH_U: u8 = 3;
+
+/// W相: ADC1チャネル（PB1 = ADC1_IN12）
+const ADC1_CH_W: u8 = 12;
+
+/// V相: ADC2チャネル（PA6 = ADC2_IN3）
+const ADC2_CH_V: u8 = 3;
+
+/// DCバス電圧: ADC2チャネル（PC1 = ADC2_IN7）
+const ADC2_CH_BUS_VOLTAGE: u8 = 7;
+
+/// DCバス電圧のピン番号（GPIOC）
+const BUS_VOLTAGE_PIN: usize = 1;
+
+/// ADC1の注入シーケンス: ランク1 = U相、ランク2 = W相
+const ADC1_JSQR: u32 = injected::jsqr(&[ADC1_CH_U, ADC1_CH_W], JEXTSEL_TIM1_CC4);
+
+/// ADC2の注入シーケンス: ランク1 = V相、ランク2 = DCバス電圧
+///
+/// DCバス電圧はV相の変換後に変換するため、相電流のサンプリング点には影響しない。
+const ADC2_JSQR: u32 = injected::jsqr(&[ADC2_CH_V, ADC2_CH_BUS_VOLTAGE], JEXTSEL_TIM1_CC4);
+
+/// 3相とも相電流を計測できるデューティの上限を取得
+///
+/// サンプリング点（CCR4 = ARR - SAMPLE_LEAD_TICKS）でローサイドがONになっているには、
+/// デューティがデッドタイム分手前までに終わっている必要がある。
+///
+/// # 引数
+/// * `max_duty` - PWMの最大Duty値（エッジアラインではARR + 1）
+/// * `dead_time` - デッドタイム [タイマーカウント]
+pub const fn max_sampled_duty(max_duty: u16, dead_time: u16) -> u16 {
+    max_duty
+        .saturating_sub(1)
+        .saturating_sub(SAMPLE_LEAD_TICKS)
+        .saturating_sub(dead_time)
+}
+
+/// TIM1同期の注入変換を初期化
+///
+/// TIM1（ComplementaryPwm）とADC1/ADC2の初期化後に呼び出すこと。
+///
+/// # Safety
+/// PACを使用した直接的なレジスタ操作を含むため、unsafe
+pub unsafe fn init_current_sense() {
+    let tim1 = pac::TIM1;
+    let adc1 = pac::ADC1;
+    let adc2 = pac::ADC2;
+
+    // 1. TIM1 CH4: サンプリングトリガー用コンペア（出力ピンは使用しない）
+    let arr = tim1.arr().read().arr();
+    let sample_point = arr.saturating_sub(SAMPLE_LEAD_TICKS);
+    tim1.ccmr_output(1)
+        .modify(|w| w.set_ocm(1, pac::timer::vals::Ocm::PWM_MODE1)); // OC4M = PWM mode 1
+    tim1.ccr(3).modify(|w| w.set_ccr(sample_point));
+
+    // 2. 注入チャネルのサンプリング時間（OPAMP出力は低インピーダンスのため短時間で可）
+    adc1.smpr()
+        .modify(|w| w.set_smp(ADC1_CH_U as _, pac::adc::vals::SampleTime::CYCLES24_5));
+    adc1.smpr2().modify(|w| {
+        w.set_smp(
+            (ADC1_CH_W - 10) as _,
+            pac::adc::vals::SampleTime::CYCLES24_5,
+        )
+    });
+    adc2.smpr()
+        .modify(|w| w.set_smp(ADC2_CH_V as _, pac::adc::vals::SampleTime::CYCLES24_5));
+    // DCバス電圧は分圧回路（出力インピーダンス約3kΩ）のため長めに取る
+    // （V相と合わせて約300 ADCクロック = 42.5MHzで約7μs、最高PWM周波数100kHzの周期内に収まる）
+    adc2.smpr().modify(|w| {
+        w.set_smp(
+            ADC2_CH_BUS_VOLTAGE as _,
+            pac::adc::vals::SampleTime::CYCLES247_5,
+        )
+    });
+    pac::GPIOC
+        .moder()
+        .modify(|w| w.set_moder(BUS_VOLTAGE_PIN, pac::gpio::vals::Moder::ANALOG));
+
+    // 3. 注入シーケンス設定（JQDIS=1: キュー無効、トリガーごとに同じシーケンスを繰り返す）
+    adc1.cfgr().modify(|w| w.set_jqdis(true));
+    adc2.cfgr().modify(|w| w.set_jqdis(true));
+
+    adc1.jsqr().write_value(pac::adc::regs::Jsqr(ADC1_JSQR));
+    adc2.jsqr().write_value(pac::adc::regs::Jsqr(ADC2_JSQR));
+
+    // 4. 注入変換開始（以降はTIM1_CC4イベントごとにハードウェアで変換される）
+    adc1.cr().modify(|w| w.set_jadstart(true));
+    adc2.cr().modify(|w| w.set_jadstart(true));
+}
+
+/// 最新の相電流サンプル（生のADCカウント）を取得
+///
+/// # 戻り値
+/// U/V/W相のADCカウント（12bit）
+#[inline(always)]
+pub fn read_raw() -> [u16; 3] {
+    let raw_u = pac::ADC1.jdr(0).read().jdata();
+    let raw_v = pac::ADC2.jdr(0).read().jdata();
+    let raw_w = pac::ADC1.jdr(1).read().jdata();
+
+    [raw_u, raw_v, raw_w]
+}
+
+/// 最新のDCバス電圧のサンプル（生のADCカウント）を取得
+///
+/// # 戻り値
+/// DCバス電圧の分圧のADCカウント（12bit、最初の注入変換の前は0）
+pub fn read_bus_voltage_raw() -> u16 {
+    pac::ADC2.jdr(1).read().jdata()
+}
+
+/// ADC1/ADC2の注入シーケンスが設定どおりトリガー待ちになっているか
+///
+/// JSQR・JQDISが初期化時の値のままで、注入変換が開始済み（JADSTART）であることを確認する。
+/// 他の処理がADCの設定を書き換えた場合（通常変換の実行など）の検出に使う。
+pub fn is_injected_sequence_armed() -> bool {
+    [(pac::ADC1, ADC1_JSQR), (pac::ADC2, ADC2_JSQR)]
+        .iter()
+        .all(|&(adc, jsqr)| {
+            adc.jsqr().read().0 == jsqr && adc.cfgr().read().jqdis() && adc.cr().read().jadstart()
+        })
+}
@@ -0,0 +1,69 @@
+//! ADC注入シーケンスレジスタ（JSQR）の値の作成
+//!
+//! 注入変換のチャネル・トリガーはJSQRだけで決まり、通常変換のレジスタ（SQR1-4、CFGRのEXTSEL/EXTEN）
+//! とは独立しています。ただしembassyの通常変換（`Adc::blocking_read`）はSMPR・CFGRを書き換えるため、
+//! 注入変換で使うADCでは通常変換を使わず、他の信号も注入シーケンスに追加して変換します。
+
+/// 注入シーケンスの最大変換数
+pub const MAX_LENGTH: usize = 4;
+
+/// JEXTEN: 立ち上がりエッジでトリガー
+pub const JEXTEN_RISING_EDGE: u32 = 0b01;
+
+/// JSQRの値を作成（RM0440: JL[1:0]、JEXTSEL[6:2]、JEXTEN[8:7]、JSQ1-4[13:9]/[19:15]/[25:21]/[31:27]）
+///
+/// # 引数
+/// * `channels` - ランク順のチャネル番号（1-4個、5個目以降は無視）
+/// * `jextsel` - 注入変換のトリガー
+///
+/// # 戻り値
+/// * `u32` - JSQRの値（トリガーは立ち上がりエッジ）
+pub const fn jsqr(channels: &[u8], jextsel: u8) -> u32 {
+    let length = if channels.len() < MAX_LENGTH {
+        channels.len()
+    } else {
+        MAX_LENGTH
+    };
+    let mut value = (length.saturating_sub(1) as u32)
+        | ((jextsel as u32 & 0x1F) << 2)
+        | (JEXTEN_RISING_EDGE << 7);
+    let mut rank = 0;
+    while rank < length {
+        value |= (channels[rank] as u32 & 0x1F) << (9 + 6 * rank);
+        rank += 1;
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_channel() {
+        // JL=0、JEXTSEL=1、JEXTEN=立ち上がり、JSQ1=3
+        assert_eq!(jsqr(&[3], 1), (1 << 2) | (1 << 7) | (3 << 9));
+    }
+
+    #[test]
+    fn test_sequence_ranks() {
+        let value = jsqr(&[3, 7, 12, 17], 1);
+        assert_eq!(value & 0x3, 3); // JL = 変換数 - 1
+        assert_eq!((value >> 9) & 0x1F, 3);
+        assert_eq!((value >> 15) & 0x1F, 7);
+        assert_eq!((value >> 21) & 0x1F, 12);
+        assert_eq!((value >> 27) & 0x1F, 17);
+
+        // 5個目以降のチャネルは無視
+        assert_eq!(jsqr(&[3, 7, 12, 17, 5], 1), value);
+    }
+
+    #[test]
+    fn test_trigger_fields() {
+        let value = jsqr(&[3, 7], 0x1F);
+        assert_eq!((value >> 2) & 0x1F, 0x1F);
+        assert_eq!((value >> 7) & 0x3, JEXTEN_RISING_EDGE);
+        // トリガーはJSQ1に漏れない
+        assert_eq!((value >> 9) & 0x1F, 3);
+    }
+}
@@ -0,0 +1,115 @@
+//! 割り込みとタスク間のロックフリーなダブルバッファ
+//!
+//! 制御割り込みとEmbassyタスクの間で、指令値・ステータスなどの構造体を
+//! ロック（`Mutex`の待ち）なしで受け渡します。
+//!
+//! ## 動作原理
+//! 1. 書き込み側は読み出し側が参照していない裏側のスロットに書き込み、表裏を切り替える
+//! 2. 書き込みごとに通し番号をインクリメントする
+//! 3. 読み出し側は表側のスロットをコピーし、コピー中に通し番号が変わった場合は読み直す
+//!
+//! シングルコアで、書き込み側・読み出し側がそれぞれ1つの実行コンテキスト（割り込みまたはタスク）
+//! であることを前提とします。割り込みがタスクに割り込まれることはないため、
+//! 割り込み側の読み出しは読み直しなしで完了し、タスク側の読み出しは割り込みによる
+//! 書き込みを挟んだ場合のみ読み直します。
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::{compiler_fence, AtomicU32, AtomicUsize, Ordering};
+
+/// ロックフリーなダブルバッファ（書き込み側・読み出し側とも1コンテキストのみ）
+pub struct DoubleBuffer<T: Copy> {
+    /// データスロット（表裏）
+    slots: [UnsafeCell<T>; 2],
+    /// 読み出し側が参照する表側のスロット番号（0または1）
+    front: AtomicUsize,
+    /// 書き込み回数（読み直し判定・更新検出に使用、ラップアラウンドあり）
+    sequence: AtomicU32,
+}
+
+// 安全性: 書き込みは裏側のスロットのみに行い、表側を読み出している間に書き込みが割り込んだ場合は
+// 通し番号の変化で検出して読み直すため、読み出し側が書きかけの値を返すことはない
+unsafe impl<T: Copy + Send> Sync for DoubleBuffer<T> {}
+
+impl<T: Copy> DoubleBuffer<T> {
+    /// 初期値で新しいダブルバッファを作成
+    ///
+    /// # 引数
+    /// * `value` - 初期値（最初の書き込みまで読み出される値）
+    pub const fn new(value: T) -> Self {
+        Self {
+            slots: [UnsafeCell::new(value), UnsafeCell::new(value)],
+            front: AtomicUsize::new(0),
+            sequence: AtomicU32::new(0),
+        }
+    }
+
+    /// 値を書き込む（書き込み側のコンテキストからのみ呼び出すこと）
+    ///
+    /// # 引数
+    /// * `value` - 書き込む値
+    pub fn write(&self, value: T) {
+        let back = self.front.load(Ordering::Relaxed) ^ 1;
+        // 安全性: 裏側のスロットは読み出し側から参照されない
+        unsafe { core::ptr::write_volatile(self.slots[back].get(), value) };
+        compiler_fence(Ordering::Release);
+        self.front.store(back, Ordering::Release);
+        self.sequence.fetch_add(1, Ordering::Release);
+    }
+
+    /// 最新の値を読み出す（読み出し側のコンテキストからのみ呼び出すこと）
+    ///
+    /// # 戻り値
+    /// 最後に書き込まれた値（書き込みがなければ初期値）
+    pub fn read(&self) -> T {
+        loop {
+            let sequence = self.sequence.load(Ordering::Acquire);
+            let front = self.front.load(Ordering::Acquire);
+            // 安全性: 表側のスロットへの書き込みは表裏が2回切り替わった後にのみ行われ、
+            // その場合は通し番号が変わるため、下で検出して読み直す
+            let value = unsafe { core::ptr::read_volatile(self.slots[front].get()) };
+            compiler_fence(Ordering::Acquire);
+            if self.sequence.load(Ordering::Acquire) == sequence {
+                return value;
+            }
+        }
+    }
+
+    /// 書き込み回数を取得（前回の値と比較して更新を検出する）
+    #[inline(always)]
+    pub fn sequence(&self) -> u32 {
+        self.sequence.load(Ordering::Acquire)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initial_value() {
+        let buffer = DoubleBuffer::new((1u8, 2.0f32));
+        assert_eq!(buffer.read(), (1, 2.0));
+        assert_eq!(buffer.sequence(), 0);
+    }
+
+    #[test]
+    fn test_write_read() {
+        let buffer = DoubleBuffer::new(0u32);
+        for value in 1..=5 {
+            buffer.write(value);
+            assert_eq!(buffer.read(), value);
+            assert_eq!(buffer.sequence(), value);
+        }
+    }
+
+    #[test]
+    fn test_write_alternates_slots() {
+        let buffer = DoubleBuffer::new(0u32);
+        buffer.write(10);
+        buffer.write(20);
+        // 直前の値は裏側のスロットに残り、表側は最新の値
+        let front = buffer.front.load(Ordering::Relaxed);
+        assert_eq!(unsafe { *buffer.slots[front].get() }, 20);
+        assert_eq!(unsafe { *buffer.slots[front ^ 1].get() }, 10);
+    }
+}
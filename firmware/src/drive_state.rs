@@ -0,0 +1,501 @@
+//! 駆動状態マシン
+//!
+//! モーターの駆動状態（待機・始動・運転・停止・故障など）を一箇所で管理します。
+//! 状態を遷移させるのは制御割り込みだけで、CAN・電圧監視・LEDは制御割り込みが公開した
+//! `DriveStatus`（`state::DRIVE_STATUS`）を参照します。
+//!
+//! ## 状態遷移
+//! ```text
+//! Init → Idle → Precharge → Starting ⇄ Running
+//!         ↑                    ↕         ↕
+//!         │                    Calibrating
+//!         └── Stopping ←── Precharge / Starting / Running / Calibrating（運転要求の解除）
+//!
+//! 任意の状態 → Fault → Idle（故障要因が解消し、運転要求が解除されている場合のみ）
+//! ```
+//! 定義されていない遷移は`DriveStateMachine::transition`が拒否します。
+//! 外部からの要求（始動・停止・制御モード・キャリブレーション・自動調整）は`DriveRequest`として
+//! `state::DRIVE_REQUESTS`チャネルに送り、制御割り込みが受信して`DriveState::accepts`で
+//! 実際の状態に対して受け付けるかを判定します。
+
+use crate::foc::ControlMode;
+
+/// 故障要因: 過電圧
+pub const FAULT_OVERVOLTAGE: u8 = 0x01;
+
+/// 故障要因: 低電圧
+pub const FAULT_UNDERVOLTAGE: u8 = 0x02;
+
+/// 故障要因: Hallセンサー故障（Hallのみで運転する場合）
+pub const FAULT_HALL: u8 = 0x04;
+
+/// 電圧監視の状態から故障要因を作成
+///
+/// # 引数
+/// * `overvoltage` - 過電圧を検出中か
+/// * `undervoltage` - 低電圧を検出中か
+///
+/// # 戻り値
+/// * `u8` - 故障要因（`FAULT_OVERVOLTAGE` / `FAULT_UNDERVOLTAGE`のビットOR）
+pub fn voltage_faults(overvoltage: bool, undervoltage: bool) -> u8 {
+    let mut faults = 0;
+    if overvoltage {
+        faults |= FAULT_OVERVOLTAGE;
+    }
+    if undervoltage {
+        faults |= FAULT_UNDERVOLTAGE;
+    }
+    faults
+}
+
+/// 駆動状態
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DriveState {
+    /// 起動直後（制御割り込みの開始前）
+    Init = 0,
+    /// 待機（PWM停止、運転要求待ち）
+    Idle = 1,
+    /// プリチャージ・アライメント（PWM停止中に相電流のゼロ点オフセットを校正）
+    Precharge = 2,
+    /// 始動（オープンループ始動・FOC直接始動の監視・FOCへの切替中）
+    Starting = 3,
+    /// 運転（FOC・トルク・電圧・位置制御、速度PIゲイン自動調整）
+    Running = 4,
+    /// 停止処理（PWM停止、回転が止まるまで待機）
+    Stopping = 5,
+    /// 故障（PWM停止、故障要因の解消と運転要求の解除待ち）
+    Fault = 6,
+    /// キャリブレーション・パラメータ同定
+    Calibrating = 7,
+}
+
+impl DriveState {
+    /// CAN受信値から変換（範囲外は`None`）
+    #[allow(dead_code)]
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Init),
+            1 => Some(Self::Idle),
+            2 => Some(Self::Precharge),
+            3 => Some(Self::Starting),
+            4 => Some(Self::Running),
+            5 => Some(Self::Stopping),
+            6 => Some(Self::Fault),
+            7 => Some(Self::Calibrating),
+            _ => None,
+        }
+    }
+
+    /// ログ表示用の状態名
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Init => "Init",
+            Self::Idle => "Idle",
+            Self::Precharge => "Precharge",
+            Self::Starting => "Starting",
+            Self::Running => "Running",
+            Self::Stopping => "Stopping",
+            Self::Fault => "Fault",
+            Self::Calibrating => "Calibrating",
+        }
+    }
+
+    /// 運転要求を受けて動作中か（プリチャージ・始動・運転・キャリブレーション）
+    pub fn is_active(self) -> bool {
+        matches!(
+            self,
+            Self::Precharge | Self::Starting | Self::Running | Self::Calibrating
+        )
+    }
+
+    /// 外部からの要求を受け付けるか
+    ///
+    /// # 引数
+    /// * `request` - 要求
+    ///
+    /// # 戻り値
+    /// * `bool` - 受け付ける場合true（停止処理中・起動直後の始動要求は停止・起動の完了後に処理）
+    pub fn accepts(self, request: DriveRequest) -> bool {
+        match request {
+            DriveRequest::Start => self != Self::Fault,
+            DriveRequest::Stop | DriveRequest::Mode(_) => true,
+            DriveRequest::Calibrate { .. } | DriveRequest::Identify { .. } => {
+                !matches!(self, Self::Fault | Self::Calibrating)
+            }
+            DriveRequest::AutoTune { .. } => self == Self::Running,
+        }
+    }
+}
+
+/// 外部からの要求（`state::DRIVE_REQUESTS`で制御割り込みに送る）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DriveRequest {
+    /// 始動（運転要求を設定）
+    Start,
+    /// 停止（運転要求を解除）
+    Stop,
+    /// 外部指令の制御モードを切り替え（速度・トルク・電圧・位置指令）
+    Mode(ControlMode),
+    /// キャリブレーション（トルク値 0-100）
+    Calibrate { torque: u8 },
+    /// パラメータ同定（試験電流 [A], 試験速度 [RPM]）
+    Identify { current: f32, speed: f32 },
+    /// 速度PIゲイン自動調整（試験速度 [RPM], リレー振幅の出力制限比）
+    AutoTune { speed: f32, relay_ratio: f32 },
+}
+
+impl DriveRequest {
+    /// ログ表示用の要求名
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Start => "Start",
+            Self::Stop => "Stop",
+            Self::Mode(_) => "Mode",
+            Self::Calibrate { .. } => "Calibrate",
+            Self::Identify { .. } => "Identify",
+            Self::AutoTune { .. } => "AutoTune",
+        }
+    }
+}
+
+/// 未定義の状態遷移
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IllegalTransition {
+    /// 遷移前の状態
+    pub from: DriveState,
+    /// 要求された遷移先の状態
+    pub to: DriveState,
+}
+
+/// 公開用の駆動状態（CAN・電圧監視・LEDが参照）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DriveStatus {
+    /// 駆動状態
+    pub state: DriveState,
+    /// 制御モード
+    pub control_mode: ControlMode,
+    /// 現在の故障要因（`FAULT_*`のビットOR）
+    pub faults: u8,
+}
+
+impl DriveStatus {
+    /// 起動直後の状態を作成
+    pub const fn new() -> Self {
+        Self {
+            state: DriveState::Init,
+            control_mode: ControlMode::OpenLoop,
+            faults: 0,
+        }
+    }
+}
+
+/// 駆動状態マシン
+///
+/// 状態と故障要因を保持し、定義された遷移のみを許可する。
+/// 遷移時の処理（PWMの停止・オフセット校正の開始など）は所有者が遷移の結果に応じて実行する。
+pub struct DriveStateMachine {
+    /// 現在の状態
+    state: DriveState,
+    /// 現在の故障要因（`FAULT_*`のビットOR）
+    faults: u8,
+    /// 現在の状態に入ってからの経過時間 [s]
+    time_in_state: f32,
+}
+
+impl DriveStateMachine {
+    /// 起動直後の状態で作成
+    pub const fn new() -> Self {
+        Self {
+            state: DriveState::Init,
+            faults: 0,
+            time_in_state: 0.0,
+        }
+    }
+
+    /// 現在の状態を取得
+    pub fn state(&self) -> DriveState {
+        self.state
+    }
+
+    /// 現在の故障要因を取得
+    pub fn faults(&self) -> u8 {
+        self.faults
+    }
+
+    /// 現在の状態に入ってからの経過時間 [s] を取得
+    pub fn time_in_state(&self) -> f32 {
+        self.time_in_state
+    }
+
+    /// 経過時間を1周期分進める
+    ///
+    /// # 引数
+    /// * `dt` - 制御周期 [s]
+    pub fn update(&mut self, dt: f32) {
+        self.time_in_state += dt;
+    }
+
+    /// 状態遷移が定義されているか
+    ///
+    /// 故障状態への遷移はどの状態からも可能（`set_faults`で行う）。
+    ///
+    /// # 引数
+    /// * `from` - 遷移前の状態
+    /// * `to` - 遷移先の状態
+    pub fn is_allowed(from: DriveState, to: DriveState) -> bool {
+        use DriveState::*;
+
+        if to == Fault {
+            return true;
+        }
+        match from {
+            Init => to == Idle,
+            Idle => to == Precharge,
+            Precharge => matches!(to, Starting | Stopping),
+            Starting => matches!(to, Running | Calibrating | Stopping),
+            Running => matches!(to, Starting | Calibrating | Stopping),
+            Calibrating => matches!(to, Starting | Running | Stopping),
+            Stopping => to == Idle,
+            Fault => to == Idle,
+        }
+    }
+
+    /// 状態を遷移
+    ///
+    /// 故障状態からは故障要因が解消するまで遷移しない。
+    ///
+    /// # 引数
+    /// * `next` - 遷移先の状態
+    ///
+    /// # 戻り値
+    /// * `Ok(true)` - 遷移した
+    /// * `Ok(false)` - 既に遷移先の状態
+    /// * `Err(IllegalTransition)` - 未定義の遷移（状態は変わらない）
+    pub fn transition(&mut self, next: DriveState) -> Result<bool, IllegalTransition> {
+        if next == self.state {
+            return Ok(false);
+        }
+
+        let illegal = IllegalTransition {
+            from: self.state,
+            to: next,
+        };
+        if !Self::is_allowed(self.state, next)
+            || (self.state == DriveState::Fault && self.faults != 0)
+        {
+            return Err(illegal);
+        }
+
+        self.state = next;
+        self.time_in_state = 0.0;
+        Ok(true)
+    }
+
+    /// 現在の故障要因を設定
+    ///
+    /// 要因があれば故障状態に遷移する。要因が解消しても故障状態を維持し、
+    /// 運転要求の解除後に`transition(DriveState::Idle)`で待機状態に戻る。
+    ///
+    /// # 引数
+    /// * `faults` - 現在の故障要因（`FAULT_*`のビットOR、0 = 故障なし）
+    ///
+    /// # 戻り値
+    /// * `Option<DriveState>` - 故障状態に遷移した場合は遷移前の状態
+    pub fn set_faults(&mut self, faults: u8) -> Option<DriveState> {
+        self.faults = faults;
+        if faults == 0 || self.state == DriveState::Fault {
+            return None;
+        }
+
+        let previous = self.state;
+        self.state = DriveState::Fault;
+        self.time_in_state = 0.0;
+        Some(previous)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 待機状態から指定の状態まで正規の経路で遷移させる
+    fn machine_in(state: DriveState) -> DriveStateMachine {
+        let mut machine = DriveStateMachine::new();
+        let path: &[DriveState] = match state {
+            DriveState::Init => &[],
+            DriveState::Idle => &[DriveState::Idle],
+            DriveState::Precharge => &[DriveState::Idle, DriveState::Precharge],
+            DriveState::Starting => &[
+                DriveState::Idle,
+                DriveState::Precharge,
+                DriveState::Starting,
+            ],
+            DriveState::Running => &[
+                DriveState::Idle,
+                DriveState::Precharge,
+                DriveState::Starting,
+                DriveState::Running,
+            ],
+            DriveState::Stopping => &[
+                DriveState::Idle,
+                DriveState::Precharge,
+                DriveState::Stopping,
+            ],
+            DriveState::Calibrating => &[
+                DriveState::Idle,
+                DriveState::Precharge,
+                DriveState::Starting,
+                DriveState::Calibrating,
+            ],
+            DriveState::Fault => &[DriveState::Idle],
+        };
+        for &next in path {
+            assert_eq!(machine.transition(next), Ok(true));
+        }
+        if state == DriveState::Fault {
+            machine.set_faults(FAULT_UNDERVOLTAGE);
+        }
+        assert_eq!(machine.state(), state);
+        machine
+    }
+
+    #[test]
+    fn test_start_stop_sequence() {
+        let mut machine = machine_in(DriveState::Running);
+
+        // キャリブレーションと再始動
+        assert_eq!(machine.transition(DriveState::Calibrating), Ok(true));
+        assert_eq!(machine.transition(DriveState::Starting), Ok(true));
+        assert_eq!(machine.transition(DriveState::Running), Ok(true));
+        assert_eq!(machine.transition(DriveState::Running), Ok(false));
+
+        // 停止処理を経て待機へ
+        assert_eq!(machine.transition(DriveState::Stopping), Ok(true));
+        assert_eq!(machine.transition(DriveState::Idle), Ok(true));
+        assert_eq!(machine.state(), DriveState::Idle);
+    }
+
+    #[test]
+    fn test_illegal_transitions_rejected() {
+        let mut machine = machine_in(DriveState::Idle);
+        assert_eq!(
+            machine.transition(DriveState::Running),
+            Err(IllegalTransition {
+                from: DriveState::Idle,
+                to: DriveState::Running
+            })
+        );
+        assert_eq!(machine.state(), DriveState::Idle);
+
+        // 停止処理中は待機に戻るまで再始動できない
+        let mut machine = machine_in(DriveState::Stopping);
+        assert!(machine.transition(DriveState::Precharge).is_err());
+        assert!(machine.transition(DriveState::Starting).is_err());
+
+        // 起動直後は待機を経由する
+        let mut machine = DriveStateMachine::new();
+        assert!(machine.transition(DriveState::Precharge).is_err());
+        assert_eq!(machine.transition(DriveState::Idle), Ok(true));
+    }
+
+    #[test]
+    fn test_fault_from_any_state() {
+        for state in [
+            DriveState::Init,
+            DriveState::Idle,
+            DriveState::Precharge,
+            DriveState::Starting,
+            DriveState::Running,
+            DriveState::Stopping,
+            DriveState::Calibrating,
+        ] {
+            let mut machine = machine_in(state);
+            assert_eq!(machine.set_faults(FAULT_OVERVOLTAGE), Some(state));
+            assert_eq!(machine.state(), DriveState::Fault);
+            assert_eq!(machine.faults(), FAULT_OVERVOLTAGE);
+
+            // 故障中に要因が増えても再遷移しない
+            assert_eq!(machine.set_faults(FAULT_OVERVOLTAGE | FAULT_HALL), None);
+        }
+    }
+
+    #[test]
+    fn test_fault_exit_requires_cleared_cause() {
+        let mut machine = machine_in(DriveState::Fault);
+
+        // 要因が残っている間は待機に戻れない
+        assert!(machine.transition(DriveState::Idle).is_err());
+        // 故障から直接始動はできない
+        machine.set_faults(0);
+        assert!(machine.transition(DriveState::Precharge).is_err());
+        assert_eq!(machine.state(), DriveState::Fault);
+
+        assert_eq!(machine.transition(DriveState::Idle), Ok(true));
+        assert_eq!(machine.set_faults(0), None);
+        assert_eq!(machine.state(), DriveState::Idle);
+    }
+
+    #[test]
+    fn test_requests() {
+        assert!(DriveState::Idle.accepts(DriveRequest::Start));
+        assert!(DriveState::Stopping.accepts(DriveRequest::Start));
+        assert!(!DriveState::Fault.accepts(DriveRequest::Start));
+
+        // 停止・制御モードの要求はどの状態でも受け付ける
+        assert!(DriveState::Fault.accepts(DriveRequest::Stop));
+        assert!(DriveState::Running.accepts(DriveRequest::Stop));
+        assert!(DriveState::Fault.accepts(DriveRequest::Mode(ControlMode::Torque)));
+        assert!(DriveState::Calibrating.accepts(DriveRequest::Mode(ControlMode::Voltage)));
+
+        let calibrate = DriveRequest::Calibrate { torque: 20 };
+        assert!(DriveState::Idle.accepts(calibrate));
+        assert!(DriveState::Running.accepts(calibrate));
+        assert!(!DriveState::Calibrating.accepts(calibrate));
+        assert!(!DriveState::Fault.accepts(calibrate));
+
+        let identify = DriveRequest::Identify {
+            current: 2.0,
+            speed: 1000.0,
+        };
+        assert!(DriveState::Running.accepts(identify));
+        assert!(!DriveState::Calibrating.accepts(identify));
+        assert!(!DriveState::Fault.accepts(identify));
+
+        let autotune = DriveRequest::AutoTune {
+            speed: 1000.0,
+            relay_ratio: 0.2,
+        };
+        assert!(DriveState::Running.accepts(autotune));
+        assert!(!DriveState::Starting.accepts(autotune));
+        assert!(!DriveState::Idle.accepts(autotune));
+    }
+
+    #[test]
+    fn test_voltage_faults() {
+        assert_eq!(voltage_faults(false, false), 0);
+        assert_eq!(voltage_faults(true, false), FAULT_OVERVOLTAGE);
+        assert_eq!(
+            voltage_faults(true, true),
+            FAULT_OVERVOLTAGE | FAULT_UNDERVOLTAGE
+        );
+    }
+
+    #[test]
+    fn test_time_in_state() {
+        let mut machine = machine_in(DriveState::Precharge);
+        machine.update(0.5);
+        machine.update(0.25);
+        assert!((machine.time_in_state() - 0.75).abs() < 1e-6);
+
+        machine.transition(DriveState::Stopping).unwrap();
+        assert_eq!(machine.time_in_state(), 0.0);
+    }
+
+    #[test]
+    fn test_state_from_u8() {
+        for value in 0..8u8 {
+            assert_eq!(DriveState::from_u8(value).map(|s| s as u8), Some(value));
+        }
+        assert_eq!(DriveState::from_u8(8), None);
+    }
+}
@@ -2,23 +2,63 @@
 // Hall sensor-based FOC implementation for BLDC motor control
 
 pub mod calibration;
+pub mod current_control;
+pub mod current_sensor;
+pub mod dead_time;
+pub mod direct_start;
+pub mod field_weakening;
+pub mod filter;
+pub mod fixed_point;
+pub mod flux_observer;
+pub mod hall_diagnostics;
 pub mod hall_sensor;
+pub mod loop_scheduler;
+pub mod motion_profile;
+pub mod motor_identification;
 pub mod openloop_six_step;
+pub mod openloop_vf;
 pub mod pi_controller;
 pub mod shaft_position;
+pub mod speed_autotune;
+pub mod speed_feedforward;
+pub mod startup_handover;
 pub mod svpwm;
 pub mod transforms;
 
 // Re-export main types for easier access
 pub use calibration::{CalibrationResult, MotorCalibration};
+#[cfg(not(feature = "fixed-point"))]
+pub use current_control::CurrentController;
+#[cfg(feature = "fixed-point")]
+pub use current_control::FixedCurrentController;
+pub use current_sensor::CurrentSensor;
+pub use dead_time::{DeadTimeCompensation, DeadTimeCompensator};
+pub use direct_start::{DirectStartMonitor, DirectStartStatus};
+pub use field_weakening::FieldWeakening;
+pub use filter::{FilterType, SignalFilter};
+pub use flux_observer::FluxObserver;
+pub use hall_diagnostics::{HallDiagnostics, HallDiagnosticsStatus, HallSample};
 pub use hall_sensor::HallSensor;
+pub use loop_scheduler::{LoopScheduler, LoopTicks};
+pub use motion_profile::MotionProfile;
+pub use motor_identification::{
+    IdentificationFailure, IdentificationResult, IdentificationState, MotorIdentification,
+};
 pub use openloop_six_step::OpenLoopSixStep;
-pub use pi_controller::PiController;
-pub use svpwm::calculate_svpwm;
-pub use transforms::{inverse_park, limit_voltage};
+pub use openloop_vf::OpenLoopVf;
+pub use pi_controller::{GainSchedule, PiController};
+pub use speed_autotune::{AutoTuneFailure, AutoTuneResult, AutoTuneState, SpeedAutoTune};
+pub use speed_feedforward::SpeedFeedforward;
+pub use startup_handover::StartupHandover;
+pub use svpwm::{calculate_sinusoidal_pwm, Modulator};
+#[cfg(not(feature = "fixed-point"))]
+pub use transforms::park;
+pub use transforms::{clarke, inverse_park, limit_voltage};
 
 // Benchmark function for performance testing
 #[cfg(not(test))]
+pub use fixed_point::benchmark_inverse_park as benchmark_inverse_park_fixed_point;
+#[cfg(not(test))]
 pub use transforms::benchmark_inverse_park;
 
 /// モーター制御モード
@@ -30,4 +70,77 @@ pub enum ControlMode {
     ClosedLoopFoc,
     /// キャリブレーションモード（電気角オフセット・回転方向の自動検出）
     Calibration,
+    /// パラメータ同定モード（抵抗・インダクタンス・鎖交磁束・慣性モーメント・摩擦の自動測定）
+    Identification,
+    /// 速度PIゲイン自動調整モード（リレーフィードバック、速度制御中のみ開始、終了後は速度制御に戻る）
+    AutoTune,
+    /// トルク制御（q軸電流指令を直接追従、速度ループなし、電流制御が必要）
+    Torque,
+    /// 電圧制御（d/q軸電圧指令を直接出力、電流・速度ループなし）
+    Voltage,
+    /// 位置制御（位置P → 速度PIのカスケード、複数回転対応）
+    Position,
+}
+
+/// 電気角・速度の取得元
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AngleSource {
+    /// Hallセンサーのみ（Hall状態が無効な場合は停止）
+    Hall = 0,
+    /// センサレス磁束オブザーバのみ（オープンループ始動後に切り替え、Hallは使用しない）
+    Sensorless = 1,
+    /// Hallセンサー優先、Hall状態が無効な場合は最低速度以上でオブザーバにフォールバック
+    HallWithFallback = 2,
+}
+
+impl AngleSource {
+    /// 設定値から変換（0 = Hall、1 = センサレス、2 = Hall＋フォールバック、それ以外は`None`）
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Hall),
+            1 => Some(Self::Sensorless),
+            2 => Some(Self::HallWithFallback),
+            _ => None,
+        }
+    }
+}
+
+/// Hallセンサーの角度・速度推定方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HallEstimator {
+    /// エッジ周期の指数移動平均フィルタ＋最終エッジからの外挿
+    Filter = 0,
+    /// エッジのタイムスタンプを入力とするType-2 PLL（帯域を設定可能）
+    Pll = 1,
+}
+
+impl HallEstimator {
+    /// 設定値から変換（0 = フィルタ、1 = PLL、それ以外は`None`）
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Filter),
+            1 => Some(Self::Pll),
+            _ => None,
+        }
+    }
+}
+
+/// オープンループ始動の駆動方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OpenLoopMode {
+    /// 6ステップ駆動（台形波、デューティ比固定）
+    SixStep = 0,
+    /// 正弦波V/f駆動（ブースト電圧 + 電気周波数に比例する電圧）
+    SineVf = 1,
+}
+
+impl OpenLoopMode {
+    /// 設定値から変換（0 = 6ステップ、1 = 正弦波V/f、それ以外は`None`）
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::SixStep),
+            1 => Some(Self::SineVf),
+            _ => None,
+        }
+    }
 }
@@ -4,9 +4,8 @@
 //! 自動的に検出するキャリブレーション機能を提供します。
 
 use super::shaft_position::ShaftPosition;
-use crate::fmt::*;
 use crate::hall_tim;
-use core::f32::consts::TAU;
+use core::f32::consts::{FRAC_PI_2, TAU};
 
 /// キャリブレーション状態
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -32,6 +31,10 @@ pub struct CalibrationResult {
     pub direction_inversed: bool,
     /// キャリブレーション成功フラグ
     pub success: bool,
+    /// 各Hallセクターの開始電気角 [rad]（正転時にセクターに入る境界、インデックス = Hall状態 - 1）
+    pub sector_angles: [f32; 6],
+    /// セクター境界テーブル有効フラグ（全セクターの境界を正転で測定できた場合のみtrue）
+    pub sector_table_valid: bool,
 }
 
 impl CalibrationResult {
@@ -41,6 +44,8 @@ impl CalibrationResult {
             electrical_offset: 0.0,
             direction_inversed: false,
             success: false,
+            sector_angles: [0.0; 6],
+            sector_table_valid: false,
         }
     }
 }
@@ -67,6 +72,8 @@ pub struct MotorCalibration {
     result: CalibrationResult,
     /// 各Hallセクターでの角度記録 [rad]（インデックス0は未使用、1-6がセクター1-6）
     sector_angles: [Option<f32>; 7],
+    /// 各Hallセクターに入った時点のロータ電気角 [rad]（インデックス0は未使用、1-6がセクター1-6）
+    sector_boundaries: [Option<f32>; 7],
     /// 前回のHallセクター（セクター遷移検出用）
     prev_hall_sector: u8,
     /// 現在のセクターでの待機カウンター（角度安定化のため）
@@ -88,6 +95,7 @@ impl MotorCalibration {
             shaft_position_act: ShaftPosition::new(),
             result: CalibrationResult::new(),
             sector_angles: [None; 7],
+            sector_boundaries: [None; 7],
             prev_hall_sector: 0,
             sector_wait_counter: 0,
         }
@@ -95,15 +103,12 @@ impl MotorCalibration {
 
     /// キャリブレーションを開始
     pub fn start(&mut self) {
-        info!("Starting motor calibration...");
-        info!("  Pole pairs: {}", self.pole_pairs);
-        info!("  Torque: {}", self.torque);
-
         self.state = CalibrationState::Init;
         self.shaft_position_req.reset();
         self.shaft_position_act.reset();
         self.result = CalibrationResult::new();
         self.sector_angles = [None; 7];
+        self.sector_boundaries = [None; 7];
         self.prev_hall_sector = 0;
         self.sector_wait_counter = 0;
     }
@@ -138,41 +143,23 @@ impl MotorCalibration {
 
         match self.state {
             CalibrationState::Init => {
-                info!("Calibration: Init");
                 self.shaft_position_req.reset();
                 self.shaft_position_act.reset();
                 self.result.electrical_offset = 0.0;
                 self.sector_angles = [None; 7];
+                self.sector_boundaries = [None; 7];
                 self.prev_hall_sector = 0;
                 self.sector_wait_counter = 0;
                 self.state = CalibrationState::FindDirection;
-                info!("Calibration: Init -> FindDirection");
                 Ok((0.0, 0.0))
             }
 
             CalibrationState::FindDirection => {
-                // デバッグ：定期的に状態をログ出力（2500サイクルごと = 1秒）
-                static mut DEBUG_COUNTER_FD: u32 = 0;
-                unsafe {
-                    DEBUG_COUNTER_FD += 1;
-                    if DEBUG_COUNTER_FD >= 2500 {
-                        DEBUG_COUNTER_FD = 0;
-                        info!(
-                            "[Calibration FindDirection] Req: {} rot + {} rad, Act: {} rot + {} rad",
-                            self.shaft_position_req.rotations,
-                            self.shaft_position_req.angle,
-                            self.shaft_position_act.rotations,
-                            self.shaft_position_act.angle
-                        );
-                    }
-                }
-
                 // 目標: 1回転以上（1電気角回転）
                 if self.shaft_position_req.rotations >= 1 {
                     // モーターが動いたかチェック
                     if self.shaft_position_act.rotations == 0 && self.shaft_position_act.angle < 0.1
                     {
-                        error!("Calibration failed: Motor did not move");
                         self.state = CalibrationState::Completed;
                         self.result.success = false;
                         return Err(());
@@ -182,17 +169,14 @@ impl MotorCalibration {
                     let actual_position = self.shaft_position_act.get_position();
                     if actual_position < 0.0 {
                         // センサーが逆方向
-                        info!("Direction: INVERSED (sensor is reversed)");
                         self.shaft_position_act.set_inversed(true);
                         self.result.direction_inversed = true;
                     } else {
-                        info!("Direction: NORMAL");
                         self.shaft_position_act.set_inversed(false);
                         self.result.direction_inversed = false;
                     }
 
                     self.state = CalibrationState::MeasureSectors;
-                    info!("Calibration: FindDirection -> MeasureSectors");
                 } else {
                     // ゆっくり回転（5 rad/s ≈ 48 RPM）- より遅く
                     // 2.5kHz更新なので、1ステップあたり: 5 / 2500 = 0.002 rad
@@ -208,32 +192,18 @@ impl MotorCalibration {
                 // 現在のHallセクターを取得（1-6）
                 let current_hall = hall_tim::get_hall_state();
 
-                // デバッグ：定期的に状態をログ出力（2500サイクルごと = 1秒）
-                static mut DEBUG_COUNTER: u32 = 0;
-                unsafe {
-                    DEBUG_COUNTER += 1;
-                    if DEBUG_COUNTER >= 2500 {
-                        DEBUG_COUNTER = 0;
-                        let recorded_count =
-                            (1..=6).filter(|&i| self.sector_angles[i].is_some()).count();
-                        info!(
-                            "[Calibration Debug] Hall={}, Req pos={} rad, Act pos={} rad, Recorded: {}/6 sectors",
-                            current_hall,
-                            self.shaft_position_req.get_position(),
-                            self.shaft_position_act.get_position(),
-                            recorded_count
-                        );
-                    }
-                }
-
                 // 有効なHallセクターかチェック
                 if (1..=6).contains(&current_hall) {
                     // セクターが変わったかチェック
                     if current_hall != self.prev_hall_sector {
-                        info!(
-                            "Calibration: Entered Hall sector {}, waiting for stabilization...",
-                            current_hall
-                        );
+                        // 最初のセクターは途中から測定を始めるため、境界は次回以降の遷移で記録する
+                        if self.prev_hall_sector != 0
+                            && self.sector_boundaries[current_hall as usize].is_none()
+                        {
+                            let boundary = self.rotor_electrical_angle();
+                            self.sector_boundaries[current_hall as usize] = Some(boundary);
+                        }
+
                         self.prev_hall_sector = current_hall;
                         self.sector_wait_counter = 0;
                     }
@@ -245,21 +215,17 @@ impl MotorCalibration {
                         // このセクターの角度をまだ記録していない場合
                         let angle = self.shaft_position_act.get_angle();
                         self.sector_angles[current_hall as usize] = Some(angle);
-                        info!(
-                            "Calibration: Recorded angle for sector {}: {} rad ({} deg)",
-                            current_hall,
-                            angle,
-                            angle * 180.0 / core::f32::consts::PI
-                        );
-
-                        // 全セクターの角度が記録されたかチェック
-                        let all_recorded = (1..=6).all(|i| self.sector_angles[i].is_some());
-                        if all_recorded {
-                            // オフセットを計算
-                            self.calculate_offset();
-                            self.state = CalibrationState::ReturnToStart;
-                            info!("Calibration: MeasureSectors -> ReturnToStart");
-                        }
+                    }
+
+                    // 全セクターの角度と境界が記録されたかチェック
+                    let all_recorded = (1..=6).all(|i| {
+                        self.sector_angles[i].is_some() && self.sector_boundaries[i].is_some()
+                    });
+                    if all_recorded {
+                        // オフセットとセクター境界テーブルを計算
+                        self.calculate_offset();
+                        self.build_sector_table();
+                        self.state = CalibrationState::ReturnToStart;
                     }
                 }
 
@@ -275,14 +241,6 @@ impl MotorCalibration {
                 if self.shaft_position_req.rotations == 0
                     && self.shaft_position_req.angle < TAU / 4.0
                 {
-                    info!("Calibration completed successfully!");
-                    info!(
-                        "  Electrical offset: {} rad ({} deg)",
-                        self.result.electrical_offset,
-                        self.result.electrical_offset * 180.0 / core::f32::consts::PI
-                    );
-                    info!("  Direction inversed: {}", self.result.direction_inversed);
-
                     self.state = CalibrationState::Completed;
                     self.result.success = true;
                     Ok((0.0, 0.0)) // トルク0で停止
@@ -309,6 +267,39 @@ impl MotorCalibration {
         self.torque = torque.clamp(0.1, 0.5);
     }
 
+    /// 極対数を設定（次回の`start`から使用）
+    ///
+    /// # 引数
+    /// * `pole_pairs` - モーターの極対数
+    pub fn set_pole_pairs(&mut self, pole_pairs: u8) {
+        self.pole_pairs = pole_pairs;
+    }
+
+    /// 要求位置に引き込まれたロータの電気角 [rad]（0～2π）
+    ///
+    /// q軸電圧のみを印加しているため、ステータ磁界（＝ロータのd軸）は
+    /// 指令電気角より90°進んだ位置にある。低速で回転させているので遅れは無視する。
+    fn rotor_electrical_angle(&self) -> f32 {
+        let command_angle = self.shaft_position_req.get_angle() * self.pole_pairs as f32;
+        ShaftPosition::clamp(command_angle + FRAC_PI_2)
+    }
+
+    /// 各セクターに入った境界角からセクター境界テーブルを作成
+    ///
+    /// センサー方向が反転している場合は、正転方向のセクター開始角が得られないため作成しない。
+    fn build_sector_table(&mut self) {
+        if self.result.direction_inversed {
+            self.result.sector_table_valid = false;
+            return;
+        }
+
+        for sector in 1..=6 {
+            let angle = self.sector_boundaries[sector].unwrap_or(0.0);
+            self.result.sector_angles[sector - 1] = angle;
+        }
+        self.result.sector_table_valid = true;
+    }
+
     /// 各セクターで記録した角度から電気角オフセットを計算
     fn calculate_offset(&mut self) {
         // 各セクターの期待される機械角（rad）
@@ -323,7 +314,6 @@ impl MotorCalibration {
             5.0 * core::f32::consts::PI / 3.0, // セクター6: 300°
         ];
 
-        info!("Calculating electrical offset from sector angles:");
         let mut offset_sum = 0.0;
         let mut count = 0;
 
@@ -345,17 +335,6 @@ impl MotorCalibration {
                     offset += TAU;
                 }
 
-                info!(
-                    "  Sector {}: measured={}° ({} rad), expected={}° ({} rad), offset={}° ({} rad)",
-                    sector,
-                    measured_angle * 180.0 / core::f32::consts::PI,
-                    measured_angle,
-                    EXPECTED_ANGLES[sector] * 180.0 / core::f32::consts::PI,
-                    EXPECTED_ANGLES[sector],
-                    offset * 180.0 / core::f32::consts::PI,
-                    offset
-                );
-
                 offset_sum += offset;
                 count += 1;
             }
@@ -367,14 +346,7 @@ impl MotorCalibration {
 
             // 0～2πに正規化
             self.result.electrical_offset = ShaftPosition::clamp(average_offset);
-
-            info!(
-                "Average electrical offset: {} rad ({} deg)",
-                self.result.electrical_offset,
-                self.result.electrical_offset * 180.0 / core::f32::consts::PI
-            );
         } else {
-            error!("No sector angles recorded, using offset=0");
             self.result.electrical_offset = 0.0;
         }
     }
@@ -399,6 +371,24 @@ mod tests {
         assert!(!cal.get_result().success);
     }
 
+    #[test]
+    fn test_sector_table_from_boundaries() {
+        let mut cal = MotorCalibration::new(2, 0.2);
+        let boundaries = [0.1, 1.2, 2.3, 3.4, 4.5, 5.6];
+        for (sector, angle) in boundaries.iter().enumerate() {
+            cal.sector_boundaries[sector + 1] = Some(*angle);
+        }
+
+        cal.build_sector_table();
+        assert!(cal.get_result().sector_table_valid);
+        assert_eq!(cal.get_result().sector_angles, boundaries);
+
+        // センサー方向が反転している場合はテーブルを作成しない
+        cal.result.direction_inversed = true;
+        cal.build_sector_table();
+        assert!(!cal.get_result().sector_table_valid);
+    }
+
     #[test]
     fn test_torque_clamping() {
         let mut cal = MotorCalibration::new(6, 0.8); // 0.8は高すぎる
@@ -0,0 +1,314 @@
+// d/q-axis current controller (inner loop under the speed controller)
+
+use libm::sqrtf;
+
+#[cfg(any(feature = "fixed-point", test))]
+use super::fixed_point::{FixedPiController, PerUnit, Q15};
+use super::pi_controller::PiController;
+
+/// Cascaded d/q current controller
+///
+/// Runs independent PI controllers on Id and Iq and limits the resulting
+/// voltage vector to the available voltage. The d-axis has priority so that
+/// the flux-producing component is never starved when the q-axis saturates.
+///
+/// With the `fixed-point` feature the control loop uses
+/// `FixedCurrentController` instead, and this controller only serves the
+/// motor identification.
+pub struct CurrentController {
+    /// d-axis current PI (output: Vd [V])
+    id_pi: PiController,
+    /// q-axis current PI (output: Vq [V])
+    iq_pi: PiController,
+    /// Maximum voltage vector magnitude [V]
+    voltage_limit: f32,
+}
+
+impl CurrentController {
+    /// Create a new current controller
+    ///
+    /// # Arguments
+    /// * `kp` - Proportional gain [V/A] (shared by both axes)
+    /// * `ki` - Integral gain [V/(A·s)] (shared by both axes)
+    /// * `voltage_limit` - Maximum voltage vector magnitude [V]
+    pub fn new(kp: f32, ki: f32, voltage_limit: f32) -> Self {
+        let mut id_pi = PiController::new_symmetric(kp, ki, voltage_limit);
+        let mut iq_pi = PiController::new_symmetric(kp, ki, voltage_limit);
+        // Saturation at the voltage limit is expected at high speed,
+        // so stop integrating there to avoid overshoot on recovery.
+        id_pi.set_anti_windup(true);
+        iq_pi.set_anti_windup(true);
+
+        Self {
+            id_pi,
+            iq_pi,
+            voltage_limit,
+        }
+    }
+
+    /// Update the current controllers
+    ///
+    /// # Arguments
+    /// * `id_ref` - d-axis current reference [A]
+    /// * `iq_ref` - q-axis current reference [A]
+    /// * `id` - Measured d-axis current [A]
+    /// * `iq` - Measured q-axis current [A]
+    /// * `dt` - Time step (seconds)
+    ///
+    /// # Returns
+    /// Tuple of (vd, vq) with |(vd, vq)| <= voltage_limit
+    pub fn update(&mut self, id_ref: f32, iq_ref: f32, id: f32, iq: f32, dt: f32) -> (f32, f32) {
+        let vd = self.id_pi.update(id_ref, id, dt);
+
+        // q-axis gets whatever voltage remains after the d-axis demand
+        let vq_limit = sqrtf((self.voltage_limit * self.voltage_limit - vd * vd).max(0.0));
+        self.iq_pi.set_symmetric_limit(vq_limit);
+        let vq = self.iq_pi.update(iq_ref, iq, dt);
+
+        (vd, vq)
+    }
+
+    /// Reset both integrators
+    pub fn reset(&mut self) {
+        self.id_pi.reset();
+        self.iq_pi.reset();
+    }
+
+    /// Set the PI gains for both axes
+    ///
+    /// # Arguments
+    /// * `kp` - Proportional gain [V/A]
+    /// * `ki` - Integral gain [V/(A·s)]
+    #[cfg(not(feature = "fixed-point"))]
+    pub fn set_gains(&mut self, kp: f32, ki: f32) {
+        self.id_pi.set_gains(kp, ki);
+        self.iq_pi.set_gains(kp, ki);
+    }
+
+    /// Set the back-calculation tracking gain for both axes
+    ///
+    /// Replaces the conditional integration at the voltage limit when nonzero.
+    ///
+    /// # Arguments
+    /// * `tracking_gain` - Tracking gain [1/s] (0 = stop integrating when saturated)
+    #[cfg(not(feature = "fixed-point"))]
+    pub fn set_tracking_gain(&mut self, tracking_gain: f32) {
+        self.id_pi.set_tracking_gain(tracking_gain);
+        self.iq_pi.set_tracking_gain(tracking_gain);
+    }
+
+    /// Set the maximum voltage vector magnitude
+    ///
+    /// # Arguments
+    /// * `voltage_limit` - Maximum voltage [V]
+    #[cfg(not(feature = "fixed-point"))]
+    pub fn set_voltage_limit(&mut self, voltage_limit: f32) {
+        self.voltage_limit = voltage_limit;
+        self.id_pi.set_symmetric_limit(voltage_limit);
+        self.iq_pi.set_symmetric_limit(voltage_limit);
+    }
+}
+
+/// Cascaded d/q current controller in fixed point (`fixed-point` feature)
+///
+/// Same structure as `CurrentController` (d-axis priority, voltage vector
+/// limit) on Q15 per-unit currents and voltages (`PerUnit`), using
+/// `FixedPiController` for both axes. The gains are given in physical units
+/// and converted with the per-unit bases and the loop period.
+///
+/// `FixedPiController` clamps its integral to the output limit, which
+/// replaces the conditional integration of the f32 controller; there is no
+/// back-calculation tracking gain.
+#[cfg(any(feature = "fixed-point", test))]
+pub struct FixedCurrentController {
+    /// d-axis current PI (output: Vd, per unit)
+    id_pi: FixedPiController,
+    /// q-axis current PI (output: Vq, per unit)
+    iq_pi: FixedPiController,
+    /// Per-unit bases of currents and voltages
+    per_unit: PerUnit,
+    /// Proportional gain [V/A]
+    kp: f32,
+    /// Integral gain [V/(A·s)]
+    ki: f32,
+    /// Loop period the integral gain was scaled with (seconds)
+    dt: f32,
+    /// Maximum voltage vector magnitude [V]
+    voltage_limit: f32,
+    /// Maximum voltage vector magnitude (per unit)
+    voltage_limit_pu: Q15,
+}
+
+#[cfg(any(feature = "fixed-point", test))]
+impl FixedCurrentController {
+    /// Create a new fixed-point current controller
+    ///
+    /// # Arguments
+    /// * `kp` - Proportional gain [V/A] (shared by both axes)
+    /// * `ki` - Integral gain [V/(A·s)] (shared by both axes)
+    /// * `voltage_limit` - Maximum voltage vector magnitude [V]
+    /// * `per_unit` - Per-unit bases of currents and voltages
+    pub fn new(kp: f32, ki: f32, voltage_limit: f32, per_unit: PerUnit) -> Self {
+        let mut controller = Self {
+            id_pi: FixedPiController::new(0.0, 0.0, 0.0, 0.0),
+            iq_pi: FixedPiController::new(0.0, 0.0, 0.0, 0.0),
+            per_unit,
+            kp,
+            ki,
+            dt: 0.0,
+            voltage_limit: 0.0,
+            voltage_limit_pu: 0,
+        };
+        controller.set_voltage_limit(voltage_limit);
+        controller
+    }
+
+    /// Update the current controllers
+    ///
+    /// # Arguments
+    /// * `id_ref` - d-axis current reference (per unit)
+    /// * `iq_ref` - q-axis current reference (per unit)
+    /// * `id` - Measured d-axis current (per unit)
+    /// * `iq` - Measured q-axis current (per unit)
+    /// * `dt` - Time step (seconds)
+    ///
+    /// # Returns
+    /// Tuple of (vd, vq) (per unit) with |(vd, vq)| <= voltage_limit
+    pub fn update(&mut self, id_ref: Q15, iq_ref: Q15, id: Q15, iq: Q15, dt: f32) -> (Q15, Q15) {
+        // The integral gain is scaled with the loop period, so rescale only when it changes
+        if dt != self.dt {
+            self.dt = dt;
+            self.scale_gains();
+        }
+
+        let vd = self.id_pi.update(id_ref, id);
+
+        // q-axis gets whatever voltage remains after the d-axis demand
+        let limit = self.voltage_limit_pu as i32;
+        let vq_limit = (limit * limit - vd as i32 * vd as i32).max(0) as u32;
+        self.iq_pi.set_limit(vq_limit.isqrt() as Q15);
+        let vq = self.iq_pi.update(iq_ref, iq);
+
+        (vd, vq)
+    }
+
+    /// Reset both integrators
+    pub fn reset(&mut self) {
+        self.id_pi.reset();
+        self.iq_pi.reset();
+    }
+
+    /// Set the PI gains for both axes
+    ///
+    /// # Arguments
+    /// * `kp` - Proportional gain [V/A]
+    /// * `ki` - Integral gain [V/(A·s)]
+    pub fn set_gains(&mut self, kp: f32, ki: f32) {
+        self.kp = kp;
+        self.ki = ki;
+        self.scale_gains();
+    }
+
+    /// Set the maximum voltage vector magnitude
+    ///
+    /// # Arguments
+    /// * `voltage_limit` - Maximum voltage [V]
+    pub fn set_voltage_limit(&mut self, voltage_limit: f32) {
+        self.voltage_limit = voltage_limit;
+        self.voltage_limit_pu = self.per_unit.voltage(voltage_limit).max(0);
+        self.id_pi.set_limit(self.voltage_limit_pu);
+        self.iq_pi.set_limit(self.voltage_limit_pu);
+    }
+
+    /// Set the per-unit bases
+    ///
+    /// The integrators hold per-unit voltages, so they are reset when the
+    /// bases change.
+    ///
+    /// # Arguments
+    /// * `per_unit` - Per-unit bases of currents and voltages
+    pub fn set_per_unit(&mut self, per_unit: PerUnit) {
+        if per_unit != self.per_unit {
+            self.per_unit = per_unit;
+            self.scale_gains();
+            self.set_voltage_limit(self.voltage_limit);
+            self.reset();
+        }
+    }
+
+    /// Get the per-unit bases
+    pub fn per_unit(&self) -> PerUnit {
+        self.per_unit
+    }
+
+    /// Convert the gains to per unit and apply them to both axes
+    fn scale_gains(&mut self) {
+        let impedance_base = self.per_unit.impedance_base();
+        let (kp, ki) = (self.kp / impedance_base, self.ki / impedance_base);
+        self.id_pi.set_gains(kp, ki, self.dt);
+        self.iq_pi.set_gains(kp, ki, self.dt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracks_reference_with_proportional_gain() {
+        let mut cc = CurrentController::new(2.0, 0.0, 24.0);
+        let (vd, vq) = cc.update(0.0, 1.5, 0.5, 0.5, 0.001);
+        assert!((vd - -1.0).abs() < 1e-6);
+        assert!((vq - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_voltage_vector_limited_with_d_priority() {
+        let mut cc = CurrentController::new(100.0, 0.0, 10.0);
+        let (vd, vq) = cc.update(0.06, 10.0, 0.0, 0.0, 0.001);
+        // vd = 6V, remaining for vq = sqrt(100 - 36) = 8V
+        assert!((vd - 6.0).abs() < 1e-4);
+        assert!((vq - 8.0).abs() < 1e-4);
+        assert!(sqrtf(vd * vd + vq * vq) <= 10.0 + 1e-4);
+    }
+    #[test]
+    fn test_fixed_point_matches_f32() {
+        let per_unit = PerUnit::new(40.0, 24.0);
+        let mut float = CurrentController::new(0.5, 200.0, 12.0);
+        let mut fixed = FixedCurrentController::new(0.5, 200.0, 12.0, per_unit);
+
+        // Both controllers see the same measurement, taken from the f32 output
+        let (mut id, mut iq) = (0.0, 0.0);
+        for _ in 0..2000 {
+            let (vd, vq) = float.update(-0.5, 3.0, id, iq, 0.00004);
+            let (vd_pu, vq_pu) = fixed.update(
+                per_unit.current(-0.5),
+                per_unit.current(3.0),
+                per_unit.current(id),
+                per_unit.current(iq),
+                0.00004,
+            );
+            assert!((per_unit.volts(vd_pu) - vd).abs() < 0.02);
+            assert!((per_unit.volts(vq_pu) - vq).abs() < 0.02);
+            id += (vd - id) * 0.01;
+            iq += (vq - iq) * 0.01;
+        }
+    }
+
+    #[test]
+    fn test_fixed_point_voltage_vector_limited_with_d_priority() {
+        let per_unit = PerUnit::new(40.0, 24.0);
+        let mut cc = FixedCurrentController::new(10.0, 0.0, 10.0, per_unit);
+        let (vd, vq) = cc.update(per_unit.current(0.6), per_unit.current(10.0), 0, 0, 0.001);
+        // vd = 6V, remaining for vq = sqrt(100 - 36) = 8V
+        assert!((per_unit.volts(vd) - 6.0).abs() < 0.02);
+        assert!((per_unit.volts(vq) - 8.0).abs() < 0.02);
+
+        // Changing the bases keeps the physical limit
+        let per_unit = PerUnit::new(20.0, 12.0);
+        cc.set_per_unit(per_unit);
+        let (vd, vq) = cc.update(0, per_unit.current(10.0), 0, 0, 0.001);
+        assert_eq!(vd, 0);
+        assert!((per_unit.volts(vq) - 10.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,216 @@
+// Phase current sensing: ADC offset calibration and conversion to amperes
+//
+// Raw samples come from the TIM1-synchronized injected ADC conversions
+// (see current_sense.rs). This module is hardware independent so the
+// scaling and offset logic can be tested on the host.
+
+/// ADC full-scale count (12-bit)
+const ADC_FULL_SCALE: f32 = 4096.0;
+
+/// ADC reference voltage [V]
+const ADC_VREF: f32 = 3.3;
+
+/// Nominal zero-current ADC count (amplifier biased at mid-rail)
+const ADC_MID_SCALE: f32 = ADC_FULL_SCALE / 2.0;
+
+/// Maximum allowed deviation of a measured offset from mid-scale [counts]
+///
+/// Anything larger indicates a disconnected amplifier or current flowing
+/// during calibration, so the measurement is rejected.
+const MAX_OFFSET_DEVIATION: f32 = 400.0;
+
+/// Three-phase current sensor with zero-current offset calibration
+pub struct CurrentSensor {
+    /// Zero-current ADC offsets for U/V/W [counts]
+    offsets: [f32; 3],
+    /// Conversion factor from ADC counts to amperes
+    amps_per_count: f32,
+    /// Offset calibration accumulator
+    accumulator: [u32; 3],
+    /// Number of samples accumulated so far
+    sample_count: u16,
+    /// Number of samples to average (0 = not calibrating)
+    target_samples: u16,
+    /// True once a valid offset calibration has completed
+    calibrated: bool,
+}
+
+impl CurrentSensor {
+    /// Create a new current sensor
+    ///
+    /// # Arguments
+    /// * `shunt_resistance` - Shunt resistance [Ω]
+    /// * `amp_gain` - Current sense amplifier gain [V/V]
+    pub fn new(shunt_resistance: f32, amp_gain: f32) -> Self {
+        let mut sensor = Self {
+            offsets: [ADC_MID_SCALE; 3],
+            amps_per_count: 0.0,
+            accumulator: [0; 3],
+            sample_count: 0,
+            target_samples: 0,
+            calibrated: false,
+        };
+        sensor.set_scaling(shunt_resistance, amp_gain);
+        sensor
+    }
+
+    /// Update the count-to-ampere scaling
+    ///
+    /// # Arguments
+    /// * `shunt_resistance` - Shunt resistance [Ω]
+    /// * `amp_gain` - Current sense amplifier gain [V/V]
+    pub fn set_scaling(&mut self, shunt_resistance: f32, amp_gain: f32) {
+        let denominator = shunt_resistance * amp_gain;
+        self.amps_per_count = if denominator > 0.0 {
+            ADC_VREF / ADC_FULL_SCALE / denominator
+        } else {
+            0.0
+        };
+    }
+
+    /// Start zero-current offset calibration
+    ///
+    /// The PWM outputs must be disabled (no phase current) while samples are fed.
+    ///
+    /// # Arguments
+    /// * `samples` - Number of samples to average
+    pub fn start_offset_calibration(&mut self, samples: u16) {
+        self.accumulator = [0; 3];
+        self.sample_count = 0;
+        self.target_samples = samples.max(1);
+        self.calibrated = false;
+    }
+
+    /// Check if offset calibration is in progress
+    pub fn is_calibrating(&self) -> bool {
+        self.target_samples != 0
+    }
+
+    /// Check if a valid offset calibration has completed
+    pub fn is_calibrated(&self) -> bool {
+        self.calibrated
+    }
+
+    /// Feed one raw sample into the offset calibration
+    ///
+    /// # Arguments
+    /// * `raw` - Raw ADC counts for U/V/W
+    ///
+    /// # Returns
+    /// `true` when calibration has finished (check `is_calibrated()` for the result)
+    pub fn feed_offset_sample(&mut self, raw: [u16; 3]) -> bool {
+        if !self.is_calibrating() {
+            return true;
+        }
+
+        for (acc, &value) in self.accumulator.iter_mut().zip(raw.iter()) {
+            *acc += value as u32;
+        }
+        self.sample_count += 1;
+
+        if self.sample_count < self.target_samples {
+            return false;
+        }
+
+        let mut offsets = [0.0; 3];
+        for (offset, &acc) in offsets.iter_mut().zip(self.accumulator.iter()) {
+            *offset = acc as f32 / self.sample_count as f32;
+        }
+
+        self.calibrated = offsets
+            .iter()
+            .all(|&offset| (offset - ADC_MID_SCALE).abs() <= MAX_OFFSET_DEVIATION);
+        if self.calibrated {
+            self.offsets = offsets;
+        }
+        self.target_samples = 0;
+
+        true
+    }
+
+    /// Get the zero-current offsets [counts]
+    pub fn offsets(&self) -> [f32; 3] {
+        self.offsets
+    }
+
+    /// Get the largest measurable current [A] (mid-scale to either rail)
+    #[cfg(any(feature = "fixed-point", test))]
+    pub fn full_scale(&self) -> f32 {
+        ADC_MID_SCALE * self.amps_per_count
+    }
+
+    /// Convert raw ADC samples to phase currents
+    ///
+    /// Low-side shunts see a negative voltage when current flows into the
+    /// motor, so the amplifier output drops below the offset for positive current.
+    ///
+    /// # Arguments
+    /// * `raw` - Raw ADC counts for U/V/W
+    ///
+    /// # Returns
+    /// Tuple of (i_u, i_v, i_w) [A], positive into the motor
+    pub fn phase_currents(&self, raw: [u16; 3]) -> (f32, f32, f32) {
+        let i_u = (self.offsets[0] - raw[0] as f32) * self.amps_per_count;
+        let i_v = (self.offsets[1] - raw[1] as f32) * self.amps_per_count;
+        let i_w = (self.offsets[2] - raw[2] as f32) * self.amps_per_count;
+
+        (i_u, i_v, i_w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx_eq(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    #[test]
+    fn test_offset_calibration() {
+        let mut sensor = CurrentSensor::new(0.01, 4.0);
+        sensor.start_offset_calibration(4);
+        assert!(sensor.is_calibrating());
+
+        assert!(!sensor.feed_offset_sample([2000, 2100, 2050]));
+        assert!(!sensor.feed_offset_sample([2002, 2098, 2050]));
+        assert!(!sensor.feed_offset_sample([2000, 2100, 2050]));
+        assert!(sensor.feed_offset_sample([2002, 2098, 2050]));
+
+        assert!(!sensor.is_calibrating());
+        assert!(sensor.is_calibrated());
+        let offsets = sensor.offsets();
+        assert!(approx_eq(offsets[0], 2001.0));
+        assert!(approx_eq(offsets[1], 2099.0));
+        assert!(approx_eq(offsets[2], 2050.0));
+
+        let (i_u, i_v, i_w) = sensor.phase_currents([2001, 2099, 2050]);
+        assert!(approx_eq(i_u, 0.0));
+        assert!(approx_eq(i_v, 0.0));
+        assert!(approx_eq(i_w, 0.0));
+    }
+
+    #[test]
+    fn test_offset_calibration_rejects_out_of_range() {
+        let mut sensor = CurrentSensor::new(0.01, 4.0);
+        sensor.start_offset_calibration(1);
+        assert!(sensor.feed_offset_sample([0, 2048, 2048]));
+        assert!(!sensor.is_calibrated());
+        assert!(approx_eq(sensor.offsets()[0], ADC_MID_SCALE));
+    }
+
+    #[test]
+    fn test_phase_current_scaling() {
+        // 0.01Ω × 4 = 0.04 V/A → 1 count = 3.3 / 4096 / 0.04 A
+        let sensor = CurrentSensor::new(0.01, 4.0);
+        let amps_per_count = 3.3 / 4096.0 / 0.04;
+
+        let (i_u, _, _) = sensor.phase_currents([2048 - 100, 2048, 2048]);
+        assert!(approx_eq(i_u, 100.0 * amps_per_count));
+
+        let (_, i_v, _) = sensor.phase_currents([2048, 2048 + 100, 2048]);
+        assert!(approx_eq(i_v, -100.0 * amps_per_count));
+
+        assert!(approx_eq(sensor.full_scale(), 2048.0 * amps_per_count));
+    }
+}
@@ -0,0 +1,468 @@
+// Inverter dead-time compensation and minimum-pulse handling
+// Corrects the PWM duties produced by SVPWM for the voltage lost during the dead time
+
+use super::transforms::inverse_clarke;
+use libm::roundf;
+
+/// Dead-time compensation method
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeadTimeCompensation {
+    /// No compensation (duties are only clamped to the minimum pulse)
+    Disabled = 0,
+    /// Sign from the measured phase currents (requires current sensing)
+    CurrentSign = 1,
+    /// Sign from the commanded phase voltages (no current sensing needed)
+    VoltageSign = 2,
+}
+
+impl DeadTimeCompensation {
+    /// Convert from the config value (0 = disabled, 1 = current sign, 2 = voltage sign)
+    ///
+    /// # Returns
+    /// `None` for any other value
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Disabled),
+            1 => Some(Self::CurrentSign),
+            2 => Some(Self::VoltageSign),
+            _ => None,
+        }
+    }
+}
+
+/// Convert a timer tick count into a fraction of the PWM period
+///
+/// # Arguments
+/// * `ticks` - Duration in timer clock ticks (e.g. the hardware dead time)
+/// * `pwm_frequency` - PWM frequency (Hz)
+/// * `timer_clock` - Timer clock frequency (Hz)
+///
+/// # Returns
+/// Duration as a fraction of the PWM period (0 if the timer clock is 0)
+pub fn ticks_to_period_ratio(ticks: u16, pwm_frequency: u32, timer_clock: u32) -> f32 {
+    if timer_clock == 0 {
+        return 0.0;
+    }
+    ticks as f32 * pwm_frequency as f32 / timer_clock as f32
+}
+
+/// Sign function with a linear region around zero
+///
+/// Phase currents are noisy near their zero crossings, and the dead-time
+/// voltage error itself fades out there because the output capacitance is
+/// charged by the small current. A hard sign would chatter between full
+/// positive and negative compensation, so the sign ramps linearly inside the band.
+///
+/// # Arguments
+/// * `value` - Input value
+/// * `band` - Half-width of the linear region (0 = hard sign)
+///
+/// # Returns
+/// Smoothed sign in the range -1.0 to 1.0
+pub fn smooth_sign(value: f32, band: f32) -> f32 {
+    if band > 0.0 {
+        (value / band).clamp(-1.0, 1.0)
+    } else if value > 0.0 {
+        1.0
+    } else if value < 0.0 {
+        -1.0
+    } else {
+        0.0
+    }
+}
+
+/// Calculate the phase compensation signs
+///
+/// # Arguments
+/// * `mode` - Compensation method
+/// * `phase_currents` - Measured phase currents (A, positive into the motor)
+/// * `phase_voltages` - Commanded phase voltages (V)
+/// * `current_band` - Linear region of the current sign (A)
+/// * `voltage_band` - Linear region of the voltage sign (V)
+///
+/// # Returns
+/// Tuple of (sign_u, sign_v, sign_w), each in the range -1.0 to 1.0
+pub fn compensation_signs(
+    mode: DeadTimeCompensation,
+    phase_currents: (f32, f32, f32),
+    phase_voltages: (f32, f32, f32),
+    current_band: f32,
+    voltage_band: f32,
+) -> (f32, f32, f32) {
+    let ((u, v, w), band) = match mode {
+        DeadTimeCompensation::Disabled => return (0.0, 0.0, 0.0),
+        DeadTimeCompensation::CurrentSign => (phase_currents, current_band),
+        DeadTimeCompensation::VoltageSign => (phase_voltages, voltage_band),
+    };
+    (
+        smooth_sign(u, band),
+        smooth_sign(v, band),
+        smooth_sign(w, band),
+    )
+}
+
+/// Apply dead-time compensation and minimum-pulse clamping to PWM duties
+///
+/// During the dead time both switches of a leg are off and the freewheeling
+/// diode sets the output: a positive phase current pulls the leg low, a negative
+/// one pulls it high. The average leg voltage therefore loses
+/// `sign(i) * dead_time_ratio * Vdc`, which is added back to the duty here.
+///
+/// Pulses shorter than the minimum pulse are distorted or swallowed by the
+/// dead time, so each duty is then rounded to the nearest value that is either
+/// fully on/off or at least one minimum pulse away from the rails.
+///
+/// # Arguments
+/// * `duties` - Duties from SVPWM (0 to max_duty)
+/// * `signs` - Compensation signs from `compensation_signs` (-1.0 to 1.0)
+/// * `dead_time_ratio` - Dead time as a fraction of the PWM period
+/// * `min_pulse_ratio` - Minimum pulse as a fraction of the PWM period (0 = no clamping)
+/// * `max_duty` - Maximum duty cycle value
+///
+/// # Returns
+/// Tuple of corrected (duty_u, duty_v, duty_w)
+pub fn compensate_duties(
+    duties: (u16, u16, u16),
+    signs: (f32, f32, f32),
+    dead_time_ratio: f32,
+    min_pulse_ratio: f32,
+    max_duty: u16,
+) -> (u16, u16, u16) {
+    let max = max_duty as f32;
+    let dead_time = dead_time_ratio * max;
+    let min_pulse = min_pulse_ratio * max;
+
+    let correct = |duty: u16, sign: f32| -> u16 {
+        let duty = (duty as f32 + sign * dead_time).max(0.0).min(max);
+        clamp_min_pulse(duty, min_pulse, max)
+    };
+
+    (
+        correct(duties.0, signs.0),
+        correct(duties.1, signs.1),
+        correct(duties.2, signs.2),
+    )
+}
+
+/// Dead-time compensation stage between SVPWM and the PWM timer
+///
+/// The dead time and the PWM frequency are fixed when the timer is configured
+/// at boot, so they are set once at construction. The method and the minimum
+/// pulse can be changed at any time.
+pub struct DeadTimeCompensator {
+    /// Compensation method
+    mode: DeadTimeCompensation,
+    /// PWM frequency (Hz)
+    pwm_frequency: u32,
+    /// Timer clock frequency (Hz)
+    timer_clock: u32,
+    /// Dead time as a fraction of the PWM period
+    dead_time_ratio: f32,
+    /// Minimum pulse as a fraction of the PWM period (0 = no clamping)
+    min_pulse_ratio: f32,
+    /// Linear region of the current sign (A)
+    current_band: f32,
+}
+
+impl DeadTimeCompensator {
+    /// Create a new dead-time compensator (compensation disabled, no minimum pulse)
+    ///
+    /// # Arguments
+    /// * `dead_time` - Hardware dead time (timer clock ticks)
+    /// * `pwm_frequency` - PWM frequency (Hz)
+    /// * `timer_clock` - Timer clock frequency (Hz)
+    /// * `current_band` - Linear region of the current sign (A)
+    pub fn new(dead_time: u16, pwm_frequency: u32, timer_clock: u32, current_band: f32) -> Self {
+        Self {
+            mode: DeadTimeCompensation::Disabled,
+            pwm_frequency,
+            timer_clock,
+            dead_time_ratio: ticks_to_period_ratio(dead_time, pwm_frequency, timer_clock),
+            min_pulse_ratio: 0.0,
+            current_band,
+        }
+    }
+
+    /// Set the compensation method
+    ///
+    /// # Arguments
+    /// * `mode` - Compensation method
+    pub fn set_mode(&mut self, mode: DeadTimeCompensation) {
+        self.mode = mode;
+    }
+
+    /// Set the minimum pulse
+    ///
+    /// # Arguments
+    /// * `min_pulse` - Minimum pulse (timer clock ticks, 0 = no clamping)
+    pub fn set_min_pulse(&mut self, min_pulse: u16) {
+        self.min_pulse_ratio =
+            ticks_to_period_ratio(min_pulse, self.pwm_frequency, self.timer_clock);
+    }
+
+    /// Correct the SVPWM duties
+    ///
+    /// # Arguments
+    /// * `duties` - Duties from SVPWM (0 to max_duty)
+    /// * `phase_currents` - Measured phase currents (A, positive into the motor)
+    /// * `v_alpha` - Alpha-axis voltage command (V)
+    /// * `v_beta` - Beta-axis voltage command (V)
+    /// * `v_dc` - DC bus voltage (V)
+    /// * `max_duty` - Maximum duty cycle value
+    ///
+    /// # Returns
+    /// Tuple of corrected (duty_u, duty_v, duty_w)
+    pub fn apply(
+        &self,
+        duties: (u16, u16, u16),
+        phase_currents: (f32, f32, f32),
+        v_alpha: f32,
+        v_beta: f32,
+        v_dc: f32,
+        max_duty: u16,
+    ) -> (u16, u16, u16) {
+        // The voltage sign ramps over the dead-time voltage error itself
+        let signs = compensation_signs(
+            self.mode,
+            phase_currents,
+            inverse_clarke(v_alpha, v_beta),
+            self.current_band,
+            self.dead_time_ratio * v_dc,
+        );
+        compensate_duties(
+            duties,
+            signs,
+            self.dead_time_ratio,
+            self.min_pulse_ratio,
+            max_duty,
+        )
+    }
+}
+
+/// Round a duty that would produce a pulse shorter than the minimum pulse
+///
+/// # Arguments
+/// * `duty` - Duty (0 to max)
+/// * `min_pulse` - Minimum pulse (duty counts, 0 = no clamping)
+/// * `max` - Maximum duty cycle value
+///
+/// # Returns
+/// Duty rounded to an integer count
+fn clamp_min_pulse(duty: f32, min_pulse: f32, max: f32) -> u16 {
+    let duty = if min_pulse <= 0.0 || 2.0 * min_pulse > max {
+        duty
+    } else if duty < min_pulse {
+        // Too short a high pulse: fully off or one minimum pulse
+        if duty < min_pulse * 0.5 {
+            0.0
+        } else {
+            min_pulse
+        }
+    } else if max - duty < min_pulse {
+        // Too short a low pulse: fully on or one minimum pulse below
+        if max - duty < min_pulse * 0.5 {
+            max
+        } else {
+            max - min_pulse
+        }
+    } else {
+        duty
+    };
+    roundf(duty) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::foc::svpwm::calculate_svpwm;
+    use crate::foc::transforms::clarke;
+    use core::f32::consts::TAU;
+    use libm::{cosf, sinf, sqrtf};
+
+    const MAX_DUTY: u16 = 3400; // 50kHz at 170MHz
+    const V_DC: f32 = 24.0;
+    const DEAD_TIME_RATIO: f32 = 0.02; // 400ns at 50kHz
+
+    /// Average leg voltages of a three-phase inverter with dead time (V)
+    fn inverter_output(
+        duties: (u16, u16, u16),
+        currents: (f32, f32, f32),
+        dead_time_ratio: f32,
+    ) -> (f32, f32, f32) {
+        let leg = |duty: u16, current: f32| {
+            let error = if current > 0.0 {
+                -dead_time_ratio
+            } else if current < 0.0 {
+                dead_time_ratio
+            } else {
+                0.0
+            };
+            (duty as f32 / MAX_DUTY as f32 + error) * V_DC
+        };
+        (
+            leg(duties.0, currents.0),
+            leg(duties.1, currents.1),
+            leg(duties.2, currents.2),
+        )
+    }
+
+    /// Drive a star-connected RL load with a rotating 1V voltage vector and
+    /// return the fundamental current amplitude (A)
+    fn run_rl_load(mode: DeadTimeCompensation, dead_time_ratio: f32) -> f32 {
+        const VOLTAGE: f32 = 1.0;
+        const R: f32 = 0.3;
+        const L: f32 = 0.0003;
+        const FREQUENCY: f32 = 10.0;
+        const DT: f32 = 0.0004; // 2.5kHz
+        const SUBSTEPS: u32 = 20;
+        const STEPS: u32 = 2500; // 10 electrical cycles
+
+        let mut currents = (0.0f32, 0.0f32, 0.0f32);
+        let (mut sum_cos, mut sum_sin) = (0.0, 0.0);
+        for step in 0..STEPS {
+            let angle = TAU * FREQUENCY * step as f32 * DT;
+            let (v_alpha, v_beta) = (VOLTAGE * cosf(angle), VOLTAGE * sinf(angle));
+
+            let duties = calculate_svpwm(v_alpha, v_beta, V_DC, MAX_DUTY);
+            let signs = compensation_signs(
+                mode,
+                currents,
+                inverse_clarke(v_alpha, v_beta),
+                0.1,
+                dead_time_ratio * V_DC,
+            );
+            let duties = compensate_duties(duties, signs, dead_time_ratio, 0.0, MAX_DUTY);
+
+            let h = DT / SUBSTEPS as f32;
+            for _ in 0..SUBSTEPS {
+                let (a, b, c) = inverter_output(duties, currents, dead_time_ratio);
+                let neutral = (a + b + c) / 3.0;
+                currents.0 += h * (a - neutral - R * currents.0) / L;
+                currents.1 += h * (b - neutral - R * currents.1) / L;
+                currents.2 = -currents.0 - currents.1;
+            }
+
+            // Fundamental over the last 5 cycles
+            if step >= STEPS / 2 {
+                let (i_alpha, _) = clarke(currents.0, currents.1, currents.2);
+                sum_cos += i_alpha * cosf(angle);
+                sum_sin += i_alpha * sinf(angle);
+            }
+        }
+        let n = (STEPS / 2) as f32;
+        2.0 * sqrtf(sum_cos * sum_cos + sum_sin * sum_sin) / n
+    }
+
+    #[test]
+    fn test_ticks_to_period_ratio() {
+        // 170 ticks at 170MHz = 1us, 5% of a 50kHz period
+        assert!((ticks_to_period_ratio(170, 50_000, 170_000_000) - 0.05).abs() < 1e-6);
+        assert_eq!(ticks_to_period_ratio(170, 50_000, 0), 0.0);
+    }
+
+    #[test]
+    fn test_compensation_restores_average_voltage() {
+        let duties = (2000, 1500, 1000);
+        let currents = (3.0, -1.0, -2.0);
+        let signs = compensation_signs(
+            DeadTimeCompensation::CurrentSign,
+            currents,
+            (0.0, 0.0, 0.0),
+            0.1,
+            0.0,
+        );
+        assert_eq!(signs, (1.0, -1.0, -1.0));
+
+        let ideal = duties.0 as f32 / MAX_DUTY as f32 * V_DC;
+        let (u, _, _) = inverter_output(duties, currents, DEAD_TIME_RATIO);
+        assert!((u - ideal).abs() > 0.4);
+
+        let compensated = compensate_duties(duties, signs, DEAD_TIME_RATIO, 0.0, MAX_DUTY);
+        let (u, v, w) = inverter_output(compensated, currents, DEAD_TIME_RATIO);
+        let tolerance = V_DC / MAX_DUTY as f32;
+        assert!((u - ideal).abs() <= tolerance);
+        assert!((v - 1500.0 / MAX_DUTY as f32 * V_DC).abs() <= tolerance);
+        assert!((w - 1000.0 / MAX_DUTY as f32 * V_DC).abs() <= tolerance);
+    }
+
+    #[test]
+    fn test_current_output_at_low_voltage() {
+        // Reference: the same modulator driving an ideal inverter without dead time
+        let ideal = run_rl_load(DeadTimeCompensation::Disabled, 0.0);
+        assert!(ideal > 1.0);
+
+        // Uncompensated, the ~0.5V dead-time error swallows most of the fundamental
+        let uncompensated = run_rl_load(DeadTimeCompensation::Disabled, DEAD_TIME_RATIO);
+        assert!(
+            uncompensated < ideal * 0.5,
+            "uncompensated={}",
+            uncompensated
+        );
+
+        let current_sign = run_rl_load(DeadTimeCompensation::CurrentSign, DEAD_TIME_RATIO);
+        assert!(
+            (current_sign - ideal).abs() < ideal * 0.05,
+            "current_sign={}",
+            current_sign
+        );
+
+        // The voltage sign ignores the current lag, which is small for an RL load at low speed
+        let voltage_sign = run_rl_load(DeadTimeCompensation::VoltageSign, DEAD_TIME_RATIO);
+        assert!(
+            (voltage_sign - ideal).abs() < ideal * 0.1,
+            "voltage_sign={}",
+            voltage_sign
+        );
+    }
+
+    #[test]
+    fn test_compensator_stage() {
+        // 1us dead time at 50kHz = 5% of the period
+        let mut stage = DeadTimeCompensator::new(170, 50_000, 170_000_000, 0.1);
+        let duties = (2000, 1500, 1000);
+        let currents = (3.0, -1.0, -2.0);
+
+        // Disabled: duties pass through
+        assert_eq!(
+            stage.apply(duties, currents, 1.0, 0.0, V_DC, MAX_DUTY),
+            duties
+        );
+
+        // 5% of 3400 = 170 counts
+        stage.set_mode(DeadTimeCompensation::CurrentSign);
+        assert_eq!(
+            stage.apply(duties, currents, 1.0, 0.0, V_DC, MAX_DUTY),
+            (2170, 1330, 830)
+        );
+
+        // Voltage sign: U = 6V, V = W = -3V (beyond the 1.2V linear region)
+        stage.set_mode(DeadTimeCompensation::VoltageSign);
+        assert_eq!(
+            stage.apply(duties, (0.0, 0.0, 0.0), 6.0, 0.0, V_DC, MAX_DUTY),
+            (2170, 1330, 830)
+        );
+
+        // 340 ticks = 10% minimum pulse
+        stage.set_mode(DeadTimeCompensation::Disabled);
+        stage.set_min_pulse(340);
+        assert_eq!(
+            stage.apply((100, 300, 3300), currents, 0.0, 0.0, V_DC, MAX_DUTY),
+            (0, 340, 3400)
+        );
+    }
+
+    #[test]
+    fn test_min_pulse_clamp() {
+        let signs = (0.0, 0.0, 0.0);
+        // 2% minimum pulse = 68 counts
+        let (u, v, w) = compensate_duties((20, 40, 1700), signs, 0.0, 0.02, MAX_DUTY);
+        assert_eq!((u, v, w), (0, 68, 1700));
+
+        let (u, v, _) = compensate_duties((3380, 3350, 0), signs, 0.0, 0.02, MAX_DUTY);
+        assert_eq!((u, v), (3400, 3332));
+
+        // No clamping when disabled
+        let (u, _, _) = compensate_duties((20, 0, 0), signs, 0.0, 0.0, MAX_DUTY);
+        assert_eq!(u, 20);
+    }
+}
@@ -0,0 +1,144 @@
+// Direct closed-loop start from standstill (align-and-go)
+// Monitors a FOC start that skips the open-loop ramp and detects a failed start
+
+/// Result of a direct start check
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DirectStartStatus {
+    /// No direct start in progress
+    Inactive,
+    /// Waiting for the motor to turn
+    Pending,
+    /// The motor turned: closed-loop start succeeded
+    Succeeded,
+    /// The motor did not turn in time: fall back to the open-loop start
+    Failed,
+}
+
+/// Direct closed-loop start monitor
+///
+/// With a valid calibration the hall sector angle is accurate enough to
+/// produce starting torque from standstill, so the open-loop ramp can be
+/// skipped. The sector angle is only known to within one sector though, and a
+/// wrong offset or a heavy load can still stall the motor.
+///
+/// The start counts as successful once the hall sensor reports the required
+/// number of edges. Time only accumulates while a nonzero speed is commanded,
+/// so the motor may sit enabled at zero speed indefinitely.
+pub struct DirectStartMonitor {
+    /// Time allowed for the motor to turn (seconds)
+    timeout: f32,
+    /// Hall edges required to confirm rotation
+    required_edges: u32,
+    /// Whether a direct start is in progress
+    active: bool,
+    /// Time with a nonzero speed command since the start (seconds)
+    elapsed: f32,
+    /// Hall edge counter value at the start
+    start_edge_count: u32,
+}
+
+impl DirectStartMonitor {
+    /// Create a new direct start monitor
+    ///
+    /// # Arguments
+    /// * `timeout` - Time allowed for the motor to turn (seconds)
+    /// * `required_edges` - Hall edges required to confirm rotation
+    pub fn new(timeout: f32, required_edges: u32) -> Self {
+        Self {
+            timeout,
+            required_edges,
+            active: false,
+            elapsed: 0.0,
+            start_edge_count: 0,
+        }
+    }
+
+    /// Start monitoring a direct start
+    ///
+    /// # Arguments
+    /// * `edge_count` - Current value of the hall edge counter
+    pub fn start(&mut self, edge_count: u32) {
+        self.active = true;
+        self.elapsed = 0.0;
+        self.start_edge_count = edge_count;
+    }
+
+    /// Stop monitoring
+    pub fn cancel(&mut self) {
+        self.active = false;
+    }
+
+    /// Check whether a direct start is in progress
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Update the monitor
+    ///
+    /// Monitoring ends when the start succeeds or fails.
+    ///
+    /// # Arguments
+    /// * `target_rpm` - Commanded speed (RPM)
+    /// * `edge_count` - Current value of the hall edge counter (wrapping)
+    /// * `dt` - Time step (seconds)
+    ///
+    /// # Returns
+    /// Status of the direct start
+    pub fn update(&mut self, target_rpm: f32, edge_count: u32, dt: f32) -> DirectStartStatus {
+        if !self.active {
+            return DirectStartStatus::Inactive;
+        }
+
+        if edge_count.wrapping_sub(self.start_edge_count) >= self.required_edges {
+            self.active = false;
+            return DirectStartStatus::Succeeded;
+        }
+
+        if target_rpm != 0.0 {
+            self.elapsed += dt;
+            if self.elapsed >= self.timeout {
+                self.active = false;
+                return DirectStartStatus::Failed;
+            }
+        }
+
+        DirectStartStatus::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 0.0004; // 2.5kHz
+
+    #[test]
+    fn test_succeeds_on_rotation() {
+        let mut monitor = DirectStartMonitor::new(1.0, 6);
+        assert_eq!(monitor.update(100.0, 0, DT), DirectStartStatus::Inactive);
+
+        // Edge counter wraps around during the start
+        monitor.start(u32::MAX - 2);
+        assert_eq!(monitor.update(100.0, 1, DT), DirectStartStatus::Pending);
+        assert_eq!(monitor.update(100.0, 3, DT), DirectStartStatus::Succeeded);
+        assert!(!monitor.is_active());
+    }
+
+    #[test]
+    fn test_fails_without_rotation() {
+        let mut monitor = DirectStartMonitor::new(1.0, 6);
+        monitor.start(10);
+
+        // No timeout while the speed command is zero
+        for _ in 0..5000 {
+            assert_eq!(monitor.update(0.0, 10, DT), DirectStartStatus::Pending);
+        }
+
+        // 1 second with a speed command but only a few edges
+        for _ in 0..2499 {
+            assert_eq!(monitor.update(-50.0, 12, DT), DirectStartStatus::Pending);
+        }
+        assert_eq!(monitor.update(-50.0, 12, DT), DirectStartStatus::Failed);
+        assert_eq!(monitor.update(-50.0, 12, DT), DirectStartStatus::Inactive);
+    }
+}
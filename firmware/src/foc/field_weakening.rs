@@ -0,0 +1,176 @@
+// Field weakening controller
+// Injects a negative d-axis reference when the voltage vector saturates
+
+use libm::sqrtf;
+
+/// Voltage-feedback field weakening controller
+///
+/// Above base speed the back-EMF alone approaches the voltage limit, so the
+/// commanded voltage vector saturates and the speed stops increasing. A
+/// negative d-axis current opposes the magnet flux and lowers the back-EMF,
+/// which lets the motor run faster at the cost of extra copper losses.
+///
+/// The controller integrates the amount by which the commanded voltage
+/// magnitude exceeds a fraction of the voltage limit. The integrator is a
+/// per-unit weakening level (0 to 1) that is scaled by the output limit, so
+/// the same gain works for a d-axis current reference (current control) and a
+/// d-axis voltage command (voltage control). When the voltage vector drops
+/// back below the threshold the level decays and the weakening is removed.
+pub struct FieldWeakening {
+    /// Integral gain (1/s per unit of voltage above the threshold)
+    gain: f32,
+    /// Voltage threshold as a fraction of the voltage limit (0 to 1)
+    voltage_ratio: f32,
+    /// Maximum d-axis output magnitude (A or V, 0 = disabled)
+    max_output: f32,
+    /// Weakening level (0 = none, 1 = maximum)
+    level: f32,
+}
+
+impl FieldWeakening {
+    /// Create a new field weakening controller
+    ///
+    /// # Arguments
+    /// * `gain` - Integral gain (1/s per unit of voltage above the threshold)
+    /// * `voltage_ratio` - Voltage threshold as a fraction of the voltage limit
+    /// * `max_output` - Maximum d-axis output magnitude (A or V, 0 = disabled)
+    pub fn new(gain: f32, voltage_ratio: f32, max_output: f32) -> Self {
+        Self {
+            gain,
+            voltage_ratio,
+            max_output,
+            level: 0.0,
+        }
+    }
+
+    /// Set the integral gain and the voltage threshold
+    ///
+    /// # Arguments
+    /// * `gain` - Integral gain (1/s per unit of voltage above the threshold)
+    /// * `voltage_ratio` - Voltage threshold as a fraction of the voltage limit
+    pub fn set_params(&mut self, gain: f32, voltage_ratio: f32) {
+        self.gain = gain;
+        self.voltage_ratio = voltage_ratio;
+    }
+
+    /// Set the maximum d-axis output magnitude
+    ///
+    /// # Arguments
+    /// * `max_output` - Maximum d-axis output magnitude (A or V, 0 = disabled)
+    pub fn set_max_output(&mut self, max_output: f32) {
+        self.max_output = max_output;
+    }
+
+    /// Check whether field weakening is enabled (nonzero output limit)
+    pub fn is_enabled(&self) -> bool {
+        self.max_output > 0.0
+    }
+
+    /// Get the d-axis reference (A or V, zero or negative)
+    pub fn output(&self) -> f32 {
+        -self.level * self.max_output
+    }
+
+    /// Update the weakening level from the commanded voltage vector
+    ///
+    /// Pass the d/q voltage command before it is limited, so saturation is
+    /// visible. The new d-axis reference takes effect on the next period.
+    ///
+    /// # Arguments
+    /// * `vd` - d-axis voltage command (V)
+    /// * `vq` - q-axis voltage command (V)
+    /// * `max_voltage` - Voltage vector limit (V)
+    /// * `dt` - Time step (seconds)
+    ///
+    /// # Returns
+    /// d-axis reference for the next period (A or V, zero or negative)
+    pub fn update(&mut self, vd: f32, vq: f32, max_voltage: f32, dt: f32) -> f32 {
+        if !self.is_enabled() || max_voltage <= 0.0 {
+            self.level = 0.0;
+            return 0.0;
+        }
+
+        let magnitude = sqrtf(vd * vd + vq * vq) / max_voltage;
+        let error = magnitude - self.voltage_ratio;
+        self.level = (self.level + self.gain * error * dt).clamp(0.0, 1.0);
+
+        self.output()
+    }
+
+    /// Remove the field weakening
+    pub fn reset(&mut self) {
+        self.level = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 0.0004; // 2.5kHz
+
+    /// Steady-state SPMSM voltage magnitude (V) for a d/q current and speed
+    fn motor_voltage(id: f32, iq: f32, omega: f32) -> f32 {
+        const R: f32 = 0.1;
+        const L: f32 = 0.0003;
+        const FLUX: f32 = 0.01;
+        let vd = R * id - omega * L * iq;
+        let vq = R * iq + omega * (L * id + FLUX);
+        sqrtf(vd * vd + vq * vq)
+    }
+
+    #[test]
+    fn test_no_weakening_below_threshold() {
+        let mut fw = FieldWeakening::new(50.0, 0.95, 10.0);
+        for _ in 0..2500 {
+            assert_eq!(fw.update(0.0, 20.0, 24.0, DT), 0.0);
+        }
+        assert_eq!(fw.output(), 0.0);
+    }
+
+    #[test]
+    fn test_weakening_extends_speed_range() {
+        // Base speed: the back-EMF alone reaches the 24V limit at 2400 rad/s.
+        // At 30% above base speed the motor needs about -9A on the d-axis.
+        let omega = 2400.0 * 1.3;
+        let iq = 1.0;
+        assert!(motor_voltage(0.0, iq, omega) > 24.0);
+
+        let mut fw = FieldWeakening::new(50.0, 0.95, 20.0);
+        let mut id = 0.0;
+        for _ in 0..25000 {
+            let v = motor_voltage(id, iq, omega);
+            // The current loop saturates at the voltage limit
+            id = fw.update(0.0, v.min(24.0), 24.0, DT);
+        }
+
+        // Settles at the voltage threshold within the output limit
+        let v = motor_voltage(id, iq, omega);
+        assert!(id < -5.0 && id > -20.0);
+        assert!((v / 24.0 - 0.95).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_output_limited_and_recovers() {
+        let mut fw = FieldWeakening::new(50.0, 0.95, 3.0);
+
+        // Saturated for a long time: clamped to the output limit
+        for _ in 0..25000 {
+            fw.update(10.0, 22.0, 24.0, DT);
+        }
+        assert_eq!(fw.output(), -3.0);
+
+        // Back below the threshold: the weakening decays to zero
+        for _ in 0..25000 {
+            fw.update(0.0, 12.0, 24.0, DT);
+        }
+        assert_eq!(fw.output(), 0.0);
+    }
+
+    #[test]
+    fn test_disabled_with_zero_limit() {
+        let mut fw = FieldWeakening::new(50.0, 0.95, 0.0);
+        assert!(!fw.is_enabled());
+        assert_eq!(fw.update(30.0, 30.0, 24.0, DT), 0.0);
+    }
+}
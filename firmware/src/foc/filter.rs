@@ -0,0 +1,601 @@
+// Digital filters for feedback and controller output signals
+// Biquad low-pass / notch / band-stop sections, moving average and median-of-N
+
+use libm::{cosf, sinf};
+
+/// Maximum moving average / median window length
+pub const MAX_WINDOW_LENGTH: usize = 16;
+
+/// Filter type selected from the config
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterType {
+    /// Pass-through
+    None = 0,
+    /// Second-order low-pass (cutoff frequency, Q)
+    LowPass = 1,
+    /// Notch (center frequency, Q)
+    Notch = 2,
+    /// Band-stop (center frequency, -3 dB bandwidth)
+    BandStop = 3,
+    /// Moving average of N samples
+    MovingAverage = 4,
+    /// Median of N samples
+    Median = 5,
+}
+
+impl FilterType {
+    /// Convert from the config value (0 = none, 1 = low-pass, 2 = notch,
+    /// 3 = band-stop, 4 = moving average, 5 = median)
+    ///
+    /// # Returns
+    /// `None` for any other value
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::None),
+            1 => Some(Self::LowPass),
+            2 => Some(Self::Notch),
+            3 => Some(Self::BandStop),
+            4 => Some(Self::MovingAverage),
+            5 => Some(Self::Median),
+            _ => None,
+        }
+    }
+}
+
+/// Normalised biquad coefficients (a0 = 1)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BiquadCoefficients {
+    /// Feedforward coefficients
+    pub b0: f32,
+    pub b1: f32,
+    pub b2: f32,
+    /// Feedback coefficients
+    pub a1: f32,
+    pub a2: f32,
+}
+
+impl BiquadCoefficients {
+    /// Design a second-order low-pass section (RBJ cookbook)
+    ///
+    /// # Arguments
+    /// * `cutoff` - Cutoff frequency (Hz)
+    /// * `q` - Quality factor (0.707 = Butterworth)
+    /// * `sample_rate` - Sample rate (Hz)
+    ///
+    /// # Returns
+    /// `None` if the frequency is not between 0 and Nyquist or Q is not positive
+    pub fn low_pass(cutoff: f32, q: f32, sample_rate: f32) -> Option<Self> {
+        let (cos_w0, alpha) = Self::prewarp(cutoff, q, sample_rate)?;
+        let b1 = 1.0 - cos_w0;
+        Some(Self::normalise(
+            b1 * 0.5,
+            b1,
+            b1 * 0.5,
+            1.0 + alpha,
+            -2.0 * cos_w0,
+            1.0 - alpha,
+        ))
+    }
+
+    /// Design a notch section (RBJ cookbook, full rejection at the center)
+    ///
+    /// # Arguments
+    /// * `center` - Center frequency (Hz)
+    /// * `q` - Quality factor (center / -3 dB bandwidth)
+    /// * `sample_rate` - Sample rate (Hz)
+    ///
+    /// # Returns
+    /// `None` if the frequency is not between 0 and Nyquist or Q is not positive
+    pub fn notch(center: f32, q: f32, sample_rate: f32) -> Option<Self> {
+        let (cos_w0, alpha) = Self::prewarp(center, q, sample_rate)?;
+        Some(Self::normalise(
+            1.0,
+            -2.0 * cos_w0,
+            1.0,
+            1.0 + alpha,
+            -2.0 * cos_w0,
+            1.0 - alpha,
+        ))
+    }
+
+    /// Design a band-stop section from its rejection bandwidth
+    ///
+    /// A notch whose Q is derived from the bandwidth, which is easier to set
+    /// from a measured resonance peak than Q.
+    ///
+    /// # Arguments
+    /// * `center` - Center frequency (Hz)
+    /// * `bandwidth` - -3 dB bandwidth (Hz)
+    /// * `sample_rate` - Sample rate (Hz)
+    ///
+    /// # Returns
+    /// `None` if the frequency is not between 0 and Nyquist or the bandwidth is not positive
+    pub fn band_stop(center: f32, bandwidth: f32, sample_rate: f32) -> Option<Self> {
+        if !(bandwidth.is_finite() && bandwidth > 0.0) {
+            return None;
+        }
+        Self::notch(center, center / bandwidth, sample_rate)
+    }
+
+    /// Gain at DC
+    pub fn dc_gain(&self) -> f32 {
+        (self.b0 + self.b1 + self.b2) / (1.0 + self.a1 + self.a2)
+    }
+
+    /// Common terms: (cos(w0), alpha)
+    fn prewarp(frequency: f32, q: f32, sample_rate: f32) -> Option<(f32, f32)> {
+        let valid = frequency.is_finite()
+            && q.is_finite()
+            && sample_rate.is_finite()
+            && frequency > 0.0
+            && q > 0.0
+            && frequency < 0.5 * sample_rate;
+        if !valid {
+            return None;
+        }
+
+        let w0 = 2.0 * core::f32::consts::PI * frequency / sample_rate;
+        Some((cosf(w0), sinf(w0) / (2.0 * q)))
+    }
+
+    /// Divide all coefficients by a0
+    fn normalise(b0: f32, b1: f32, b2: f32, a0: f32, a1: f32, a2: f32) -> Self {
+        Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        }
+    }
+}
+
+/// Biquad section (transposed direct form II)
+///
+/// The first sample after a reset initialises the state to the steady state
+/// for that input, so inserting the filter on a running signal (e.g. the speed
+/// at a mode switch) does not produce a step transient.
+#[derive(Debug, Clone, Copy)]
+pub struct Biquad {
+    /// Coefficients
+    coefficients: BiquadCoefficients,
+    /// State
+    s1: f32,
+    s2: f32,
+    /// State has been initialised since the last reset
+    primed: bool,
+}
+
+impl Biquad {
+    /// Create a new biquad section
+    ///
+    /// # Arguments
+    /// * `coefficients` - Normalised coefficients
+    pub fn new(coefficients: BiquadCoefficients) -> Self {
+        Self {
+            coefficients,
+            s1: 0.0,
+            s2: 0.0,
+            primed: false,
+        }
+    }
+
+    /// Filter one sample
+    ///
+    /// # Arguments
+    /// * `input` - Input sample
+    ///
+    /// # Returns
+    /// Filtered sample
+    pub fn update(&mut self, input: f32) -> f32 {
+        let c = &self.coefficients;
+        if !self.primed {
+            let output = c.dc_gain() * input;
+            self.s2 = c.b2 * input - c.a2 * output;
+            self.s1 = output - c.b0 * input;
+            self.primed = true;
+        }
+
+        let output = c.b0 * input + self.s1;
+        self.s1 = c.b1 * input - c.a1 * output + self.s2;
+        self.s2 = c.b2 * input - c.a2 * output;
+        output
+    }
+
+    /// Clear the state (the next sample re-initialises it)
+    pub fn reset(&mut self) {
+        self.s1 = 0.0;
+        self.s2 = 0.0;
+        self.primed = false;
+    }
+}
+
+/// Sliding window of the last N samples
+///
+/// Shared by the moving average and median filters. The first sample after a
+/// reset fills the whole window.
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    /// Ring buffer
+    samples: [f32; MAX_WINDOW_LENGTH],
+    /// Window length (1 to MAX_WINDOW_LENGTH)
+    length: usize,
+    /// Next write position
+    index: usize,
+    /// Window has been filled since the last reset
+    primed: bool,
+}
+
+impl Window {
+    fn new(length: usize) -> Self {
+        Self {
+            samples: [0.0; MAX_WINDOW_LENGTH],
+            length: length.clamp(1, MAX_WINDOW_LENGTH),
+            index: 0,
+            primed: false,
+        }
+    }
+
+    fn push(&mut self, input: f32) -> &[f32] {
+        if !self.primed {
+            self.samples[..self.length].fill(input);
+            self.primed = true;
+        }
+        self.samples[self.index] = input;
+        self.index = (self.index + 1) % self.length;
+        &self.samples[..self.length]
+    }
+
+    fn reset(&mut self) {
+        self.index = 0;
+        self.primed = false;
+    }
+}
+
+/// Moving average of the last N samples
+#[derive(Debug, Clone, Copy)]
+pub struct MovingAverage {
+    /// Sample window
+    window: Window,
+    /// Running sum of the window
+    sum: f32,
+}
+
+impl MovingAverage {
+    /// Create a new moving average
+    ///
+    /// # Arguments
+    /// * `length` - Window length (clamped to 1..MAX_WINDOW_LENGTH)
+    pub fn new(length: usize) -> Self {
+        Self {
+            window: Window::new(length),
+            sum: 0.0,
+        }
+    }
+
+    /// Filter one sample
+    ///
+    /// # Arguments
+    /// * `input` - Input sample
+    ///
+    /// # Returns
+    /// Average of the last N samples
+    pub fn update(&mut self, input: f32) -> f32 {
+        let length = self.window.length;
+        if !self.window.primed {
+            self.sum = input * length as f32;
+        } else {
+            self.sum += input - self.window.samples[self.window.index];
+        }
+        self.window.push(input);
+
+        // Re-sum once per window to stop rounding errors accumulating
+        if self.window.index == 0 {
+            self.sum = self.window.samples[..length].iter().sum();
+        }
+        self.sum / length as f32
+    }
+
+    /// Clear the window (the next sample re-fills it)
+    pub fn reset(&mut self) {
+        self.window.reset();
+        self.sum = 0.0;
+    }
+}
+
+/// Median of the last N samples
+///
+/// Removes isolated spikes (e.g. a glitched Hall period) without smearing
+/// them over the following samples like a linear filter does.
+#[derive(Debug, Clone, Copy)]
+pub struct MedianFilter {
+    /// Sample window
+    window: Window,
+}
+
+impl MedianFilter {
+    /// Create a new median filter
+    ///
+    /// # Arguments
+    /// * `length` - Window length (clamped to 1..MAX_WINDOW_LENGTH, odd lengths
+    ///   give a true median, even lengths the lower of the two middle samples)
+    pub fn new(length: usize) -> Self {
+        Self {
+            window: Window::new(length),
+        }
+    }
+
+    /// Filter one sample
+    ///
+    /// # Arguments
+    /// * `input` - Input sample
+    ///
+    /// # Returns
+    /// Median of the last N samples
+    pub fn update(&mut self, input: f32) -> f32 {
+        let samples = self.window.push(input);
+        let length = samples.len();
+
+        // Insertion sort of a copy (N is small)
+        let mut sorted = [0.0f32; MAX_WINDOW_LENGTH];
+        sorted[..length].copy_from_slice(samples);
+        for i in 1..length {
+            let value = sorted[i];
+            let mut j = i;
+            while j > 0 && sorted[j - 1] > value {
+                sorted[j] = sorted[j - 1];
+                j -= 1;
+            }
+            sorted[j] = value;
+        }
+
+        sorted[(length - 1) / 2]
+    }
+
+    /// Clear the window (the next sample re-fills it)
+    pub fn reset(&mut self) {
+        self.window.reset();
+    }
+}
+
+/// Filter stage
+#[derive(Debug, Clone, Copy)]
+enum Stage {
+    None,
+    Biquad(Biquad),
+    MovingAverage(MovingAverage),
+    Median(MedianFilter),
+}
+
+/// Config-selectable filter stage for a control loop signal
+///
+/// Wraps one of the filters so a loop can insert it on its feedback or output
+/// from the config. Reconfiguring with unchanged parameters keeps the state,
+/// so the config can be re-applied while running.
+#[derive(Debug, Clone, Copy)]
+pub struct SignalFilter {
+    /// Active stage
+    stage: Stage,
+    /// Parameters of the active stage (type, frequency, shape, length, sample rate)
+    params: (FilterType, f32, f32, usize, f32),
+    /// Parameters were valid
+    valid: bool,
+}
+
+impl SignalFilter {
+    /// Create a pass-through filter
+    pub const fn disabled() -> Self {
+        Self {
+            stage: Stage::None,
+            params: (FilterType::None, 0.0, 0.0, 0, 0.0),
+            valid: true,
+        }
+    }
+
+    /// Select the filter type and design its coefficients
+    ///
+    /// # Arguments
+    /// * `filter_type` - Filter type
+    /// * `frequency` - Cutoff / center frequency (Hz, biquad types only)
+    /// * `shape` - Q (low-pass, notch) or -3 dB bandwidth in Hz (band-stop)
+    /// * `length` - Window length (moving average, median)
+    /// * `sample_rate` - Rate at which `update` is called (Hz)
+    ///
+    /// # Returns
+    /// False if the parameters are invalid (the filter is then pass-through)
+    pub fn configure(
+        &mut self,
+        filter_type: FilterType,
+        frequency: f32,
+        shape: f32,
+        length: usize,
+        sample_rate: f32,
+    ) -> bool {
+        let params = (filter_type, frequency, shape, length, sample_rate);
+        if params == self.params {
+            return self.valid;
+        }
+        self.params = params;
+
+        let coefficients = match filter_type {
+            FilterType::LowPass => BiquadCoefficients::low_pass(frequency, shape, sample_rate),
+            FilterType::Notch => BiquadCoefficients::notch(frequency, shape, sample_rate),
+            FilterType::BandStop => BiquadCoefficients::band_stop(frequency, shape, sample_rate),
+            _ => None,
+        };
+        let valid_length = (1..=MAX_WINDOW_LENGTH).contains(&length);
+
+        let (stage, valid) = match filter_type {
+            FilterType::None => (Stage::None, true),
+            FilterType::LowPass | FilterType::Notch | FilterType::BandStop => match coefficients {
+                Some(coefficients) => (Stage::Biquad(Biquad::new(coefficients)), true),
+                None => (Stage::None, false),
+            },
+            FilterType::MovingAverage if valid_length => {
+                (Stage::MovingAverage(MovingAverage::new(length)), true)
+            }
+            FilterType::Median if valid_length => (Stage::Median(MedianFilter::new(length)), true),
+            FilterType::MovingAverage | FilterType::Median => (Stage::None, false),
+        };
+        self.stage = stage;
+        self.valid = valid;
+        valid
+    }
+
+    /// Take over a filter configured elsewhere
+    ///
+    /// Lets the coefficients be designed outside the control loop. Like
+    /// `configure`, the state is kept if the parameters are unchanged.
+    ///
+    /// # Arguments
+    /// * `designed` - Filter configured with the new parameters
+    pub fn adopt(&mut self, designed: &SignalFilter) {
+        if designed.params != self.params {
+            *self = *designed;
+        }
+    }
+
+    /// Filter one sample
+    ///
+    /// # Arguments
+    /// * `input` - Input sample
+    ///
+    /// # Returns
+    /// Filtered sample (the input itself when disabled)
+    pub fn update(&mut self, input: f32) -> f32 {
+        match &mut self.stage {
+            Stage::None => input,
+            Stage::Biquad(filter) => filter.update(input),
+            Stage::MovingAverage(filter) => filter.update(input),
+            Stage::Median(filter) => filter.update(input),
+        }
+    }
+
+    /// Clear the state (the next sample re-initialises it)
+    pub fn reset(&mut self) {
+        match &mut self.stage {
+            Stage::None => {}
+            Stage::Biquad(filter) => filter.reset(),
+            Stage::MovingAverage(filter) => filter.reset(),
+            Stage::Median(filter) => filter.reset(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 2500.0;
+
+    /// Steady-state amplitude of a sine passed through a biquad
+    fn sine_gain(coefficients: BiquadCoefficients, frequency: f32) -> f32 {
+        let mut filter = Biquad::new(coefficients);
+        filter.update(0.0);
+        let mut peak: f32 = 0.0;
+        for n in 1..20000 {
+            let t = n as f32 / SAMPLE_RATE;
+            let output = filter.update(sinf(2.0 * core::f32::consts::PI * frequency * t));
+            if n > 15000 {
+                peak = peak.max(output.abs());
+            }
+        }
+        peak
+    }
+
+    #[test]
+    fn test_low_pass_response() {
+        let lp = BiquadCoefficients::low_pass(100.0, 0.707, SAMPLE_RATE).unwrap();
+        assert!((lp.dc_gain() - 1.0).abs() < 1e-4);
+        assert!((sine_gain(lp, 10.0) - 1.0).abs() < 0.01);
+        // -3 dB at the cutoff for Q = 0.707
+        assert!((sine_gain(lp, 100.0) - 0.707).abs() < 0.02);
+        // -12 dB/octave well above the cutoff
+        assert!(sine_gain(lp, 800.0) < 0.03);
+    }
+
+    #[test]
+    fn test_notch_and_band_stop_reject_center() {
+        let notch = BiquadCoefficients::notch(200.0, 5.0, SAMPLE_RATE).unwrap();
+        assert!(sine_gain(notch, 200.0) < 0.02);
+        assert!(sine_gain(notch, 50.0) > 0.95);
+        assert!(sine_gain(notch, 600.0) > 0.95);
+
+        // 40 Hz bandwidth at 200 Hz is the same section as Q = 5
+        let band_stop = BiquadCoefficients::band_stop(200.0, 40.0, SAMPLE_RATE).unwrap();
+        assert_eq!(band_stop, notch);
+    }
+
+    #[test]
+    fn test_design_rejects_invalid_frequency() {
+        assert!(BiquadCoefficients::low_pass(0.0, 0.707, SAMPLE_RATE).is_none());
+        assert!(BiquadCoefficients::notch(1250.0, 1.0, SAMPLE_RATE).is_none());
+        assert!(BiquadCoefficients::notch(100.0, 0.0, SAMPLE_RATE).is_none());
+        assert!(BiquadCoefficients::band_stop(100.0, 0.0, SAMPLE_RATE).is_none());
+    }
+
+    #[test]
+    fn test_moving_average() {
+        let mut ma = MovingAverage::new(4);
+        // First sample fills the window
+        assert_eq!(ma.update(8.0), 8.0);
+        assert_eq!(ma.update(0.0), 6.0);
+        assert_eq!(ma.update(0.0), 4.0);
+        assert_eq!(ma.update(0.0), 2.0);
+        assert_eq!(ma.update(0.0), 0.0);
+    }
+
+    #[test]
+    fn test_median_rejects_spike() {
+        let mut median = MedianFilter::new(5);
+        assert_eq!(median.update(100.0), 100.0);
+        assert_eq!(median.update(102.0), 100.0);
+        // Isolated spike does not reach the output
+        assert_eq!(median.update(5000.0), 100.0);
+        assert_eq!(median.update(101.0), 101.0);
+        assert_eq!(median.update(103.0), 102.0);
+    }
+
+    #[test]
+    fn test_signal_filter_configuration() {
+        let mut filter = SignalFilter::disabled();
+        assert_eq!(filter.update(3.0), 3.0);
+
+        // Biquad starts at steady state with the first sample
+        assert!(filter.configure(FilterType::LowPass, 50.0, 0.707, 0, SAMPLE_RATE));
+        assert!((filter.update(1000.0) - 1000.0).abs() < 1e-2);
+        let filtered = filter.update(0.0);
+        assert!(filtered > 900.0);
+
+        // Unchanged parameters keep the state
+        assert!(filter.configure(FilterType::LowPass, 50.0, 0.707, 0, SAMPLE_RATE));
+        assert!(filter.update(0.0) < filtered);
+
+        // Invalid parameters fall back to pass-through
+        assert!(!filter.configure(FilterType::Notch, 2000.0, 1.0, 0, SAMPLE_RATE));
+        assert!(!filter.configure(FilterType::Notch, 2000.0, 1.0, 0, SAMPLE_RATE));
+        assert_eq!(filter.update(7.0), 7.0);
+        assert!(!filter.configure(FilterType::Median, 0.0, 0.0, 0, SAMPLE_RATE));
+        assert!(filter.configure(FilterType::Median, 0.0, 0.0, 3, SAMPLE_RATE));
+        assert_eq!(filter.update(7.0), 7.0);
+    }
+
+    #[test]
+    fn test_signal_filter_adopt() {
+        let mut designed = SignalFilter::disabled();
+        assert!(designed.configure(FilterType::LowPass, 50.0, 0.707, 0, SAMPLE_RATE));
+
+        let mut filter = SignalFilter::disabled();
+        filter.adopt(&designed);
+        assert!((filter.update(1000.0) - 1000.0).abs() < 1e-2);
+        let filtered = filter.update(0.0);
+        assert!(filtered > 900.0);
+
+        // Same parameters keep the running state
+        filter.adopt(&designed);
+        assert!(filter.update(0.0) < filtered);
+
+        filter.adopt(&SignalFilter::disabled());
+        assert_eq!(filter.update(7.0), 7.0);
+    }
+}
@@ -0,0 +1,568 @@
+// Fixed-point FOC math (Q15/Q31)
+// Clarke, Park, inverse Park, SVPWM and PI in integer arithmetic on per-unit
+// quantities, with sin/cos and atan2 on the STM32G4 CORDIC when the `cordic`
+// feature is enabled (idsp otherwise)
+//
+// Quantities are per-unit values in Q15 (1.0 = 32768, i.e. the range is
+// [-1, 1)) relative to a base chosen by the caller, e.g. the maximum
+// measurable current for currents and the linear modulation limit v_dc/√3
+// for voltages. Angles are phases in the full i32 range (i32::MIN = -π,
+// one LSB = π/2^31), which wraps naturally and matches the idsp and CORDIC
+// angle representation.
+//
+// With the `fixed-point` feature the current loop runs on these functions
+// (`current_control::FixedCurrentController`, `svpwm::Modulator::duties`).
+// Without it, only sin/cos (for `transforms`) and inverse Park (for the
+// benchmark) are built into the firmware; the rest is built for host tests.
+
+use libm::roundf;
+
+/// Q15 fixed-point value (1.0 = 2^15)
+pub type Q15 = i16;
+
+/// Q31 fixed-point value (1.0 = 2^31)
+pub type Q31 = i32;
+
+/// One in Q15 (not representable in `Q15` itself)
+const Q15_ONE: f32 = 32768.0;
+
+/// One in Q31 (not representable in `Q31` itself)
+const Q31_ONE: f32 = 2_147_483_648.0;
+
+/// 1/3 in Q15
+#[cfg(any(feature = "fixed-point", test))]
+const ONE_DIV_3_Q15: i32 = 10_923;
+
+/// 1/√3 in Q15
+#[cfg(any(feature = "fixed-point", test))]
+const ONE_DIV_SQRT3_Q15: i32 = 18_919;
+
+/// √3 in Q15 (held in i32, exceeds the Q15 range)
+#[cfg(any(feature = "fixed-point", test))]
+const SQRT3_Q15: i32 = 56_756;
+
+/// Convert a per-unit value to Q15 (rounded, saturated to [-1, 1))
+pub fn q15_from_f32(value: f32) -> Q15 {
+    roundf(value * Q15_ONE).clamp(i16::MIN as f32, i16::MAX as f32) as i16
+}
+
+/// Convert a Q15 value to a per-unit value
+pub fn q15_to_f32(value: Q15) -> f32 {
+    value as f32 / Q15_ONE
+}
+
+/// Convert a per-unit value to Q31 (saturated to [-1, 1))
+#[cfg(any(feature = "fixed-point", test))]
+pub fn q31_from_f32(value: f32) -> Q31 {
+    // f32 to integer casts saturate, so +1.0 maps to i32::MAX
+    (value * Q31_ONE) as i32
+}
+
+/// Convert a Q31 value to a per-unit value
+pub fn q31_to_f32(value: Q31) -> f32 {
+    value as f32 / Q31_ONE
+}
+
+/// Convert an angle in radians to a phase (wraps to [-π, π))
+pub fn phase_from_rad(theta: f32) -> i32 {
+    const SCALE: f32 = Q31_ONE / core::f32::consts::PI; // 2^31 / π
+    (theta * SCALE) as i64 as i32
+}
+
+/// Convert a phase to an angle in radians ([-π, π))
+#[cfg(any(feature = "fixed-point", test))]
+pub fn phase_to_rad(phase: i32) -> f32 {
+    phase as f32 * (core::f32::consts::PI / Q31_ONE)
+}
+
+/// Round a Q31 value to Q15
+#[inline]
+fn q31_to_q15(value: Q31) -> Q15 {
+    saturate_q15(((value as i64 + (1 << 15)) >> 16) as i32)
+}
+
+/// Saturate an i32 intermediate to Q15
+#[inline]
+fn saturate_q15(value: i32) -> Q15 {
+    value.clamp(i16::MIN as i32, i16::MAX as i32) as i16
+}
+
+/// Round a product of two Q15 values (Q30) back to Q15, without saturation
+#[inline]
+fn round_q30(value: i32) -> i32 {
+    (value + (1 << 14)) >> 15
+}
+
+/// Calculate (cos, sin) of a phase in Q31
+///
+/// Uses the CORDIC unit with the `cordic` feature, idsp::cossin() otherwise.
+#[inline]
+pub fn cossin_q31(phase: i32) -> (Q31, Q31) {
+    #[cfg(all(feature = "cordic", not(test)))]
+    {
+        crate::cordic::cossin(phase)
+    }
+    #[cfg(not(all(feature = "cordic", not(test))))]
+    {
+        idsp::cossin(phase)
+    }
+}
+
+/// Calculate (cos, sin) of a phase in Q15
+///
+/// The results are limited to ±(1 - 2^-15) so that sums of two products
+/// with Q15 operands cannot overflow an i32.
+#[inline]
+pub fn cossin(phase: i32) -> (Q15, Q15) {
+    let (cos, sin) = cossin_q31(phase);
+    (
+        q31_to_q15(cos).max(-i16::MAX),
+        q31_to_q15(sin).max(-i16::MAX),
+    )
+}
+
+/// Calculate the phase of the vector (x, y)
+///
+/// Uses the CORDIC unit with the `cordic` feature, idsp::atan2() otherwise.
+///
+/// # Arguments
+/// * `y` - Y component (any scale shared with `x`)
+/// * `x` - X component
+///
+/// # Returns
+/// Phase of the vector (i32::MIN = -π)
+#[inline]
+#[cfg(any(feature = "fixed-point", test))]
+pub fn atan2(y: i32, x: i32) -> i32 {
+    #[cfg(all(feature = "cordic", not(test)))]
+    {
+        crate::cordic::atan2(y, x)
+    }
+    #[cfg(not(all(feature = "cordic", not(test))))]
+    {
+        idsp::atan2(y, x)
+    }
+}
+
+/// Clarke transformation (abc/uvw → αβ) in Q15
+///
+/// Amplitude-invariant form using all three phases, like `transforms::clarke`.
+///
+/// # Arguments
+/// * `i_u` - U-phase current (per unit)
+/// * `i_v` - V-phase current (per unit)
+/// * `i_w` - W-phase current (per unit)
+///
+/// # Returns
+/// Tuple of (i_alpha, i_beta) (per unit, saturated)
+#[cfg(any(feature = "fixed-point", test))]
+pub fn clarke(i_u: Q15, i_v: Q15, i_w: Q15) -> (Q15, Q15) {
+    let (i_u, i_v, i_w) = (i_u as i32, i_v as i32, i_w as i32);
+
+    let i_alpha = round_q30(ONE_DIV_3_Q15 * (2 * i_u - i_v - i_w));
+    let i_beta = round_q30(ONE_DIV_SQRT3_Q15 * (i_v - i_w));
+
+    (saturate_q15(i_alpha), saturate_q15(i_beta))
+}
+
+/// Park transformation (αβ → dq) in Q15
+///
+/// # Arguments
+/// * `alpha` - Alpha-axis component (per unit)
+/// * `beta` - Beta-axis component (per unit)
+/// * `phase` - Electrical angle as a phase (see `phase_from_rad`)
+///
+/// # Returns
+/// Tuple of (d, q) (per unit, saturated)
+#[cfg(any(feature = "fixed-point", test))]
+pub fn park(alpha: Q15, beta: Q15, phase: i32) -> (Q15, Q15) {
+    let (cos, sin) = cossin(phase);
+    let (alpha, beta, cos, sin) = (alpha as i32, beta as i32, cos as i32, sin as i32);
+
+    let d = round_q30(alpha * cos + beta * sin);
+    let q = round_q30(beta * cos - alpha * sin);
+
+    (saturate_q15(d), saturate_q15(q))
+}
+
+/// Inverse Park transformation (dq → αβ) in Q15
+///
+/// # Arguments
+/// * `d` - d-axis component (per unit)
+/// * `q` - q-axis component (per unit)
+/// * `phase` - Electrical angle as a phase (see `phase_from_rad`)
+///
+/// # Returns
+/// Tuple of (alpha, beta) (per unit, saturated)
+pub fn inverse_park(d: Q15, q: Q15, phase: i32) -> (Q15, Q15) {
+    let (cos, sin) = cossin(phase);
+    let (d, q, cos, sin) = (d as i32, q as i32, cos as i32, sin as i32);
+
+    let alpha = round_q30(d * cos - q * sin);
+    let beta = round_q30(d * sin + q * cos);
+
+    (saturate_q15(alpha), saturate_q15(beta))
+}
+
+/// Calculate Space Vector PWM duty cycles in fixed point
+///
+/// Same x/y/z sector algorithm as `svpwm::calculate_svpwm`, with the voltage
+/// already normalized by the linear modulation limit: a vector magnitude of
+/// 1.0 is v_dc/√3, i.e. `q15_from_f32(√3 · v / v_dc)`. Longer vectors
+/// saturate the duties.
+///
+/// # Arguments
+/// * `v_alpha` - Alpha-axis voltage command (per unit of v_dc/√3)
+/// * `v_beta` - Beta-axis voltage command (per unit of v_dc/√3)
+/// * `max_duty` - Maximum duty cycle value
+///
+/// # Returns
+/// Tuple of (duty_u, duty_v, duty_w)
+#[cfg(any(feature = "fixed-point", test))]
+pub fn svpwm(v_alpha: Q15, v_beta: Q15, max_duty: u16) -> (u16, u16, u16) {
+    let sqrt_3_alpha = round_q30(SQRT3_Q15 * v_alpha as i32);
+    let x = v_beta as i32;
+    let y = (x + sqrt_3_alpha) / 2;
+    let z = (x - sqrt_3_alpha) / 2;
+
+    let (ta, tb, tc) = match (x >= 0, y >= 0, z >= 0) {
+        (true, true, false) | (false, false, true) => (x - z, x + z, -x + z),
+        (_, true, true) | (_, false, false) => (y - z, y + z, -y - z),
+        (true, false, true) | (false, true, false) => (y - x, -y + x, -y - x),
+    };
+
+    // [-1, 1] in Q15 → [0, max_duty] (rounded)
+    let duty = |t: i32| {
+        let t = (t.clamp(-(1 << 15), 1 << 15) + (1 << 15)) as u32;
+        ((t * max_duty as u32 + (1 << 15)) >> 16) as u16
+    };
+
+    (duty(ta), duty(tb), duty(tc))
+}
+
+/// PI controller in fixed point
+///
+/// Input and output are Q15 per-unit values, so the gains must be given in
+/// per-unit terms (e.g. for a current loop with voltage output:
+/// kp_pu = kp · I_base / V_base). The integral gain is combined with the
+/// fixed loop period, and the integral is accumulated in Q31 to keep
+/// resolution for small errors at high loop rates.
+///
+/// Unlike `PiController` (no anti-windup by default), the integral is
+/// clamped to the output limit, since an unbounded integer accumulator
+/// would overflow.
+#[cfg(any(feature = "fixed-point", test))]
+pub struct FixedPiController {
+    /// Proportional gain (Q16.16)
+    kp: i32,
+    /// Integral gain × loop period (Q16.16)
+    ki_dt: i32,
+    /// Integral accumulator (Q31 per unit)
+    integral: Q31,
+    /// Output limit (symmetric, Q15 per unit)
+    output_limit: Q15,
+}
+
+#[cfg(any(feature = "fixed-point", test))]
+impl FixedPiController {
+    /// Create a new fixed-point PI controller
+    ///
+    /// # Arguments
+    /// * `kp` - Proportional gain (per unit, below 32768)
+    /// * `ki` - Integral gain (per unit, 1/s)
+    /// * `dt` - Loop period (seconds)
+    /// * `output_limit` - Output limit (per unit, symmetric)
+    pub fn new(kp: f32, ki: f32, dt: f32, output_limit: f32) -> Self {
+        let mut controller = Self {
+            kp: 0,
+            ki_dt: 0,
+            integral: 0,
+            output_limit: 0,
+        };
+        controller.set_gains(kp, ki, dt);
+        controller.set_limit(q15_from_f32(output_limit.abs()));
+        controller
+    }
+
+    /// Set the gains
+    ///
+    /// # Arguments
+    /// * `kp` - Proportional gain (per unit, below 32768)
+    /// * `ki` - Integral gain (per unit, 1/s)
+    /// * `dt` - Loop period (seconds)
+    pub fn set_gains(&mut self, kp: f32, ki: f32, dt: f32) {
+        const Q16: f32 = 65536.0;
+        self.kp = roundf(kp * Q16) as i32;
+        self.ki_dt = roundf(ki * dt * Q16) as i32;
+    }
+
+    /// Set the symmetric output limit (per unit, non-negative)
+    pub fn set_limit(&mut self, output_limit: Q15) {
+        self.output_limit = output_limit.max(0);
+        let limit = (self.output_limit as i32) << 16;
+        self.integral = self.integral.clamp(-limit, limit);
+    }
+
+    /// Update the controller
+    ///
+    /// # Arguments
+    /// * `setpoint` - Desired value (per unit)
+    /// * `measured` - Actual measured value (per unit)
+    ///
+    /// # Returns
+    /// Controller output (per unit, limited to ±output_limit)
+    pub fn update(&mut self, setpoint: Q15, measured: Q15) -> Q15 {
+        let error = setpoint as i64 - measured as i64;
+        let limit = self.output_limit as i64;
+
+        // Q16.16 × Q15 = Q31, rounded to Q15 for the output
+        let p_term = (self.kp as i64 * error + (1 << 15)) >> 16;
+        self.integral = (self.integral as i64 + self.ki_dt as i64 * error)
+            .clamp(-(limit << 16), limit << 16) as i32;
+        let i_term = (self.integral as i64 + (1 << 15)) >> 16;
+
+        (p_term + i_term).clamp(-limit, limit) as i16
+    }
+
+    /// Reset the integral term to zero
+    pub fn reset(&mut self) {
+        self.integral = 0;
+    }
+
+    /// Get the integral term (per unit)
+    #[cfg(test)]
+    pub fn get_integral(&self) -> f32 {
+        q31_to_f32(self.integral)
+    }
+}
+
+/// Per-unit bases for converting physical quantities to and from Q15
+#[cfg(any(feature = "fixed-point", test))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PerUnit {
+    /// Current corresponding to 1.0 (amperes)
+    current_base: f32,
+    /// Voltage corresponding to 1.0 (volts)
+    voltage_base: f32,
+}
+
+#[cfg(any(feature = "fixed-point", test))]
+impl PerUnit {
+    /// Create per-unit bases
+    ///
+    /// # Arguments
+    /// * `current_base` - Current corresponding to 1.0 (amperes, positive)
+    /// * `voltage_base` - Voltage corresponding to 1.0 (volts, positive)
+    pub fn new(current_base: f32, voltage_base: f32) -> Self {
+        Self {
+            current_base,
+            voltage_base,
+        }
+    }
+
+    /// Convert a current to per unit (amperes → Q15, saturated)
+    pub fn current(&self, amps: f32) -> Q15 {
+        q15_from_f32(amps / self.current_base)
+    }
+
+    /// Convert a per-unit current to amperes
+    pub fn amps(&self, value: Q15) -> f32 {
+        q15_to_f32(value) * self.current_base
+    }
+
+    /// Convert a voltage to per unit (volts → Q15, saturated)
+    pub fn voltage(&self, volts: f32) -> Q15 {
+        q15_from_f32(volts / self.voltage_base)
+    }
+
+    /// Convert a per-unit voltage to volts
+    pub fn volts(&self, value: Q15) -> f32 {
+        q15_to_f32(value) * self.voltage_base
+    }
+
+    /// Impedance corresponding to 1.0 (ohms), for converting V/A gains to per unit
+    pub fn impedance_base(&self) -> f32 {
+        self.voltage_base / self.current_base
+    }
+}
+
+/// Benchmark the fixed-point inverse Park transformation
+///
+/// Counterpart of `transforms::benchmark_inverse_park` with the same test
+/// vector (12 V, 8 V at ~90°) scaled to a 24 V base.
+///
+/// # Arguments
+/// * `iterations` - Number of iterations to run
+///
+/// # Returns
+/// Tuple of (result converted back to volts, ticks)
+#[cfg(not(test))]
+#[allow(dead_code)]
+pub fn benchmark_inverse_park(iterations: u32) -> ((f32, f32), u32) {
+    use cortex_m::peripheral::DWT;
+
+    const V_BASE: f32 = 24.0;
+    let d = q15_from_f32(12.0 / V_BASE);
+    let q = q15_from_f32(8.0 / V_BASE);
+    let phase = phase_from_rad(1.57);
+
+    unsafe {
+        let dwt = &*DWT::PTR;
+
+        let start = dwt.cyccnt.read();
+        let mut result = (0, 0);
+        for _ in 0..iterations {
+            result = inverse_park(d, q, phase);
+            // Prevent optimization
+            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+        }
+        let end = dwt.cyccnt.read();
+
+        (
+            (q15_to_f32(result.0) * V_BASE, q15_to_f32(result.1) * V_BASE),
+            end.wrapping_sub(start),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::pi_controller::PiController;
+    use super::super::svpwm::calculate_svpwm;
+    use super::super::transforms;
+    use super::*;
+    use core::f32::consts::TAU;
+
+    /// One Q15 LSB
+    const LSB: f32 = 1.0 / 32768.0;
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(q15_from_f32(0.5), 16384);
+        assert_eq!(q15_from_f32(1.0), i16::MAX);
+        assert_eq!(q15_from_f32(-2.0), i16::MIN);
+        assert!((q15_to_f32(q15_from_f32(-0.3)) + 0.3).abs() <= LSB);
+        assert!((q31_to_f32(q31_from_f32(0.123)) - 0.123).abs() < 1e-6);
+
+        assert_eq!(phase_from_rad(0.0), 0);
+        // π/2 and the wrapped 5π/2 map to the same phase
+        assert!((phase_from_rad(1.570_796_4) - (1 << 30)).abs() < 256);
+        assert!((phase_from_rad(1.570_796_4 + TAU) - (1 << 30)).abs() < 1024);
+        assert!((phase_to_rad(phase_from_rad(-1.0)) + 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_cossin_matches_f32() {
+        for step in 0..720 {
+            let theta = step as f32 * TAU / 720.0;
+            let (cos, sin) = cossin(phase_from_rad(theta));
+            assert!((q15_to_f32(cos) - libm::cosf(theta)).abs() < 2.0 * LSB);
+            assert!((q15_to_f32(sin) - libm::sinf(theta)).abs() < 2.0 * LSB);
+        }
+    }
+
+    #[test]
+    fn test_atan2_matches_f32() {
+        for step in 0..360 {
+            let theta = step as f32 * TAU / 360.0 - core::f32::consts::PI;
+            let (x, y) = (0.4 * libm::cosf(theta), 0.4 * libm::sinf(theta));
+            let phase = atan2(q31_from_f32(y), q31_from_f32(x));
+            let error = libm::remainderf(phase_to_rad(phase) - theta, TAU);
+            assert!(error.abs() < 2e-4, "theta {}: error {}", theta, error);
+        }
+    }
+
+    #[test]
+    fn test_clarke_matches_f32() {
+        let currents = [(0.5, -0.25, -0.25), (0.1, 0.3, -0.4), (-0.6, 0.2, 0.45)];
+        for (u, v, w) in currents {
+            let (alpha, beta) = clarke(q15_from_f32(u), q15_from_f32(v), q15_from_f32(w));
+            let (alpha_f32, beta_f32) = transforms::clarke(u, v, w);
+            assert!((q15_to_f32(alpha) - alpha_f32).abs() < 2.0 * LSB);
+            assert!((q15_to_f32(beta) - beta_f32).abs() < 2.0 * LSB);
+        }
+    }
+
+    #[test]
+    fn test_park_inverse_park_match_f32() {
+        for step in 0..72 {
+            let theta = step as f32 * TAU / 72.0;
+            let phase = phase_from_rad(theta);
+            let (d, q) = (0.3 - step as f32 * 0.005, -0.2 + step as f32 * 0.008);
+
+            let (alpha, beta) = inverse_park(q15_from_f32(d), q15_from_f32(q), phase);
+            let (alpha_f32, beta_f32) = transforms::inverse_park(d, q, theta);
+            assert!((q15_to_f32(alpha) - alpha_f32).abs() < 3.0 * LSB);
+            assert!((q15_to_f32(beta) - beta_f32).abs() < 3.0 * LSB);
+
+            let (d_back, q_back) = park(alpha, beta, phase);
+            let (d_f32, q_f32) = transforms::park(alpha_f32, beta_f32, theta);
+            assert!((q15_to_f32(d_back) - d_f32).abs() < 5.0 * LSB);
+            assert!((q15_to_f32(q_back) - q_f32).abs() < 5.0 * LSB);
+        }
+    }
+
+    #[test]
+    fn test_svpwm_matches_f32() {
+        const V_DC: f32 = 24.0;
+        const MAX_DUTY: u16 = 1700;
+        let linear_limit = V_DC / libm::sqrtf(3.0);
+
+        for step in 0..120 {
+            let theta = step as f32 * TAU / 120.0;
+            for magnitude in [0.0, 0.35, 0.7, 0.99] {
+                let v_alpha = magnitude * linear_limit * libm::cosf(theta);
+                let v_beta = magnitude * linear_limit * libm::sinf(theta);
+
+                let fixed = svpwm(
+                    q15_from_f32(v_alpha / linear_limit),
+                    q15_from_f32(v_beta / linear_limit),
+                    MAX_DUTY,
+                );
+                let float = calculate_svpwm(v_alpha, v_beta, V_DC, MAX_DUTY);
+                for (a, b) in [(fixed.0, float.0), (fixed.1, float.1), (fixed.2, float.2)] {
+                    assert!(a.abs_diff(b) <= 1, "theta {}: {} vs {}", theta, a, b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pi_matches_f32() {
+        const DT: f32 = 0.00004; // 25kHz
+        let mut fixed = FixedPiController::new(0.8, 200.0, DT, 0.9);
+        let mut float = PiController::new_symmetric(0.8, 200.0, 0.9);
+
+        // First-order plant driven by the f32 output so both see the same input
+        let mut measured = 0.0;
+        for _ in 0..2000 {
+            let out_fixed = fixed.update(q15_from_f32(0.3), q15_from_f32(measured));
+            let out_float = float.update(0.3, measured, DT);
+            assert!((q15_to_f32(out_fixed) - out_float).abs() < 2e-3);
+            measured += (out_float - measured) * 0.01;
+        }
+        assert!((fixed.get_integral() - float.get_integral()).abs() < 2e-3);
+    }
+
+    #[test]
+    fn test_pi_integral_clamped() {
+        let mut pi = FixedPiController::new(0.0, 1000.0, 0.001, 0.5);
+        for _ in 0..100 {
+            pi.update(i16::MAX, i16::MIN);
+        }
+        assert_eq!(pi.update(0, 0), q15_from_f32(0.5));
+
+        pi.reset();
+        assert_eq!(pi.update(0, 0), 0);
+    }
+
+    #[test]
+    fn test_per_unit_conversions() {
+        let per_unit = PerUnit::new(40.0, 24.0);
+        assert_eq!(per_unit.current(20.0), 16384);
+        assert_eq!(per_unit.current(-80.0), i16::MIN);
+        assert!((per_unit.amps(per_unit.current(3.3)) - 3.3).abs() <= 40.0 * LSB);
+        assert!((per_unit.volts(per_unit.voltage(-6.0)) + 6.0).abs() <= 24.0 * LSB);
+        assert!((per_unit.impedance_base() - 0.6).abs() < 1e-6);
+    }
+}
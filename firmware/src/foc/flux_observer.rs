@@ -0,0 +1,375 @@
+// Sensorless rotor angle estimation
+// Nonlinear flux observer with a PLL for angle and speed tracking
+
+use core::f32::consts::TAU;
+use libm::{cosf, sinf, sqrtf};
+
+/// Radial convergence rate of the flux estimate [1/s]
+///
+/// Pulls the estimated rotor flux back onto the circle of radius
+/// `flux_linkage`, which removes integrator drift and offset errors.
+const CONVERGENCE_GAIN: f32 = 1000.0;
+
+/// Flux magnitude band, relative to `flux_linkage`, accepted as locked
+const LOCK_FLUX_MIN: f32 = 0.5;
+const LOCK_FLUX_MAX: f32 = 1.5;
+
+/// PLL phase error (sine of the angle error) accepted as locked
+const LOCK_MAX_PHASE_ERROR: f32 = 0.5;
+
+/// Sensorless angle and speed estimator
+///
+/// Estimates the stator flux by integrating the back-EMF `v - R·i` in the
+/// stationary αβ frame. Subtracting `L·i` leaves the rotor magnet flux, whose
+/// direction is the electrical angle. The integrator drift is corrected by the
+/// nonlinear observer term `γ/2 · η · (ψm² - |η|²)` (Ortega et al.), which
+/// keeps the rotor flux estimate `η` on a circle of radius ψm.
+///
+/// A type-2 PLL (critically damped) tracks the angle of `η`, giving a smooth
+/// angle and speed. The back-EMF vanishes at standstill, so the estimate is
+/// only usable above a minimum speed (see `is_locked`).
+pub struct FluxObserver {
+    /// Phase resistance [Ω]
+    resistance: f32,
+    /// Phase inductance [H]
+    inductance: f32,
+    /// Permanent magnet flux linkage [Wb] (peak, per phase)
+    flux_linkage: f32,
+    /// PLL proportional gain [rad/s per unit phase error]
+    pll_kp: f32,
+    /// PLL integral gain [rad/s² per unit phase error]
+    pll_ki: f32,
+    /// Number of pole pairs (for RPM conversion)
+    pole_pairs: u8,
+    /// Stator flux estimate, α component [Wb]
+    flux_alpha: f32,
+    /// Stator flux estimate, β component [Wb]
+    flux_beta: f32,
+    /// Previous α current sample [A]
+    prev_i_alpha: f32,
+    /// Previous β current sample [A]
+    prev_i_beta: f32,
+    /// PLL electrical angle [rad] in [0, TAU)
+    angle: f32,
+    /// PLL electrical angular velocity [rad/s]
+    omega: f32,
+    /// Last PLL phase error (sine of the angle error)
+    phase_error: f32,
+    /// Last rotor flux magnitude relative to `flux_linkage`
+    flux_ratio: f32,
+}
+
+impl FluxObserver {
+    /// Create a new flux observer at standstill
+    ///
+    /// # Arguments
+    /// * `resistance` - Phase resistance [Ω]
+    /// * `inductance` - Phase inductance [H]
+    /// * `flux_linkage` - Permanent magnet flux linkage [Wb]
+    /// * `pll_bandwidth` - PLL bandwidth [rad/s]
+    /// * `pole_pairs` - Number of motor pole pairs
+    pub fn new(
+        resistance: f32,
+        inductance: f32,
+        flux_linkage: f32,
+        pll_bandwidth: f32,
+        pole_pairs: u8,
+    ) -> Self {
+        let mut observer = Self {
+            resistance,
+            inductance,
+            flux_linkage,
+            pll_kp: 0.0,
+            pll_ki: 0.0,
+            pole_pairs,
+            flux_alpha: 0.0,
+            flux_beta: 0.0,
+            prev_i_alpha: 0.0,
+            prev_i_beta: 0.0,
+            angle: 0.0,
+            omega: 0.0,
+            phase_error: 0.0,
+            flux_ratio: 0.0,
+        };
+        observer.set_pll_bandwidth(pll_bandwidth);
+        observer
+    }
+
+    /// Set the motor model parameters
+    ///
+    /// # Arguments
+    /// * `resistance` - Phase resistance [Ω]
+    /// * `inductance` - Phase inductance [H]
+    /// * `flux_linkage` - Permanent magnet flux linkage [Wb]
+    pub fn set_motor_params(&mut self, resistance: f32, inductance: f32, flux_linkage: f32) {
+        self.resistance = resistance;
+        self.inductance = inductance;
+        self.flux_linkage = flux_linkage;
+    }
+
+    /// Set the PLL bandwidth (critically damped)
+    ///
+    /// # Arguments
+    /// * `bandwidth` - PLL natural frequency [rad/s]
+    pub fn set_pll_bandwidth(&mut self, bandwidth: f32) {
+        self.pll_kp = 2.0 * bandwidth;
+        self.pll_ki = bandwidth * bandwidth;
+    }
+
+    /// Set the number of pole pairs
+    pub fn set_pole_pairs(&mut self, pole_pairs: u8) {
+        self.pole_pairs = pole_pairs;
+    }
+
+    /// Reset the observer to standstill
+    pub fn reset(&mut self) {
+        self.flux_alpha = 0.0;
+        self.flux_beta = 0.0;
+        self.prev_i_alpha = 0.0;
+        self.prev_i_beta = 0.0;
+        self.angle = 0.0;
+        self.omega = 0.0;
+        self.phase_error = 0.0;
+        self.flux_ratio = 0.0;
+    }
+
+    /// Restart the observer from a known angle and speed
+    ///
+    /// Used when handing over from open-loop start-up: the flux estimate is
+    /// placed on the magnet flux circle at `electrical_angle` and the PLL
+    /// starts at `speed_rpm`, so the estimate only has to correct the error.
+    ///
+    /// # Arguments
+    /// * `electrical_angle` - Initial electrical angle [rad]
+    /// * `speed_rpm` - Initial mechanical speed [RPM]
+    pub fn reset_to(&mut self, electrical_angle: f32, speed_rpm: f32) {
+        self.reset();
+        self.flux_alpha = self.flux_linkage * cosf(electrical_angle);
+        self.flux_beta = self.flux_linkage * sinf(electrical_angle);
+        self.angle = wrap_angle(electrical_angle);
+        self.omega = speed_rpm * TAU / 60.0 * self.pole_pairs as f32;
+        self.flux_ratio = 1.0;
+    }
+
+    /// Update the observer with one control period of measurements
+    ///
+    /// # Arguments
+    /// * `v_alpha` - α voltage applied during the period that just ended [V]
+    /// * `v_beta` - β voltage applied during the period that just ended [V]
+    /// * `i_alpha` - α current sampled at the end of the period [A]
+    /// * `i_beta` - β current sampled at the end of the period [A]
+    /// * `dt` - Time step (seconds)
+    ///
+    /// # Returns
+    /// Tuple of (electrical angle in rad, mechanical speed in RPM)
+    pub fn update(
+        &mut self,
+        v_alpha: f32,
+        v_beta: f32,
+        i_alpha: f32,
+        i_beta: f32,
+        dt: f32,
+    ) -> (f32, f32) {
+        // Back-EMF integration (resistive drop at the mid-period current)
+        let i_alpha_mid = 0.5 * (i_alpha + self.prev_i_alpha);
+        let i_beta_mid = 0.5 * (i_beta + self.prev_i_beta);
+        self.prev_i_alpha = i_alpha;
+        self.prev_i_beta = i_beta;
+        self.flux_alpha += (v_alpha - self.resistance * i_alpha_mid) * dt;
+        self.flux_beta += (v_beta - self.resistance * i_beta_mid) * dt;
+
+        // Rotor flux = stator flux - inductive flux
+        let mut eta_alpha = self.flux_alpha - self.inductance * i_alpha;
+        let mut eta_beta = self.flux_beta - self.inductance * i_beta;
+
+        // Nonlinear correction towards |η| = ψm. The normalized error is
+        // clamped and the gain limited so the discrete update stays stable.
+        if self.flux_linkage > 0.0 {
+            let psi_sq = self.flux_linkage * self.flux_linkage;
+            let error =
+                (1.0 - (eta_alpha * eta_alpha + eta_beta * eta_beta) / psi_sq).clamp(-1.0, 1.0);
+            let gain = CONVERGENCE_GAIN.min(0.5 / dt);
+            let correction = 0.5 * gain * error * dt;
+            self.flux_alpha += correction * eta_alpha;
+            self.flux_beta += correction * eta_beta;
+            eta_alpha = self.flux_alpha - self.inductance * i_alpha;
+            eta_beta = self.flux_beta - self.inductance * i_beta;
+        }
+
+        let magnitude = sqrtf(eta_alpha * eta_alpha + eta_beta * eta_beta);
+        self.flux_ratio = if self.flux_linkage > 0.0 {
+            magnitude / self.flux_linkage
+        } else {
+            0.0
+        };
+
+        // PLL: predict the angle at this sample, then correct it with the
+        // phase error sin(θ_flux - θ_pll)
+        let predicted = wrap_angle(self.angle + self.omega * dt);
+        self.phase_error = if magnitude > 0.0 {
+            (eta_beta * cosf(predicted) - eta_alpha * sinf(predicted)) / magnitude
+        } else {
+            0.0
+        };
+        self.omega += self.pll_ki * self.phase_error * dt;
+        self.angle = wrap_angle(predicted + self.pll_kp * self.phase_error * dt);
+
+        (self.angle, self.get_speed_rpm())
+    }
+
+    /// Check whether the estimate can be used for commutation
+    ///
+    /// Requires the speed to be at least `min_speed_rpm`, the rotor flux
+    /// estimate to be close to the expected magnitude and the PLL to be
+    /// tracking it.
+    ///
+    /// # Arguments
+    /// * `min_speed_rpm` - Minimum mechanical speed [RPM]
+    pub fn is_locked(&self, min_speed_rpm: f32) -> bool {
+        self.get_speed_rpm().abs() >= min_speed_rpm
+            && (LOCK_FLUX_MIN..=LOCK_FLUX_MAX).contains(&self.flux_ratio)
+            && self.phase_error.abs() <= LOCK_MAX_PHASE_ERROR
+    }
+
+    /// Get the estimated electrical angle [rad]
+    pub fn get_electrical_angle(&self) -> f32 {
+        self.angle
+    }
+
+    /// Get the estimated mechanical speed [RPM]
+    pub fn get_speed_rpm(&self) -> f32 {
+        if self.pole_pairs == 0 {
+            return 0.0;
+        }
+        self.omega * 60.0 / (TAU * self.pole_pairs as f32)
+    }
+}
+
+/// Wrap an angle to [0, TAU)
+fn wrap_angle(angle: f32) -> f32 {
+    let mut wrapped = angle % TAU;
+    if wrapped < 0.0 {
+        wrapped += TAU;
+    }
+    wrapped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 0.0004; // 2.5kHz
+    const R: f32 = 0.5;
+    const L: f32 = 0.0005;
+    const PSI: f32 = 0.005;
+    const POLE_PAIRS: u8 = 6;
+
+    /// Surface PM motor running at a fixed electrical speed, driven by a
+    /// voltage held constant over each control period (as with PWM)
+    struct SimMotor {
+        angle: f32,
+        omega: f32,
+        i_alpha: f32,
+        i_beta: f32,
+    }
+
+    impl SimMotor {
+        fn new(omega: f32) -> Self {
+            Self {
+                angle: 0.3,
+                omega,
+                i_alpha: 0.0,
+                i_beta: 0.0,
+            }
+        }
+
+        /// Apply a voltage for one control period
+        fn step(&mut self, v_alpha: f32, v_beta: f32) {
+            const SUBSTEPS: usize = 50;
+            let h = DT / SUBSTEPS as f32;
+            for _ in 0..SUBSTEPS {
+                let e_alpha = -self.omega * PSI * sinf(self.angle);
+                let e_beta = self.omega * PSI * cosf(self.angle);
+                self.i_alpha += (v_alpha - R * self.i_alpha - e_alpha) / L * h;
+                self.i_beta += (v_beta - R * self.i_beta - e_beta) / L * h;
+                self.angle = wrap_angle(self.angle + self.omega * h);
+            }
+        }
+
+        /// Voltage that leads the back-EMF by a fixed amount
+        fn drive_voltage(&self) -> (f32, f32) {
+            let amplitude = 1.1 * self.omega.abs() * PSI;
+            let phase =
+                self.angle + libm::copysignf(core::f32::consts::FRAC_PI_2, self.omega) + 0.1;
+            (amplitude * cosf(phase), amplitude * sinf(phase))
+        }
+    }
+
+    fn angle_error(a: f32, b: f32) -> f32 {
+        let diff = wrap_angle(a - b);
+        if diff > TAU / 2.0 {
+            diff - TAU
+        } else {
+            diff
+        }
+    }
+
+    fn run(observer: &mut FluxObserver, motor: &mut SimMotor, periods: usize) {
+        for _ in 0..periods {
+            let (v_alpha, v_beta) = motor.drive_voltage();
+            motor.step(v_alpha, v_beta);
+            observer.update(v_alpha, v_beta, motor.i_alpha, motor.i_beta, DT);
+        }
+    }
+
+    #[test]
+    fn test_tracks_angle_and_speed() {
+        // 1000 RPM mechanical
+        let omega = 1000.0 * TAU / 60.0 * POLE_PAIRS as f32;
+        let mut motor = SimMotor::new(omega);
+        let mut observer = FluxObserver::new(R, L, PSI, 300.0, POLE_PAIRS);
+
+        run(&mut observer, &mut motor, 2500);
+
+        assert!(angle_error(observer.get_electrical_angle(), motor.angle).abs() < 0.05);
+        assert!((observer.get_speed_rpm() - 1000.0).abs() < 10.0);
+        assert!(observer.is_locked(300.0));
+        assert!(!observer.is_locked(1500.0));
+    }
+
+    #[test]
+    fn test_tracks_reverse_rotation() {
+        let omega = -600.0 * TAU / 60.0 * POLE_PAIRS as f32;
+        let mut motor = SimMotor::new(omega);
+        let mut observer = FluxObserver::new(R, L, PSI, 300.0, POLE_PAIRS);
+
+        run(&mut observer, &mut motor, 2500);
+
+        assert!(angle_error(observer.get_electrical_angle(), motor.angle).abs() < 0.05);
+        assert!((observer.get_speed_rpm() + 600.0).abs() < 10.0);
+        assert!(observer.is_locked(300.0));
+    }
+
+    #[test]
+    fn test_converges_from_wrong_initial_angle() {
+        let omega = 1000.0 * TAU / 60.0 * POLE_PAIRS as f32;
+        let mut motor = SimMotor::new(omega);
+        let mut observer = FluxObserver::new(R, L, PSI, 300.0, POLE_PAIRS);
+        observer.reset_to(motor.angle + 1.0, 800.0);
+
+        run(&mut observer, &mut motor, 250);
+
+        assert!(angle_error(observer.get_electrical_angle(), motor.angle).abs() < 0.05);
+        assert!((observer.get_speed_rpm() - 1000.0).abs() < 20.0);
+    }
+
+    #[test]
+    fn test_not_locked_at_standstill() {
+        let mut observer = FluxObserver::new(R, L, PSI, 300.0, POLE_PAIRS);
+        for _ in 0..2500 {
+            observer.update(0.0, 0.0, 0.0, 0.0, DT);
+        }
+
+        assert!(!observer.is_locked(300.0));
+    }
+}
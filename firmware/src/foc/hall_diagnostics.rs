@@ -0,0 +1,455 @@
+// Hall sensor fault detection and diagnostics
+// Checks the hall state sequence against the drive command and latches faults
+
+use super::hall_sensor::HallSensor;
+
+/// Fault flag: hall state 000/111 for longer than `INVALID_STATE_FAULT_TIME`
+pub const FAULT_INVALID_STATE: u8 = 0x01;
+
+/// Fault flag: repeated single edges that skip a sector (two hall lines changed at once)
+pub const FAULT_SKIPPED_SECTOR: u8 = 0x02;
+
+/// Fault flag: hall sequence keeps accelerating against the drive direction
+pub const FAULT_WRONG_DIRECTION: u8 = 0x04;
+
+/// Fault flag: no hall edge for `STUCK_FAULT_TIME` while driving
+pub const FAULT_STUCK: u8 = 0x08;
+
+/// Fault flag: repeated edges closer than physically possible
+pub const FAULT_GLITCH: u8 = 0x10;
+
+/// Invalid hall state duration that latches a fault (seconds)
+const INVALID_STATE_FAULT_TIME: f32 = 0.05;
+
+/// Drive level (|Vq| / max voltage) above which the rotor is expected to move
+const ACTIVE_DRIVE_LEVEL: f32 = 0.5;
+
+/// Time without hall edges at an active drive level that latches a fault (seconds)
+///
+/// Holding a stalled rotor at more than half the voltage limit for this long
+/// is also reported as a stuck hall.
+const STUCK_FAULT_TIME: f32 = 1.0;
+
+/// Consecutive accelerating edges against the drive direction that latch a fault
+/// (two electrical revolutions)
+const WRONG_DIRECTION_FAULT_EDGES: u8 = 12;
+
+/// Skipped-sector or glitch events that latch a fault
+///
+/// Each event type has its own count, which decays by one every
+/// `EVENT_DECAY_TIME` so that isolated events are tolerated.
+const EVENT_FAULT_COUNT: f32 = 5.0;
+
+/// Time for one skipped-sector or glitch event to be forgiven (seconds)
+const EVENT_DECAY_TIME: f32 = 1.0;
+
+/// Hall interface readings for one control period
+#[derive(Debug, Clone, Copy)]
+pub struct HallSample {
+    /// Raw hall state (0-7)
+    pub state: u8,
+    /// Total number of hall edges (wrapping)
+    pub edge_count: u32,
+    /// Total number of glitch edges (wrapping)
+    pub glitch_count: u32,
+    /// Timer cycles between the last two edges
+    pub period_cycles: u32,
+}
+
+/// Latched fault flags and event counters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HallDiagnosticsStatus {
+    /// Latched fault flags (`FAULT_*`)
+    pub fault_flags: u8,
+    /// Number of times the hall state became invalid (000/111)
+    pub invalid_states: u16,
+    /// Number of single edges that skipped a sector
+    pub skipped_sectors: u16,
+    /// Number of edges closer than physically possible
+    pub glitches: u16,
+    /// Number of accelerating edges against the drive direction
+    pub wrong_direction_edges: u8,
+}
+
+impl HallDiagnosticsStatus {
+    /// Create a status without faults or events
+    pub const fn new() -> Self {
+        Self {
+            fault_flags: 0,
+            invalid_states: 0,
+            skipped_sectors: 0,
+            glitches: 0,
+            wrong_direction_edges: 0,
+        }
+    }
+}
+
+impl Default for HallDiagnosticsStatus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Hall sensor fault detector
+///
+/// Fed once per control period with the hall interface readings and the
+/// drive command. Faults stay latched until `clear_fault()`; event counters
+/// accumulate for the lifetime of the detector.
+pub struct HallDiagnostics {
+    /// Latched faults and counters
+    status: HallDiagnosticsStatus,
+    /// Whether the previous sample has been stored since the last reset
+    initialized: bool,
+    /// Previous sample
+    prev: HallSample,
+    /// Edge period at the previous edge (timer cycles)
+    prev_edge_period: u32,
+    /// Time the hall state has been invalid (seconds)
+    invalid_time: f32,
+    /// Time without edges at an active drive level (seconds)
+    stuck_time: f32,
+    /// Consecutive accelerating edges against the drive direction
+    wrong_direction_run: u8,
+    /// Decaying skipped-sector event count
+    skip_score: f32,
+    /// Decaying glitch event count
+    glitch_score: f32,
+    /// Hall sequence runs backwards for a positive drive (calibration result)
+    direction_inversed: bool,
+}
+
+impl HallDiagnostics {
+    /// Create a new fault detector without faults
+    pub fn new() -> Self {
+        Self {
+            status: HallDiagnosticsStatus::new(),
+            initialized: false,
+            prev: HallSample {
+                state: 0,
+                edge_count: 0,
+                glitch_count: 0,
+                period_cycles: 0,
+            },
+            prev_edge_period: 0,
+            invalid_time: 0.0,
+            stuck_time: 0.0,
+            wrong_direction_run: 0,
+            skip_score: 0.0,
+            glitch_score: 0.0,
+            direction_inversed: false,
+        }
+    }
+
+    /// Restart detection (e.g. when the motor is enabled)
+    ///
+    /// Keeps latched faults and counters.
+    pub fn reset(&mut self) {
+        self.initialized = false;
+        self.prev_edge_period = 0;
+        self.invalid_time = 0.0;
+        self.stuck_time = 0.0;
+        self.wrong_direction_run = 0;
+        self.skip_score = 0.0;
+        self.glitch_score = 0.0;
+    }
+
+    /// Clear the latched faults and restart detection
+    pub fn clear_fault(&mut self) {
+        self.status.fault_flags = 0;
+        self.reset();
+    }
+
+    /// Set whether the hall sequence runs backwards for a positive drive
+    ///
+    /// # Arguments
+    /// * `inversed` - Sensor direction inversed (from calibration)
+    pub fn set_direction_inversed(&mut self, inversed: bool) {
+        self.direction_inversed = inversed;
+    }
+
+    /// Check whether any fault is latched
+    pub fn is_faulted(&self) -> bool {
+        self.status.fault_flags != 0
+    }
+
+    /// Get latched faults and counters
+    pub fn status(&self) -> HallDiagnosticsStatus {
+        self.status
+    }
+
+    /// Check one control period of hall readings
+    ///
+    /// # Arguments
+    /// * `sample` - Hall interface readings
+    /// * `drive` - Signed drive level (Vq / max voltage, positive = forward)
+    /// * `dt` - Time step (seconds)
+    ///
+    /// # Returns
+    /// Fault flags latched by this update (0 if none)
+    pub fn update(&mut self, sample: HallSample, drive: f32, dt: f32) -> u8 {
+        if !self.initialized {
+            self.prev = sample;
+            self.initialized = true;
+            return 0;
+        }
+
+        let previous_faults = self.status.fault_flags;
+        let edges = sample.edge_count.wrapping_sub(self.prev.edge_count);
+        let glitches = sample.glitch_count.wrapping_sub(self.prev.glitch_count);
+        let active = drive.abs() >= ACTIVE_DRIVE_LEVEL;
+
+        // Edges closer than physically possible
+        if glitches > 0 {
+            self.status.glitches = self.status.glitches.saturating_add(glitches as u16);
+            self.glitch_score += glitches as f32;
+        }
+
+        // Invalid hall state (000/111)
+        let state_index = HallSensor::normalize_state(sample.state);
+        if state_index.is_some() {
+            self.invalid_time = 0.0;
+        } else {
+            if HallSensor::normalize_state(self.prev.state).is_some() {
+                self.status.invalid_states = self.status.invalid_states.saturating_add(1);
+            }
+            self.invalid_time += dt;
+            if self.invalid_time >= INVALID_STATE_FAULT_TIME {
+                self.status.fault_flags |= FAULT_INVALID_STATE;
+            }
+        }
+
+        // Sector transitions (only a single edge can be checked for skipped sectors)
+        let prev_index = HallSensor::normalize_state(self.prev.state);
+        if let (Some(index), Some(prev_index)) = (state_index, prev_index) {
+            if edges == 1 && index != prev_index {
+                match (index + 6 - prev_index) % 6 {
+                    1 => self.check_direction(1.0, sample.period_cycles, drive, active),
+                    5 => self.check_direction(-1.0, sample.period_cycles, drive, active),
+                    _ => {
+                        self.status.skipped_sectors = self.status.skipped_sectors.saturating_add(1);
+                        self.skip_score += 1.0;
+                    }
+                }
+            }
+        }
+        if edges > 0 {
+            self.prev_edge_period = sample.period_cycles;
+        }
+
+        // Stuck hall: no edges while driving
+        if edges == 0 && active {
+            self.stuck_time += dt;
+            if self.stuck_time >= STUCK_FAULT_TIME {
+                self.status.fault_flags |= FAULT_STUCK;
+            }
+        } else {
+            self.stuck_time = 0.0;
+        }
+
+        // Repeated skipped sectors / glitches (isolated events decay)
+        if self.skip_score >= EVENT_FAULT_COUNT {
+            self.status.fault_flags |= FAULT_SKIPPED_SECTOR;
+        }
+        if self.glitch_score >= EVENT_FAULT_COUNT {
+            self.status.fault_flags |= FAULT_GLITCH;
+        }
+        let decay = dt / EVENT_DECAY_TIME;
+        self.skip_score = (self.skip_score - decay).max(0.0);
+        self.glitch_score = (self.glitch_score - decay).max(0.0);
+
+        self.prev = sample;
+        self.status.fault_flags & !previous_faults
+    }
+
+    /// Check an adjacent-sector edge against the drive direction
+    ///
+    /// Moving against the drive is normal while braking, but then the edges
+    /// slow down. Edges that keep getting faster against an active drive mean
+    /// the hall sequence does not match the motor phases.
+    fn check_direction(&mut self, direction: f32, period_cycles: u32, drive: f32, active: bool) {
+        let drive_direction = if self.direction_inversed {
+            -drive
+        } else {
+            drive
+        };
+        let accelerating = period_cycles < self.prev_edge_period;
+
+        if active && direction * drive_direction < 0.0 && accelerating {
+            self.status.wrong_direction_edges = self.status.wrong_direction_edges.saturating_add(1);
+            self.wrong_direction_run += 1;
+            if self.wrong_direction_run >= WRONG_DIRECTION_FAULT_EDGES {
+                self.status.fault_flags |= FAULT_WRONG_DIRECTION;
+            }
+        } else {
+            self.wrong_direction_run = 0;
+        }
+    }
+}
+
+impl Default for HallDiagnostics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 0.0004; // 2.5kHz
+
+    /// Forward rotation order of raw hall states
+    const SEQUENCE: [u8; 6] = [1, 3, 2, 6, 4, 5];
+
+    /// Feeds hall samples into a detector like the TIM4 interface would
+    struct Bench {
+        diag: HallDiagnostics,
+        sample: HallSample,
+    }
+
+    impl Bench {
+        fn new() -> Self {
+            let sample = HallSample {
+                state: 1,
+                edge_count: 0,
+                glitch_count: 0,
+                period_cycles: 0,
+            };
+            let mut diag = HallDiagnostics::new();
+            diag.update(sample, 0.0, DT);
+            Self { diag, sample }
+        }
+
+        /// One edge to a new raw state, then one control period
+        fn edge(&mut self, state: u8, period_cycles: u32, drive: f32) -> u8 {
+            self.sample.state = state;
+            self.sample.edge_count += 1;
+            self.sample.period_cycles = period_cycles;
+            self.diag.update(self.sample, drive, DT)
+        }
+
+        /// Control periods without edges
+        fn idle(&mut self, periods: u32, drive: f32) {
+            for _ in 0..periods {
+                self.diag.update(self.sample, drive, DT);
+            }
+        }
+    }
+
+    #[test]
+    fn test_normal_rotation_has_no_fault() {
+        let mut bench = Bench::new();
+
+        // Forward acceleration, then braking with negative drive while still turning forward
+        let mut period = 100_000;
+        for i in 0..60 {
+            bench.edge(SEQUENCE[(i + 1) % 6], period, 0.8);
+            bench.idle(2, 0.8);
+            period -= 1000;
+        }
+        for i in 60..120 {
+            period += 1000;
+            bench.edge(SEQUENCE[(i + 1) % 6], period, -0.8);
+            bench.idle(2, -0.8);
+        }
+
+        assert!(!bench.diag.is_faulted());
+        assert_eq!(bench.diag.status(), HallDiagnosticsStatus::new());
+    }
+
+    #[test]
+    fn test_invalid_state_latches() {
+        let mut bench = Bench::new();
+
+        bench.edge(7, 50_000, 0.0);
+        bench.idle(10, 0.0);
+        assert!(!bench.diag.is_faulted());
+
+        bench.idle(150, 0.0);
+        assert_eq!(bench.diag.status().fault_flags, FAULT_INVALID_STATE);
+        assert_eq!(bench.diag.status().invalid_states, 1);
+    }
+
+    #[test]
+    fn test_skipped_sectors_latch_when_repeated() {
+        let mut bench = Bench::new();
+
+        // A single skip is tolerated
+        bench.edge(2, 50_000, 0.0); // 1 -> 2 skips state 3
+        bench.idle(100, 0.0);
+        assert!(!bench.diag.is_faulted());
+
+        // Two edges within one period are not a skip (fast rotation)
+        bench.sample.edge_count += 1;
+        bench.edge(4, 1_000, 0.0); // 2 -> (6) -> 4
+        assert_eq!(bench.diag.status().skipped_sectors, 1);
+
+        for state in [1, 6, 3, 4, 2] {
+            bench.edge(state, 50_000, 0.0);
+        }
+        assert_ne!(bench.diag.status().fault_flags & FAULT_SKIPPED_SECTOR, 0);
+    }
+
+    #[test]
+    fn test_stuck_hall_latches_only_while_driving() {
+        let mut bench = Bench::new();
+
+        bench.idle(5000, 0.2);
+        assert!(!bench.diag.is_faulted());
+
+        bench.idle(2400, 0.8);
+        assert!(!bench.diag.is_faulted());
+        bench.idle(200, 0.8);
+        assert_eq!(bench.diag.status().fault_flags, FAULT_STUCK);
+    }
+
+    #[test]
+    fn test_wrong_direction_latches() {
+        let mut bench = Bench::new();
+
+        // Accelerating backwards with a forward drive
+        // (the first edge has no previous period to compare with)
+        let mut period = 100_000;
+        let mut index = 600;
+        for _ in 0..WRONG_DIRECTION_FAULT_EDGES {
+            index -= 1;
+            bench.edge(SEQUENCE[index % 6], period, 0.8);
+            period -= 5000;
+        }
+        assert!(!bench.diag.is_faulted());
+
+        index -= 1;
+        bench.edge(SEQUENCE[index % 6], period, 0.8);
+        assert_eq!(bench.diag.status().fault_flags, FAULT_WRONG_DIRECTION);
+
+        // Correct when the sensor direction is known to be inversed
+        bench.diag.clear_fault();
+        bench.diag.set_direction_inversed(true);
+        bench.idle(1, 0.8);
+        for _ in 0..20 {
+            index -= 1;
+            bench.edge(SEQUENCE[index % 6], period, 0.8);
+            period -= 1000;
+        }
+        assert!(!bench.diag.is_faulted());
+    }
+
+    #[test]
+    fn test_glitches_latch_and_clear() {
+        let mut bench = Bench::new();
+
+        bench.sample.glitch_count += 3;
+        bench.idle(1, 0.0);
+        assert!(!bench.diag.is_faulted());
+
+        bench.sample.glitch_count += 3;
+        let latched = bench.diag.update(bench.sample, 0.0, DT);
+        assert_eq!(latched, FAULT_GLITCH);
+        assert_eq!(bench.diag.status().glitches, 6);
+
+        // Clearing keeps the counters
+        bench.diag.clear_fault();
+        assert!(!bench.diag.is_faulted());
+        assert_eq!(bench.diag.status().glitches, 6);
+    }
+}
@@ -1,10 +1,12 @@
 // Hall sensor processing for BLDC motor position and speed estimation
 // Uses TIM4 hardware Hall interface for high-precision edge detection and speed calculation
 // Implements foc-simple compatible mechanical angle based calculation
+// with an optional PLL tracker fed by hall edge timestamps
 
-use crate::fmt::*;
+use super::shaft_position::ShaftPosition;
+use super::HallEstimator;
 use crate::hall_tim;
-use core::f32::consts::TAU;
+use core::f32::consts::{PI, TAU};
 
 /// Hall state lookup table (foc-simple compatible)
 /// Maps raw hall state (1-6) to normalized index (0-5)
@@ -22,6 +24,140 @@ const HALL_STATE_TABLE: [u8; 8] = [
     255, // 0b111: Invalid state (use 255 as marker)
 ];
 
+/// Uniform sector table: electrical start angle of each normalized sector
+/// relative to sector 0 for ideally placed (60° spaced) hall sensors
+const UNIFORM_SECTOR_OFFSETS: [f32; 6] = [
+    0.0,
+    TAU / 6.0,
+    2.0 * TAU / 6.0,
+    3.0 * TAU / 6.0,
+    4.0 * TAU / 6.0,
+    5.0 * TAU / 6.0,
+];
+
+/// Upper bound of the per-edge PLL loop gain (bandwidth * edge interval)
+///
+/// The PLL is only corrected at hall edges, so it behaves as a discrete loop
+/// sampled once per edge. Limiting `bandwidth * interval` keeps it stable and
+/// well damped at low speed where edges are far apart.
+const MAX_PLL_EDGE_GAIN: f32 = 0.5;
+
+/// Initial PLL bandwidth (rad/s), overwritten by `set_pll_bandwidth`
+const DEFAULT_PLL_BANDWIDTH: f32 = 200.0;
+
+/// Hall edge measurement fed to the PLL
+struct HallEdge {
+    /// Mechanical angle of the crossed sector boundary (radians)
+    angle: f32,
+    /// Time elapsed since the edge (seconds)
+    age: f32,
+    /// Time between this edge and the previous one (seconds)
+    interval: f32,
+    /// Rotation direction (+1.0 forward, -1.0 backward)
+    direction: f32,
+}
+
+/// Type-2 PLL tracking the mechanical angle from hall edge timestamps
+///
+/// Between edges the angle advances at the estimated speed, limited to the
+/// sector reported by the hall sensors. At each edge the phase error against
+/// the crossed sector boundary (advanced by the edge age) corrects the angle
+/// proportionally and the speed through the integrator, as a PLL with
+/// `kp = 2 * bandwidth` and `ki = bandwidth²` whose error is held over the
+/// edge interval.
+struct HallPll {
+    /// Loop bandwidth (rad/s)
+    bandwidth: f32,
+    /// Estimated mechanical angle (radians, [0, TAU))
+    angle: f32,
+    /// Estimated mechanical speed (rad/s, signed)
+    omega: f32,
+    /// Whether an edge has been seen since the last reset
+    tracking: bool,
+}
+
+impl HallPll {
+    fn new(bandwidth: f32) -> Self {
+        Self {
+            bandwidth,
+            angle: 0.0,
+            omega: 0.0,
+            tracking: false,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.angle = 0.0;
+        self.omega = 0.0;
+        self.tracking = false;
+    }
+
+    /// Advance the PLL by one control period
+    ///
+    /// # Arguments
+    /// * `dt` - Time step (seconds)
+    /// * `edge` - Hall edge detected since the previous update, if any
+    /// * `sector_start` - Mechanical angle where the current hall sector starts (radians)
+    /// * `sector_width` - Mechanical width of a hall sector (radians)
+    ///
+    /// # Returns
+    /// Estimated mechanical angle in radians
+    fn update(
+        &mut self,
+        dt: f32,
+        edge: Option<HallEdge>,
+        sector_start: f32,
+        sector_width: f32,
+    ) -> f32 {
+        self.angle = wrap_angle(self.angle + self.omega * dt);
+
+        match edge {
+            Some(edge) if self.tracking => {
+                let measured = edge.angle + self.omega * edge.age;
+                let error = wrap_pi(measured - self.angle);
+                let gain = (self.bandwidth * edge.interval).min(MAX_PLL_EDGE_GAIN);
+
+                self.angle = wrap_angle(self.angle + 2.0 * gain * error);
+                self.omega += gain * gain * error / edge.interval;
+            }
+            Some(edge) => {
+                // First edge: start from the boundary at the speed of the last sector
+                self.omega = edge.direction * sector_width / edge.interval;
+                self.angle = wrap_angle(edge.angle + self.omega * edge.age);
+                self.tracking = true;
+            }
+            None if !self.tracking => {
+                // No edge yet: hold the discrete hall angle
+                self.angle = sector_start;
+                self.omega = 0.0;
+            }
+            None => {}
+        }
+
+        // The rotor is known to be inside the current sector
+        let offset = wrap_pi(self.angle - sector_start)
+            .max(0.0)
+            .min(sector_width);
+        self.angle = wrap_angle(sector_start + offset);
+        self.angle
+    }
+}
+
+/// Wrap an angle to [0, TAU)
+fn wrap_angle(angle: f32) -> f32 {
+    let wrapped = angle % TAU;
+    if wrapped < 0.0 {
+        wrapped + TAU
+    } else {
+        wrapped
+    }
+}
+
+/// Wrap an angle difference to [-PI, PI)
+fn wrap_pi(angle: f32) -> f32 {
+    wrap_angle(angle + PI) - PI
+}
+
 /// Hall sensor state machine for position and speed estimation
 /// Implements foc-simple compatible mechanical angle based calculation
 /// Relies on hall_tim (TIM4 hardware) for edge detection and speed calculation
@@ -49,6 +185,21 @@ pub struct HallSensor {
     enable_interpolation: bool,
     /// Electrical offset in radians (calibration value)
     electrical_offset: f32,
+    /// Multi-turn shaft position tracked from the (interpolated) mechanical angle
+    shaft_position: ShaftPosition,
+    /// Mechanical angle at which position tracking started (position zero)
+    position_origin: f32,
+    /// Whether position tracking has been initialized since the last reset
+    position_tracking: bool,
+    /// Electrical start angle of each normalized sector relative to sector 0
+    /// (radians, strictly increasing, from the calibrated sector table)
+    sector_offsets: [f32; 6],
+    /// Normalized sector in which the last edge period was spent
+    traversed_sector: u8,
+    /// Angle and speed estimator
+    estimator: HallEstimator,
+    /// PLL tracker (used when `estimator` is `HallEstimator::Pll`)
+    pll: HallPll,
 }
 
 impl HallSensor {
@@ -73,6 +224,13 @@ impl HallSensor {
             pole_pairs,
             enable_interpolation: true, // Enable angle interpolation by default
             electrical_offset: 0.0,
+            shaft_position: ShaftPosition::new(),
+            position_origin: 0.0,
+            position_tracking: false,
+            sector_offsets: UNIFORM_SECTOR_OFFSETS,
+            traversed_sector: 0,
+            estimator: HallEstimator::Filter,
+            pll: HallPll::new(DEFAULT_PLL_BANDWIDTH),
         }
     }
 
@@ -87,9 +245,25 @@ impl HallSensor {
         (1..=6).contains(&state)
     }
 
+    /// Convert a raw hall state to its position in the rotation order
+    ///
+    /// # Arguments
+    /// * `state` - Raw hall state (0-7)
+    ///
+    /// # Returns
+    /// Normalized index (0-5, increasing in the forward direction), or `None`
+    /// for the invalid states 000/111
+    pub fn normalize_state(state: u8) -> Option<u8> {
+        match HALL_STATE_TABLE.get(state as usize) {
+            Some(&index) if index != 255 => Some(index),
+            _ => None,
+        }
+    }
+
     /// Update hall sensor state and estimate position/speed
     /// Uses foc-simple compatible mechanical angle based calculation
     /// Uses TIM4 hardware for both speed calculation and Hall state reading
+    /// With the PLL estimator the speed is signed (negative when rotating backwards)
     ///
     /// # Arguments
     /// * `dt` - Time step since last update (seconds) - used for angle interpolation
@@ -100,21 +274,8 @@ impl HallSensor {
         // Get Hall state from TIM4 interrupt handler (captured on edge)
         let raw_hall_state = hall_tim::get_hall_state();
 
-        // Validate hall state (throttle error logging to avoid flooding)
+        // Validate hall state (invalid states are counted by the hall diagnostics)
         if !Self::is_valid_state(raw_hall_state) {
-            static mut ERROR_LOG_COUNTER: u32 = 0;
-            unsafe {
-                ERROR_LOG_COUNTER += 1;
-                // Log error only once every 2500 calls (1 second at 2.5kHz)
-                if ERROR_LOG_COUNTER >= 2500 {
-                    ERROR_LOG_COUNTER = 0;
-                    error!(
-                        "Invalid hall state: {} (repeated, throttling log)",
-                        raw_hall_state
-                    );
-                }
-            }
-
             // Check timeout from TIM4
             if hall_tim::is_timeout() {
                 self.speed_rpm = 0.0;
@@ -163,15 +324,16 @@ impl HallSensor {
         if hall_tim::is_timeout() || period_cycles == 0 {
             self.speed_rpm = 0.0;
             self.time_since_edge = 0.0;
+            self.pll.reset();
 
-            // Calculate mechanical angle from hall_idx (discrete, no interpolation)
-            let hall_state_idx = self.hall_idx_base + (normalized_state as u32);
-            self.mechanical_angle = (hall_state_idx as f32) * self.angle_per_state;
+            // Calculate mechanical angle from the sector start (discrete, no interpolation)
+            self.mechanical_angle = self.sector_start(normalized_state);
 
             // Normalize mechanical angle to [0, TAU)
             while self.mechanical_angle >= TAU {
                 self.mechanical_angle -= TAU;
             }
+            self.update_position();
 
             // Calculate electrical angle: mechanical_angle * pole_pairs - offset
             let mut electrical_angle =
@@ -188,11 +350,19 @@ impl HallSensor {
             return (electrical_angle, self.speed_rpm);
         }
 
-        // Calculate instant speed from TIM4 period
-        let instant_rpm = hall_tim::calculate_speed_rpm(period_cycles, self.pole_pairs);
-
         // Detect state change (hall edge)
         let state_changed = normalized_state != self.prev_state && self.prev_state != 255;
+        let mut edge_direction = 0.0;
+
+        if state_changed {
+            // The period ending at this edge was spent in the sector being left
+            self.traversed_sector = self.prev_state;
+        }
+
+        // Calculate instant speed from TIM4 period, scaled by the width of the traversed sector
+        let instant_rpm = hall_tim::calculate_speed_rpm(period_cycles, self.pole_pairs)
+            * self.sector_width(self.traversed_sector)
+            / self.angle_per_state;
 
         if state_changed {
             // Handle hall index wrapping (foc-simple compatible)
@@ -212,22 +382,18 @@ impl HallSensor {
                 }
             }
 
+            // Adjacent states only; a skipped state gives no usable edge angle
+            if normalized_state == (self.prev_state + 1) % 6 {
+                edge_direction = 1.0;
+            } else if normalized_state == (self.prev_state + 5) % 6 {
+                edge_direction = -1.0;
+            }
+
             // Apply low-pass filter to speed (foc-simple formula: new = (instant + 19*old)/20 for alpha=0.05)
             // Equivalent to: new = alpha*instant + (1-alpha)*old where alpha = 1/20 = 0.05
             self.speed_rpm = self.speed_filter_alpha * instant_rpm
                 + (1.0 - self.speed_filter_alpha) * self.speed_rpm;
 
-            trace!(
-                "Hall edge: {} -> {} (normalized: {} -> {}), period={} cycles, instant_rpm={}, filtered_rpm={}",
-                self.prev_state,
-                normalized_state,
-                self.prev_state,
-                normalized_state,
-                period_cycles,
-                instant_rpm,
-                self.speed_rpm
-            );
-
             // Reset edge timer
             self.time_since_edge = 0.0;
 
@@ -242,12 +408,29 @@ impl HallSensor {
                 + (1.0 - self.speed_filter_alpha) * self.speed_rpm;
         }
 
-        // Calculate mechanical angle from hall index (foc-simple method)
-        let hall_state_idx = self.hall_idx_base + (normalized_state as u32);
-        let base_mechanical_angle = (hall_state_idx as f32) * self.angle_per_state;
+        // Calculate mechanical angle from hall index and the sector table
+        let base_mechanical_angle = self.sector_start(normalized_state);
+        let sector_width = self.sector_width(normalized_state);
 
-        // Apply angle interpolation if enabled and motor is moving
-        if self.enable_interpolation && self.speed_rpm.abs() > 1.0 {
+        if self.estimator == HallEstimator::Pll {
+            // Forward edges enter the sector at its start, backward edges at its end
+            let edge = (edge_direction != 0.0).then(|| HallEdge {
+                angle: if edge_direction > 0.0 {
+                    base_mechanical_angle
+                } else {
+                    base_mechanical_angle + sector_width
+                },
+                age: hall_tim::get_cycles_since_edge() as f32 / hall_tim::TIMER_CLOCK_HZ as f32,
+                interval: period_cycles as f32 / hall_tim::TIMER_CLOCK_HZ as f32,
+                direction: edge_direction,
+            });
+
+            self.mechanical_angle = self
+                .pll
+                .update(dt, edge, base_mechanical_angle, sector_width);
+            self.speed_rpm = self.pll.omega * (60.0 / TAU); // rad/s to RPM
+        } else if self.enable_interpolation && self.speed_rpm.abs() > 1.0 {
+            // Apply angle interpolation if enabled and motor is moving
             // Calculate mechanical angular velocity (rad/s)
             let mechanical_omega = self.speed_rpm * (TAU / 60.0); // RPM to rad/s (2*PI/60)
 
@@ -266,6 +449,7 @@ impl HallSensor {
         while self.mechanical_angle < 0.0 {
             self.mechanical_angle += TAU;
         }
+        self.update_position();
 
         // Calculate electrical angle: mechanical_angle * pole_pairs - offset (foc-simple formula)
         let mut electrical_angle =
@@ -282,6 +466,81 @@ impl HallSensor {
         (electrical_angle, self.speed_rpm)
     }
 
+    /// Mechanical angle where a normalized sector starts in the current electrical revolution
+    fn sector_start(&self, normalized_state: u8) -> f32 {
+        (self.hall_idx_base as f32) * self.angle_per_state
+            + self.sector_offsets[normalized_state as usize] / (self.pole_pairs as f32)
+    }
+
+    /// Mechanical width of a normalized sector
+    fn sector_width(&self, normalized_state: u8) -> f32 {
+        let idx = normalized_state as usize;
+        let end = if idx + 1 < 6 {
+            self.sector_offsets[idx + 1]
+        } else {
+            TAU
+        };
+        (end - self.sector_offsets[idx]) / (self.pole_pairs as f32)
+    }
+
+    /// Convert calibrated sector start angles into offsets relative to sector 0
+    ///
+    /// # Arguments
+    /// * `angles` - Electrical start angle of each sector, indexed by raw hall state - 1
+    ///
+    /// # Returns
+    /// Offsets in normalized sector order, or `None` if the angles are not
+    /// strictly increasing in the rotation order
+    fn relative_sector_offsets(angles: &[f32; 6]) -> Option<[f32; 6]> {
+        let mut start = [0.0; 6];
+        for (raw, angle) in (1..=6).zip(angles.iter()) {
+            if !angle.is_finite() {
+                return None;
+            }
+            start[HALL_STATE_TABLE[raw] as usize] = *angle;
+        }
+
+        let mut offsets = [0.0; 6];
+        for idx in 1..6 {
+            offsets[idx] = wrap_angle(start[idx] - start[0]);
+            if offsets[idx] <= offsets[idx - 1] {
+                return None;
+            }
+        }
+        Some(offsets)
+    }
+
+    /// Use a calibrated sector table for the sector to angle mapping
+    ///
+    /// # Arguments
+    /// * `angles` - Electrical angle at which each sector starts when rotating
+    ///   forward, indexed by raw hall state - 1 (radians)
+    ///
+    /// # Returns
+    /// `true` if the table was applied, `false` if it was rejected (sectors
+    /// out of rotation order) and the previous mapping is kept
+    ///
+    /// The table replaces the electrical offset, since it already holds
+    /// absolute electrical angles.
+    pub fn set_sector_angles(&mut self, angles: &[f32; 6]) -> bool {
+        match Self::relative_sector_offsets(angles) {
+            Some(offsets) => {
+                self.sector_offsets = offsets;
+                // Sector 0 starts at mechanical angle 0 of each electrical revolution
+                self.electrical_offset = wrap_angle(-angles[0]);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Go back to evenly spaced (60° electrical) sectors
+    ///
+    /// The electrical offset must be set again with `set_electrical_offset`.
+    pub fn reset_sector_angles(&mut self) {
+        self.sector_offsets = UNIFORM_SECTOR_OFFSETS;
+    }
+
     /// Get current electrical angle in radians
     #[allow(dead_code)]
     pub fn get_electrical_angle(&self) -> f32 {
@@ -293,8 +552,33 @@ impl HallSensor {
         self.mechanical_angle
     }
 
+    /// Track multi-turn position from the current mechanical angle
+    ///
+    /// The first valid angle after a reset becomes the position zero, so the
+    /// initial jump from the reset angle is not counted as a revolution.
+    /// Must be called every control period so that successive angles differ by
+    /// less than half a revolution.
+    fn update_position(&mut self) {
+        if self.position_tracking {
+            self.shaft_position
+                .update_shaft_angle(self.mechanical_angle);
+        } else {
+            self.shaft_position.reset_at(self.mechanical_angle);
+            self.position_origin = self.mechanical_angle;
+            self.position_tracking = true;
+        }
+    }
+
+    /// Get multi-turn shaft position in radians
+    ///
+    /// Position is relative to the first valid Hall state after `reset()`
+    /// (i.e. where the motor was enabled). Uses the interpolated mechanical
+    /// angle when interpolation is enabled.
+    pub fn get_position(&self) -> f32 {
+        self.shaft_position.get_position() - self.position_origin
+    }
+
     /// Get current speed in RPM
-    #[allow(dead_code)]
     pub fn get_speed_rpm(&self) -> f32 {
         self.speed_rpm
     }
@@ -306,6 +590,11 @@ impl HallSensor {
         self.hall_idx_base = 0;
         self.speed_rpm = 0.0;
         self.time_since_edge = 0.0;
+        self.shaft_position.reset();
+        self.position_origin = 0.0;
+        self.position_tracking = false;
+        self.traversed_sector = 0;
+        self.pll.reset();
     }
 
     /// Reset speed filter and interpolation timer to a specific speed value
@@ -345,6 +634,56 @@ impl HallSensor {
         self.speed_filter_alpha = alpha.clamp(0.0, 1.0);
     }
 
+    /// Select the angle and speed estimator
+    ///
+    /// # Arguments
+    /// * `estimator` - `Filter` for the filtered edge period with extrapolation,
+    ///   `Pll` for the edge-timestamp PLL
+    ///
+    /// Restarts the PLL, so this should only be called while the motor is stopped.
+    pub fn set_estimator(&mut self, estimator: HallEstimator) {
+        self.estimator = estimator;
+        self.pll.reset();
+    }
+
+    /// Get the selected estimator
+    #[allow(dead_code)]
+    pub fn get_estimator(&self) -> HallEstimator {
+        self.estimator
+    }
+
+    /// Set the PLL bandwidth
+    ///
+    /// # Arguments
+    /// * `bandwidth` - Loop bandwidth in rad/s (higher = faster but noisier)
+    ///
+    /// The effective bandwidth is limited at low speed to keep the loop stable,
+    /// since the PLL is only corrected at hall edges.
+    pub fn set_pll_bandwidth(&mut self, bandwidth: f32) {
+        self.pll.bandwidth = bandwidth.max(0.0);
+    }
+
+    /// Set the number of pole pairs
+    ///
+    /// # Arguments
+    /// * `pole_pairs` - Number of pole pairs in the motor (must be >= 1)
+    ///
+    /// Recomputes the hall index range and resets the tracked position,
+    /// so this should only be called while the motor is stopped.
+    pub fn set_pole_pairs(&mut self, pole_pairs: u8) {
+        let pole_pairs = pole_pairs.max(1);
+        self.pole_pairs = pole_pairs;
+        self.hall_idx_max = (pole_pairs as u32) * 6;
+        self.angle_per_state = TAU / (self.hall_idx_max as f32);
+        self.reset();
+    }
+
+    /// Get the number of pole pairs
+    #[allow(dead_code)]
+    pub fn get_pole_pairs(&self) -> u8 {
+        self.pole_pairs
+    }
+
     /// Set the electrical offset (calibration value)
     ///
     /// # Arguments
@@ -416,4 +755,178 @@ mod tests {
         // electrical_angle should be 1.047198 rad (60 deg)
         // This is because: 10 deg mechanical * 6 pole_pairs = 60 deg electrical
     }
+
+    /// Simulate a rotor with the given speed profile and feed its hall edges to the PLL
+    ///
+    /// Returns the final (true angle, PLL angle, PLL speed) in rad and rad/s.
+    fn run_pll(
+        pll: &mut HallPll,
+        start: f32,
+        omega: impl Fn(f32) -> f32,
+        steps: u32,
+    ) -> (f32, f32, f32) {
+        const DT: f32 = 0.0004; // 2.5kHz
+        const SUBSTEPS: u32 = 100;
+        let width = TAU / 6.0; // 1 pole pair
+        let sector_of = |angle: f32| libm::floorf(angle / width);
+
+        let mut true_angle = start;
+        let mut sector = sector_of(true_angle);
+        let mut last_edge_time = None;
+        let mut time = 0.0f32;
+        let mut angle = 0.0;
+
+        for _ in 0..steps {
+            // Integrate finely to timestamp the edges
+            let mut edge = None;
+            for _ in 0..SUBSTEPS {
+                let h = DT / SUBSTEPS as f32;
+                time += h;
+                true_angle += omega(time) * h;
+                let new_sector = sector_of(true_angle);
+                if new_sector != sector {
+                    let direction = new_sector - sector;
+                    let boundary = if direction > 0.0 { new_sector } else { sector };
+                    edge = Some((boundary * width, time, last_edge_time, direction));
+                    last_edge_time = Some(time);
+                    sector = new_sector;
+                }
+            }
+
+            let edge = edge.and_then(|(boundary, edge_time, prev, direction)| {
+                prev.map(|prev: f32| HallEdge {
+                    angle: wrap_angle(boundary),
+                    age: time - edge_time,
+                    interval: edge_time - prev,
+                    direction,
+                })
+            });
+            angle = pll.update(DT, edge, wrap_angle(sector * width), width);
+        }
+
+        (wrap_angle(true_angle), angle, pll.omega)
+    }
+
+    #[test]
+    fn test_pll_tracks_constant_speed() {
+        let mut pll = HallPll::new(200.0);
+        let omega = 100.0; // rad/s mechanical (~955 RPM)
+
+        let (true_angle, angle, speed) = run_pll(&mut pll, 0.1, |_| omega, 2500);
+
+        assert!((speed - omega).abs() < omega * 0.01);
+        assert!(wrap_pi(angle - true_angle).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_pll_tracks_reverse_rotation() {
+        let mut pll = HallPll::new(200.0);
+        let omega = -60.0;
+
+        let (true_angle, angle, speed) = run_pll(&mut pll, 3.0, |_| omega, 2500);
+
+        assert!((speed - omega).abs() < omega.abs() * 0.01);
+        assert!(wrap_pi(angle - true_angle).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_pll_follows_acceleration() {
+        let mut pll = HallPll::new(200.0);
+
+        // Ramp 20 -> 120 rad/s over 1s, then hold
+        let omega = |t: f32| 20.0 + 100.0 * t.min(1.0);
+        let (true_angle, angle, speed) = run_pll(&mut pll, 0.0, omega, 3750);
+
+        assert!((speed - 120.0).abs() < 1.0);
+        assert!(wrap_pi(angle - true_angle).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_pll_holds_sector_without_edges() {
+        let mut pll = HallPll::new(200.0);
+        let width = TAU / 6.0;
+
+        // Before the first edge the angle is the discrete hall angle
+        assert_eq!(pll.update(0.0004, None, 2.0 * width, width), 2.0 * width);
+
+        // Extrapolation stops at the end of the sector when the edge is late
+        pll.tracking = true;
+        pll.omega = 100.0;
+        let mut angle = 0.0;
+        for _ in 0..100 {
+            angle = pll.update(0.0004, None, 2.0 * width, width);
+        }
+        assert!((angle - 3.0 * width).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_uniform_sector_table() {
+        let sensor = HallSensor::new(6, 0.05);
+
+        for state in 0..6 {
+            let expected = state as f32 * sensor.angle_per_state;
+            assert!((sensor.sector_start(state) - expected).abs() < 1e-6);
+            assert!((sensor.sector_width(state) - sensor.angle_per_state).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_calibrated_sector_table() {
+        let mut sensor = HallSensor::new(2, 0.05);
+        let deg = PI / 180.0;
+
+        // Raw states 1..6, rotation order 1 -> 3 -> 2 -> 6 -> 4 -> 5,
+        // with sector 1 starting at 350° electrical and uneven spacing
+        let angles = [
+            350.0 * deg,
+            115.0 * deg,
+            52.0 * deg,
+            238.0 * deg,
+            296.0 * deg,
+            178.0 * deg,
+        ];
+        assert!(sensor.set_sector_angles(&angles));
+
+        // Normalized order: raw 1, 3, 2, 6, 4, 5
+        let expected_electrical = [350.0, 52.0, 115.0, 178.0, 238.0, 296.0];
+        for (state, expected) in expected_electrical.iter().enumerate() {
+            sensor.mechanical_angle = sensor.sector_start(state as u8);
+            let electrical = wrap_angle(sensor.get_electrical_angle());
+            assert!(wrap_pi(electrical - expected * deg).abs() < 1e-4);
+        }
+
+        // Sector widths follow the table and add up to one electrical revolution
+        assert!((sensor.sector_width(0) * 2.0 - 62.0 * deg).abs() < 1e-4);
+        assert!((sensor.sector_width(5) * 2.0 - 54.0 * deg).abs() < 1e-4);
+        let total: f32 = (0..6).map(|state| sensor.sector_width(state)).sum();
+        assert!((total - PI).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_sector_table_out_of_order_rejected() {
+        let mut sensor = HallSensor::new(2, 0.05);
+        let deg = PI / 180.0;
+
+        // Raw states 2 and 3 swapped
+        let angles = [
+            0.0,
+            60.0 * deg,
+            120.0 * deg,
+            240.0 * deg,
+            300.0 * deg,
+            180.0 * deg,
+        ];
+        assert!(!sensor.set_sector_angles(&angles));
+        assert!(!sensor.set_sector_angles(&[f32::NAN; 6]));
+
+        // The uniform mapping is kept
+        assert!((sensor.sector_width(1) - sensor.angle_per_state).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_wrap_pi() {
+        assert!((wrap_pi(TAU - 0.1) + 0.1).abs() < 1e-5);
+        assert!((wrap_pi(-TAU + 0.1) - 0.1).abs() < 1e-5);
+        assert!((wrap_pi(0.5) - 0.5).abs() < 1e-6);
+    }
 }
@@ -0,0 +1,185 @@
+// Multi-rate loop scheduler
+// Runs the speed and position loops at integer fractions of the current loop rate
+
+/// Rate groups due in a control cycle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoopTicks {
+    /// The speed loop runs in this cycle
+    pub speed: bool,
+    /// The position loop runs in this cycle (only together with the speed loop)
+    pub position: bool,
+}
+
+/// Multi-rate loop scheduler
+///
+/// The current loop and the angle estimate run in every control cycle, at the
+/// PWM-synchronous control rate. The speed loop runs once every
+/// `speed_divider` control cycles and the position loop once every
+/// `position_divider` speed loop cycles, each with its own time step.
+///
+/// After a reset the next cycle runs every rate group, so a loop that has just
+/// been (re)started produces an output immediately instead of holding a stale
+/// one until its next slot.
+pub struct LoopScheduler {
+    /// Control cycle time step (seconds)
+    dt: f32,
+    /// Control cycles per speed loop cycle
+    speed_divider: u16,
+    /// Speed loop cycles per position loop cycle
+    position_divider: u16,
+    /// Control cycles until the next speed loop cycle
+    speed_countdown: u16,
+    /// Speed loop cycles until the next position loop cycle
+    position_countdown: u16,
+}
+
+impl LoopScheduler {
+    /// Create a new loop scheduler
+    ///
+    /// # Arguments
+    /// * `dt` - Control cycle time step (seconds)
+    /// * `speed_divider` - Control cycles per speed loop cycle (0 is treated as 1)
+    /// * `position_divider` - Speed loop cycles per position loop cycle (0 is treated as 1)
+    pub fn new(dt: f32, speed_divider: u16, position_divider: u16) -> Self {
+        Self {
+            dt,
+            speed_divider: speed_divider.max(1),
+            position_divider: position_divider.max(1),
+            speed_countdown: 0,
+            position_countdown: 0,
+        }
+    }
+
+    /// Set the rate dividers and restart the schedule
+    ///
+    /// # Arguments
+    /// * `speed_divider` - Control cycles per speed loop cycle (0 is treated as 1)
+    /// * `position_divider` - Speed loop cycles per position loop cycle (0 is treated as 1)
+    pub fn set_dividers(&mut self, speed_divider: u16, position_divider: u16) {
+        self.speed_divider = speed_divider.max(1);
+        self.position_divider = position_divider.max(1);
+        self.reset();
+    }
+
+    /// Restart the schedule so the next cycle runs every rate group
+    pub fn reset(&mut self) {
+        self.speed_countdown = 0;
+        self.position_countdown = 0;
+    }
+
+    /// Advance by one control cycle
+    ///
+    /// # Returns
+    /// Rate groups that run in this cycle
+    pub fn tick(&mut self) -> LoopTicks {
+        let speed = self.speed_countdown == 0;
+        let mut position = false;
+
+        if speed {
+            self.speed_countdown = self.speed_divider;
+
+            position = self.position_countdown == 0;
+            if position {
+                self.position_countdown = self.position_divider;
+            }
+            self.position_countdown -= 1;
+        }
+        self.speed_countdown -= 1;
+
+        LoopTicks { speed, position }
+    }
+
+    /// Get the control cycle (current loop) time step (seconds)
+    pub fn current_dt(&self) -> f32 {
+        self.dt
+    }
+
+    /// Get the speed loop time step (seconds)
+    pub fn speed_dt(&self) -> f32 {
+        self.dt * self.speed_divider as f32
+    }
+
+    /// Get the position loop time step (seconds)
+    pub fn position_dt(&self) -> f32 {
+        self.speed_dt() * self.position_divider as f32
+    }
+
+    /// Get the control cycles per speed loop cycle
+    pub fn speed_divider(&self) -> u16 {
+        self.speed_divider
+    }
+
+    /// Get the speed loop cycles per position loop cycle
+    pub fn position_divider(&self) -> u16 {
+        self.position_divider
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 0.00004; // 25kHz
+
+    #[test]
+    fn test_rate_groups() {
+        let mut scheduler = LoopScheduler::new(DT, 4, 3);
+
+        let ticks: [LoopTicks; 24] = core::array::from_fn(|_| scheduler.tick());
+        for (cycle, tick) in ticks.iter().enumerate() {
+            assert_eq!(tick.speed, cycle % 4 == 0, "speed at cycle {}", cycle);
+            assert_eq!(
+                tick.position,
+                cycle % 12 == 0,
+                "position at cycle {}",
+                cycle
+            );
+        }
+    }
+
+    #[test]
+    fn test_unity_dividers() {
+        let mut scheduler = LoopScheduler::new(DT, 0, 1);
+        assert_eq!(scheduler.speed_divider(), 1);
+
+        for _ in 0..5 {
+            assert_eq!(
+                scheduler.tick(),
+                LoopTicks {
+                    speed: true,
+                    position: true
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_reset_runs_all_groups() {
+        let mut scheduler = LoopScheduler::new(DT, 10, 5);
+        scheduler.tick();
+        assert!(!scheduler.tick().speed);
+
+        scheduler.reset();
+        assert_eq!(
+            scheduler.tick(),
+            LoopTicks {
+                speed: true,
+                position: true
+            }
+        );
+        assert!(!scheduler.tick().speed);
+
+        scheduler.set_dividers(2, 1);
+        assert!(scheduler.tick().position);
+        assert!(!scheduler.tick().speed);
+        assert!(scheduler.tick().position);
+    }
+
+    #[test]
+    fn test_time_steps() {
+        let scheduler = LoopScheduler::new(DT, 10, 5);
+        assert!((scheduler.current_dt() - 0.00004).abs() < 1e-9);
+        assert!((scheduler.speed_dt() - 0.0004).abs() < 1e-8);
+        assert!((scheduler.position_dt() - 0.002).abs() < 1e-7);
+    }
+}
@@ -0,0 +1,331 @@
+// Motion profile generator for speed and position setpoints
+// Trapezoidal (jerk = 0) or S-curve (jerk > 0) acceleration limiting
+
+use core::f32::consts::TAU;
+use libm::{ceilf, copysignf, sqrtf};
+
+/// RPM to rad/s conversion factor
+const RPM_TO_RAD_PER_S: f32 = TAU / 60.0;
+
+/// Length of the S-curve smoothing history (control periods)
+///
+/// Bounds the smoothing window: at 2.5kHz the jerk limit is honoured for
+/// acceleration ramps up to ~100ms long (acceleration / jerk).
+pub const SMOOTHING_HISTORY_LEN: usize = 256;
+
+/// Setpoint profile generator
+///
+/// Shapes steps in the speed or position command into a smooth reference with
+/// bounded acceleration and, optionally, bounded jerk.
+///
+/// The first stage is a trapezoidal profile: the speed moves towards the target
+/// at the acceleration limit while its magnitude increases and at the
+/// deceleration limit while it decreases. For position targets the speed is
+/// additionally limited so that the profile stops exactly on the target.
+///
+/// With a positive jerk the trapezoidal output is passed through a moving
+/// average of length `max(acceleration, deceleration) / jerk`, which turns each
+/// acceleration step into a ramp (S-curve). The moving average keeps the end
+/// point, so position moves still stop exactly on the target without
+/// overshoot, delayed by half the window. A direct switch from full
+/// acceleration to full deceleration can momentarily reach up to twice the
+/// jerk limit.
+pub struct MotionProfile {
+    /// Acceleration limit [RPM/s]
+    acceleration: f32,
+    /// Deceleration limit [RPM/s]
+    deceleration: f32,
+    /// Jerk limit [RPM/s²] (0 = trapezoidal)
+    jerk: f32,
+    /// Trapezoidal stage speed [RPM]
+    velocity: f32,
+    /// Trapezoidal stage position [rad]
+    position: f32,
+    /// Recent trapezoidal outputs (speeds in speed mode, positions in position mode)
+    history: [f32; SMOOTHING_HISTORY_LEN],
+    /// Index of the newest entry in `history`
+    head: usize,
+}
+
+impl MotionProfile {
+    /// Create a new profile generator at rest at position zero
+    ///
+    /// # Arguments
+    /// * `acceleration` - Acceleration limit [RPM/s]
+    /// * `deceleration` - Deceleration limit [RPM/s]
+    /// * `jerk` - Jerk limit [RPM/s²] (0 for a trapezoidal profile)
+    pub fn new(acceleration: f32, deceleration: f32, jerk: f32) -> Self {
+        Self {
+            acceleration,
+            deceleration,
+            jerk,
+            velocity: 0.0,
+            position: 0.0,
+            history: [0.0; SMOOTHING_HISTORY_LEN],
+            head: 0,
+        }
+    }
+
+    /// Set the profile limits
+    ///
+    /// Takes effect on the next update without resetting the profile state.
+    ///
+    /// # Arguments
+    /// * `acceleration` - Acceleration limit [RPM/s]
+    /// * `deceleration` - Deceleration limit [RPM/s]
+    /// * `jerk` - Jerk limit [RPM/s²] (0 for a trapezoidal profile)
+    pub fn set_limits(&mut self, acceleration: f32, deceleration: f32, jerk: f32) {
+        self.acceleration = acceleration;
+        self.deceleration = deceleration;
+        self.jerk = jerk;
+    }
+
+    /// Restart a speed profile from a given speed
+    ///
+    /// # Arguments
+    /// * `velocity` - Start speed [RPM]
+    pub fn reset_velocity(&mut self, velocity: f32) {
+        self.velocity = velocity;
+        self.history = [velocity; SMOOTHING_HISTORY_LEN];
+    }
+
+    /// Restart a position profile from a given position
+    ///
+    /// # Arguments
+    /// * `position` - Start position [rad]
+    /// * `velocity` - Start speed [RPM]
+    pub fn reset_position(&mut self, position: f32, velocity: f32) {
+        self.position = position;
+        self.velocity = velocity;
+        self.history = [position; SMOOTHING_HISTORY_LEN];
+    }
+
+    /// Advance a speed profile towards a target speed
+    ///
+    /// # Arguments
+    /// * `target_velocity` - Target speed [RPM]
+    /// * `dt` - Time step (seconds)
+    ///
+    /// # Returns
+    /// Profile speed reference [RPM]
+    pub fn update_velocity(&mut self, target_velocity: f32, dt: f32) -> f32 {
+        self.step_velocity(target_velocity, dt);
+        self.push_history(self.velocity);
+
+        let window = self.smoothing_window(dt);
+        self.history_mean(window)
+    }
+
+    /// Advance a position profile towards a target position
+    ///
+    /// # Arguments
+    /// * `target_position` - Target position [rad]
+    /// * `max_velocity` - Cruise speed limit [RPM]
+    /// * `dt` - Time step (seconds)
+    ///
+    /// # Returns
+    /// Tuple of (position reference in rad, speed reference in RPM)
+    pub fn update_position(
+        &mut self,
+        target_position: f32,
+        max_velocity: f32,
+        dt: f32,
+    ) -> (f32, f32) {
+        let error = target_position - self.position;
+
+        // Settle on the target once it is reachable within one step at a speed
+        // that can be stopped within one step
+        let reach = (self.velocity.abs() + self.deceleration * dt) * RPM_TO_RAD_PER_S * dt;
+        if error.abs() <= reach && self.velocity.abs() <= self.deceleration * dt {
+            self.position = target_position;
+            self.velocity = 0.0;
+        } else {
+            let stopping_velocity = self.stopping_velocity(error.abs(), dt) / RPM_TO_RAD_PER_S;
+            let target_velocity = copysignf(stopping_velocity.min(max_velocity.abs()), error);
+
+            self.step_velocity(target_velocity, dt);
+            self.position += self.velocity * RPM_TO_RAD_PER_S * dt;
+        }
+
+        let window = self.smoothing_window(dt);
+        let oldest = self.history_at(window - 1);
+        self.push_history(self.position);
+
+        // Speed reference is the slope of the averaged position
+        let position = self.history_mean(window);
+        let velocity = (self.position - oldest) / (window as f32 * dt) / RPM_TO_RAD_PER_S;
+
+        (position, velocity)
+    }
+
+    /// Highest speed [rad/s] from which the trapezoidal stage stops within a distance
+    ///
+    /// Stepping the speed once per `dt` adds `v * dt / 2` to the continuous
+    /// stopping distance `v² / (2 * decel)`; this solves that quadratic for `v`.
+    fn stopping_velocity(&self, distance: f32, dt: f32) -> f32 {
+        let decel = self.deceleration * RPM_TO_RAD_PER_S;
+        let offset = decel * dt / 2.0;
+        sqrtf(offset * offset + 2.0 * decel * distance) - offset
+    }
+
+    /// Step the trapezoidal stage speed towards a target within the rate limits
+    fn step_velocity(&mut self, target_velocity: f32, dt: f32) {
+        let error = target_velocity - self.velocity;
+
+        // Speed magnitude decreasing: use the deceleration limit
+        let limit = if self.velocity * error < 0.0 {
+            self.deceleration
+        } else {
+            self.acceleration
+        };
+
+        let max_delta = limit * dt;
+        self.velocity += error.max(-max_delta).min(max_delta);
+    }
+
+    /// Number of control periods averaged for the S-curve (1 = trapezoidal)
+    fn smoothing_window(&self, dt: f32) -> usize {
+        if self.jerk <= 0.0 || dt <= 0.0 {
+            return 1;
+        }
+
+        let ramp_time = self.acceleration.max(self.deceleration) / self.jerk;
+        let window = ceilf(ramp_time / dt);
+        if window >= SMOOTHING_HISTORY_LEN as f32 {
+            SMOOTHING_HISTORY_LEN - 1
+        } else {
+            (window as usize).max(1)
+        }
+    }
+
+    /// Append a trapezoidal stage output to the history
+    fn push_history(&mut self, value: f32) {
+        self.head = (self.head + 1) % SMOOTHING_HISTORY_LEN;
+        self.history[self.head] = value;
+    }
+
+    /// Get a history entry, `age` periods before the newest one
+    fn history_at(&self, age: usize) -> f32 {
+        self.history[(self.head + SMOOTHING_HISTORY_LEN - age) % SMOOTHING_HISTORY_LEN]
+    }
+
+    /// Average of the newest `window` history entries
+    ///
+    /// Sums the deviations from the newest entry so that large multi-turn
+    /// positions do not lose precision, and a settled profile averages to
+    /// exactly its final value.
+    fn history_mean(&self, window: usize) -> f32 {
+        let newest = self.history_at(0);
+        let mut deviation = 0.0;
+        for age in 1..window {
+            deviation += self.history_at(age) - newest;
+        }
+        newest + deviation / window as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 0.0004; // 2.5kHz
+
+    #[test]
+    fn test_trapezoidal_speed_ramp() {
+        let mut profile = MotionProfile::new(1000.0, 500.0, 0.0);
+
+        // 0 → 1000 RPM at 1000 RPM/s takes 1s
+        let mut velocity = 0.0;
+        for _ in 0..1250 {
+            velocity = profile.update_velocity(1000.0, DT);
+        }
+        assert!((velocity - 500.0).abs() < 1.0);
+        for _ in 0..1300 {
+            velocity = profile.update_velocity(1000.0, DT);
+        }
+        assert_eq!(velocity, 1000.0);
+
+        // 1000 → 0 RPM at 500 RPM/s takes 2s
+        for _ in 0..2500 {
+            velocity = profile.update_velocity(0.0, DT);
+        }
+        assert!((velocity - 500.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_reversal_uses_decel_then_accel() {
+        let mut profile = MotionProfile::new(1000.0, 250.0, 0.0);
+
+        // Slowing down from +100 RPM is deceleration (250 RPM/s)
+        profile.reset_velocity(100.0);
+        let velocity = profile.update_velocity(-100.0, 0.1);
+        assert!((velocity - 75.0).abs() < 1e-3);
+
+        // Speeding up in the negative direction is acceleration (1000 RPM/s)
+        profile.reset_velocity(0.0);
+        let velocity = profile.update_velocity(-100.0, 0.05);
+        assert!((velocity + 50.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_s_curve_limits_jerk() {
+        let jerk = 20000.0;
+        let mut profile = MotionProfile::new(1000.0, 1000.0, jerk);
+
+        let mut prev_velocity = 0.0;
+        let mut prev_accel = 0.0;
+        let mut max_velocity: f32 = 0.0;
+        let mut max_accel: f32 = 0.0;
+        for _ in 0..5000 {
+            let velocity = profile.update_velocity(1000.0, DT);
+            let accel = (velocity - prev_velocity) / DT;
+            // f32 finite differences add noise of a few percent at ~1000 RPM
+            assert!((accel - prev_accel).abs() <= jerk * DT * 1.1);
+            max_velocity = max_velocity.max(velocity);
+            max_accel = max_accel.max(accel);
+            prev_velocity = velocity;
+            prev_accel = accel;
+        }
+
+        assert!((prev_velocity - 1000.0).abs() < 1e-3);
+        assert!(max_velocity <= 1000.0 + 1e-3);
+        assert!(max_accel <= 1000.0 * 1.01);
+    }
+
+    #[test]
+    fn test_position_move_arrives_at_rest() {
+        for jerk in [0.0, 20000.0] {
+            let mut profile = MotionProfile::new(2000.0, 1000.0, jerk);
+            let target = 10.0 * TAU; // 10 revolutions
+
+            let mut max_velocity: f32 = 0.0;
+            let mut max_position: f32 = 0.0;
+            let (mut position, mut velocity) = (0.0, 0.0);
+            for _ in 0..10000 {
+                (position, velocity) = profile.update_position(target, 600.0, DT);
+                max_velocity = max_velocity.max(velocity);
+                max_position = max_position.max(position);
+            }
+
+            assert_eq!(position, target);
+            assert_eq!(velocity, 0.0);
+            assert!(max_velocity <= 600.0 * 1.001);
+            assert!(max_position <= target + 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_position_move_backwards() {
+        let mut profile = MotionProfile::new(1000.0, 1000.0, 50000.0);
+        profile.reset_position(5.0, 0.0);
+
+        let (mut position, mut velocity) = (0.0, 0.0);
+        for _ in 0..10000 {
+            (position, velocity) = profile.update_position(-5.0, 300.0, DT);
+            assert!(position >= -5.0 - 1e-3);
+        }
+
+        assert_eq!(position, -5.0);
+        assert_eq!(velocity, 0.0);
+    }
+}
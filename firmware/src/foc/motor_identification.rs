@@ -0,0 +1,792 @@
+//! モーターパラメータ同定モジュール
+//!
+//! 相抵抗・インダクタンス・鎖交磁束・慣性モーメント・摩擦を自動測定します。
+//!
+//! 測定手順:
+//! 1. 相抵抗: α軸（U相）方向に直流電流を流し、2点の電流での電圧差から算出
+//!    （デッドタイム等による一定の電圧誤差は差をとることで相殺される）
+//! 2. インダクタンス: 同じ方向に電圧ステップを繰り返し印加し、1制御周期後の電流の立ち上がりから
+//!    時定数を求めて算出
+//! 3. 鎖交磁束: 一定の大きさの電流ベクトルを回転させて（I/f駆動）試験速度まで回し、逆起電力から算出
+//! 4. 慣性モーメント・摩擦: 出力停止での惰性減速と、q軸電流一定での加速にかかる時間から算出
+//!    （Hallの電気角でq軸電流を流すため、角度キャリブレーション済みの場合のみ実施）
+//!
+//! 電流は振幅不変のClarke変換（α軸電流 = U相電流）で扱うため、
+//! トルク定数は 1.5 × 極対数 × 鎖交磁束 [N·m/A] となる。
+
+use super::current_control::CurrentController;
+use super::transforms::{inverse_park, park};
+use core::f32::consts::TAU;
+use libm::{logf, sqrtf};
+
+/// 抵抗測定：電圧調整の積分ゲイン [V/(A·s)]（抵抗が未知のため積分のみで電流を合わせる）
+const RESISTANCE_KI: f32 = 20.0;
+
+/// 抵抗測定：電流が安定するまでの待ち時間 [s]
+const RESISTANCE_SETTLE_TIME: f32 = 1.0;
+
+/// 抵抗測定：電圧・電流の平均をとる時間 [s]
+const RESISTANCE_MEASURE_TIME: f32 = 0.2;
+
+/// インダクタンス測定：電圧ステップのON/OFFそれぞれの制御周期数
+const INDUCTANCE_HALF_PERIOD: u32 = 50;
+
+/// インダクタンス測定：電圧ステップの繰り返し回数
+const INDUCTANCE_REPEATS: u32 = 8;
+
+/// 鎖交磁束測定：I/f駆動の加速度 [RPM/s]
+const FLUX_ACCELERATION: f32 = 500.0;
+
+/// 鎖交磁束測定：試験速度到達後の安定待ち時間 [s]
+const FLUX_SETTLE_TIME: f32 = 1.0;
+
+/// 鎖交磁束測定：逆起電力の平均をとる時間 [s]
+const FLUX_MEASURE_TIME: f32 = 0.5;
+
+/// I/f駆動・加速試験の電流制御帯域 [rad/s]（測定した抵抗・インダクタンスからゲインを決定）
+const CURRENT_BANDWIDTH: f32 = 1000.0;
+
+/// 回転子が強制回転に追従していないと判断する速度誤差（試験速度に対する比）
+const SPEED_TOLERANCE: f32 = 0.3;
+
+/// 機械定数測定：速度区間の上端（試験速度に対する比）
+const MECHANICAL_HIGH_RATIO: f32 = 0.8;
+
+/// 機械定数測定：速度区間の中間（試験速度に対する比、粘性摩擦の算出用）
+const MECHANICAL_MID_RATIO: f32 = 0.55;
+
+/// 機械定数測定：速度区間の下端（試験速度に対する比）
+const MECHANICAL_LOW_RATIO: f32 = 0.3;
+
+/// 機械定数測定：惰性減速・加速それぞれのタイムアウト [s]
+const MECHANICAL_TIMEOUT: f32 = 10.0;
+
+/// RPMからrad/sへの変換係数
+const RPM_TO_RAD_PER_S: f32 = TAU / 60.0;
+
+/// パラメータ同定の状態
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IdentificationState {
+    /// 未実施
+    Idle = 0,
+    /// 相抵抗測定中（直流注入）
+    Resistance = 1,
+    /// インダクタンス測定中（電圧ステップ）
+    Inductance = 2,
+    /// 鎖交磁束測定中（I/f駆動で回転）
+    FluxLinkage = 3,
+    /// 惰性減速の測定中（出力停止）
+    Coastdown = 4,
+    /// 加速の測定中（q軸電流一定）
+    Acceleration = 5,
+    /// 同定完了
+    Completed = 6,
+    /// 同定失敗
+    Failed = 7,
+}
+
+/// パラメータ同定の失敗要因
+///
+/// 機械定数の測定のみ失敗した場合は、電気定数を有効として同定を完了し、要因を記録する。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IdentificationFailure {
+    /// 最大電圧で試験電流に達しない（相の断線等）
+    CurrentNotReached,
+    /// 算出した相抵抗が不正
+    InvalidResistance,
+    /// 電流ステップの立ち上がりが測定範囲外
+    StepRatioOutOfRange,
+    /// 回転子がI/f駆動に追従しない
+    RotorNotFollowing,
+    /// 算出した鎖交磁束が不正
+    InvalidFluxLinkage,
+    /// 惰性減速がタイムアウト（機械定数のみ）
+    CoastdownTimeout,
+    /// 加速がタイムアウト（機械定数のみ）
+    AccelerationTimeout,
+    /// 算出した慣性モーメントが不正（機械定数のみ）
+    InvalidInertia,
+}
+
+impl IdentificationFailure {
+    /// ログ出力用の失敗要因
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::CurrentNotReached => "current did not reach the test current (phase open?)",
+            Self::InvalidResistance => "invalid resistance",
+            Self::StepRatioOutOfRange => "current step ratio out of measurable range",
+            Self::RotorNotFollowing => "rotor not following",
+            Self::InvalidFluxLinkage => "invalid flux linkage",
+            Self::CoastdownTimeout => "coastdown timed out",
+            Self::AccelerationTimeout => "acceleration timed out",
+            Self::InvalidInertia => "invalid inertia",
+        }
+    }
+}
+
+/// パラメータ同定結果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IdentificationResult {
+    /// 相抵抗 [Ω]
+    pub resistance: f32,
+    /// 相インダクタンス [H]
+    pub inductance: f32,
+    /// 永久磁石の鎖交磁束 [Wb]（相ピーク値）
+    pub flux_linkage: f32,
+    /// 慣性モーメント [kg·m²]
+    pub inertia: f32,
+    /// クーロン摩擦トルク [N·m]
+    pub friction_torque: f32,
+    /// 粘性摩擦係数 [N·m·s/rad]
+    pub viscous_friction: f32,
+    /// 電気定数（抵抗・インダクタンス・鎖交磁束）有効フラグ
+    pub electrical_valid: bool,
+    /// 機械定数（慣性モーメント・摩擦）有効フラグ
+    pub mechanical_valid: bool,
+}
+
+impl IdentificationResult {
+    /// 新しい同定結果を作成（未測定状態）
+    pub const fn new() -> Self {
+        Self {
+            resistance: 0.0,
+            inductance: 0.0,
+            flux_linkage: 0.0,
+            inertia: 0.0,
+            friction_torque: 0.0,
+            viscous_friction: 0.0,
+            electrical_valid: false,
+            mechanical_valid: false,
+        }
+    }
+}
+
+impl Default for IdentificationResult {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// モーターパラメータ同定
+pub struct MotorIdentification {
+    /// 現在の状態
+    state: IdentificationState,
+    /// 極対数
+    pole_pairs: u8,
+    /// 試験電流 [A]
+    test_current: f32,
+    /// 試験速度 [RPM]
+    test_speed: f32,
+    /// 最大電圧 [V]
+    max_voltage: f32,
+    /// 機械定数の測定を行うか（Hallの電気角が校正済みの場合のみ）
+    mechanical_test: bool,
+    /// 同定結果
+    result: IdentificationResult,
+    /// 現在の測定ステップの経過時間 [s]
+    elapsed: f32,
+    /// 測定ステップ内のカウンタ（抵抗: 測定点、インダクタンス: 制御周期）
+    step: u32,
+    /// 抵抗測定の印加電圧 / インダクタンス測定のステップ電圧 [V]
+    voltage: f32,
+    /// 抵抗測定の1点目（電圧 [V], 電流 [A]）
+    first_point: (f32, f32),
+    /// 測定値の積算（抵抗: 電圧、インダクタンス: ステップ直前の電流、鎖交磁束: 逆起電力）
+    sum_a: f32,
+    /// 測定値の積算（抵抗: 電流、インダクタンス: 1周期後の電流、加速: q軸電流）
+    sum_b: f32,
+    /// 測定値の積算（インダクタンス: 定常電流）
+    sum_c: f32,
+    /// 積算したサンプル数
+    samples: u32,
+    /// I/f駆動の電流ベクトルの電気角 [rad]
+    angle: f32,
+    /// I/f駆動の回転数 [RPM]
+    speed: f32,
+    /// 前周期の相電流（α/β軸） [A]
+    prev_current: (f32, f32),
+    /// 前周期に出力した電圧（α/β軸） [V]
+    prev_voltage: (f32, f32),
+    /// 電流制御（I/f駆動・加速試験）
+    controller: CurrentController,
+    /// 惰性減速で速度区間の上端・中間・下端を通過した時刻 [s]
+    coast_marks: [Option<f32>; 3],
+    /// 惰性減速にかかった時間（上端→中間、中間→下端） [s]
+    coast_times: (f32, f32),
+    /// 加速で速度区間の下端を通過した時刻 [s]
+    accel_start: Option<f32>,
+    /// 失敗要因（失敗時、または機械定数の測定のみ失敗した場合）
+    failure: Option<IdentificationFailure>,
+}
+
+impl MotorIdentification {
+    /// 新しいモーターパラメータ同定を作成
+    ///
+    /// # 引数
+    /// * `pole_pairs` - モーターの極対数
+    pub fn new(pole_pairs: u8) -> Self {
+        Self {
+            state: IdentificationState::Idle,
+            pole_pairs,
+            test_current: 0.0,
+            test_speed: 0.0,
+            max_voltage: 0.0,
+            mechanical_test: false,
+            result: IdentificationResult::new(),
+            elapsed: 0.0,
+            step: 0,
+            voltage: 0.0,
+            first_point: (0.0, 0.0),
+            sum_a: 0.0,
+            sum_b: 0.0,
+            sum_c: 0.0,
+            samples: 0,
+            angle: 0.0,
+            speed: 0.0,
+            prev_current: (0.0, 0.0),
+            prev_voltage: (0.0, 0.0),
+            controller: CurrentController::new(0.0, 0.0, 0.0),
+            coast_marks: [None; 3],
+            coast_times: (0.0, 0.0),
+            accel_start: None,
+            failure: None,
+        }
+    }
+
+    /// パラメータ同定を開始
+    ///
+    /// # 引数
+    /// * `test_current` - 試験電流 [A]（直流注入・I/f駆動・加速試験の電流）
+    /// * `test_speed` - 試験速度 [RPM]（I/f駆動の回転数）
+    /// * `max_voltage` - 最大電圧 [V]
+    /// * `mechanical_test` - 慣性モーメント・摩擦も測定するか（Hallの電気角が校正済みの場合のみ）
+    pub fn start(
+        &mut self,
+        test_current: f32,
+        test_speed: f32,
+        max_voltage: f32,
+        mechanical_test: bool,
+    ) {
+        self.test_current = test_current;
+        self.test_speed = test_speed;
+        self.max_voltage = max_voltage;
+        self.mechanical_test = mechanical_test;
+        self.result = IdentificationResult::new();
+        self.voltage = 0.0;
+        self.coast_marks = [None; 3];
+        self.accel_start = None;
+        self.failure = None;
+        self.enter(IdentificationState::Resistance);
+    }
+
+    /// 現在の状態を取得
+    pub fn get_state(&self) -> IdentificationState {
+        self.state
+    }
+
+    /// 同定結果を取得
+    pub fn get_result(&self) -> IdentificationResult {
+        self.result
+    }
+
+    /// 失敗要因を取得（失敗していなければ`None`）
+    pub fn get_failure(&self) -> Option<IdentificationFailure> {
+        self.failure
+    }
+
+    /// 同定が終了したか（完了または失敗）チェック
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.state,
+            IdentificationState::Completed | IdentificationState::Failed
+        )
+    }
+
+    /// パラメータ同定を更新
+    ///
+    /// # 引数
+    /// * `i_alpha` - α軸電流 [A]
+    /// * `i_beta` - β軸電流 [A]
+    /// * `electrical_angle` - Hallセンサーの電気角 [rad]（加速試験のみ使用）
+    /// * `speed_rpm` - Hallセンサーの回転数 [RPM]
+    /// * `dt` - 制御周期 [s]
+    ///
+    /// # 戻り値
+    /// * `Some((v_alpha, v_beta))` - α/β軸電圧指令 [V]
+    /// * `None` - 出力停止（惰性減速中・終了後）
+    pub fn update(
+        &mut self,
+        i_alpha: f32,
+        i_beta: f32,
+        electrical_angle: f32,
+        speed_rpm: f32,
+        dt: f32,
+    ) -> Option<(f32, f32)> {
+        let output = match self.state {
+            IdentificationState::Resistance => self.update_resistance(i_alpha, dt),
+            IdentificationState::Inductance => self.update_inductance(i_alpha, dt),
+            IdentificationState::FluxLinkage => {
+                self.update_flux_linkage(i_alpha, i_beta, speed_rpm, dt)
+            }
+            IdentificationState::Coastdown => self.update_coastdown(speed_rpm, dt),
+            IdentificationState::Acceleration => {
+                self.update_acceleration(i_alpha, i_beta, electrical_angle, speed_rpm, dt)
+            }
+            _ => None,
+        };
+
+        self.prev_current = (i_alpha, i_beta);
+        self.prev_voltage = output.unwrap_or((0.0, 0.0));
+        output
+    }
+
+    /// 次の測定ステップに移行
+    fn enter(&mut self, state: IdentificationState) {
+        self.state = state;
+        self.elapsed = 0.0;
+        self.step = 0;
+        self.sum_a = 0.0;
+        self.sum_b = 0.0;
+        self.sum_c = 0.0;
+        self.samples = 0;
+    }
+
+    /// 同定を失敗として終了
+    fn fail(&mut self, failure: IdentificationFailure) -> Option<(f32, f32)> {
+        self.state = IdentificationState::Failed;
+        self.failure = Some(failure);
+        None
+    }
+
+    /// 機械定数を測定せずに同定を完了（電気定数は有効）
+    fn skip_mechanical(&mut self, failure: IdentificationFailure) {
+        self.failure = Some(failure);
+        self.enter(IdentificationState::Completed);
+    }
+
+    /// 相抵抗測定（α軸方向の直流注入、試験電流の1/2と1倍の2点）
+    fn update_resistance(&mut self, i_alpha: f32, dt: f32) -> Option<(f32, f32)> {
+        let i_ref = if self.step == 0 {
+            0.5 * self.test_current
+        } else {
+            self.test_current
+        };
+
+        self.elapsed += dt;
+        if self.elapsed > RESISTANCE_SETTLE_TIME {
+            // 安定後：前周期に印加した電圧と今周期の電流を積算
+            if self.voltage >= self.max_voltage {
+                return self.fail(IdentificationFailure::CurrentNotReached);
+            }
+            self.sum_a += self.voltage;
+            self.sum_b += i_alpha;
+            self.samples += 1;
+        }
+
+        if self.elapsed >= RESISTANCE_SETTLE_TIME + RESISTANCE_MEASURE_TIME {
+            let voltage = self.sum_a / self.samples as f32;
+            let current = self.sum_b / self.samples as f32;
+
+            if self.step == 0 {
+                self.first_point = (voltage, current);
+                self.enter(IdentificationState::Resistance);
+                self.step = 1;
+            } else {
+                let resistance = (voltage - self.first_point.0) / (current - self.first_point.1);
+                if !(resistance.is_finite() && resistance > 0.0) {
+                    return self.fail(IdentificationFailure::InvalidResistance);
+                }
+                self.result.resistance = resistance;
+
+                // インダクタンス測定のステップ電圧 = 試験電流を流す電圧
+                self.voltage = voltage;
+                self.enter(IdentificationState::Inductance);
+                return Some((0.0, 0.0));
+            }
+        }
+
+        self.voltage = (self.voltage + RESISTANCE_KI * (i_ref - i_alpha) * dt)
+            .max(0.0)
+            .min(self.max_voltage);
+        Some((self.voltage, 0.0))
+    }
+
+    /// インダクタンス測定（α軸方向の電圧ステップを繰り返し印加）
+    ///
+    /// OFF期間の最後の電流 i0、ON後1周期の電流 i1、ON期間の最後の電流 i∞ から
+    /// (i1 - i0) / (i∞ - i0) = 1 - exp(-dt / τ) として時定数 τ = L / R を求める。
+    fn update_inductance(&mut self, i_alpha: f32, dt: f32) -> Option<(f32, f32)> {
+        let period = 2 * INDUCTANCE_HALF_PERIOD;
+        let phase = self.step % period;
+
+        // 今周期の電流は前周期の出力に対する応答
+        if phase == INDUCTANCE_HALF_PERIOD {
+            self.sum_a += i_alpha;
+        } else if phase == INDUCTANCE_HALF_PERIOD + 1 {
+            self.sum_b += i_alpha;
+        } else if phase == 0 && self.step > 0 {
+            self.sum_c += i_alpha;
+            self.samples += 1;
+        }
+
+        if self.step >= period * INDUCTANCE_REPEATS {
+            let ratio = (self.sum_b - self.sum_a) / (self.sum_c - self.sum_a);
+            if !(ratio > 0.0 && ratio < 0.99) {
+                return self.fail(IdentificationFailure::StepRatioOutOfRange);
+            }
+            let time_constant = -dt / logf(1.0 - ratio);
+            let inductance = time_constant * self.result.resistance;
+            self.result.inductance = inductance;
+
+            // I/f駆動の電流制御ゲインを測定値から決定
+            self.controller = CurrentController::new(
+                inductance * CURRENT_BANDWIDTH,
+                self.result.resistance * CURRENT_BANDWIDTH,
+                self.max_voltage,
+            );
+            self.angle = 0.0;
+            self.speed = 0.0;
+            self.enter(IdentificationState::FluxLinkage);
+            return Some((0.0, 0.0));
+        }
+
+        self.step += 1;
+        if phase >= INDUCTANCE_HALF_PERIOD {
+            Some((self.voltage, 0.0))
+        } else {
+            Some((0.0, 0.0))
+        }
+    }
+
+    /// 鎖交磁束測定（I/f駆動で試験速度まで回転させ、逆起電力を測定）
+    fn update_flux_linkage(
+        &mut self,
+        i_alpha: f32,
+        i_beta: f32,
+        speed_rpm: f32,
+        dt: f32,
+    ) -> Option<(f32, f32)> {
+        let omega = self.speed * RPM_TO_RAD_PER_S * self.pole_pairs as f32;
+
+        if self.speed >= self.test_speed {
+            self.elapsed += dt;
+        }
+        if self.elapsed > FLUX_SETTLE_TIME {
+            if (speed_rpm.abs() - self.test_speed).abs() > SPEED_TOLERANCE * self.test_speed {
+                return self.fail(IdentificationFailure::RotorNotFollowing);
+            }
+
+            // 前周期の区間平均の逆起電力: e = v - R·i_avg - L·Δi/dt
+            let (r, l) = (self.result.resistance, self.result.inductance);
+            let (prev_alpha, prev_beta) = self.prev_current;
+            let e_alpha = self.prev_voltage.0
+                - r * 0.5 * (i_alpha + prev_alpha)
+                - l * (i_alpha - prev_alpha) / dt;
+            let e_beta = self.prev_voltage.1
+                - r * 0.5 * (i_beta + prev_beta)
+                - l * (i_beta - prev_beta) / dt;
+            self.sum_a += sqrtf(e_alpha * e_alpha + e_beta * e_beta);
+            self.samples += 1;
+        }
+
+        if self.elapsed >= FLUX_SETTLE_TIME + FLUX_MEASURE_TIME {
+            let flux_linkage = self.sum_a / self.samples as f32 / omega;
+            if !(flux_linkage.is_finite() && flux_linkage > 0.0) {
+                return self.fail(IdentificationFailure::InvalidFluxLinkage);
+            }
+            self.result.flux_linkage = flux_linkage;
+            self.result.electrical_valid = true;
+
+            if self.mechanical_test {
+                self.coast_marks = [None; 3];
+                self.enter(IdentificationState::Coastdown);
+            } else {
+                self.enter(IdentificationState::Completed);
+            }
+            return None;
+        }
+
+        // 電流ベクトルを加速度に従って回転させる
+        self.speed = (self.speed + FLUX_ACCELERATION * dt).min(self.test_speed);
+        self.angle = (self.angle + omega * dt) % TAU;
+
+        // 電流ベクトル方向をd軸とし、試験電流を流す（出力区間の中央の角度で電圧を出力）
+        let (id, iq) = park(i_alpha, i_beta, self.angle);
+        let (vd, vq) = self.controller.update(self.test_current, 0.0, id, iq, dt);
+        Some(inverse_park(vd, vq, self.angle + 0.5 * omega * dt))
+    }
+
+    /// 惰性減速の測定（出力停止、速度区間の通過時刻を記録）
+    fn update_coastdown(&mut self, speed_rpm: f32, dt: f32) -> Option<(f32, f32)> {
+        self.elapsed += dt;
+        let speed = speed_rpm.abs();
+        let thresholds = [
+            MECHANICAL_HIGH_RATIO,
+            MECHANICAL_MID_RATIO,
+            MECHANICAL_LOW_RATIO,
+        ];
+
+        for (i, ratio) in thresholds.iter().enumerate() {
+            let passed_previous = i == 0 || self.coast_marks[i - 1].is_some();
+            if self.coast_marks[i].is_none() && passed_previous && speed <= ratio * self.test_speed
+            {
+                self.coast_marks[i] = Some(self.elapsed);
+            }
+        }
+
+        if let [Some(high), Some(mid), Some(low)] = self.coast_marks {
+            self.coast_times = (mid - high, low - mid);
+            self.controller.reset();
+            self.accel_start = None;
+            self.enter(IdentificationState::Acceleration);
+        } else if self.elapsed > MECHANICAL_TIMEOUT {
+            self.skip_mechanical(IdentificationFailure::CoastdownTimeout);
+        }
+
+        None
+    }
+
+    /// 加速の測定（Hallの電気角で試験電流をq軸に流し、速度区間の通過時間を記録）
+    fn update_acceleration(
+        &mut self,
+        i_alpha: f32,
+        i_beta: f32,
+        electrical_angle: f32,
+        speed_rpm: f32,
+        dt: f32,
+    ) -> Option<(f32, f32)> {
+        self.elapsed += dt;
+        let speed = speed_rpm.abs();
+        let (id, iq) = park(i_alpha, i_beta, electrical_angle);
+
+        if self.accel_start.is_none() && speed >= MECHANICAL_LOW_RATIO * self.test_speed {
+            self.accel_start = Some(self.elapsed);
+        }
+        if let Some(start) = self.accel_start {
+            self.sum_b += iq;
+            self.samples += 1;
+
+            if speed >= MECHANICAL_HIGH_RATIO * self.test_speed {
+                let accel_time = self.elapsed - start;
+                let iq_avg = self.sum_b / self.samples as f32;
+                if self.calculate_mechanical(accel_time, iq_avg) {
+                    self.enter(IdentificationState::Completed);
+                } else {
+                    self.skip_mechanical(IdentificationFailure::InvalidInertia);
+                }
+                return None;
+            }
+        }
+
+        if self.elapsed > MECHANICAL_TIMEOUT {
+            self.skip_mechanical(IdentificationFailure::AccelerationTimeout);
+            return None;
+        }
+
+        let (vd, vq) = self.controller.update(0.0, self.test_current, id, iq, dt);
+        Some(inverse_park(vd, vq, electrical_angle))
+    }
+
+    /// 加速・惰性減速の時間から慣性モーメントと摩擦を計算
+    ///
+    /// 同じ速度区間 Δω で、加速: J·Δω/t_acc = Kt·iq - Tf、減速: J·Δω/t_dec = Tf
+    /// （Tf は区間平均の摩擦トルク）から J = Kt·iq / (Δω·(1/t_acc + 1/t_dec))。
+    /// 減速を2区間に分けて各区間の摩擦トルクを求め、クーロン摩擦と粘性摩擦に分離する。
+    ///
+    /// # 引数
+    /// * `accel_time` - 速度区間の加速にかかった時間 [s]
+    /// * `iq_avg` - 加速中の平均q軸電流 [A]
+    ///
+    /// # 戻り値
+    /// * `bool` - 慣性モーメントが有効な値になったか
+    fn calculate_mechanical(&mut self, accel_time: f32, iq_avg: f32) -> bool {
+        let high = MECHANICAL_HIGH_RATIO * self.test_speed * RPM_TO_RAD_PER_S;
+        let mid = MECHANICAL_MID_RATIO * self.test_speed * RPM_TO_RAD_PER_S;
+        let low = MECHANICAL_LOW_RATIO * self.test_speed * RPM_TO_RAD_PER_S;
+        let (coast_high, coast_low) = self.coast_times;
+        let coast_time = coast_high + coast_low;
+
+        let torque_constant = 1.5 * self.pole_pairs as f32 * self.result.flux_linkage;
+        let inertia =
+            torque_constant * iq_avg / ((high - low) * (1.0 / accel_time + 1.0 / coast_time));
+        if !(inertia.is_finite() && inertia > 0.0) {
+            return false;
+        }
+
+        // 各減速区間の平均摩擦トルク（区間の中央速度での値とみなす）
+        let friction_high = inertia * (high - mid) / coast_high;
+        let friction_low = inertia * (mid - low) / coast_low;
+        let speed_high = 0.5 * (high + mid);
+        let speed_low = 0.5 * (mid + low);
+        let viscous = ((friction_high - friction_low) / (speed_high - speed_low)).max(0.0);
+        let coulomb = (friction_low - viscous * speed_low).max(0.0);
+
+        self.result.inertia = inertia;
+        self.result.friction_torque = coulomb;
+        self.result.viscous_friction = viscous;
+        self.result.mechanical_valid = true;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libm::{cosf, expf, sinf};
+
+    const DT: f32 = 0.0004; // 2.5kHz
+    const POLE_PAIRS: u8 = 6;
+
+    /// 簡易SPMSMモデル（制御周期内をサブステップで積分）
+    struct SimMotor {
+        resistance: f32,
+        inductance: f32,
+        flux_linkage: f32,
+        inertia: f32,
+        friction_torque: f32,
+        viscous_friction: f32,
+        current: (f32, f32),
+        angle: f32,
+        omega: f32,
+    }
+
+    impl SimMotor {
+        fn new(resistance: f32) -> Self {
+            Self {
+                resistance,
+                inductance: 0.0003,
+                flux_linkage: 0.005,
+                inertia: 0.0001,
+                friction_torque: 0.005,
+                viscous_friction: 0.00001,
+                current: (0.0, 0.0),
+                angle: 0.0,
+                omega: 0.0,
+            }
+        }
+
+        fn speed_rpm(&self) -> f32 {
+            self.omega / RPM_TO_RAD_PER_S
+        }
+
+        fn step(&mut self, voltage: Option<(f32, f32)>) {
+            const SUBSTEPS: u32 = 40;
+            let h = DT / SUBSTEPS as f32;
+            let p = POLE_PAIRS as f32;
+            // 電気系は一定電圧の区間で厳密に積分（抵抗が大きく時定数が短くても発散しない）
+            let decay = 1.0 - expf(-self.resistance * h / self.inductance);
+
+            for _ in 0..SUBSTEPS {
+                let (sin, cos) = (sinf(self.angle), cosf(self.angle));
+                let omega_e = p * self.omega;
+                match voltage {
+                    Some((v_alpha, v_beta)) => {
+                        let e_alpha = -omega_e * self.flux_linkage * sin;
+                        let e_beta = omega_e * self.flux_linkage * cos;
+                        let (i_alpha, i_beta) = self.current;
+                        self.current.0 += decay * ((v_alpha - e_alpha) / self.resistance - i_alpha);
+                        self.current.1 += decay * ((v_beta - e_beta) / self.resistance - i_beta);
+                    }
+                    None => self.current = (0.0, 0.0),
+                }
+
+                let torque =
+                    1.5 * p * self.flux_linkage * (self.current.1 * cos - self.current.0 * sin);
+                if self.omega == 0.0 && torque.abs() <= self.friction_torque {
+                    continue;
+                }
+                let direction = if self.omega != 0.0 {
+                    self.omega.signum()
+                } else {
+                    torque.signum()
+                };
+                let friction =
+                    direction * (self.friction_torque + self.viscous_friction * self.omega.abs());
+                let next = self.omega + h * (torque - friction) / self.inertia;
+                self.omega = if self.omega != 0.0 && next.signum() != self.omega.signum() {
+                    0.0
+                } else {
+                    next
+                };
+                self.angle = (self.angle + h * p * self.omega) % TAU;
+            }
+        }
+    }
+
+    /// 同定が終了するまでシミュレーションを実行
+    fn run(identification: &mut MotorIdentification, motor: &mut SimMotor) {
+        for _ in 0..100_000 {
+            let output = identification.update(
+                motor.current.0,
+                motor.current.1,
+                motor.angle,
+                motor.speed_rpm(),
+                DT,
+            );
+            if identification.is_finished() {
+                return;
+            }
+            motor.step(output);
+        }
+        panic!("identification did not finish");
+    }
+
+    fn assert_close(actual: f32, expected: f32, tolerance: f32) {
+        assert!(
+            (actual - expected).abs() <= tolerance * expected,
+            "actual {} expected {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_identifies_all_parameters() {
+        let mut motor = SimMotor::new(0.3);
+        let mut identification = MotorIdentification::new(POLE_PAIRS);
+        identification.start(2.0, 1000.0, 24.0, true);
+        run(&mut identification, &mut motor);
+
+        assert_eq!(identification.get_state(), IdentificationState::Completed);
+        assert_eq!(identification.get_failure(), None);
+        let result = identification.get_result();
+        assert!(result.electrical_valid);
+        assert!(result.mechanical_valid);
+        assert_close(result.resistance, 0.3, 0.02);
+        assert_close(result.inductance, 0.0003, 0.05);
+        assert_close(result.flux_linkage, 0.005, 0.02);
+        assert_close(result.inertia, 0.0001, 0.05);
+        assert_close(result.friction_torque, 0.005, 0.1);
+    }
+
+    #[test]
+    fn test_electrical_only_without_calibration() {
+        let mut motor = SimMotor::new(0.5);
+        let mut identification = MotorIdentification::new(POLE_PAIRS);
+        identification.start(2.0, 1000.0, 24.0, false);
+        run(&mut identification, &mut motor);
+
+        assert_eq!(identification.get_state(), IdentificationState::Completed);
+        let result = identification.get_result();
+        assert!(result.electrical_valid);
+        assert!(!result.mechanical_valid);
+        assert_close(result.resistance, 0.5, 0.02);
+        assert_eq!(result.inertia, 0.0);
+    }
+
+    #[test]
+    fn test_fails_with_open_phase() {
+        // 非常に大きな抵抗（断線相当）では試験電流に達しない
+        let mut motor = SimMotor::new(1000.0);
+        let mut identification = MotorIdentification::new(POLE_PAIRS);
+        identification.start(2.0, 1000.0, 24.0, true);
+        run(&mut identification, &mut motor);
+
+        assert_eq!(identification.get_state(), IdentificationState::Failed);
+        assert_eq!(
+            identification.get_failure(),
+            Some(IdentificationFailure::CurrentNotReached)
+        );
+        assert!(!identification.get_result().electrical_valid);
+    }
+}
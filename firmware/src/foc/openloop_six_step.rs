@@ -197,6 +197,27 @@ impl OpenLoopSixStep {
         60.0 / (self.step_period * steps_per_rotation)
     }
 
+    /// 現在のステップの電圧ベクトルの電気角を取得 [rad]
+    ///
+    /// ステップ0（U→V）が-30°、以降ステップごとに60°進む（α軸 = U相）。
+    /// 軽負荷では回転子の磁束はこの方向付近にあるため、センサレス切替時の初期角度に使う。
+    pub fn get_electrical_angle(&self) -> f32 {
+        let angle_deg = (self.current_step as f32 * 60.0 + 330.0) % 360.0;
+        angle_deg.to_radians()
+    }
+
+    /// 現在のステップの電圧ベクトルの大きさを取得 [V]
+    ///
+    /// 通電する2相間の線間電圧はデューティ × DCバス電圧で、
+    /// 振幅不変のClarke変換では電圧ベクトルの大きさはその1/√3になる。
+    ///
+    /// # 引数
+    /// * `v_dc_bus` - DCバス電圧 [V]
+    pub fn get_voltage_amplitude(&self, v_dc_bus: f32) -> f32 {
+        const ONE_DIV_SQRT3: f32 = 0.577_350_26; // 1 / sqrt(3)
+        self.duty_ratio as f32 / 100.0 * v_dc_bus * ONE_DIV_SQRT3
+    }
+
     /// 現在のステップを取得
     #[allow(dead_code)]
     pub fn get_current_step(&self) -> u8 {
@@ -0,0 +1,186 @@
+//! オープンループ正弦波V/f駆動制御
+//!
+//! 始動時に正弦波の電圧ベクトルを一定の加速度で回転させてモーターを回転させるための制御モジュールです。
+//! 電圧の大きさはV/f特性（0Hzでのブースト電圧 + 電気周波数に比例する電圧）で決めます。
+//! 6ステップ駆動（`OpenLoopSixStep`）より振動・騒音が小さく、低速から滑らかに始動できます。
+
+use core::f32::consts::TAU;
+use libm::{cosf, sinf};
+
+/// オープンループ正弦波V/f駆動制御
+pub struct OpenLoopVf {
+    /// 現在の回転数 [RPM]
+    current_rpm: f32,
+    /// 初期回転数 [RPM]
+    initial_rpm: f32,
+    /// 目標回転数 [RPM]（この速度に達したらFOCに切り替え）
+    target_rpm: f32,
+    /// 加速度 [RPM/s]
+    acceleration: f32,
+    /// 0Hzでのブースト電圧 [V]（静止摩擦・巻線抵抗の電圧降下を補う）
+    boost_voltage: f32,
+    /// V/fゲイン [V/Hz]（電気周波数あたりの電圧）
+    vf_gain: f32,
+    /// 最大電圧 [V]
+    max_voltage: f32,
+    /// 電圧ベクトルの電気角 [rad] (0～2π)
+    electrical_angle: f32,
+    /// 極対数
+    pole_pairs: u8,
+}
+
+impl OpenLoopVf {
+    /// 新しいオープンループ正弦波V/f駆動制御を作成
+    ///
+    /// # 引数
+    /// * `initial_rpm` - 初期回転数 [RPM]
+    /// * `target_rpm` - 目標回転数 [RPM]（この速度に達したらFOCに切り替え）
+    /// * `acceleration_rpm_per_s` - 加速度 [RPM/s]
+    /// * `boost_voltage` - 0Hzでのブースト電圧 [V]
+    /// * `vf_gain` - V/fゲイン [V/Hz]（電気周波数あたりの電圧）
+    /// * `max_voltage` - 最大電圧 [V]
+    /// * `pole_pairs` - モーターの極対数
+    pub fn new(
+        initial_rpm: f32,
+        target_rpm: f32,
+        acceleration_rpm_per_s: f32,
+        boost_voltage: f32,
+        vf_gain: f32,
+        max_voltage: f32,
+        pole_pairs: u8,
+    ) -> Self {
+        Self {
+            current_rpm: initial_rpm,
+            initial_rpm,
+            target_rpm,
+            acceleration: acceleration_rpm_per_s,
+            boost_voltage,
+            vf_gain,
+            max_voltage,
+            electrical_angle: 0.0,
+            pole_pairs,
+        }
+    }
+
+    /// 最大電圧を設定
+    ///
+    /// # 引数
+    /// * `max_voltage` - 最大電圧 [V]
+    pub fn set_max_voltage(&mut self, max_voltage: f32) {
+        self.max_voltage = max_voltage;
+    }
+
+    /// 正弦波駆動を更新
+    ///
+    /// 回転数を加速度に従って目標回転数まで増加させ、電圧ベクトルを回転させる。
+    ///
+    /// # 引数
+    /// * `dt` - 制御周期 [s]
+    ///
+    /// # 戻り値
+    /// * `(v_alpha, v_beta)` - α/β軸電圧指令 [V]
+    pub fn update(&mut self, dt: f32) -> (f32, f32) {
+        // 加速（目標回転数で頭打ち）
+        self.current_rpm = (self.current_rpm + self.acceleration * dt).min(self.target_rpm);
+
+        // 電気角を進める
+        let electrical_hz = self.get_electrical_frequency();
+        self.electrical_angle = (self.electrical_angle + TAU * electrical_hz * dt) % TAU;
+
+        let voltage = self.get_voltage_amplitude();
+        (
+            voltage * cosf(self.electrical_angle),
+            voltage * sinf(self.electrical_angle),
+        )
+    }
+
+    /// 目標速度に達したかチェック
+    pub fn is_target_reached(&self) -> bool {
+        self.current_rpm >= self.target_rpm
+    }
+
+    /// リセット
+    pub fn reset(&mut self) {
+        self.current_rpm = self.initial_rpm;
+        self.electrical_angle = 0.0;
+    }
+
+    /// 現在の速度を取得 [RPM]
+    pub fn get_current_rpm(&self) -> f32 {
+        self.current_rpm
+    }
+
+    /// 現在の電気周波数を取得 [Hz]
+    pub fn get_electrical_frequency(&self) -> f32 {
+        self.current_rpm * self.pole_pairs as f32 / 60.0
+    }
+
+    /// 電圧ベクトルの電気角を取得 [rad]（α軸 = U相）
+    ///
+    /// 軽負荷では回転子の磁束はこの方向付近にあるため、センサレス切替時の初期角度に使う。
+    pub fn get_electrical_angle(&self) -> f32 {
+        self.electrical_angle
+    }
+
+    /// 現在の電圧ベクトルの大きさを取得 [V]
+    ///
+    /// ブースト電圧 + V/fゲイン × 電気周波数（最大電圧で制限）
+    pub fn get_voltage_amplitude(&self) -> f32 {
+        (self.boost_voltage + self.vf_gain * self.get_electrical_frequency())
+            .max(0.0)
+            .min(self.max_voltage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 0.0004; // 2.5kHz
+
+    #[test]
+    fn test_linear_ramp_to_target() {
+        // 10 → 1000 RPM、500 RPM/s → 1.98秒で到達
+        let mut vf = OpenLoopVf::new(10.0, 1000.0, 500.0, 1.0, 0.05, 24.0, 6);
+        for _ in 0..2500 {
+            vf.update(DT);
+        }
+        assert!((vf.get_current_rpm() - 510.0).abs() < 0.5);
+        assert!(!vf.is_target_reached());
+
+        for _ in 0..2500 {
+            vf.update(DT);
+        }
+        assert_eq!(vf.get_current_rpm(), 1000.0);
+        assert!(vf.is_target_reached());
+    }
+
+    #[test]
+    fn test_vf_curve() {
+        // 1000 RPM × 6極対 = 100Hz → 1.0 + 0.05 × 100 = 6.0V
+        let mut vf = OpenLoopVf::new(1000.0, 1000.0, 500.0, 1.0, 0.05, 24.0, 6);
+        assert!((vf.get_voltage_amplitude() - 6.0).abs() < 1e-4);
+
+        let (v_alpha, v_beta) = vf.update(DT);
+        let amplitude = libm::sqrtf(v_alpha * v_alpha + v_beta * v_beta);
+        assert!((amplitude - 6.0).abs() < 1e-4);
+
+        // 最大電圧で制限
+        let vf = OpenLoopVf::new(1000.0, 1000.0, 500.0, 1.0, 0.5, 24.0, 6);
+        assert_eq!(vf.get_voltage_amplitude(), 24.0);
+    }
+
+    #[test]
+    fn test_angle_advances_with_frequency() {
+        // 100 RPM × 6極対 = 10Hz → 10ms で 0.1回転
+        let mut vf = OpenLoopVf::new(100.0, 100.0, 500.0, 1.0, 0.05, 24.0, 6);
+        for _ in 0..25 {
+            vf.update(DT);
+        }
+        assert!((vf.get_electrical_angle() - 0.1 * TAU).abs() < 1e-3);
+
+        vf.reset();
+        assert_eq!(vf.get_electrical_angle(), 0.0);
+        assert_eq!(vf.get_current_rpm(), 100.0);
+    }
+}
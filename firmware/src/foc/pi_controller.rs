@@ -1,11 +1,30 @@
 // PI (Proportional-Integral) controller with anti-windup
+// Optional filtered D term, setpoint weighting, output rate limiting and gain scheduling
+
+/// Maximum number of points in a gain schedule
+pub const MAX_SCHEDULE_POINTS: usize = 8;
 
 /// PI controller with anti-windup and output limiting
+///
+/// The basic controller is a PI with output clamping. The following
+/// extensions are disabled by default and can be enabled per loop:
+/// - Back-calculation anti-windup: the difference between the limited and
+///   the unlimited output is fed back into the integrator with a tracking gain
+/// - Filtered D term on the weighted error (first-order low-pass)
+/// - Setpoint weighting: the P term acts on `b * setpoint - measured` and the
+///   D term on `c * setpoint - measured` (b = 1, c = 0 by default, so the D
+///   term acts on the measurement only and a setpoint step does not kick it)
+/// - Output rate limiting
+///
+/// The integral is accumulated in output units (`ki * error * dt`), so gains
+/// can be changed while running (e.g. from a [`GainSchedule`]) without a bump.
 pub struct PiController {
     /// Proportional gain
     kp: f32,
     /// Integral gain
     ki: f32,
+    /// Derivative gain (0 = no D term)
+    kd: f32,
     /// Integral accumulator
     integral: f32,
     /// Minimum output limit
@@ -16,6 +35,20 @@ pub struct PiController {
     last_output: f32,
     /// Enable anti-windup (stops integral accumulation when saturated)
     anti_windup_enabled: bool,
+    /// Back-calculation tracking gain [1/s] (0 = disabled)
+    tracking_gain: f32,
+    /// Derivative low-pass filter time constant [s] (0 = unfiltered)
+    derivative_filter_time: f32,
+    /// Setpoint weight of the P term (b)
+    proportional_weight: f32,
+    /// Setpoint weight of the D term (c)
+    derivative_weight: f32,
+    /// Maximum output rate of change [output units/s] (0 = unlimited)
+    rate_limit: f32,
+    /// Filtered derivative of the weighted error
+    derivative: f32,
+    /// Weighted error of the previous update for the D term (None after reset)
+    last_derivative_error: Option<f32>,
 }
 
 impl PiController {
@@ -39,6 +72,14 @@ impl PiController {
             output_max,
             last_output: 0.0,
             anti_windup_enabled: false, // Disabled by default to match reference implementation
+            kd: 0.0,
+            tracking_gain: 0.0,
+            derivative_filter_time: 0.0,
+            proportional_weight: 1.0,
+            derivative_weight: 0.0,
+            rate_limit: 0.0,
+            derivative: 0.0,
+            last_derivative_error: None,
         }
     }
 
@@ -65,14 +106,19 @@ impl PiController {
         // Calculate error
         let error = setpoint - measured;
 
-        // Proportional term
-        let p_term = self.kp * error;
+        // Proportional term (setpoint weighted)
+        let p_term = self.kp * (self.proportional_weight * setpoint - measured);
+
+        // Derivative term (setpoint weighted, low-pass filtered)
+        let d_term = self.update_derivative(self.derivative_weight * setpoint - measured, dt);
 
         // Integral term with anti-windup
         // Based on calebfletcher/foc implementation:
         // Accumulate ki * error * dt directly for better numerical stability
-        // Only accumulate if anti-windup is disabled, or if output is not saturated
-        let should_integrate = !self.anti_windup_enabled
+        // Only accumulate if anti-windup is disabled, or if output is not saturated.
+        // Back-calculation replaces the conditional integration when enabled.
+        let should_integrate = self.tracking_gain > 0.0
+            || !self.anti_windup_enabled
             || (self.last_output > self.output_min && self.last_output < self.output_max);
 
         if should_integrate {
@@ -80,18 +126,55 @@ impl PiController {
         }
 
         // Calculate output (integral already includes ki)
-        let output = p_term + self.integral;
+        let unlimited = p_term + self.integral + d_term;
 
-        // Apply output limits
-        self.last_output = output.clamp(self.output_min, self.output_max);
+        // Apply rate limit, then output limits
+        let mut output = unlimited;
+        if self.rate_limit > 0.0 {
+            let step = self.rate_limit * dt;
+            output = output
+                .max(self.last_output - step)
+                .min(self.last_output + step);
+        }
+        output = output.max(self.output_min).min(self.output_max);
+
+        // Back-calculation: bleed the integrator towards the achievable output
+        if self.tracking_gain > 0.0 {
+            self.integral += self.tracking_gain * (output - unlimited) * dt;
+        }
+
+        self.last_output = output;
 
         self.last_output
     }
 
+    /// Update the filtered derivative of the weighted error
+    ///
+    /// # Returns
+    /// D term (0 when the derivative gain is 0 or there is no previous sample)
+    fn update_derivative(&mut self, derivative_error: f32, dt: f32) -> f32 {
+        if self.kd == 0.0 || dt <= 0.0 {
+            self.last_derivative_error = None;
+            self.derivative = 0.0;
+            return 0.0;
+        }
+
+        if let Some(last) = self.last_derivative_error {
+            let raw = (derivative_error - last) / dt;
+            let alpha = dt / (self.derivative_filter_time + dt);
+            self.derivative += alpha * (raw - self.derivative);
+        }
+        self.last_derivative_error = Some(derivative_error);
+
+        self.kd * self.derivative
+    }
+
     /// Reset the integral term to zero
     pub fn reset(&mut self) {
         self.integral = 0.0;
         self.last_output = 0.0;
+        self.derivative = 0.0;
+        self.last_derivative_error = None;
     }
 
     /// Set the proportional and integral gains
@@ -125,6 +208,16 @@ impl PiController {
         self.output_max = output_limit;
     }
 
+    /// Clamp a value to the output limits
+    ///
+    /// Used when a feedforward term is added to the controller output.
+    ///
+    /// # Arguments
+    /// * `value` - Value to clamp
+    pub fn limit(&self, value: f32) -> f32 {
+        value.max(self.output_min).min(self.output_max)
+    }
+
     /// Get the current output
     #[allow(dead_code)]
     pub fn get_output(&self) -> f32 {
@@ -137,6 +230,14 @@ impl PiController {
         self.integral
     }
 
+    /// Preset the integral term (bumpless transfer from another controller)
+    ///
+    /// # Arguments
+    /// * `integral` - Integral term value (in output units)
+    pub fn set_integral(&mut self, integral: f32) {
+        self.integral = integral;
+    }
+
     /// Get the proportional gain
     pub fn get_kp(&self) -> f32 {
         self.kp
@@ -156,6 +257,63 @@ impl PiController {
         self.anti_windup_enabled = enabled;
     }
 
+    /// Set the derivative gain and filter
+    ///
+    /// # Arguments
+    /// * `kd` - Derivative gain (0 = no D term)
+    /// * `filter_time` - Low-pass filter time constant [s] (0 = unfiltered)
+    pub fn set_derivative(&mut self, kd: f32, filter_time: f32) {
+        self.kd = kd;
+        self.derivative_filter_time = filter_time.max(0.0);
+    }
+
+    /// Set the setpoint weights
+    ///
+    /// # Arguments
+    /// * `proportional` - Setpoint weight of the P term (b, 1 = error feedback)
+    /// * `derivative` - Setpoint weight of the D term (c, 0 = derivative on measurement)
+    pub fn set_setpoint_weights(&mut self, proportional: f32, derivative: f32) {
+        self.proportional_weight = proportional;
+        self.derivative_weight = derivative;
+    }
+
+    /// Set the back-calculation tracking gain
+    ///
+    /// A tracking gain around `ki / kp` (the integral time constant inverted)
+    /// is a common starting point.
+    ///
+    /// # Arguments
+    /// * `tracking_gain` - Tracking gain [1/s] (0 = disabled)
+    pub fn set_tracking_gain(&mut self, tracking_gain: f32) {
+        self.tracking_gain = tracking_gain.max(0.0);
+    }
+
+    /// Set the output rate limit
+    ///
+    /// # Arguments
+    /// * `rate_limit` - Maximum output change [output units/s] (0 = unlimited)
+    pub fn set_rate_limit(&mut self, rate_limit: f32) {
+        self.rate_limit = rate_limit.max(0.0);
+    }
+
+    /// Set the gains from a schedule at the given operating point
+    ///
+    /// # Arguments
+    /// * `schedule` - Gain schedule
+    /// * `operating_point` - Operating point (e.g. speed [RPM])
+    ///
+    /// # Returns
+    /// True if the gains were updated (false for an empty schedule)
+    pub fn apply_schedule(&mut self, schedule: &GainSchedule, operating_point: f32) -> bool {
+        match schedule.gains(operating_point) {
+            Some((kp, ki)) => {
+                self.set_gains(kp, ki);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Check if output is currently saturated
     #[allow(dead_code)]
     pub fn is_saturated(&self) -> bool {
@@ -163,6 +321,110 @@ impl PiController {
     }
 }
 
+/// Gain schedule point
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GainPoint {
+    /// Operating point (e.g. speed [RPM])
+    pub operating_point: f32,
+    /// Proportional gain
+    pub kp: f32,
+    /// Integral gain
+    pub ki: f32,
+}
+
+/// Kp/Ki table indexed by an operating point
+///
+/// Gains are linearly interpolated between points and held at the first or
+/// last point outside the table. For a speed-indexed schedule, pass the speed
+/// magnitude so both directions use the same gains.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GainSchedule {
+    /// Points in increasing operating point order
+    points: [GainPoint; MAX_SCHEDULE_POINTS],
+    /// Number of valid points
+    len: usize,
+}
+
+impl GainSchedule {
+    /// Create an empty schedule
+    pub const fn new() -> Self {
+        Self {
+            points: [GainPoint {
+                operating_point: 0.0,
+                kp: 0.0,
+                ki: 0.0,
+            }; MAX_SCHEDULE_POINTS],
+            len: 0,
+        }
+    }
+
+    /// Append a point
+    ///
+    /// # Arguments
+    /// * `operating_point` - Operating point (must be above the previous point)
+    /// * `kp` - Proportional gain
+    /// * `ki` - Integral gain
+    ///
+    /// # Returns
+    /// False if the table is full or the operating point is not increasing
+    pub fn push(&mut self, operating_point: f32, kp: f32, ki: f32) -> bool {
+        if self.len >= MAX_SCHEDULE_POINTS {
+            return false;
+        }
+        if self.len > 0 && operating_point <= self.points[self.len - 1].operating_point {
+            return false;
+        }
+
+        self.points[self.len] = GainPoint {
+            operating_point,
+            kp,
+            ki,
+        };
+        self.len += 1;
+        true
+    }
+
+    /// Remove all points
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Get the valid points
+    pub fn points(&self) -> &[GainPoint] {
+        &self.points[..self.len]
+    }
+
+    /// Interpolate the gains at an operating point
+    ///
+    /// # Arguments
+    /// * `operating_point` - Operating point
+    ///
+    /// # Returns
+    /// `Some((kp, ki))`, or `None` if the table is empty
+    pub fn gains(&self, operating_point: f32) -> Option<(f32, f32)> {
+        let points = self.points();
+        let first = points.first()?;
+        if operating_point <= first.operating_point {
+            return Some((first.kp, first.ki));
+        }
+
+        for pair in points.windows(2) {
+            let (low, high) = (pair[0], pair[1]);
+            if operating_point <= high.operating_point {
+                let t = (operating_point - low.operating_point)
+                    / (high.operating_point - low.operating_point);
+                return Some((
+                    low.kp + (high.kp - low.kp) * t,
+                    low.ki + (high.ki - low.ki) * t,
+                ));
+            }
+        }
+
+        let last = points[points.len() - 1];
+        Some((last.kp, last.ki))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -191,4 +453,101 @@ mod tests {
         pi.update(10.0, 0.0, 0.1);
         assert_eq!(pi.get_integral(), 2.0);
     }
+
+    #[test]
+    fn test_limit_feedforward_sum() {
+        let pi = PiController::new_symmetric(1.0, 0.0, 5.0);
+        assert_eq!(pi.limit(3.0), 3.0);
+        assert_eq!(pi.limit(7.5), 5.0);
+        assert_eq!(pi.limit(-7.5), -5.0);
+    }
+
+    #[test]
+    fn test_preset_integral() {
+        let mut pi = PiController::new(1.0, 1.0, -10.0, 10.0);
+        pi.set_integral(3.0);
+        // Zero error: output is the preset integral
+        assert_eq!(pi.update(5.0, 5.0, 0.1), 3.0);
+    }
+
+    #[test]
+    fn test_back_calculation_limits_windup() {
+        let mut clamped = PiController::new_symmetric(1.0, 10.0, 1.0);
+        let mut tracking = PiController::new_symmetric(1.0, 10.0, 1.0);
+        tracking.set_tracking_gain(10.0);
+        for _ in 0..10000 {
+            clamped.update(5.0, 0.0, 0.001);
+            tracking.update(5.0, 0.0, 0.001);
+        }
+        // Without anti-windup the integral grows with the error (10 * 5 * 10s)
+        assert!(clamped.get_integral() > 400.0);
+        // Back-calculation settles where ki * e = kt * (unlimited - limit):
+        // 10 * 5 = 10 * (5 + I - 1) -> I = 1 (minus one integration step)
+        assert!((tracking.get_integral() - 1.0).abs() < 0.1);
+
+        // After the error reverses the output leaves saturation immediately
+        assert!(tracking.update(0.0, 1.0, 0.001) < 1.0);
+    }
+
+    #[test]
+    fn test_filtered_derivative_on_measurement() {
+        let mut pid = PiController::new_symmetric(0.0, 0.0, 100.0);
+        pid.set_derivative(1.0, 0.0);
+        // First sample only initialises the history
+        assert_eq!(pid.update(0.0, 0.0, 0.01), 0.0);
+        // Measurement rising at 1/s: D = -1
+        assert!((pid.update(0.0, 0.01, 0.01) - -1.0).abs() < 1e-4);
+
+        // Filter time constant equal to dt halves each step towards the raw value
+        let mut filtered = PiController::new_symmetric(0.0, 0.0, 100.0);
+        filtered.set_derivative(1.0, 0.01);
+        filtered.update(0.0, 0.0, 0.01);
+        assert!((filtered.update(0.0, 0.01, 0.01) - -0.5).abs() < 1e-4);
+        assert!((filtered.update(0.0, 0.02, 0.01) - -0.75).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_setpoint_weighting() {
+        let mut pid = PiController::new_symmetric(2.0, 0.0, 100.0);
+        pid.set_derivative(1.0, 0.0);
+        pid.set_setpoint_weights(0.5, 0.0);
+        pid.update(0.0, 0.0, 0.01);
+        // P acts on 0.5 * 4 - 0, D ignores the setpoint step
+        assert!((pid.update(4.0, 0.0, 0.01) - 4.0).abs() < 1e-4);
+
+        // c = 1: the setpoint step also drives the D term
+        let mut pid = PiController::new_symmetric(0.0, 0.0, 1000.0);
+        pid.set_derivative(1.0, 0.0);
+        pid.set_setpoint_weights(1.0, 1.0);
+        pid.update(0.0, 0.0, 0.01);
+        assert!((pid.update(1.0, 0.0, 0.01) - 100.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let mut pi = PiController::new_symmetric(1.0, 0.0, 10.0);
+        pi.set_rate_limit(100.0);
+        assert!((pi.update(10.0, 0.0, 0.01) - 1.0).abs() < 1e-5);
+        assert!((pi.update(10.0, 0.0, 0.01) - 2.0).abs() < 1e-5);
+        // Falling output is limited as well
+        assert!((pi.update(-10.0, 0.0, 0.01) - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_gain_schedule() {
+        let mut schedule = GainSchedule::default();
+        assert!(schedule.gains(100.0).is_none());
+        assert!(schedule.push(0.0, 1.0, 10.0));
+        assert!(schedule.push(1000.0, 0.5, 5.0));
+        assert!(!schedule.push(500.0, 0.1, 0.1));
+
+        assert_eq!(schedule.gains(-100.0), Some((1.0, 10.0)));
+        assert_eq!(schedule.gains(500.0), Some((0.75, 7.5)));
+        assert_eq!(schedule.gains(2000.0), Some((0.5, 5.0)));
+
+        let mut pi = PiController::new_symmetric(0.0, 0.0, 10.0);
+        assert!(pi.apply_schedule(&schedule, 250.0));
+        assert_eq!(pi.get_kp(), 0.875);
+        assert_eq!(pi.get_ki(), 8.75);
+    }
 }
@@ -47,6 +47,24 @@ impl ShaftPosition {
         self.prev_angle = 0.0;
     }
 
+    /// 回転数をリセットし、指定角度を現在位置として設定
+    ///
+    /// `reset`後の最初の更新で角度が大きく変化すると回転として数えてしまうため、
+    /// センサー角度の追跡を開始する時点で呼び出す。
+    ///
+    /// # 引数
+    /// * `sensor_angle` - センサーから取得した角度 [rad]
+    pub fn reset_at(&mut self, mut sensor_angle: f32) {
+        if self.inversed {
+            sensor_angle = TAU - sensor_angle;
+        }
+        sensor_angle = Self::clamp(sensor_angle);
+
+        self.angle = sensor_angle;
+        self.rotations = 0;
+        self.prev_angle = sensor_angle;
+    }
+
     /// 方向反転フラグを設定
     pub fn set_inversed(&mut self, inversed: bool) {
         self.inversed = inversed;
@@ -202,8 +220,11 @@ mod tests {
         assert_eq!(pos.angle, 2.0);
         assert_eq!(pos.rotations, 0);
 
-        // 境界を越える: 6.0 → 0.5（1回転完了）
+        // 境界を越える: 4.0 → 6.0 → 0.5（1回転完了）
+        // 1回の更新で半回転以上進むと逆転とみなすため、半回転未満ずつ進める
+        pos.update_shaft_angle(4.0);
         pos.update_shaft_angle(6.0);
+        assert_eq!(pos.rotations, 0);
         pos.update_shaft_angle(0.5);
         assert_eq!(pos.rotations, 1);
     }
@@ -242,12 +263,29 @@ mod tests {
         pos.update_shaft_angle(1.0);
         assert!((pos.get_position() - 1.0).abs() < 1e-6);
 
+        pos.update_shaft_angle(3.5);
         pos.update_shaft_angle(6.0);
         pos.update_shaft_angle(0.5);
         // 1回転 + 0.5rad
         assert!((pos.get_position() - (TAU + 0.5)).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_reset_at() {
+        let mut pos = ShaftPosition::new();
+        pos.update_shaft_angle(1.0);
+
+        // 基準角度を5.0に設定しても回転として数えない
+        pos.reset_at(5.0);
+        assert_eq!(pos.rotations, 0);
+        assert!((pos.get_position() - 5.0).abs() < 1e-6);
+
+        // 5.0 → 6.0 → 0.5（1回転）
+        pos.update_shaft_angle(6.0);
+        pos.update_shaft_angle(0.5);
+        assert_eq!(pos.rotations, 1);
+    }
+
     #[test]
     fn test_inversed() {
         let mut pos = ShaftPosition::new();
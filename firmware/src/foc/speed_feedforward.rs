@@ -0,0 +1,158 @@
+// Speed-loop feedforward
+// Back-EMF, friction and acceleration terms computed from the speed reference
+
+use super::dead_time::smooth_sign;
+
+/// Speed-loop feedforward
+///
+/// A pure PI speed loop has to build up the whole output needed to hold a
+/// speed through its integrator: the back-EMF voltage, the friction torque and
+/// the torque that accelerates the inertia along the setpoint ramp. Computing
+/// these terms from a motor model lets the PI gains stay low, because the PI
+/// only has to correct the model error.
+///
+/// All terms are driven by the speed reference rather than the measured speed,
+/// so no measurement noise is fed forward. Coulomb friction uses a smooth sign
+/// so the compensation does not chatter when the reference crosses zero.
+///
+/// The coefficients are given in the output units of the loop (q-axis current
+/// or q-axis voltage for the torque terms, volts for the back-EMF term); the
+/// caller converts the motor constants.
+pub struct SpeedFeedforward {
+    /// Back-EMF per unit speed (V/RPM, 0 = disabled)
+    back_emf: f32,
+    /// Coulomb friction output (0 = disabled)
+    friction: f32,
+    /// Viscous friction output per unit speed (per RPM)
+    viscous: f32,
+    /// Half-width of the smooth friction sign [RPM]
+    friction_band: f32,
+    /// Acceleration output per unit acceleration (per RPM/s, 0 = disabled)
+    acceleration: f32,
+    /// Reference speed of the previous update [RPM] (None after reset)
+    last_reference: Option<f32>,
+}
+
+impl SpeedFeedforward {
+    /// Create a new feedforward
+    ///
+    /// # Arguments
+    /// * `back_emf` - Back-EMF per unit speed (V/RPM)
+    /// * `friction` - Coulomb friction output
+    /// * `viscous` - Viscous friction output per RPM
+    /// * `friction_band` - Half-width of the smooth friction sign [RPM] (0 = hard sign)
+    /// * `acceleration` - Acceleration output per RPM/s
+    pub fn new(
+        back_emf: f32,
+        friction: f32,
+        viscous: f32,
+        friction_band: f32,
+        acceleration: f32,
+    ) -> Self {
+        Self {
+            back_emf,
+            friction,
+            viscous,
+            friction_band,
+            acceleration,
+            last_reference: None,
+        }
+    }
+
+    /// Set the back-EMF coefficient
+    ///
+    /// # Arguments
+    /// * `back_emf` - Back-EMF per unit speed (V/RPM, 0 = disabled)
+    pub fn set_back_emf(&mut self, back_emf: f32) {
+        self.back_emf = back_emf;
+    }
+
+    /// Set the friction coefficients
+    ///
+    /// # Arguments
+    /// * `friction` - Coulomb friction output
+    /// * `viscous` - Viscous friction output per RPM
+    /// * `friction_band` - Half-width of the smooth friction sign [RPM] (0 = hard sign)
+    pub fn set_friction(&mut self, friction: f32, viscous: f32, friction_band: f32) {
+        self.friction = friction;
+        self.viscous = viscous;
+        self.friction_band = friction_band;
+    }
+
+    /// Set the acceleration coefficient
+    ///
+    /// # Arguments
+    /// * `acceleration` - Acceleration output per RPM/s (0 = disabled)
+    pub fn set_acceleration(&mut self, acceleration: f32) {
+        self.acceleration = acceleration;
+    }
+
+    /// Calculate the feedforward terms for this cycle
+    ///
+    /// The acceleration is the difference of the reference from the previous
+    /// update, so the reference should already be ramped (motion profile);
+    /// the first update after a reset has no acceleration term.
+    ///
+    /// # Arguments
+    /// * `reference` - Speed reference [RPM]
+    /// * `dt` - Time step (seconds)
+    ///
+    /// # Returns
+    /// Tuple of (torque term in loop output units, back-EMF voltage [V])
+    pub fn update(&mut self, reference: f32, dt: f32) -> (f32, f32) {
+        let acceleration = match self.last_reference {
+            Some(last) if dt > 0.0 => (reference - last) / dt,
+            _ => 0.0,
+        };
+        self.last_reference = Some(reference);
+
+        let torque = self.friction * smooth_sign(reference, self.friction_band)
+            + self.viscous * reference
+            + self.acceleration * acceleration;
+
+        (torque, self.back_emf * reference)
+    }
+
+    /// Forget the previous reference (no acceleration term on the next update)
+    pub fn reset(&mut self) {
+        self.last_reference = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_back_emf_proportional_to_reference() {
+        let mut ff = SpeedFeedforward::new(0.01, 0.0, 0.0, 0.0, 0.0);
+        let (torque, voltage) = ff.update(1000.0, 0.001);
+        assert_eq!(torque, 0.0);
+        assert!((voltage - 10.0).abs() < 1e-4);
+        let (_, voltage) = ff.update(-500.0, 0.001);
+        assert!((voltage - -5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_friction_smooth_sign() {
+        let mut ff = SpeedFeedforward::new(0.0, 0.5, 0.0, 10.0, 0.0);
+        // Inside the band the compensation ramps linearly through zero
+        assert_eq!(ff.update(0.0, 0.001).0, 0.0);
+        ff.reset();
+        assert!((ff.update(5.0, 0.001).0 - 0.25).abs() < 1e-6);
+        ff.reset();
+        // Outside the band the full Coulomb friction is applied
+        assert!((ff.update(-100.0, 0.001).0 - -0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_acceleration_from_reference_ramp() {
+        let mut ff = SpeedFeedforward::new(0.0, 0.0, 0.0, 0.0, 0.002);
+        // No history: no acceleration term
+        assert_eq!(ff.update(100.0, 0.01).0, 0.0);
+        // 10 RPM in 10 ms = 1000 RPM/s
+        assert!((ff.update(110.0, 0.01).0 - 2.0).abs() < 1e-4);
+        // Constant reference: no acceleration term
+        assert!(ff.update(110.0, 0.01).0.abs() < 1e-6);
+    }
+}
@@ -0,0 +1,222 @@
+// Open-loop to closed-loop startup handover
+// Blends the forced open-loop voltage vector into the closed-loop FOC output
+
+use core::f32::consts::{PI, TAU};
+
+/// RPM to rad/s conversion factor
+const RPM_TO_RAD_PER_S: f32 = TAU / 60.0;
+
+/// Open-loop to closed-loop handover blender
+///
+/// At the end of the open-loop start the forced voltage vector is known:
+/// its angle rotates at the open-loop speed and its amplitude is set by the
+/// open-loop duty. Switching straight to the sensed angle and the PI output
+/// moves the voltage vector by up to 90° in one control period, which causes a
+/// torque bump and can stall a loaded motor.
+///
+/// During the handover the forced angle keeps rotating at the open-loop speed
+/// and the applied (d-axis) angle and the d/q voltages are linearly blended
+/// from the forced vector into the closed-loop values over the handover time.
+/// The forced vector is expressed as a pure q-axis voltage on a d-axis 90°
+/// behind the forced voltage angle, so the output is continuous at the start.
+pub struct StartupHandover {
+    /// Handover time (seconds, 0 = immediate switch)
+    duration: f32,
+    /// Time since the handover started (seconds)
+    elapsed: f32,
+    /// Whether a handover is in progress
+    active: bool,
+    /// Forced d-axis electrical angle (radians, 0 to 2π)
+    forced_angle: f32,
+    /// Forced electrical angular speed (rad/s)
+    forced_omega: f32,
+    /// Forced q-axis voltage (V)
+    forced_vq: f32,
+}
+
+impl StartupHandover {
+    /// Create a new handover blender
+    ///
+    /// # Arguments
+    /// * `duration` - Handover time (seconds, 0 = immediate switch)
+    pub fn new(duration: f32) -> Self {
+        Self {
+            duration,
+            elapsed: 0.0,
+            active: false,
+            forced_angle: 0.0,
+            forced_omega: 0.0,
+            forced_vq: 0.0,
+        }
+    }
+
+    /// Set the handover time
+    ///
+    /// Takes effect on the next handover.
+    ///
+    /// # Arguments
+    /// * `duration` - Handover time (seconds, 0 = immediate switch)
+    pub fn set_duration(&mut self, duration: f32) {
+        self.duration = duration;
+    }
+
+    /// Start a handover from the forced open-loop voltage vector
+    ///
+    /// Does nothing if the handover time is zero (immediate switch).
+    ///
+    /// # Arguments
+    /// * `voltage_angle` - Electrical angle of the forced voltage vector (radians)
+    /// * `voltage` - Amplitude of the forced voltage vector (V)
+    /// * `electrical_rpm` - Forced electrical speed (electrical RPM)
+    pub fn start(&mut self, voltage_angle: f32, voltage: f32, electrical_rpm: f32) {
+        if self.duration <= 0.0 {
+            self.active = false;
+            return;
+        }
+
+        self.elapsed = 0.0;
+        self.active = true;
+        self.forced_angle = wrap_angle(voltage_angle - PI / 2.0);
+        self.forced_omega = electrical_rpm * RPM_TO_RAD_PER_S;
+        self.forced_vq = voltage;
+    }
+
+    /// Abort the handover (the closed-loop values are used from now on)
+    pub fn cancel(&mut self) {
+        self.active = false;
+    }
+
+    /// Check whether a handover is in progress
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Get the closed-loop weight (0 = forced vector only, 1 = closed loop only)
+    pub fn weight(&self) -> f32 {
+        if self.active {
+            (self.elapsed / self.duration).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+
+    /// Blend the closed-loop angle and voltages with the forced vector
+    ///
+    /// Returns the closed-loop values unchanged when no handover is in progress.
+    ///
+    /// # Arguments
+    /// * `angle` - Closed-loop electrical angle (radians)
+    /// * `vd` - Closed-loop d-axis voltage command (V)
+    /// * `vq` - Closed-loop q-axis voltage command (V)
+    /// * `dt` - Time step (seconds)
+    ///
+    /// # Returns
+    /// (angle, vd, vq) - Electrical angle and d/q voltages to apply
+    pub fn update(&mut self, angle: f32, vd: f32, vq: f32, dt: f32) -> (f32, f32, f32) {
+        if !self.active {
+            return (angle, vd, vq);
+        }
+
+        self.elapsed += dt;
+        self.forced_angle = wrap_angle(self.forced_angle + self.forced_omega * dt);
+        if self.elapsed >= self.duration {
+            self.active = false;
+            return (angle, vd, vq);
+        }
+
+        let weight = self.weight();
+        let blended_angle =
+            wrap_angle(self.forced_angle + weight * wrap_pi(angle - self.forced_angle));
+        let blended_vd = weight * vd;
+        let blended_vq = self.forced_vq + weight * (vq - self.forced_vq);
+
+        (blended_angle, blended_vd, blended_vq)
+    }
+}
+
+/// Wrap an angle into 0 to 2π
+fn wrap_angle(angle: f32) -> f32 {
+    let wrapped = angle % TAU;
+    if wrapped < 0.0 {
+        wrapped + TAU
+    } else {
+        wrapped
+    }
+}
+
+/// Wrap an angle difference into -π to π
+fn wrap_pi(angle: f32) -> f32 {
+    let wrapped = wrap_angle(angle);
+    if wrapped > PI {
+        wrapped - TAU
+    } else {
+        wrapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 0.0004; // 2.5kHz
+
+    #[test]
+    fn test_starts_on_forced_vector() {
+        let mut handover = StartupHandover::new(0.2);
+        handover.start(PI, 2.0, 6000.0);
+        assert!(handover.is_active());
+
+        // The first output is (almost) the forced vector regardless of the closed loop
+        let (angle, vd, vq) = handover.update(0.0, 1.0, 5.0, DT);
+        let forced_angle = PI / 2.0 + 6000.0 * RPM_TO_RAD_PER_S * DT;
+        assert!((angle - forced_angle).abs() < 0.01);
+        assert!(vd.abs() < 0.01);
+        assert!((vq - 2.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_blends_into_closed_loop() {
+        let mut handover = StartupHandover::new(0.2);
+        handover.start(0.0, 2.0, 0.0);
+
+        // Halfway through: angle and voltage halfway between forced and closed loop
+        let mut output = (0.0, 0.0, 0.0);
+        for _ in 0..250 {
+            output = handover.update(0.2, 0.0, 4.0, DT);
+        }
+        let forced_angle = TAU - PI / 2.0;
+        let expected_angle = wrap_angle(forced_angle + 0.5 * wrap_pi(0.2 - forced_angle));
+        assert!((handover.weight() - 0.5).abs() < 0.01);
+        assert!((output.0 - expected_angle).abs() < 0.02);
+        assert!((output.2 - 3.0).abs() < 0.02);
+
+        // After the handover time the closed-loop values pass through unchanged
+        for _ in 0..260 {
+            output = handover.update(0.2, 0.5, 4.0, DT);
+        }
+        assert!(!handover.is_active());
+        assert_eq!(output, (0.2, 0.5, 4.0));
+    }
+
+    #[test]
+    fn test_blend_takes_shortest_path() {
+        let mut handover = StartupHandover::new(0.2);
+        // Forced d-axis angle 0.1 rad, closed-loop angle just below 2π
+        handover.start(0.1 + PI / 2.0, 1.0, 0.0);
+
+        let mut angle = 0.0;
+        for _ in 0..250 {
+            angle = handover.update(TAU - 0.1, 0.0, 1.0, DT).0;
+        }
+        // Halfway between 0.1 and -0.1 is 0, not π
+        assert!(wrap_pi(angle).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_zero_duration_is_immediate() {
+        let mut handover = StartupHandover::new(0.0);
+        handover.start(1.0, 2.0, 1000.0);
+        assert!(!handover.is_active());
+        assert_eq!(handover.update(0.3, 0.0, 1.5, DT), (0.3, 0.0, 1.5));
+    }
+}
@@ -6,11 +6,29 @@
 // It uses a fast x/y/z coordinate transformation and sign-based sector
 // detection instead of trigonometric functions, providing better
 // performance and accuracy for embedded systems.
+//
+// Voltage commands are phase-voltage amplitudes. The linear modulation
+// range ends at the inscribed circle of the voltage hexagon (v_dc/√3);
+// beyond that, `overmodulate` reshapes the reference onto the hexagon up to
+// six-step operation (fundamental 2·v_dc/π).
 
-use libm::roundf;
+use core::f32::consts::{FRAC_PI_3, FRAC_PI_6, PI, TAU};
+use libm::{cosf, roundf, sinf, sqrtf};
 
 const SQRT3: f32 = 1.732_050_8; // sqrt(3)
 
+/// Fundamental of the overmodulated trajectory normalised to six-step, sampled
+/// at trajectory parameter p = 0, 1/8, ..., 2
+///
+/// p in [0, 1]: circle radius grows from the inscribed to the circumscribed
+/// circle of the hexagon and is clipped to the hexagon (mode I).
+/// p in [1, 2]: hold angle at the vertices grows from 0 to π/6 (mode II).
+/// Precomputed by numerical integration of the trajectory over one sector.
+const OVERMODULATION_TABLE: [f32; 17] = [
+    0.90690, 0.92007, 0.92967, 0.93702, 0.94257, 0.94663, 0.94936, 0.95093, 0.95143, 0.96269,
+    0.97251, 0.98086, 0.98773, 0.99309, 0.99692, 0.99923, 1.00000,
+];
+
 /// Calculate Space Vector PWM duty cycles
 ///
 /// Implements SVPWM algorithm to generate three-phase PWM duty cycles
@@ -19,9 +37,12 @@ const SQRT3: f32 = 1.732_050_8; // sqrt(3)
 ///
 /// Based on: https://github.com/calebfletcher/foc
 ///
+/// The output is linear up to a vector magnitude of v_dc/√3; longer vectors
+/// saturate the duties.
+///
 /// # Arguments
-/// * `v_alpha` - Alpha-axis voltage command (volts)
-/// * `v_beta` - Beta-axis voltage command (volts)
+/// * `v_alpha` - Alpha-axis voltage command (volts, phase amplitude)
+/// * `v_beta` - Beta-axis voltage command (volts, phase amplitude)
 /// * `v_dc` - DC bus voltage (volts)
 /// * `max_duty` - Maximum duty cycle value (e.g., 100 for 0-100 range)
 ///
@@ -29,7 +50,7 @@ const SQRT3: f32 = 1.732_050_8; // sqrt(3)
 /// Tuple of (duty_u, duty_v, duty_w) as u16 values
 ///
 /// # Algorithm
-/// 1. Normalize alpha/beta voltages by DC bus voltage
+/// 1. Normalize alpha/beta voltages by the linear limit (v_dc/√3)
 /// 2. Convert normalized alpha/beta to x/y/z coordinates
 /// 3. Determine sector (1-6) based on signs of x/y/z
 /// 4. Calculate duty cycles directly from x/y/z values
@@ -40,10 +61,10 @@ pub fn calculate_svpwm(v_alpha: f32, v_beta: f32, v_dc: f32, max_duty: u16) -> (
         return (max_duty / 2, max_duty / 2, max_duty / 2);
     }
 
-    // Normalize voltages by DC bus voltage
-    // This maps the voltage commands to the range that can be achieved by the inverter
-    let v_alpha_norm = v_alpha / v_dc;
-    let v_beta_norm = v_beta / v_dc;
+    // Normalize voltages by the linear modulation limit (v_dc/√3)
+    // This maps the inscribed circle of the voltage hexagon to the full duty range
+    let v_alpha_norm = SQRT3 * v_alpha / v_dc;
+    let v_beta_norm = SQRT3 * v_beta / v_dc;
 
     // Convert normalized alpha/beta to x/y/z coordinates
     // This transformation maps the alpha-beta plane to three axes
@@ -75,13 +96,232 @@ pub fn calculate_svpwm(v_alpha: f32, v_beta: f32, v_dc: f32, max_duty: u16) -> (
 
     // Convert from range [-1, 1] to [0, max_duty]
     // Formula: duty = (value + 1.0) / 2.0 * max_duty
-    let duty_u = roundf((ta + 1.0) / 2.0 * max_duty as f32).clamp(0.0, max_duty as f32) as u16;
-    let duty_v = roundf((tb + 1.0) / 2.0 * max_duty as f32).clamp(0.0, max_duty as f32) as u16;
-    let duty_w = roundf((tc + 1.0) / 2.0 * max_duty as f32).clamp(0.0, max_duty as f32) as u16;
+    let duty_u = roundf((ta + 1.0) / 2.0 * max_duty as f32)
+        .max(0.0)
+        .min(max_duty as f32) as u16;
+    let duty_v = roundf((tb + 1.0) / 2.0 * max_duty as f32)
+        .max(0.0)
+        .min(max_duty as f32) as u16;
+    let duty_w = roundf((tc + 1.0) / 2.0 * max_duty as f32)
+        .max(0.0)
+        .min(max_duty as f32) as u16;
 
     (duty_u, duty_v, duty_w)
 }
 
+/// Maximum fundamental phase voltage the inverter can produce
+///
+/// # Arguments
+/// * `v_dc` - DC bus voltage (volts)
+/// * `overmodulation` - Whether the overmodulation region is allowed
+///
+/// # Returns
+/// v_dc/√3 (linear SVPWM) or 2·v_dc/π (six-step)
+pub fn max_output_voltage(v_dc: f32, overmodulation: bool) -> f32 {
+    if overmodulation {
+        2.0 * v_dc / PI
+    } else {
+        v_dc / SQRT3
+    }
+}
+
+/// Radius of the voltage hexagon at an angle measured from a vertex
+///
+/// # Arguments
+/// * `v_dc` - DC bus voltage (volts)
+/// * `angle` - Angle from the nearest preceding vertex (0 to π/3)
+fn hexagon_radius(v_dc: f32, angle: f32) -> f32 {
+    v_dc / (SQRT3 * cosf(angle - FRAC_PI_6))
+}
+
+/// Reshape a voltage vector beyond the linear range onto the voltage hexagon
+///
+/// Vectors within the inscribed circle (v_dc/√3) are returned unchanged.
+/// Longer vectors are replaced by a trajectory on the hexagon whose
+/// fundamental equals the requested magnitude, using the two-mode scheme:
+/// a clipped circle (mode I), then a hold angle at the vertices that
+/// reaches six-step at 2·v_dc/π (mode II). Requests beyond six-step are
+/// limited to six-step.
+///
+/// # Arguments
+/// * `v_alpha` - Alpha-axis voltage command (volts)
+/// * `v_beta` - Beta-axis voltage command (volts)
+/// * `v_dc` - DC bus voltage (volts)
+///
+/// # Returns
+/// Tuple of (v_alpha, v_beta) inside the voltage hexagon
+pub fn overmodulate(v_alpha: f32, v_beta: f32, v_dc: f32) -> (f32, f32) {
+    let magnitude = sqrtf(v_alpha * v_alpha + v_beta * v_beta);
+    if v_dc <= 0.0 || magnitude <= v_dc / SQRT3 {
+        return (v_alpha, v_beta);
+    }
+
+    // Trajectory parameter for the requested modulation index (six-step = 1)
+    let index = (magnitude / max_output_voltage(v_dc, true)).min(1.0);
+    let mut p = 2.0;
+    for i in 0..OVERMODULATION_TABLE.len() - 1 {
+        let (lower, upper) = (OVERMODULATION_TABLE[i], OVERMODULATION_TABLE[i + 1]);
+        if index <= upper {
+            p = (i as f32 + (index - lower) / (upper - lower)) / 8.0;
+            break;
+        }
+    }
+
+    // Position within the current sector (vertices at multiples of π/3)
+    let mut angle = vector_angle(v_alpha, v_beta);
+    if angle < 0.0 {
+        angle += TAU;
+    }
+    let sector_start = (angle / FRAC_PI_3) as u32 as f32 * FRAC_PI_3;
+    let sector_angle = angle - sector_start;
+
+    let (radius, output_angle) = if p <= 1.0 {
+        // Mode I: clip the enlarged circle to the hexagon, keeping the angle
+        let inscribed = v_dc / SQRT3;
+        let radius = inscribed + p * (2.0 * v_dc / 3.0 - inscribed);
+        (radius.min(hexagon_radius(v_dc, sector_angle)), sector_angle)
+    } else {
+        // Mode II: hold the vertex for the hold angle, sweep the side in between
+        let hold = (p - 1.0) * FRAC_PI_6;
+        let vertex = 2.0 * v_dc / 3.0;
+        if sector_angle <= hold && sector_angle < FRAC_PI_6 {
+            (vertex, 0.0)
+        } else if sector_angle >= FRAC_PI_3 - hold {
+            (vertex, FRAC_PI_3)
+        } else {
+            let swept = (sector_angle - hold) * FRAC_PI_3 / (FRAC_PI_3 - 2.0 * hold);
+            (hexagon_radius(v_dc, swept), swept)
+        }
+    };
+
+    let output_angle = sector_start + output_angle;
+    (radius * cosf(output_angle), radius * sinf(output_angle))
+}
+
+/// Angle of a voltage vector (radians, -π to π)
+///
+/// Uses the fixed-point `atan2` (CORDIC with the `cordic` feature) with the
+/// `fixed-point` feature, libm otherwise.
+///
+/// # Arguments
+/// * `v_alpha` - Alpha-axis voltage (volts)
+/// * `v_beta` - Beta-axis voltage (volts)
+#[inline]
+fn vector_angle(v_alpha: f32, v_beta: f32) -> f32 {
+    #[cfg(feature = "fixed-point")]
+    {
+        use super::fixed_point::{atan2, phase_to_rad, q31_from_f32};
+
+        // Normalize by the larger component so that both fit in Q31 (the phase is unchanged)
+        let scale = 1.0 / v_alpha.abs().max(v_beta.abs());
+        phase_to_rad(atan2(
+            q31_from_f32(v_beta * scale),
+            q31_from_f32(v_alpha * scale),
+        ))
+    }
+    #[cfg(not(feature = "fixed-point"))]
+    {
+        libm::atan2f(v_beta, v_alpha)
+    }
+}
+
+/// PWM modulator with DC bus voltage tracking
+///
+/// Holds the bus voltage used to normalise the voltage commands and whether
+/// the overmodulation region is allowed.
+pub struct Modulator {
+    /// DC bus voltage (volts)
+    v_dc: f32,
+    /// Whether the overmodulation region is allowed
+    overmodulation: bool,
+}
+
+impl Modulator {
+    /// Create a new modulator (linear range only)
+    ///
+    /// # Arguments
+    /// * `v_dc` - Initial DC bus voltage (volts)
+    pub fn new(v_dc: f32) -> Self {
+        Self {
+            v_dc,
+            overmodulation: false,
+        }
+    }
+
+    /// Set the DC bus voltage
+    ///
+    /// # Arguments
+    /// * `v_dc` - DC bus voltage (volts)
+    pub fn set_bus_voltage(&mut self, v_dc: f32) {
+        self.v_dc = v_dc;
+    }
+
+    /// Get the DC bus voltage (volts)
+    pub fn get_bus_voltage(&self) -> f32 {
+        self.v_dc
+    }
+
+    /// Enable or disable the overmodulation region
+    ///
+    /// # Arguments
+    /// * `enabled` - Allow voltages beyond the linear range up to six-step
+    pub fn set_overmodulation(&mut self, enabled: bool) {
+        self.overmodulation = enabled;
+    }
+
+    /// Maximum voltage vector magnitude at the current bus voltage (volts)
+    pub fn voltage_limit(&self) -> f32 {
+        max_output_voltage(self.v_dc, self.overmodulation)
+    }
+
+    /// Reshape the voltage vector for the overmodulation region
+    ///
+    /// # Arguments
+    /// * `v_alpha` - Alpha-axis voltage command (volts)
+    /// * `v_beta` - Beta-axis voltage command (volts)
+    ///
+    /// # Returns
+    /// Tuple of (v_alpha, v_beta) actually applied to the motor
+    pub fn shape(&self, v_alpha: f32, v_beta: f32) -> (f32, f32) {
+        if self.overmodulation {
+            overmodulate(v_alpha, v_beta, self.v_dc)
+        } else {
+            (v_alpha, v_beta)
+        }
+    }
+
+    /// Calculate SVPWM duty cycles at the current bus voltage
+    ///
+    /// With the `fixed-point` feature the duties come from the fixed-point
+    /// SVPWM. The overmodulated trajectory reaches the hexagon vertices
+    /// (2·v_dc/3), beyond the Q15 range of the normalized voltage, so the f32
+    /// SVPWM is used while overmodulation is enabled.
+    ///
+    /// # Arguments
+    /// * `v_alpha` - Alpha-axis voltage command (volts)
+    /// * `v_beta` - Beta-axis voltage command (volts)
+    /// * `max_duty` - Maximum duty cycle value
+    ///
+    /// # Returns
+    /// Tuple of (duty_u, duty_v, duty_w) as u16 values
+    pub fn duties(&self, v_alpha: f32, v_beta: f32, max_duty: u16) -> (u16, u16, u16) {
+        #[cfg(feature = "fixed-point")]
+        if !self.overmodulation && self.v_dc > 0.0 {
+            use super::fixed_point::{q15_from_f32, svpwm};
+
+            // Normalize by the linear modulation limit (v_dc/√3)
+            let scale = SQRT3 / self.v_dc;
+            return svpwm(
+                q15_from_f32(v_alpha * scale),
+                q15_from_f32(v_beta * scale),
+                max_duty,
+            );
+        }
+
+        calculate_svpwm(v_alpha, v_beta, self.v_dc, max_duty)
+    }
+}
+
 /// Calculate sinusoidal PWM duty cycles (simpler alternative to SVPWM)
 ///
 /// Generates three-phase PWM duty cycles using direct sinusoidal modulation.
@@ -95,7 +335,6 @@ pub fn calculate_svpwm(v_alpha: f32, v_beta: f32, v_dc: f32, max_duty: u16) -> (
 ///
 /// # Returns
 /// Tuple of (duty_u, duty_v, duty_w) as u16 values
-#[allow(dead_code)]
 pub fn calculate_sinusoidal_pwm(
     v_alpha: f32,
     v_beta: f32,
@@ -114,9 +353,15 @@ pub fn calculate_sinusoidal_pwm(
 
     // Normalize to DC bus voltage and convert to duty cycle
     // Add 0.5 offset to center around 50% duty cycle
-    let duty_u = ((v_u / v_dc + 0.5) * max_duty as f32).clamp(0.0, max_duty as f32) as u16;
-    let duty_v = ((v_v / v_dc + 0.5) * max_duty as f32).clamp(0.0, max_duty as f32) as u16;
-    let duty_w = ((v_w / v_dc + 0.5) * max_duty as f32).clamp(0.0, max_duty as f32) as u16;
+    let duty_u = ((v_u / v_dc + 0.5) * max_duty as f32)
+        .max(0.0)
+        .min(max_duty as f32) as u16;
+    let duty_v = ((v_v / v_dc + 0.5) * max_duty as f32)
+        .max(0.0)
+        .min(max_duty as f32) as u16;
+    let duty_w = ((v_w / v_dc + 0.5) * max_duty as f32)
+        .max(0.0)
+        .min(max_duty as f32) as u16;
 
     (duty_u, duty_v, duty_w)
 }
@@ -142,6 +387,115 @@ mod tests {
         assert!(du > dv && du > dw);
     }
 
+    use crate::foc::transforms::inverse_clarke;
+
+    const V_DC: f32 = 24.0;
+    const MAX_DUTY: u16 = 10000;
+
+    /// Line-to-line voltages (U-V, V-W) produced by a set of duties
+    fn line_voltages(duties: (u16, u16, u16)) -> (f32, f32) {
+        let scale = V_DC / MAX_DUTY as f32;
+        (
+            (duties.0 as f32 - duties.1 as f32) * scale,
+            (duties.1 as f32 - duties.2 as f32) * scale,
+        )
+    }
+
+    /// Fundamental amplitude of the overmodulated trajectory over one revolution
+    fn overmodulated_fundamental(magnitude: f32) -> f32 {
+        let steps = 3600;
+        let mut sum = 0.0;
+        for i in 0..steps {
+            let angle = (i as f32 + 0.5) * TAU / steps as f32;
+            let (v_alpha, v_beta) =
+                overmodulate(magnitude * cosf(angle), magnitude * sinf(angle), V_DC);
+            // In-phase component with the reference
+            sum += v_alpha * cosf(angle) + v_beta * sinf(angle);
+        }
+        sum / steps as f32
+    }
+
+    #[test]
+    fn test_svpwm_linear_gain() {
+        // Line voltages match the commanded phase amplitude up to the linear limit
+        let magnitude = V_DC / SQRT3 * 0.999;
+        for i in 0..24 {
+            let angle = i as f32 * TAU / 24.0;
+            let (v_alpha, v_beta) = (magnitude * cosf(angle), magnitude * sinf(angle));
+            let duties = calculate_svpwm(v_alpha, v_beta, V_DC, MAX_DUTY);
+            let (v_u, v_v, v_w) = inverse_clarke(v_alpha, v_beta);
+            let (v_uv, v_vw) = line_voltages(duties);
+            assert!((v_uv - (v_u - v_v)).abs() < 0.01);
+            assert!((v_vw - (v_v - v_w)).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_overmodulation_linear_range_unchanged() {
+        let (v_alpha, v_beta) = overmodulate(8.0, 6.0, V_DC);
+        assert_eq!((v_alpha, v_beta), (8.0, 6.0));
+        assert!((max_output_voltage(V_DC, false) - 13.856).abs() < 0.01);
+        assert!((max_output_voltage(V_DC, true) - 15.279).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_overmodulation_fundamental() {
+        // The fundamental tracks the request across both overmodulation modes
+        let linear = max_output_voltage(V_DC, false);
+        let six_step = max_output_voltage(V_DC, true);
+        for i in 1..=10 {
+            let magnitude = linear + (six_step - linear) * i as f32 / 10.0;
+            let fundamental = overmodulated_fundamental(magnitude);
+            assert!(
+                (fundamental - magnitude).abs() / magnitude < 0.005,
+                "request {} V, fundamental {} V",
+                magnitude,
+                fundamental
+            );
+        }
+
+        // Every output vector stays inside the hexagon (duties not saturated beyond it)
+        for i in 0..360 {
+            let angle = i as f32 * TAU / 360.0;
+            let (v_alpha, v_beta) = overmodulate(14.5 * cosf(angle), 14.5 * sinf(angle), V_DC);
+            let (v_u, v_v, v_w) = inverse_clarke(v_alpha, v_beta);
+            let spread = v_u.max(v_v).max(v_w) - v_u.min(v_v).min(v_w);
+            assert!(spread <= V_DC * 1.0001);
+        }
+    }
+
+    #[test]
+    fn test_overmodulation_six_step() {
+        // At (and beyond) the six-step limit, only the six active vectors are applied
+        for magnitude in [max_output_voltage(V_DC, true), 30.0] {
+            for i in 0..72 {
+                let angle = (i as f32 + 0.5) * TAU / 72.0;
+                let (v_alpha, v_beta) =
+                    overmodulate(magnitude * cosf(angle), magnitude * sinf(angle), V_DC);
+                let duties = calculate_svpwm(v_alpha, v_beta, V_DC, MAX_DUTY);
+                for duty in [duties.0, duties.1, duties.2] {
+                    assert!(duty <= 1 || duty >= MAX_DUTY - 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_modulator() {
+        let mut modulator = Modulator::new(V_DC);
+        assert!((modulator.voltage_limit() - V_DC / SQRT3).abs() < 1e-4);
+        // Linear range only: long vectors pass through (limited upstream)
+        assert_eq!(modulator.shape(15.0, 0.0), (15.0, 0.0));
+
+        modulator.set_overmodulation(true);
+        modulator.set_bus_voltage(12.0);
+        assert!((modulator.voltage_limit() - 24.0 / PI).abs() < 1e-4);
+        // 7.5V on the alpha axis lies beyond the 6.93V linear limit at 12V
+        let (v_alpha, v_beta) = modulator.shape(7.5, 0.0);
+        assert!(v_alpha > 7.5 && v_beta.abs() < 1e-4);
+        assert_eq!(modulator.get_bus_voltage(), 12.0);
+    }
+
     #[test]
     fn test_sinusoidal_pwm_zero_voltage() {
         let (du, dv, dw) = calculate_sinusoidal_pwm(0.0, 0.0, 12.0, 100);
@@ -1,9 +1,10 @@
 // Coordinate transformations for FOC (Field Oriented Control)
-// Includes Park and Clarke inverse transforms
+// Includes Park and Clarke forward and inverse transforms
 
 use libm::{cosf, sinf, sqrtf};
 
 // Enable idsp-based fast trigonometric functions
+// (with the `fixed-point` feature, sin/cos come from `fixed_point::cossin_q31` instead)
 const USE_IDSP_COSSIN: bool = true;
 
 /// Inverse Park transformation (dq → αβ)
@@ -21,8 +22,11 @@ const USE_IDSP_COSSIN: bool = true;
 /// # Implementation
 /// Uses idsp::cossin() for fast trigonometric calculation (~40 cycles on Cortex-M)
 /// compared to libm::cosf/sinf (~100-200 cycles). Can be switched via USE_IDSP_COSSIN.
+/// With the `fixed-point` feature the fixed-point sin/cos is used (CORDIC with `cordic`).
 pub fn inverse_park(vd: f32, vq: f32, theta: f32) -> (f32, f32) {
-    if USE_IDSP_COSSIN {
+    if cfg!(feature = "fixed-point") {
+        inverse_park_fixed_point(vd, vq, theta)
+    } else if USE_IDSP_COSSIN {
         inverse_park_idsp(vd, vq, theta)
     } else {
         inverse_park_libm(vd, vq, theta)
@@ -32,6 +36,70 @@ pub fn inverse_park(vd: f32, vq: f32, theta: f32) -> (f32, f32) {
 /// Inverse Park using idsp::cossin() (fast, ~40 cycles on Cortex-M)
 #[inline]
 fn inverse_park_idsp(vd: f32, vq: f32, theta: f32) -> (f32, f32) {
+    let (cos_theta, sin_theta) = cossin_idsp(theta);
+
+    let v_alpha = vd * cos_theta - vq * sin_theta;
+    let v_beta = vd * sin_theta + vq * cos_theta;
+
+    (v_alpha, v_beta)
+}
+
+/// Inverse Park using the fixed-point sin/cos (idsp, or CORDIC with the `cordic` feature)
+#[inline]
+fn inverse_park_fixed_point(vd: f32, vq: f32, theta: f32) -> (f32, f32) {
+    let (cos_theta, sin_theta) = cossin_fixed_point(theta);
+
+    let v_alpha = vd * cos_theta - vq * sin_theta;
+    let v_beta = vd * sin_theta + vq * cos_theta;
+
+    (v_alpha, v_beta)
+}
+
+/// Inverse Park using libm (slower, ~100-200 cycles, but more familiar)
+#[inline]
+fn inverse_park_libm(vd: f32, vq: f32, theta: f32) -> (f32, f32) {
+    let cos_theta = cosf(theta);
+    let sin_theta = sinf(theta);
+
+    let v_alpha = vd * cos_theta - vq * sin_theta;
+    let v_beta = vd * sin_theta + vq * cos_theta;
+
+    (v_alpha, v_beta)
+}
+
+/// Park transformation (αβ → dq)
+///
+/// Transforms from the stationary αβ frame to the rotating dq reference frame
+///
+/// # Arguments
+/// * `alpha` - Alpha-axis component (e.g. current)
+/// * `beta` - Beta-axis component
+/// * `theta` - Electrical angle in radians
+///
+/// # Returns
+/// Tuple of (d, q) in the rotating frame
+///
+/// # Implementation
+/// Shares the sin/cos implementation with `inverse_park` (switched via the
+/// `fixed-point` feature and USE_IDSP_COSSIN)
+pub fn park(alpha: f32, beta: f32, theta: f32) -> (f32, f32) {
+    let (cos_theta, sin_theta) = if cfg!(feature = "fixed-point") {
+        cossin_fixed_point(theta)
+    } else if USE_IDSP_COSSIN {
+        cossin_idsp(theta)
+    } else {
+        (cosf(theta), sinf(theta))
+    };
+
+    let d = alpha * cos_theta + beta * sin_theta;
+    let q = -alpha * sin_theta + beta * cos_theta;
+
+    (d, q)
+}
+
+/// Calculate (cos, sin) using idsp::cossin()
+#[inline]
+fn cossin_idsp(theta: f32) -> (f32, f32) {
     // Convert theta (radians, 0 to 2π) to idsp phase format (i32, full scale)
     // idsp uses i32::MIN (-2^31) to i32::MAX (2^31-1) to represent -π to π
     // First normalize theta from [0, 2π] to [-π, π]
@@ -48,25 +116,39 @@ fn inverse_park_idsp(vd: f32, vq: f32, theta: f32) -> (f32, f32) {
     // Convert i32 to f32 and normalize to [-1.0, 1.0]
     // Note: i32::MIN as f32 = -2147483648.0, but we want to normalize to 2^31
     const I32_TO_F32: f32 = 1.0 / 2147483648.0; // 1 / 2^31
-    let cos_theta = cos_i32 as f32 * I32_TO_F32;
-    let sin_theta = sin_i32 as f32 * I32_TO_F32;
+    (cos_i32 as f32 * I32_TO_F32, sin_i32 as f32 * I32_TO_F32)
+}
 
-    let v_alpha = vd * cos_theta - vq * sin_theta;
-    let v_beta = vd * sin_theta + vq * cos_theta;
+/// Calculate (cos, sin) using the fixed-point path (`fixed_point::cossin_q31`)
+#[inline]
+fn cossin_fixed_point(theta: f32) -> (f32, f32) {
+    use super::fixed_point::{cossin_q31, phase_from_rad, q31_to_f32};
 
-    (v_alpha, v_beta)
+    let (cos_q31, sin_q31) = cossin_q31(phase_from_rad(theta));
+    (q31_to_f32(cos_q31), q31_to_f32(sin_q31))
 }
 
-/// Inverse Park using libm (slower, ~100-200 cycles, but more familiar)
-#[inline]
-fn inverse_park_libm(vd: f32, vq: f32, theta: f32) -> (f32, f32) {
-    let cos_theta = cosf(theta);
-    let sin_theta = sinf(theta);
+/// Clarke transformation (abc/uvw → αβ)
+///
+/// Transforms three-phase quantities to the stationary αβ frame
+/// (amplitude-invariant form). Using all three phases cancels any
+/// common-mode error (e.g. residual ADC offset) shared by the channels.
+///
+/// # Arguments
+/// * `i_u` - U-phase current
+/// * `i_v` - V-phase current
+/// * `i_w` - W-phase current
+///
+/// # Returns
+/// Tuple of (i_alpha, i_beta)
+pub fn clarke(i_u: f32, i_v: f32, i_w: f32) -> (f32, f32) {
+    const ONE_DIV_SQRT3: f32 = 0.577_350_26; // 1 / sqrt(3)
+    const ONE_DIV_3: f32 = 1.0 / 3.0;
 
-    let v_alpha = vd * cos_theta - vq * sin_theta;
-    let v_beta = vd * sin_theta + vq * cos_theta;
+    let i_alpha = ONE_DIV_3 * (2.0 * i_u - i_v - i_w);
+    let i_beta = ONE_DIV_SQRT3 * (i_v - i_w);
 
-    (v_alpha, v_beta)
+    (i_alpha, i_beta)
 }
 
 /// Inverse Clarke transformation (αβ → abc/uvw)
@@ -79,7 +161,6 @@ fn inverse_park_libm(vd: f32, vq: f32, theta: f32) -> (f32, f32) {
 ///
 /// # Returns
 /// Tuple of (v_u, v_v, v_w) three-phase voltages
-#[allow(dead_code)]
 pub fn inverse_clarke(v_alpha: f32, v_beta: f32) -> (f32, f32, f32) {
     // Constants for Clarke transform
     const SQRT3_DIV_2: f32 = 0.866_025_4; // sqrt(3) / 2
@@ -191,6 +272,7 @@ pub fn benchmark_inverse_park(iterations: u32) -> ((f32, f32), (f32, f32), u32,
 #[cfg(test)]
 mod tests {
     use super::*;
+    use core::f32::consts::TAU;
 
     const EPSILON: f32 = 0.0001;
 
@@ -205,6 +287,30 @@ mod tests {
         assert!(approx_eq(v_beta, 0.0));
     }
 
+    #[test]
+    fn test_park_inverse_park_roundtrip() {
+        let theta = 1.2;
+        let (alpha, beta) = inverse_park(0.5, 2.0, theta);
+        let (d, q) = park(alpha, beta, theta);
+        assert!(approx_eq(d, 0.5));
+        assert!(approx_eq(q, 2.0));
+    }
+
+    #[test]
+    fn test_clarke_inverse_clarke_roundtrip() {
+        let (u, v, w) = inverse_clarke(1.5, -0.7);
+        let (alpha, beta) = clarke(u, v, w);
+        assert!(approx_eq(alpha, 1.5));
+        assert!(approx_eq(beta, -0.7));
+    }
+
+    #[test]
+    fn test_clarke_rejects_common_mode() {
+        let (alpha, beta) = clarke(1.0 + 0.3, -0.5 + 0.3, -0.5 + 0.3);
+        assert!(approx_eq(alpha, 1.0));
+        assert!(approx_eq(beta, 0.0));
+    }
+
     #[test]
     fn test_inverse_clarke() {
         let (v_u, v_v, v_w) = inverse_clarke(1.0, 0.0);
@@ -230,7 +336,7 @@ mod tests {
     #[test]
     fn test_normalize_angle() {
         assert!(approx_eq(normalize_angle(0.0), 0.0));
-        assert!(approx_eq(normalize_angle(7.0), 7.0 - 6.283185307));
-        assert!(approx_eq(normalize_angle(-1.0), -1.0 + 6.283185307));
+        assert!(approx_eq(normalize_angle(7.0), 7.0 - TAU));
+        assert!(approx_eq(normalize_angle(-1.0), -1.0 + TAU));
     }
 }
@@ -39,6 +39,19 @@ pub static PERIOD_CYCLES: AtomicU32 = AtomicU32::new(0);
 /// タイムアウトフラグ（モーター停止検出）
 pub static TIMEOUT_FLAG: AtomicU8 = AtomicU8::new(0);
 
+/// TIM4のカウントクロック [Hz]（APB1 170MHz、PSC=0）
+pub const TIMER_CLOCK_HZ: u32 = 170_000_000;
+
+/// Hallエッジ総数（診断用、ラップアラウンドあり）
+pub static EDGE_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+/// グリッチ（短すぎるエッジ間隔）の総数（診断用、ラップアラウンドあり）
+pub static GLITCH_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+/// グリッチ判定のエッジ間隔下限 [cycles]（20μs @ 170MHz）
+/// 実回転では到達しない間隔で、これより短いエッジはノイズとみなす
+pub const MIN_EDGE_PERIOD_CYCLES: u32 = 3_400;
+
 /// TIM4 Hall Sensor Interface の初期化
 ///
 /// # Safety
@@ -209,7 +222,13 @@ pub unsafe fn tim4_irq_handler() {
         // 周期 = overflow * 65536 + capture が前回リセットからの絶対経過サイクル数になる
         let period = (overflow << 16) | capture;
 
-        // 4. グローバル変数更新
+        // 4. 診断カウンタ更新
+        EDGE_COUNTER.fetch_add(1, Ordering::Relaxed);
+        if period < MIN_EDGE_PERIOD_CYCLES {
+            GLITCH_COUNTER.fetch_add(1, Ordering::Relaxed);
+        }
+
+        // 5. グローバル変数更新
         HALL_STATE.store(hall_state, Ordering::Relaxed);
         LAST_CAPTURE.store(capture, Ordering::Relaxed);
         LAST_OVERFLOW.store(overflow, Ordering::Relaxed); // デバッグ用に保持
@@ -239,6 +258,34 @@ pub fn get_period_cycles() -> u32 {
     PERIOD_CYCLES.load(Ordering::Relaxed)
 }
 
+/// 最後のHallエッジからの経過サイクル数を取得
+///
+/// TIM4はエッジごとにカウンタがリセットされるため、
+/// 経過サイクル = オーバーフロー回数 * 65536 + 現在のカウンタ値
+/// 読み取り中にオーバーフローが発生した場合は読み直す
+pub fn get_cycles_since_edge() -> u32 {
+    let tim4 = pac::TIM4;
+    loop {
+        let overflow = OVERFLOW_COUNTER.load(Ordering::Relaxed);
+        let count = tim4.cnt().read().cnt() as u32;
+        if OVERFLOW_COUNTER.load(Ordering::Relaxed) == overflow {
+            return (overflow << 16) | count;
+        }
+    }
+}
+
+/// Hallエッジ総数を取得
+#[inline(always)]
+pub fn get_edge_count() -> u32 {
+    EDGE_COUNTER.load(Ordering::Relaxed)
+}
+
+/// グリッチ総数を取得
+#[inline(always)]
+pub fn get_glitch_count() -> u32 {
+    GLITCH_COUNTER.load(Ordering::Relaxed)
+}
+
 /// タイムアウトフラグを取得
 #[inline(always)]
 pub fn is_timeout() -> bool {
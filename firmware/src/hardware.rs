@@ -4,6 +4,10 @@
 
 use embassy_stm32::{bind_interrupts, can, peripherals, Config};
 
+use crate::control_timer;
+#[cfg(feature = "cordic")]
+use crate::cordic;
+use crate::current_sense;
 use crate::fmt::*;
 use crate::hall_tim;
 
@@ -52,3 +56,41 @@ pub unsafe fn init_hall_sensor() {
     hall_tim::init_hall_timer();
     info!("TIM4 Hall Sensor Interface initialized");
 }
+
+/// 相電流サンプリング初期化（TIM1 CH4トリガーのADC注入変換）
+///
+/// U=ADC1_IN3、V=ADC2_IN3、W=ADC1_IN12
+///
+/// # Safety
+/// PACを使用した直接レジスタ操作を含む
+pub unsafe fn init_current_sense() {
+    info!("Initializing phase current sensing (TIM1-synced injected ADC)...");
+    current_sense::init_current_sense();
+    info!("Phase current sensing initialized");
+}
+
+/// CORDIC初期化（固定小数点演算のsin/cos・atan2に使用）
+///
+/// # Safety
+/// PACを使用した直接レジスタ操作を含む
+#[cfg(feature = "cordic")]
+pub unsafe fn init_cordic() {
+    cordic::init_cordic();
+    info!("CORDIC coprocessor initialized");
+}
+
+/// 制御割り込み初期化（TIM1更新割り込み、PWM周期を分周して制御周期を生成）
+///
+/// # 引数
+/// * `divider` - 制御周期の分周比（PWM周期数）
+///
+/// # Safety
+/// PACを使用した直接レジスタ操作を含む
+pub unsafe fn init_control_timer(divider: u32) {
+    info!(
+        "Initializing control interrupt (TIM1 update, divider={})...",
+        divider
+    );
+    control_timer::init_control_timer(divider);
+    info!("Control interrupt initialized");
+}
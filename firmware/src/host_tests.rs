@@ -0,0 +1,33 @@
+//! ホスト（PC）で単体テストを実行するためのクレートルート
+//!
+//! ファームウェアはthumbv7em専用のバイナリのため、ハードウェアに依存しないモジュールだけを
+//! `firmware-tests`クレートのライブラリとしてホストでビルドし、各モジュールの
+//! `#[cfg(test)]`テストを実行します（ファームウェアのビルドには含まれません）。
+//!
+//! ```text
+//! cd firmware-tests && cargo test
+//! cd firmware-tests && cargo test --features fixed-point
+//! ```
+
+#![no_std]
+// ファームウェア本体（main.rsと各タスク）から使う項目・再エクスポートはここでは未使用
+#![allow(dead_code, unused_imports)]
+
+#[cfg(test)]
+extern crate std;
+
+/// 設定（EEPROMエミュレーションはフラッシュに依存するため除く）
+#[path = "host_tests/config.rs"]
+mod config;
+
+/// 相電流・DCバス電圧サンプリング（注入シーケンスの設定値のみ）
+#[path = "current_sense/injected.rs"]
+mod current_sense_injected;
+
+mod double_buffer;
+mod drive_state;
+mod foc;
+
+/// TIM4 Hallセンサーインターフェースの代替（割り込みの代わりにテストから状態を設定する）
+#[path = "host_tests/hall_tim.rs"]
+mod hall_tim;
@@ -0,0 +1,11 @@
+//! ホストテスト用の設定モジュール
+//!
+//! `config`から、フラッシュに依存するEEPROMエミュレーションを除いたもの。
+
+#[path = "../config/params.rs"]
+pub mod params;
+#[path = "../config/storage.rs"]
+pub mod storage;
+
+pub use params::*;
+pub use storage::StoredConfig;
@@ -0,0 +1,51 @@
+//! ホストテスト用のHallセンサーインターフェース
+//!
+//! `hall_tim`と同じAPIを、TIM4の代わりにテストから設定する状態で提供します。
+
+use core::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+
+/// Hallセンサー状態
+pub static HALL_STATE: AtomicU8 = AtomicU8::new(0);
+
+/// 前回エッジ間のサイクル数
+pub static PERIOD_CYCLES: AtomicU32 = AtomicU32::new(0);
+
+/// 最後のエッジからの経過サイクル数（実機ではTIM4のカウンタ）
+pub static CYCLES_SINCE_EDGE: AtomicU32 = AtomicU32::new(0);
+
+/// タイムアウトフラグ
+pub static TIMEOUT_FLAG: AtomicU8 = AtomicU8::new(0);
+
+/// TIM4のカウントクロック [Hz]
+pub const TIMER_CLOCK_HZ: u32 = 170_000_000;
+
+/// Hall状態を取得
+pub fn get_hall_state() -> u8 {
+    HALL_STATE.load(Ordering::Relaxed)
+}
+
+/// 周期（サイクル数）を取得
+pub fn get_period_cycles() -> u32 {
+    PERIOD_CYCLES.load(Ordering::Relaxed)
+}
+
+/// 最後のHallエッジからの経過サイクル数を取得
+pub fn get_cycles_since_edge() -> u32 {
+    CYCLES_SINCE_EDGE.load(Ordering::Relaxed)
+}
+
+/// タイムアウトフラグを取得
+pub fn is_timeout() -> bool {
+    TIMEOUT_FLAG.load(Ordering::Relaxed) != 0
+}
+
+/// 周期から速度（RPM）を計算（`hall_tim::calculate_speed_rpm`と同じ式）
+pub fn calculate_speed_rpm(period_cycles: u32, pole_pairs: u8) -> f32 {
+    if period_cycles == 0 {
+        return 0.0;
+    }
+
+    let freq_hz = TIMER_CLOCK_HZ as f32 / period_cycles as f32;
+    let elec_rpm = freq_hz * 60.0 / 6.0;
+    elec_rpm / pole_pairs as f32
+}
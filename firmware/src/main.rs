@@ -4,6 +4,12 @@
 mod benchmark;
 mod can_protocol;
 mod config;
+mod control_timer;
+#[cfg(feature = "cordic")]
+mod cordic;
+mod current_sense;
+mod double_buffer;
+mod drive_state;
 mod fmt;
 mod foc;
 mod hall_tim;
@@ -20,12 +26,13 @@ use {defmt_rtt as _, panic_probe as _};
 
 use embassy_executor::Spawner;
 use embassy_stm32::{
-    adc::{Adc, AdcChannel, SampleTime},
+    adc::Adc,
     can,
     crc::{Config as CrcConfig, Crc},
     flash::Flash,
     gpio::{Level, Output, Speed},
     opamp::{OpAmp, OpAmpSpeed},
+    time::Hertz,
     timer::{
         complementary_pwm::{ComplementaryPwm, ComplementaryPwmPin},
         low_level::CountingMode,
@@ -35,6 +42,7 @@ use embassy_stm32::{
 };
 use embassy_time::{Duration, Timer};
 
+use drive_state::DriveRequest;
 use fmt::*;
 use hardware::Irqs;
 use tasks::{can_task, led_task, motor_control_task, voltage_monitor_task};
@@ -76,13 +84,53 @@ async fn main(spawner: Spawner) {
 
     // 設定をフラッシュから読み込み（失敗時はデフォルト初期化）
     info!("Loading configuration from flash...");
-    let loaded_config =
+    let mut loaded_config =
         config::load_or_initialize_config(&mut flash_blocking, &mut crc_blocking).await;
 
+    // ハードウェア設定を検証（範囲外の値はデフォルトにフォールバック）
+    let invalid = loaded_config.sanitize_hardware_params();
+    if invalid & config::storage::INVALID_PWM_FREQUENCY != 0 {
+        error!(
+            "Stored PWM frequency out of range, falling back to {}Hz",
+            loaded_config.pwm_frequency
+        );
+    }
+    if invalid & config::storage::INVALID_PWM_DEAD_TIME != 0 {
+        error!(
+            "Stored PWM dead time out of range, falling back to {}",
+            loaded_config.pwm_dead_time
+        );
+    }
+    if invalid & config::storage::INVALID_CAN_BITRATE != 0 {
+        error!(
+            "Stored CAN bitrate not supported, falling back to {}bps",
+            loaded_config.can_bitrate
+        );
+    }
+    if invalid & config::storage::INVALID_CONTROL_PERIOD != 0 {
+        error!(
+            "Stored control period out of range, falling back to {}us",
+            loaded_config.control_period_us
+        );
+    }
+    if invalid & config::storage::INVALID_LOOP_DIVIDER != 0 {
+        error!(
+            "Stored loop dividers out of range, falling back to speed=/{}, position=/{}",
+            loaded_config.speed_loop_divider, loaded_config.position_loop_divider
+        );
+    }
+    let non_finite = loaded_config.sanitize_float_params();
+    if non_finite != 0 {
+        error!(
+            "Stored config had {} non-finite values, reset to defaults",
+            non_finite
+        );
+    }
+
     // グローバル状態に設定を適用
     {
         let mut runtime_config = state::RUNTIME_CONFIG.lock().await;
-        *runtime_config = loaded_config;
+        **runtime_config = loaded_config;
 
         let mut version = state::CONFIG_VERSION.lock().await;
         *version = loaded_config.version;
@@ -97,6 +145,71 @@ async fn main(spawner: Spawner) {
         );
         info!("  Max voltage: {}V", loaded_config.max_voltage);
         info!("  Pole pairs: {}", loaded_config.pole_pairs);
+        info!(
+            "  PWM: {}Hz, dead_time={}, dead_time_compensation={}, min_pulse={}, overmodulation={}",
+            loaded_config.pwm_frequency,
+            loaded_config.pwm_dead_time,
+            loaded_config.pwm_dead_time_compensation,
+            loaded_config.pwm_min_pulse,
+            loaded_config.pwm_overmodulation
+        );
+        info!("  CAN bitrate: {}bps", loaded_config.can_bitrate);
+        info!(
+            "  Control period: {}us, speed loop=/{}, position loop=/{}",
+            loaded_config.control_period_us,
+            loaded_config.speed_loop_divider,
+            loaded_config.position_loop_divider
+        );
+        info!(
+            "  Current loop: enabled={}, Kp={}, Ki={}, max={}A",
+            loaded_config.current_control_enabled,
+            loaded_config.current_kp,
+            loaded_config.current_ki,
+            loaded_config.max_current
+        );
+        info!(
+            "  Position loop: Kp={}, max_speed={}RPM",
+            loaded_config.position_kp, loaded_config.position_max_speed
+        );
+        info!(
+            "  Motion profile: accel={}RPM/s, decel={}RPM/s, jerk={}RPM/s^2",
+            loaded_config.profile_acceleration,
+            loaded_config.profile_deceleration,
+            loaded_config.profile_jerk
+        );
+        info!(
+            "  Angle source: {} (sensorless: R={}ohm, L={}H, flux={}Wb, PLL={}rad/s, min={}RPM)",
+            loaded_config.angle_source,
+            loaded_config.motor_resistance,
+            loaded_config.motor_inductance,
+            loaded_config.motor_flux_linkage,
+            loaded_config.observer_pll_bandwidth,
+            loaded_config.sensorless_min_speed
+        );
+        info!(
+            "  Hall estimator: {} (PLL={}rad/s)",
+            loaded_config.hall_estimator, loaded_config.hall_pll_bandwidth
+        );
+        info!(
+            "  OpenLoop: mode={}, V/f boost={}V, gain={}V/Hz, handover={}s",
+            loaded_config.openloop_mode,
+            loaded_config.openloop_vf_boost_voltage,
+            loaded_config.openloop_vf_gain,
+            loaded_config.openloop_handover_time
+        );
+        info!(
+            "  Field weakening: gain={}, voltage ratio={}, max Id={}A, max Vd={}V",
+            loaded_config.field_weakening_gain,
+            loaded_config.field_weakening_voltage_ratio,
+            loaded_config.field_weakening_max_current,
+            loaded_config.field_weakening_max_voltage
+        );
+        info!(
+            "  Mechanics: J={}kg*m^2, friction={}Nm, viscous={}Nm*s/rad",
+            loaded_config.motor_inertia,
+            loaded_config.motor_friction_torque,
+            loaded_config.motor_viscous_friction
+        );
     }
 
     // PIゲインをSPEED_PI_GAINSに適用
@@ -111,6 +224,8 @@ async fn main(spawner: Spawner) {
         calib_result.electrical_offset = loaded_config.calibration_electrical_offset;
         calib_result.direction_inversed = loaded_config.calibration_direction_inversed;
         calib_result.success = loaded_config.calibration_success;
+        calib_result.sector_angles = loaded_config.calibration_sector_angles;
+        calib_result.sector_table_valid = loaded_config.calibration_sector_table_valid;
 
         if loaded_config.calibration_success {
             info!("  Calibration data loaded:");
@@ -122,19 +237,35 @@ async fn main(spawner: Spawner) {
                 "    Direction inversed: {}",
                 loaded_config.calibration_direction_inversed
             );
+            info!(
+                "    Sector table: valid={}, angles={} rad",
+                loaded_config.calibration_sector_table_valid,
+                loaded_config.calibration_sector_angles
+            );
             false // キャリブレーション不要
         } else {
             info!("  No calibration data found (calibration not performed)");
-            info!("  Auto-calibration will start after motor control task initialization");
+            info!("  Auto-calibration will start when the motor is enabled");
             true // キャリブレーション必要
         }
     };
 
+    // 起動直後から運転する（CANの無効化コマンド・非常停止で停止）
+    if state::DRIVE_REQUESTS.try_send(DriveRequest::Start).is_ok() {
+        info!("Motor enabled at startup");
+    }
+
     // 自動キャリブレーション設定
     if needs_calibration {
-        let mut calibration_request = state::CALIBRATION_REQUEST.lock().await;
-        *calibration_request = true;
-        info!("Auto-calibration enabled");
+        // 始動後、運転中になってから実行される
+        if state::DRIVE_REQUESTS
+            .try_send(DriveRequest::Calibrate {
+                torque: config::drive::AUTO_CALIBRATION_TORQUE,
+            })
+            .is_ok()
+        {
+            info!("Auto-calibration enabled");
+        }
     }
 
     // CAN task用にFlash/CRCをAsync版で再初期化
@@ -169,24 +300,15 @@ async fn main(spawner: Spawner) {
         can::filter::StandardFilterSlot::_0,
         can::filter::StandardFilter::accept_all_into_fifo0(),
     );
-    can_configurator.set_bitrate(config::can::DEFAULT_BITRATE);
+    can_configurator.set_bitrate(loaded_config.can_bitrate);
     let can = can_configurator.start(can::OperatingMode::NormalOperationMode);
     spawner.spawn(can_task(can, flash, crc)).unwrap();
 
-    // ADC初期化
-    let mut adc1 = Adc::new(p.ADC1);
-    adc1.set_sample_time(SampleTime::CYCLES640_5);
-    let mut adc2 = Adc::new(p.ADC2);
-    adc2.set_sample_time(SampleTime::CYCLES640_5);
+    // ADC初期化（有効化・キャリブレーションのみ、変換はすべてTIM1同期の注入変換で行う）
+    let _adc1 = Adc::new(p.ADC1);
+    let _adc2 = Adc::new(p.ADC2);
 
-    // 電圧監視タスク起動（PC1 = ADC2_IN7）
-    let voltage_pin = p.PC1.degrade_adc();
-    spawner
-        .spawn(voltage_monitor_task(adc2, voltage_pin))
-        .unwrap();
-    info!("Voltage monitoring started on PC1 (ADC2_IN7)");
-
-    // OPAMP初期化（将来の電流リミット監視用、現在は未使用）
+    // OPAMP初期化（相電流検出アンプ、出力はADC注入変換でサンプリング）
     let mut _op1 = OpAmp::new(p.OPAMP1, OpAmpSpeed::HighSpeed);
     let _op1_sa = _op1.pga_ext(p.PA1, p.PA2, embassy_stm32::opamp::OpAmpGain::Mul4);
     let mut _op2 = OpAmp::new(p.OPAMP2, OpAmpSpeed::Normal);
@@ -223,22 +345,37 @@ async fn main(spawner: Spawner) {
         )),
         None,
         None,
-        config::pwm::DEFAULT_FREQUENCY,
+        Hertz(loaded_config.pwm_frequency),
         CountingMode::EdgeAlignedUp,
     );
     uvw_pwm.disable(Channel::Ch1);
     uvw_pwm.disable(Channel::Ch2);
     uvw_pwm.disable(Channel::Ch3);
-    uvw_pwm.set_dead_time(config::pwm::DEFAULT_DEAD_TIME);
+    uvw_pwm.set_dead_time(loaded_config.pwm_dead_time);
     uvw_pwm.enable(Channel::Ch1);
     uvw_pwm.enable(Channel::Ch2);
     uvw_pwm.enable(Channel::Ch3);
 
+    // 相電流・DCバス電圧サンプリング初期化（TIM1とADCの初期化後に行う）
+    unsafe {
+        hardware::init_current_sense();
+    }
+
+    // 電圧監視タスク起動（PC1 = ADC2_IN7、ADC2の注入ランク2）
+    spawner.spawn(voltage_monitor_task()).unwrap();
+    info!("Voltage monitoring started on PC1 (ADC2_IN7, injected)");
+
     // TIM4 Hallセンサーインターフェース初期化
     unsafe {
         hardware::init_hall_sensor();
     }
 
+    // CORDIC初期化（制御割り込みの有効化前に行う）
+    #[cfg(feature = "cordic")]
+    unsafe {
+        hardware::init_cordic();
+    }
+
     info!("Starting FOC motor control...");
 
     // モーター制御タスクを起動
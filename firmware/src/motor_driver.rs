@@ -14,6 +14,7 @@ use embassy_stm32::{
 pub struct MotorDriver {
     pwm: ComplementaryPwm<'static, peripherals::TIM1>,
     max_duty: u16,
+    duty_limit: u16,
 }
 
 impl MotorDriver {
@@ -23,7 +24,11 @@ impl MotorDriver {
     /// * `pwm` - PWMペリフェラル（TIM1）
     pub fn new(pwm: ComplementaryPwm<'static, peripherals::TIM1>) -> Self {
         let max_duty = pwm.get_max_duty();
-        Self { pwm, max_duty }
+        Self {
+            pwm,
+            max_duty,
+            duty_limit: max_duty,
+        }
     }
 
     /// PWMの最大Duty値を取得
@@ -31,6 +36,16 @@ impl MotorDriver {
         self.max_duty
     }
 
+    /// Duty比の上限を設定
+    ///
+    /// 以降に設定するDuty比はこの値で制限される（初期値は最大Duty値）。
+    ///
+    /// # 引数
+    /// * `limit` - Duty比の上限
+    pub fn set_duty_limit(&mut self, limit: u16) {
+        self.duty_limit = limit.min(self.max_duty);
+    }
+
     /// 3相全てのDuty比を設定
     ///
     /// # 引数
@@ -38,9 +53,9 @@ impl MotorDriver {
     /// * `duty_v` - V相のDuty比
     /// * `duty_w` - W相のDuty比
     pub fn set_duty_uvw(&mut self, duty_u: u16, duty_v: u16, duty_w: u16) {
-        self.pwm.set_duty(Channel::Ch1, duty_u);
-        self.pwm.set_duty(Channel::Ch2, duty_v);
-        self.pwm.set_duty(Channel::Ch3, duty_w);
+        self.pwm.set_duty(Channel::Ch1, duty_u.min(self.duty_limit));
+        self.pwm.set_duty(Channel::Ch2, duty_v.min(self.duty_limit));
+        self.pwm.set_duty(Channel::Ch3, duty_w.min(self.duty_limit));
     }
 
     /// 全チャネルを有効化
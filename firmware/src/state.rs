@@ -1,126 +1,23 @@
 //! グローバル共有状態管理
 //!
 //! タスク間で共有される状態をMutexで保護して管理します。
-//! 状態は論理的にグループ化されたコンテキストに整理されています。
+//! 駆動状態を変える外部からの要求は`DRIVE_REQUESTS`チャネルで制御割り込みに送ります。
 
-use embassy_sync::blocking_mutex::raw::ThreadModeRawMutex;
+use core::ops::{Deref, DerefMut};
+
+use embassy_sync::blocking_mutex::raw::{CriticalSectionRawMutex, ThreadModeRawMutex};
+use embassy_sync::channel::Channel;
 use embassy_sync::mutex::Mutex;
 
-use crate::can_protocol::MotorStatus;
-use crate::config::{StoredConfig, DEFAULT_SPEED_KI, DEFAULT_SPEED_KP};
-use crate::foc::{CalibrationResult, ControlMode};
+use crate::can_protocol::{ControlRates, MotorStatus};
+use crate::config::{drive, StoredConfig, DEFAULT_SPEED_KI, DEFAULT_SPEED_KP};
+use crate::drive_state::{DriveRequest, DriveStatus};
+use crate::foc::{
+    AutoTuneResult, AutoTuneState, CalibrationResult, HallDiagnosticsStatus, IdentificationResult,
+    IdentificationState,
+};
 use crate::voltage_monitor::VoltageMonitorState;
 
-/// モーター制御コンテキスト
-///
-/// モーター制御に関連する全ての状態を一つの構造体にまとめます。
-#[allow(dead_code)]
-#[derive(Clone, Copy)]
-pub struct MotorContext {
-    /// 目標速度 [RPM]
-    pub target_speed: f32,
-    /// 速度PIコントローラのゲイン (Kp, Ki)
-    pub pi_gains: (f32, f32),
-    /// モーター有効/無効フラグ
-    pub enabled: bool,
-    /// モーターステータス（CAN送信用）
-    pub status: MotorStatus,
-    /// モーター制御モード（ClosedLoopFoc / Calibration等）
-    pub control_mode: ControlMode,
-}
-
-impl MotorContext {
-    /// デフォルト値で新しいモーターコンテキストを作成
-    #[allow(dead_code)]
-    pub const fn new() -> Self {
-        Self {
-            target_speed: 2000.0, // デバッグ用: 起動時に2000 RPM
-            pi_gains: (DEFAULT_SPEED_KP, DEFAULT_SPEED_KI),
-            enabled: true, // デバッグ用: 起動時に有効化
-            status: MotorStatus::new(),
-            control_mode: ControlMode::OpenLoop,
-        }
-    }
-}
-
-/// キャリブレーションコンテキスト
-///
-/// キャリブレーションに関連する全ての状態を一つの構造体にまとめます。
-#[allow(dead_code)]
-#[derive(Clone, Copy)]
-pub struct CalibrationContext {
-    /// キャリブレーション開始フラグ
-    pub request: bool,
-    /// キャリブレーション用トルク値 (0-100)
-    pub torque: u8,
-    /// キャリブレーション結果
-    pub result: CalibrationResult,
-}
-
-impl CalibrationContext {
-    /// デフォルト値で新しいキャリブレーションコンテキストを作成
-    #[allow(dead_code)]
-    pub const fn new() -> Self {
-        Self {
-            request: false,
-            torque: 10,
-            result: CalibrationResult {
-                electrical_offset: 0.0,
-                direction_inversed: false,
-                success: false,
-            },
-        }
-    }
-}
-
-/// システムコンテキスト
-///
-/// システム全体の状態を一つの構造体にまとめます。
-#[allow(dead_code)]
-#[derive(Clone, Copy)]
-pub struct SystemContext {
-    /// 電圧監視ステータス（CAN送信用）
-    pub voltage_state: VoltageMonitorState,
-    /// ランタイム設定（フラッシュから読み込まれた設定）
-    pub runtime_config: StoredConfig,
-    /// 設定バージョン番号（CAN送信用）
-    pub config_version: u16,
-    /// CRC検証フラグ（CAN送信用）
-    pub config_crc_valid: bool,
-}
-
-impl SystemContext {
-    /// デフォルト値で新しいシステムコンテキストを作成
-    #[allow(dead_code)]
-    pub const fn new() -> Self {
-        Self {
-            voltage_state: VoltageMonitorState::new(),
-            runtime_config: StoredConfig::default(),
-            config_version: 0,
-            config_crc_valid: false,
-        }
-    }
-}
-
-/// グローバルモーターコンテキスト
-#[allow(dead_code)]
-pub static MOTOR_CONTEXT: Mutex<ThreadModeRawMutex, MotorContext> = Mutex::new(MotorContext::new());
-
-/// グローバルキャリブレーションコンテキスト
-#[allow(dead_code)]
-pub static CALIBRATION_CONTEXT: Mutex<ThreadModeRawMutex, CalibrationContext> =
-    Mutex::new(CalibrationContext::new());
-
-/// グローバルシステムコンテキスト
-#[allow(dead_code)]
-pub static SYSTEM_CONTEXT: Mutex<ThreadModeRawMutex, SystemContext> =
-    Mutex::new(SystemContext::new());
-
-// ========================================
-// 後方互換性のための旧API
-// 段階的な移行のため、既存のAPIを維持
-// ========================================
-
 /// 目標速度 [RPM]
 /// デバッグ用: 起動時に2000 RPMに設定
 pub static TARGET_SPEED: Mutex<ThreadModeRawMutex, f32> = Mutex::new(1000.0);
@@ -129,9 +26,21 @@ pub static TARGET_SPEED: Mutex<ThreadModeRawMutex, f32> = Mutex::new(1000.0);
 pub static SPEED_PI_GAINS: Mutex<ThreadModeRawMutex, (f32, f32)> =
     Mutex::new((DEFAULT_SPEED_KP, DEFAULT_SPEED_KI));
 
-/// モーター有効/無効フラグ
-/// デバッグ用: 起動時に有効化
-pub static MOTOR_ENABLE: Mutex<ThreadModeRawMutex, bool> = Mutex::new(true);
+/// 駆動要求（始動・停止・制御モード・キャリブレーション・パラメータ同定・自動調整）
+///
+/// CANタスク・起動処理が送信し、制御割り込みが毎周期すべて受信して`DriveState::accepts`で
+/// 受け付けるかを判定する（割り込みから受信するためクリティカルセクションで保護する）。
+/// 運転要求は起動処理が`Start`で設定し（起動直後から運転）、CANの無効化コマンド・非常停止の`Stop`で解除する。
+pub static DRIVE_REQUESTS: Channel<
+    CriticalSectionRawMutex,
+    DriveRequest,
+    { drive::REQUEST_QUEUE_LEN },
+> = Channel::new();
+
+/// 駆動状態（状態・制御モード・故障要因、CAN・電圧監視・LED用）
+///
+/// 制御割り込みの駆動状態マシンだけが更新する。状態の変更は`DRIVE_REQUESTS`で要求する。
+pub static DRIVE_STATUS: Mutex<ThreadModeRawMutex, DriveStatus> = Mutex::new(DriveStatus::new());
 
 /// モーターステータス（CAN送信用）
 pub static MOTOR_STATUS: Mutex<ThreadModeRawMutex, MotorStatus> = Mutex::new(MotorStatus::new());
@@ -140,9 +49,50 @@ pub static MOTOR_STATUS: Mutex<ThreadModeRawMutex, MotorStatus> = Mutex::new(Mot
 pub static VOLTAGE_STATE: Mutex<ThreadModeRawMutex, VoltageMonitorState> =
     Mutex::new(VoltageMonitorState::new());
 
+/// ランタイム設定と変更回数
+///
+/// 設定の可変参照を取得するたびに変更回数を進める。モーター制御タスクは変更回数で設定の変更を検出する
+/// （NaNを含む設定は浮動小数点の比較で常に不一致になるため、値の比較では検出しない）。
+pub struct RuntimeConfig {
+    /// ランタイム設定
+    config: StoredConfig,
+    /// 変更回数（可変参照を取得するたびにインクリメント）
+    generation: u32,
+}
+
+impl RuntimeConfig {
+    /// 設定を変更回数0で作成
+    pub const fn new(config: StoredConfig) -> Self {
+        Self {
+            config,
+            generation: 0,
+        }
+    }
+
+    /// 変更回数を取得
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+impl Deref for RuntimeConfig {
+    type Target = StoredConfig;
+
+    fn deref(&self) -> &StoredConfig {
+        &self.config
+    }
+}
+
+impl DerefMut for RuntimeConfig {
+    fn deref_mut(&mut self) -> &mut StoredConfig {
+        self.generation = self.generation.wrapping_add(1);
+        &mut self.config
+    }
+}
+
 /// ランタイム設定（フラッシュから読み込まれた設定）
-pub static RUNTIME_CONFIG: Mutex<ThreadModeRawMutex, StoredConfig> =
-    Mutex::new(StoredConfig::default());
+pub static RUNTIME_CONFIG: Mutex<ThreadModeRawMutex, RuntimeConfig> =
+    Mutex::new(RuntimeConfig::new(StoredConfig::default()));
 
 /// 設定バージョン番号（CAN送信用）
 pub static CONFIG_VERSION: Mutex<ThreadModeRawMutex, u16> = Mutex::new(0);
@@ -150,15 +100,24 @@ pub static CONFIG_VERSION: Mutex<ThreadModeRawMutex, u16> = Mutex::new(0);
 /// CRC検証フラグ（CAN送信用）
 pub static CONFIG_CRC_VALID: Mutex<ThreadModeRawMutex, bool> = Mutex::new(false);
 
-/// モーター制御モード（ClosedLoopFoc / Calibration等）
-pub static CONTROL_MODE: Mutex<ThreadModeRawMutex, ControlMode> =
-    Mutex::new(ControlMode::ClosedLoopFoc);
+/// 使用中の制御ループの周期（電流ループの周波数・速度/位置ループの分周比、CAN送信用）
+pub static CONTROL_RATES: Mutex<ThreadModeRawMutex, ControlRates> = Mutex::new(ControlRates::new());
+
+/// トルク（q軸電流）指令 [A]
+pub static TARGET_CURRENT: Mutex<ThreadModeRawMutex, f32> = Mutex::new(0.0);
 
-/// キャリブレーション開始フラグ
-pub static CALIBRATION_REQUEST: Mutex<ThreadModeRawMutex, bool> = Mutex::new(false);
+/// 電圧指令 (Vd, Vq) [V]
+pub static TARGET_VOLTAGE: Mutex<ThreadModeRawMutex, (f32, f32)> = Mutex::new((0.0, 0.0));
 
-/// キャリブレーション用トルク値 (0-100)
-pub static CALIBRATION_TORQUE: Mutex<ThreadModeRawMutex, u8> = Mutex::new(10);
+/// 位置指令 [rad]（機械角、複数回転、モーター有効化時の位置が基準）
+pub static TARGET_POSITION: Mutex<ThreadModeRawMutex, f32> = Mutex::new(0.0);
+
+/// Hallセンサー診断ステータス（ラッチされた故障フラグ・イベントカウンタ、CAN送信用）
+pub static HALL_DIAGNOSTICS: Mutex<ThreadModeRawMutex, HallDiagnosticsStatus> =
+    Mutex::new(HallDiagnosticsStatus::new());
+
+/// Hallセンサー故障クリア要求フラグ
+pub static HALL_FAULT_CLEAR_REQUEST: Mutex<ThreadModeRawMutex, bool> = Mutex::new(false);
 
 /// キャリブレーション結果
 pub static CALIBRATION_RESULT: Mutex<ThreadModeRawMutex, CalibrationResult> =
@@ -166,4 +125,22 @@ pub static CALIBRATION_RESULT: Mutex<ThreadModeRawMutex, CalibrationResult> =
         electrical_offset: 0.0,
         direction_inversed: false,
         success: false,
+        sector_angles: [0.0; 6],
+        sector_table_valid: false,
     });
+
+/// パラメータ同定の進行状態（CAN送信用）
+pub static IDENTIFICATION_STATE: Mutex<ThreadModeRawMutex, IdentificationState> =
+    Mutex::new(IdentificationState::Idle);
+
+/// パラメータ同定結果（CAN送信用）
+pub static IDENTIFICATION_RESULT: Mutex<ThreadModeRawMutex, IdentificationResult> =
+    Mutex::new(IdentificationResult::new());
+
+/// 速度PIゲイン自動調整の進行状態（CAN送信用）
+pub static AUTOTUNE_STATE: Mutex<ThreadModeRawMutex, AutoTuneState> =
+    Mutex::new(AutoTuneState::Idle);
+
+/// 速度PIゲイン自動調整結果（CAN送信用、適用コマンドで速度PIに反映）
+pub static AUTOTUNE_RESULT: Mutex<ThreadModeRawMutex, AutoTuneResult> =
+    Mutex::new(AutoTuneResult::new());
@@ -11,17 +11,36 @@ use embassy_time::{Duration, Ticker};
 use embedded_can::{Id, StandardId};
 
 use crate::can_protocol::{
-    can_ids, encode_calibration_status, encode_config_status, encode_status, encode_voltage_status,
-    parse_angle_interpolation, parse_can_config, parse_control_timing, parse_enable_command,
-    parse_hall_sensor_params, parse_motor_basic_params, parse_motor_voltage_params,
-    parse_openloop_accel_duty_params, parse_openloop_rpm_params, parse_pi_gains, parse_pwm_config,
-    parse_speed_command,
+    can_ids, encode_autotune_gains, encode_autotune_status, encode_calibration_status,
+    encode_config_status, encode_control_rates_status, encode_current_status, encode_drive_status,
+    encode_hall_diagnostics_status, encode_hall_sector_table_status, encode_identification_status,
+    encode_identified_params, encode_position_status, encode_status, encode_voltage_status,
+    parse_angle_interpolation, parse_angle_source, parse_autotune_command, parse_can_config,
+    parse_control_timing, parse_current_limit, parse_current_pi_gains, parse_current_sense_params,
+    parse_dead_time_compensation, parse_derivative_params, parse_enable_command,
+    parse_field_weakening_limits, parse_field_weakening_params, parse_gain_schedule_point,
+    parse_hall_estimator_params, parse_hall_sensor_params, parse_identification_command,
+    parse_loop_dividers, parse_motion_profile_jerk, parse_motion_profile_params,
+    parse_motor_basic_params, parse_motor_electrical_params, parse_motor_voltage_params,
+    parse_openloop_accel_duty_params, parse_openloop_handover_params, parse_openloop_mode,
+    parse_openloop_rpm_params, parse_openloop_vf_params, parse_overmodulation, parse_pi_gains,
+    parse_pi_tracking_gains, parse_position_command, parse_position_params, parse_pwm_config,
+    parse_rate_limit, parse_sensorless_params, parse_setpoint_weights, parse_speed_command,
+    parse_speed_ff_acceleration, parse_speed_ff_back_emf, parse_speed_ff_friction,
+    parse_speed_filter_params, parse_speed_filter_types, parse_torque_command,
+    parse_voltage_command,
 };
 use crate::config;
+use crate::drive_state::DriveRequest;
 use crate::fmt::*;
+use crate::foc::{
+    AngleSource, ControlMode, DeadTimeCompensation, FilterType, HallEstimator, OpenLoopMode,
+};
 use crate::state::{
-    CALIBRATION_REQUEST, CALIBRATION_RESULT, CALIBRATION_TORQUE, CONFIG_CRC_VALID, CONFIG_VERSION,
-    MOTOR_ENABLE, MOTOR_STATUS, RUNTIME_CONFIG, SPEED_PI_GAINS, TARGET_SPEED, VOLTAGE_STATE,
+    AUTOTUNE_RESULT, AUTOTUNE_STATE, CALIBRATION_RESULT, CONFIG_CRC_VALID, CONFIG_VERSION,
+    CONTROL_RATES, DRIVE_REQUESTS, DRIVE_STATUS, HALL_DIAGNOSTICS, HALL_FAULT_CLEAR_REQUEST,
+    IDENTIFICATION_RESULT, IDENTIFICATION_STATE, MOTOR_STATUS, RUNTIME_CONFIG, SPEED_PI_GAINS,
+    TARGET_CURRENT, TARGET_POSITION, TARGET_SPEED, TARGET_VOLTAGE, VOLTAGE_STATE,
 };
 
 /// CAN通信タスク - モーター制御コマンド処理とステータス送信
@@ -38,10 +57,11 @@ pub async fn can_task(
     // ステータス送信用タイマー（100ms周期）
     let mut status_ticker = Ticker::every(Duration::from_millis(100));
 
-    loop {
-        // CANフレーム受信とステータス送信を並行処理
-        embassy_futures::select::select(
-            async {
+    // CANフレーム受信とステータス送信を独立したループで並行処理
+    // （selectで待つと、フレームを受信するたびに送信途中のステータスが中断される）
+    embassy_futures::join::join(
+        async {
+            loop {
                 // CANフレーム受信処理
                 match rx.read().await {
                     Ok(envelope) => {
@@ -59,23 +79,56 @@ pub async fn can_task(
                             can_ids::SPEED_CMD => {
                                 if let Some(speed) = parse_speed_command(data) {
                                     *TARGET_SPEED.lock().await = speed;
+                                    send_drive_request(DriveRequest::Mode(ControlMode::ClosedLoopFoc));
+                                }
+                            }
+                            can_ids::TORQUE_CMD => {
+                                if let Some(iq_current) = parse_torque_command(data) {
+                                    *TARGET_CURRENT.lock().await = iq_current;
+                                    send_drive_request(DriveRequest::Mode(ControlMode::Torque));
+                                }
+                            }
+                            can_ids::VOLTAGE_CMD => {
+                                if let Some((vd, vq)) = parse_voltage_command(data) {
+                                    *TARGET_VOLTAGE.lock().await = (vd, vq);
+                                    send_drive_request(DriveRequest::Mode(ControlMode::Voltage));
+                                }
+                            }
+                            can_ids::POSITION_CMD => {
+                                if let Some(position) = parse_position_command(data) {
+                                    if !position.is_finite() {
+                                        error!("Rejected position command: {} rad", position);
+                                    } else {
+                                        *TARGET_POSITION.lock().await = position;
+                                        send_drive_request(DriveRequest::Mode(ControlMode::Position));
+                                    }
                                 }
                             }
                             can_ids::PI_GAINS => {
                                 if let Some((kp, ki)) = parse_pi_gains(data) {
-                                    *SPEED_PI_GAINS.lock().await = (kp, ki);
+                                    if !config::is_valid_speed_gain(kp) || !config::is_valid_speed_gain(ki) {
+                                        error!("Rejected speed PI gains: Kp={}, Ki={}", kp, ki);
+                                    } else {
+                                        *SPEED_PI_GAINS.lock().await = (kp, ki);
+                                    }
                                 }
                             }
                             can_ids::ENABLE_CMD => {
                                 if let Some(enable) = parse_enable_command(data) {
-                                    *MOTOR_ENABLE.lock().await = enable;
+                                    // 故障中の始動要求は制御割り込みが拒否する
                                     if enable {
                                         info!("Motor ENABLED via CAN");
+                                        send_drive_request(DriveRequest::Start);
                                     } else {
                                         info!("Motor DISABLED via CAN");
+                                        send_drive_request(DriveRequest::Stop);
                                     }
                                 }
                             }
+                            can_ids::CLEAR_HALL_FAULT => {
+                                info!("Clear hall fault command received");
+                                *HALL_FAULT_CLEAR_REQUEST.lock().await = true;
+                            }
                             can_ids::START_CALIBRATION => {
                                 info!("Start calibration command received");
                                 // トルク値をパース（1バイト, 0-100, デフォルト20）
@@ -85,22 +138,85 @@ pub async fn can_task(
                                     20 // デフォルト値
                                 };
                                 info!("Calibration torque: {}", torque);
-                                *CALIBRATION_TORQUE.lock().await = torque;
-                                // キャリブレーションリクエストフラグを設定
-                                *CALIBRATION_REQUEST.lock().await = true;
-                                info!("Calibration request flag set");
+                                // 受け付けるかは制御割り込みが駆動状態で判定する
+                                send_drive_request(DriveRequest::Calibrate { torque });
+                            }
+                            can_ids::START_IDENTIFICATION => {
+                                info!("Start identification command received");
+                                // 試験条件をパース（データなしの場合はデフォルト値）
+                                let params = if data.is_empty() {
+                                    Some((
+                                        config::identification::DEFAULT_TEST_CURRENT,
+                                        config::identification::DEFAULT_TEST_SPEED,
+                                    ))
+                                } else {
+                                    parse_identification_command(data)
+                                };
+                                if let Some((test_current, test_speed)) = params {
+                                    let max_current = RUNTIME_CONFIG.lock().await.max_current;
+                                    if !config::identification::is_valid_positive(test_current)
+                                        || !config::identification::is_valid_positive(test_speed)
+                                        || test_current > max_current
+                                    {
+                                        error!("Rejected identification: current={}A (max {}A), speed={}RPM", test_current, max_current, test_speed);
+                                    } else {
+                                        send_drive_request(DriveRequest::Identify {
+                                            current: test_current,
+                                            speed: test_speed,
+                                        });
+                                    }
+                                }
+                            }
+                            can_ids::START_AUTOTUNE => {
+                                info!("Start auto-tune command received");
+                                // 試験条件をパース（データなしの場合はデフォルト値）
+                                let params = if data.is_empty() {
+                                    Some((
+                                        config::autotune::DEFAULT_TEST_SPEED,
+                                        config::autotune::DEFAULT_RELAY_RATIO,
+                                    ))
+                                } else {
+                                    parse_autotune_command(data)
+                                };
+                                if let Some((test_speed, relay_ratio)) = params {
+                                    if !config::autotune::is_valid_test_speed(test_speed)
+                                        || !config::autotune::is_valid_relay_ratio(relay_ratio)
+                                    {
+                                        error!("Rejected auto-tune: speed={}RPM, relay_ratio={}", test_speed, relay_ratio);
+                                    } else {
+                                        send_drive_request(DriveRequest::AutoTune {
+                                            speed: test_speed,
+                                            relay_ratio,
+                                        });
+                                    }
+                                }
+                            }
+                            can_ids::APPLY_AUTOTUNE => {
+                                let result = *AUTOTUNE_RESULT.lock().await;
+                                if result.valid {
+                                    // 速度PIに即時反映し、設定保存で永続化できるようランタイム設定にも反映
+                                    *SPEED_PI_GAINS.lock().await = (result.kp, result.ki);
+                                    let mut config = RUNTIME_CONFIG.lock().await;
+                                    config.speed_kp = result.kp;
+                                    config.speed_ki = result.ki;
+                                    info!("Auto-tuned PI gains applied: Kp={}, Ki={}", result.kp, result.ki);
+                                } else {
+                                    error!("Rejected auto-tune apply: no valid result");
+                                }
                             }
                             can_ids::SAVE_CONFIG => {
                                 info!("Save config command received");
 
                                 // 現在の設定を取得
-                                let mut config = *RUNTIME_CONFIG.lock().await;
+                                let mut config = **RUNTIME_CONFIG.lock().await;
 
                                 // キャリブレーション結果を設定に反映
                                 let calib_result = *CALIBRATION_RESULT.lock().await;
                                 config.calibration_electrical_offset = calib_result.electrical_offset;
                                 config.calibration_direction_inversed = calib_result.direction_inversed;
                                 config.calibration_success = calib_result.success;
+                                config.calibration_sector_angles = calib_result.sector_angles;
+                                config.calibration_sector_table_valid = calib_result.sector_table_valid;
 
                                 // フラッシュに保存
                                 match config::write_config(&mut flash, &mut crc, &mut config).await {
@@ -119,11 +235,19 @@ pub async fn can_task(
 
                                 // フラッシュから設定を読み込み
                                 match config::read_config(&mut flash, &mut crc) {
-                                    Ok(loaded_config) => {
+                                    Ok(mut loaded_config) => {
                                         info!("Config reloaded successfully");
 
+                                        // ハードウェア設定を検証（次回起動時と同じ値を保持する）
+                                        if loaded_config.sanitize_hardware_params() != 0 {
+                                            error!("Reloaded config had out-of-range hardware params, reset to defaults");
+                                        }
+                                        if loaded_config.sanitize_float_params() != 0 {
+                                            error!("Reloaded config had non-finite values, reset to defaults");
+                                        }
+
                                         // グローバル状態に適用
-                                        *RUNTIME_CONFIG.lock().await = loaded_config;
+                                        **RUNTIME_CONFIG.lock().await = loaded_config;
                                         *CONFIG_VERSION.lock().await = loaded_config.version;
                                         *CONFIG_CRC_VALID.lock().await = true;
 
@@ -148,7 +272,7 @@ pub async fn can_task(
                                         info!("Config reset to defaults successfully");
 
                                         // グローバル状態に適用
-                                        *RUNTIME_CONFIG.lock().await = default_config;
+                                        **RUNTIME_CONFIG.lock().await = default_config;
                                         *CONFIG_VERSION.lock().await = default_config.version;
                                         *CONFIG_CRC_VALID.lock().await = true;
 
@@ -167,26 +291,38 @@ pub async fn can_task(
                             // === Motor Control Parameter Commands ===
                             can_ids::MOTOR_VOLTAGE_PARAMS => {
                                 if let Some((max_voltage, v_dc_bus)) = parse_motor_voltage_params(data) {
-                                    let mut config = RUNTIME_CONFIG.lock().await;
-                                    config.max_voltage = max_voltage;
-                                    config.v_dc_bus = v_dc_bus;
-                                    info!("Updated motor voltage params: max={}, vdc={}", max_voltage, v_dc_bus);
+                                    if !config::is_valid_voltage(max_voltage) || !config::is_valid_voltage(v_dc_bus) {
+                                        error!("Rejected motor voltage params: max={}V, vdc={}V", max_voltage, v_dc_bus);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.max_voltage = max_voltage;
+                                        config.v_dc_bus = v_dc_bus;
+                                        info!("Updated motor voltage params: max={}, vdc={}", max_voltage, v_dc_bus);
+                                    }
                                 }
                             }
                             can_ids::MOTOR_BASIC_PARAMS => {
                                 if let Some((pole_pairs, max_duty)) = parse_motor_basic_params(data) {
-                                    let mut config = RUNTIME_CONFIG.lock().await;
-                                    config.pole_pairs = pole_pairs;
-                                    config.max_duty = max_duty;
-                                    info!("Updated motor basic params: pole_pairs={}, max_duty={}", pole_pairs, max_duty);
+                                    if !config::is_valid_pole_pairs(pole_pairs) {
+                                        error!("Rejected motor basic params: pole_pairs={}", pole_pairs);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.pole_pairs = pole_pairs;
+                                        config.max_duty = max_duty;
+                                        info!("Updated motor basic params: pole_pairs={}, max_duty={}", pole_pairs, max_duty);
+                                    }
                                 }
                             }
                             can_ids::HALL_SENSOR_PARAMS => {
                                 if let Some((alpha, offset)) = parse_hall_sensor_params(data) {
-                                    let mut config = RUNTIME_CONFIG.lock().await;
-                                    config.speed_filter_alpha = alpha;
-                                    config.hall_angle_offset = offset;
-                                    info!("Updated hall sensor params: alpha={}, offset={}", alpha, offset);
+                                    if !config::is_valid_speed_filter_alpha(alpha) || !config::is_valid_angle_offset(offset) {
+                                        error!("Rejected hall sensor params: alpha={}, offset={}", alpha, offset);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.speed_filter_alpha = alpha;
+                                        config.hall_angle_offset = offset;
+                                        info!("Updated hall sensor params: alpha={}, offset={}", alpha, offset);
+                                    }
                                 }
                             }
                             can_ids::ANGLE_INTERPOLATION => {
@@ -196,53 +332,545 @@ pub async fn can_task(
                                     info!("Updated angle interpolation: {}", enable);
                                 }
                             }
+                            // === Current Control Parameter Commands ===
+                            can_ids::CURRENT_PI_GAINS => {
+                                if let Some((kp, ki)) = parse_current_pi_gains(data) {
+                                    if !config::current::is_valid_gain(kp) || !config::current::is_valid_gain(ki) {
+                                        error!("Rejected current PI gains: Kp={}, Ki={}", kp, ki);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.current_kp = kp;
+                                        config.current_ki = ki;
+                                        info!("Updated current PI gains: Kp={}, Ki={}", kp, ki);
+                                    }
+                                }
+                            }
+                            can_ids::CURRENT_LIMIT => {
+                                if let Some((max_current, enabled)) = parse_current_limit(data) {
+                                    if !config::current::is_valid_positive(max_current) {
+                                        error!("Rejected current limit: {}A", max_current);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.max_current = max_current;
+                                        config.current_control_enabled = enabled;
+                                        info!("Updated current limit: max={}A, enabled={}", max_current, enabled);
+                                    }
+                                }
+                            }
+                            can_ids::CURRENT_SENSE_PARAMS => {
+                                if let Some((shunt, gain)) = parse_current_sense_params(data) {
+                                    if !config::current::is_valid_positive(shunt)
+                                        || !config::current::is_valid_positive(gain)
+                                    {
+                                        error!("Rejected current sense params: shunt={}ohm, gain={}", shunt, gain);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.current_shunt_resistance = shunt;
+                                        config.current_amp_gain = gain;
+                                        info!("Updated current sense params: shunt={}ohm, gain={}", shunt, gain);
+                                    }
+                                }
+                            }
+                            // === Position Control Parameter Commands ===
+                            can_ids::POSITION_PARAMS => {
+                                if let Some((kp, max_speed)) = parse_position_params(data) {
+                                    if !config::position::is_valid_gain(kp)
+                                        || !config::position::is_valid_max_speed(max_speed)
+                                    {
+                                        error!("Rejected position params: Kp={}, max_speed={} RPM", kp, max_speed);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.position_kp = kp;
+                                        config.position_max_speed = max_speed;
+                                        info!("Updated position params: Kp={}, max_speed={} RPM", kp, max_speed);
+                                    }
+                                }
+                            }
+                            // === Motion Profile Parameter Commands ===
+                            can_ids::MOTION_PROFILE_PARAMS => {
+                                if let Some((acceleration, deceleration)) = parse_motion_profile_params(data) {
+                                    if !config::profile::is_valid_rate(acceleration)
+                                        || !config::profile::is_valid_rate(deceleration)
+                                    {
+                                        error!("Rejected motion profile params: accel={}, decel={} RPM/s", acceleration, deceleration);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.profile_acceleration = acceleration;
+                                        config.profile_deceleration = deceleration;
+                                        info!("Updated motion profile params: accel={}, decel={} RPM/s", acceleration, deceleration);
+                                    }
+                                }
+                            }
+                            can_ids::MOTION_PROFILE_JERK => {
+                                if let Some(jerk) = parse_motion_profile_jerk(data) {
+                                    if !config::profile::is_valid_jerk(jerk) {
+                                        error!("Rejected motion profile jerk: {} RPM/s^2", jerk);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.profile_jerk = jerk;
+                                        info!("Updated motion profile jerk: {} RPM/s^2", jerk);
+                                    }
+                                }
+                            }
+                            // === Sensorless Parameter Commands ===
+                            can_ids::MOTOR_ELECTRICAL_PARAMS => {
+                                if let Some((resistance, inductance)) = parse_motor_electrical_params(data) {
+                                    if !config::sensorless::is_valid_positive(resistance)
+                                        || !config::sensorless::is_valid_positive(inductance)
+                                    {
+                                        error!("Rejected motor electrical params: R={}ohm, L={}H", resistance, inductance);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.motor_resistance = resistance;
+                                        config.motor_inductance = inductance;
+                                        info!("Updated motor electrical params: R={}ohm, L={}H", resistance, inductance);
+                                    }
+                                }
+                            }
+                            can_ids::SENSORLESS_PARAMS => {
+                                if let Some((flux_linkage, pll_bandwidth)) = parse_sensorless_params(data) {
+                                    if !config::sensorless::is_valid_positive(flux_linkage)
+                                        || !config::sensorless::is_valid_positive(pll_bandwidth)
+                                    {
+                                        error!("Rejected sensorless params: flux_linkage={}Wb, pll_bandwidth={}rad/s", flux_linkage, pll_bandwidth);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.motor_flux_linkage = flux_linkage;
+                                        config.observer_pll_bandwidth = pll_bandwidth;
+                                        info!("Updated sensorless params: flux_linkage={}Wb, pll_bandwidth={}rad/s", flux_linkage, pll_bandwidth);
+                                    }
+                                }
+                            }
+                            can_ids::ANGLE_SOURCE => {
+                                if let Some((min_speed, source)) = parse_angle_source(data) {
+                                    if AngleSource::from_u8(source).is_none()
+                                        || !config::sensorless::is_valid_min_speed(min_speed)
+                                    {
+                                        error!("Rejected angle source: source={}, min_speed={} RPM", source, min_speed);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.angle_source = source;
+                                        config.sensorless_min_speed = min_speed;
+                                        info!("Updated angle source: source={}, min_speed={} RPM", source, min_speed);
+                                    }
+                                }
+                            }
+                            // === Hall Estimator Parameter Commands ===
+                            can_ids::HALL_ESTIMATOR_PARAMS => {
+                                if let Some((pll_bandwidth, estimator)) = parse_hall_estimator_params(data) {
+                                    if HallEstimator::from_u8(estimator).is_none()
+                                        || !config::hall::is_valid_pll_bandwidth(pll_bandwidth)
+                                    {
+                                        error!("Rejected hall estimator params: estimator={}, pll_bandwidth={}rad/s", estimator, pll_bandwidth);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.hall_estimator = estimator;
+                                        config.hall_pll_bandwidth = pll_bandwidth;
+                                        info!("Updated hall estimator params: estimator={}, pll_bandwidth={}rad/s", estimator, pll_bandwidth);
+                                    }
+                                }
+                            }
+                            // === Field Weakening Parameter Commands ===
+                            can_ids::FIELD_WEAKENING_PARAMS => {
+                                if let Some((gain, voltage_ratio)) = parse_field_weakening_params(data) {
+                                    if !config::field_weakening::is_valid_non_negative(gain)
+                                        || !config::field_weakening::is_valid_voltage_ratio(voltage_ratio)
+                                    {
+                                        error!("Rejected field weakening params: gain={}, voltage_ratio={}", gain, voltage_ratio);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.field_weakening_gain = gain;
+                                        config.field_weakening_voltage_ratio = voltage_ratio;
+                                        info!("Updated field weakening params: gain={}, voltage_ratio={}", gain, voltage_ratio);
+                                    }
+                                }
+                            }
+                            can_ids::FIELD_WEAKENING_LIMITS => {
+                                if let Some((max_current, max_voltage)) = parse_field_weakening_limits(data) {
+                                    if !config::field_weakening::is_valid_non_negative(max_current)
+                                        || !config::field_weakening::is_valid_non_negative(max_voltage)
+                                    {
+                                        error!("Rejected field weakening limits: max_current={}A, max_voltage={}V", max_current, max_voltage);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.field_weakening_max_current = max_current;
+                                        config.field_weakening_max_voltage = max_voltage;
+                                        info!("Updated field weakening limits: max_current={}A, max_voltage={}V", max_current, max_voltage);
+                                    }
+                                }
+                            }
                             // === OpenLoop Parameter Commands ===
                             can_ids::OPENLOOP_RPM_PARAMS => {
                                 if let Some((initial_rpm, target_rpm)) = parse_openloop_rpm_params(data) {
-                                    let mut config = RUNTIME_CONFIG.lock().await;
-                                    config.openloop_initial_rpm = initial_rpm;
-                                    config.openloop_target_rpm = target_rpm;
-                                    info!("Updated openloop RPM params: initial={}, target={}", initial_rpm, target_rpm);
+                                    if !config::openloop::is_valid_rpm_range(initial_rpm, target_rpm) {
+                                        error!("Rejected openloop RPM params: initial={}, target={}", initial_rpm, target_rpm);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.openloop_initial_rpm = initial_rpm;
+                                        config.openloop_target_rpm = target_rpm;
+                                        info!("Updated openloop RPM params: initial={}, target={}", initial_rpm, target_rpm);
+                                    }
                                 }
                             }
                             can_ids::OPENLOOP_ACCEL_DUTY_PARAMS => {
                                 if let Some((acceleration, duty_ratio)) = parse_openloop_accel_duty_params(data) {
-                                    let mut config = RUNTIME_CONFIG.lock().await;
-                                    config.openloop_acceleration = acceleration;
-                                    config.openloop_duty_ratio = duty_ratio;
-                                    info!("Updated openloop accel/duty: accel={}, duty={}", acceleration, duty_ratio);
+                                    if !config::openloop::is_valid_acceleration(acceleration)
+                                        || !config::openloop::is_valid_duty_ratio(duty_ratio)
+                                    {
+                                        error!("Rejected openloop accel/duty: accel={}, duty={}", acceleration, duty_ratio);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.openloop_acceleration = acceleration;
+                                        config.openloop_duty_ratio = duty_ratio;
+                                        info!("Updated openloop accel/duty: accel={}, duty={}", acceleration, duty_ratio);
+                                    }
+                                }
+                            }
+                            can_ids::OPENLOOP_HANDOVER_PARAMS => {
+                                if let Some(handover_time) = parse_openloop_handover_params(data) {
+                                    if !config::openloop::is_valid_handover_time(handover_time) {
+                                        error!("Rejected openloop handover time: {}s (max {}s)", handover_time, config::openloop::MAX_HANDOVER_TIME);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.openloop_handover_time = handover_time;
+                                        info!("Updated openloop handover time: {}s", handover_time);
+                                    }
+                                }
+                            }
+                            can_ids::OPENLOOP_VF_PARAMS => {
+                                if let Some((boost_voltage, vf_gain)) = parse_openloop_vf_params(data) {
+                                    if !config::openloop::is_valid_vf_param(boost_voltage)
+                                        || !config::openloop::is_valid_vf_param(vf_gain)
+                                    {
+                                        error!("Rejected openloop V/f params: boost={}V, gain={}V/Hz", boost_voltage, vf_gain);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.openloop_vf_boost_voltage = boost_voltage;
+                                        config.openloop_vf_gain = vf_gain;
+                                        info!("Updated openloop V/f params: boost={}V, gain={}V/Hz", boost_voltage, vf_gain);
+                                    }
+                                }
+                            }
+                            can_ids::OPENLOOP_MODE => {
+                                if let Some(mode) = parse_openloop_mode(data) {
+                                    if OpenLoopMode::from_u8(mode).is_none() {
+                                        error!("Rejected openloop mode: {}", mode);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.openloop_mode = mode;
+                                        info!("Updated openloop mode: {}", mode);
+                                    }
+                                }
+                            }
+                            // === Speed Feedforward Parameter Commands ===
+                            can_ids::SPEED_FF_BACK_EMF => {
+                                if let Some((motor_kv, gain)) = parse_speed_ff_back_emf(data) {
+                                    if !config::feedforward::is_valid_non_negative(motor_kv)
+                                        || !config::feedforward::is_valid_gain(gain)
+                                    {
+                                        error!("Rejected speed FF back-EMF: kv={}RPM/V, gain={} (max {})", motor_kv, gain, config::feedforward::MAX_GAIN);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.motor_kv = motor_kv;
+                                        config.ff_back_emf_gain = gain;
+                                        info!("Updated speed FF back-EMF: kv={}RPM/V, gain={}", motor_kv, gain);
+                                    }
+                                }
+                            }
+                            can_ids::SPEED_FF_FRICTION => {
+                                if let Some((gain, band)) = parse_speed_ff_friction(data) {
+                                    if !config::feedforward::is_valid_gain(gain)
+                                        || !config::feedforward::is_valid_non_negative(band)
+                                    {
+                                        error!("Rejected speed FF friction: gain={} (max {}), band={}RPM", gain, config::feedforward::MAX_GAIN, band);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.ff_friction_gain = gain;
+                                        config.ff_friction_band = band;
+                                        info!("Updated speed FF friction: gain={}, band={}RPM", gain, band);
+                                    }
+                                }
+                            }
+                            can_ids::SPEED_FF_ACCELERATION => {
+                                if let Some(gain) = parse_speed_ff_acceleration(data) {
+                                    if !config::feedforward::is_valid_gain(gain) {
+                                        error!("Rejected speed FF acceleration: gain={} (max {})", gain, config::feedforward::MAX_GAIN);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.ff_acceleration_gain = gain;
+                                        info!("Updated speed FF acceleration: gain={}", gain);
+                                    }
+                                }
+                            }
+                            // === PI Controller Extension Commands ===
+                            can_ids::PI_TRACKING_GAINS => {
+                                if let Some((speed, current)) = parse_pi_tracking_gains(data) {
+                                    if !config::pid::is_valid_non_negative(speed)
+                                        || !config::pid::is_valid_non_negative(current)
+                                    {
+                                        error!("Rejected PI tracking gains: speed={}/s, current={}/s", speed, current);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.speed_tracking_gain = speed;
+                                        config.current_tracking_gain = current;
+                                        info!("Updated PI tracking gains: speed={}/s, current={}/s", speed, current);
+                                    }
+                                }
+                            }
+                            can_ids::SPEED_PI_DERIVATIVE => {
+                                if let Some((kd, filter_time)) = parse_derivative_params(data) {
+                                    if !config::pid::is_valid_non_negative(kd)
+                                        || !config::pid::is_valid_non_negative(filter_time)
+                                    {
+                                        error!("Rejected speed PI derivative: Kd={}, filter_time={}s", kd, filter_time);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.speed_kd = kd;
+                                        config.speed_derivative_filter_time = filter_time;
+                                        info!("Updated speed PI derivative: Kd={}, filter_time={}s", kd, filter_time);
+                                    }
+                                }
+                            }
+                            can_ids::SPEED_PI_SETPOINT_WEIGHTS => {
+                                if let Some((proportional, derivative)) = parse_setpoint_weights(data) {
+                                    if !config::pid::is_valid_weight(proportional)
+                                        || !config::pid::is_valid_weight(derivative)
+                                    {
+                                        error!("Rejected speed PI setpoint weights: b={}, c={} (range 0-1)", proportional, derivative);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.speed_proportional_weight = proportional;
+                                        config.speed_derivative_weight = derivative;
+                                        info!("Updated speed PI setpoint weights: b={}, c={}", proportional, derivative);
+                                    }
+                                }
+                            }
+                            can_ids::SPEED_PI_RATE_LIMIT => {
+                                if let Some(rate_limit) = parse_rate_limit(data) {
+                                    if !config::pid::is_valid_non_negative(rate_limit) {
+                                        error!("Rejected speed PI rate limit: {}/s", rate_limit);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.speed_rate_limit = rate_limit;
+                                        info!("Updated speed PI rate limit: {}/s", rate_limit);
+                                    }
+                                }
+                            }
+                            can_ids::POSITION_DERIVATIVE => {
+                                if let Some((kd, filter_time)) = parse_derivative_params(data) {
+                                    if !config::pid::is_valid_non_negative(kd)
+                                        || !config::pid::is_valid_non_negative(filter_time)
+                                    {
+                                        error!("Rejected position derivative: Kd={}, filter_time={}s", kd, filter_time);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.position_kd = kd;
+                                        config.position_derivative_filter_time = filter_time;
+                                        info!("Updated position derivative: Kd={}, filter_time={}s", kd, filter_time);
+                                    }
+                                }
+                            }
+                            // === Speed Loop Filter Parameter Commands ===
+                            can_ids::SPEED_FILTER_TYPES => {
+                                if let Some(((feedback_type, feedback_length), (output_type, output_length))) =
+                                    parse_speed_filter_types(data)
+                                {
+                                    if FilterType::from_u8(feedback_type).is_none()
+                                        || FilterType::from_u8(output_type).is_none()
+                                        || !config::filter::is_valid_length(feedback_length)
+                                        || !config::filter::is_valid_length(output_length)
+                                    {
+                                        error!(
+                                            "Rejected speed filter types: feedback={} (length {}), output={} (length {}), max length {}",
+                                            feedback_type,
+                                            feedback_length,
+                                            output_type,
+                                            output_length,
+                                            config::filter::MAX_LENGTH
+                                        );
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.speed_feedback_filter = feedback_type;
+                                        config.speed_feedback_filter_length = feedback_length;
+                                        config.speed_output_filter = output_type;
+                                        config.speed_output_filter_length = output_length;
+                                        info!("Updated speed filter types: feedback={} (length {}), output={} (length {})", feedback_type, feedback_length, output_type, output_length);
+                                    }
+                                }
+                            }
+                            can_ids::SPEED_FEEDBACK_FILTER => {
+                                if let Some((frequency, shape)) = parse_speed_filter_params(data) {
+                                    if !config::filter::is_valid_positive(frequency)
+                                        || !config::filter::is_valid_positive(shape)
+                                    {
+                                        error!("Rejected speed feedback filter: frequency={}Hz, shape={}", frequency, shape);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.speed_feedback_filter_frequency = frequency;
+                                        config.speed_feedback_filter_shape = shape;
+                                        info!("Updated speed feedback filter: frequency={}Hz, shape={}", frequency, shape);
+                                    }
+                                }
+                            }
+                            can_ids::SPEED_OUTPUT_FILTER => {
+                                if let Some((frequency, shape)) = parse_speed_filter_params(data) {
+                                    if !config::filter::is_valid_positive(frequency)
+                                        || !config::filter::is_valid_positive(shape)
+                                    {
+                                        error!("Rejected speed output filter: frequency={}Hz, shape={}", frequency, shape);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.speed_output_filter_frequency = frequency;
+                                        config.speed_output_filter_shape = shape;
+                                        info!("Updated speed output filter: frequency={}Hz, shape={}", frequency, shape);
+                                    }
                                 }
                             }
                             // === PWM/CAN/Timing Configuration ===
                             can_ids::PWM_CONFIG => {
                                 if let Some((frequency, dead_time)) = parse_pwm_config(data) {
+                                    if !config::pwm::is_valid_frequency(frequency)
+                                        || !config::pwm::is_valid_dead_time(dead_time)
+                                    {
+                                        error!(
+                                            "Rejected PWM config: freq={}Hz (range {}-{}), dead_time={} (range {}-{})",
+                                            frequency,
+                                            config::pwm::MIN_FREQUENCY_HZ,
+                                            config::pwm::MAX_FREQUENCY_HZ,
+                                            dead_time,
+                                            config::pwm::MIN_DEAD_TIME,
+                                            config::pwm::MAX_DEAD_TIME
+                                        );
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.pwm_frequency = frequency;
+                                        config.pwm_dead_time = dead_time;
+                                        info!("Updated PWM config: freq={}Hz, dead_time={}", frequency, dead_time);
+                                        info!("⚠ PWM changes require reboot to take effect. Save config and restart.");
+                                    }
+                                }
+                            }
+                            can_ids::DEAD_TIME_COMPENSATION => {
+                                if let Some((mode, min_pulse)) = parse_dead_time_compensation(data) {
+                                    if DeadTimeCompensation::from_u8(mode).is_none()
+                                        || !config::pwm::is_valid_min_pulse(min_pulse)
+                                    {
+                                        error!(
+                                            "Rejected dead-time compensation: mode={}, min_pulse={} (max {})",
+                                            mode,
+                                            min_pulse,
+                                            config::pwm::MAX_MIN_PULSE
+                                        );
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.pwm_dead_time_compensation = mode;
+                                        config.pwm_min_pulse = min_pulse;
+                                        info!("Updated dead-time compensation: mode={}, min_pulse={}", mode, min_pulse);
+                                    }
+                                }
+                            }
+                            can_ids::OVERMODULATION => {
+                                if let Some(enable) = parse_overmodulation(data) {
                                     let mut config = RUNTIME_CONFIG.lock().await;
-                                    config.pwm_frequency = frequency;
-                                    config.pwm_dead_time = dead_time;
-                                    info!("Updated PWM config: freq={}Hz, dead_time={}", frequency, dead_time);
-                                    info!("⚠ PWM changes require reboot to take effect. Save config and restart.");
+                                    config.pwm_overmodulation = enable;
+                                    info!("Updated overmodulation: {}", enable);
                                 }
                             }
                             can_ids::CAN_CONFIG => {
                                 if let Some(bitrate) = parse_can_config(data) {
-                                    let mut config = RUNTIME_CONFIG.lock().await;
-                                    config.can_bitrate = bitrate;
-                                    info!("Updated CAN config: bitrate={}", bitrate);
-                                    info!("⚠ CAN bitrate changes require reboot to take effect. Save config and restart.");
+                                    if !config::can::is_valid_bitrate(bitrate) {
+                                        error!("Rejected CAN config: unsupported bitrate {}", bitrate);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.can_bitrate = bitrate;
+                                        info!("Updated CAN config: bitrate={}", bitrate);
+                                        info!("⚠ CAN bitrate changes require reboot to take effect. Save config and restart.");
+                                    }
                                 }
                             }
                             can_ids::CONTROL_TIMING => {
                                 if let Some(period_us) = parse_control_timing(data) {
-                                    let mut config = RUNTIME_CONFIG.lock().await;
-                                    config.control_period_us = period_us;
-                                    info!("Updated control timing: {}us", period_us);
-                                    info!("⚠ Control period changes require reboot to take effect. Save config and restart.");
+                                    if !config::timing::is_valid_control_period(period_us) {
+                                        error!(
+                                            "Rejected control timing: {}us (range {}-{}us)",
+                                            period_us,
+                                            config::timing::MIN_CONTROL_PERIOD_US,
+                                            config::timing::MAX_CONTROL_PERIOD_US
+                                        );
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.control_period_us = period_us;
+                                        info!("Updated control timing: {}us", period_us);
+                                        info!("⚠ Control period changes require reboot to take effect. Save config and restart.");
+                                    }
+                                }
+                            }
+                            can_ids::LOOP_DIVIDERS => {
+                                if let Some((speed_divider, position_divider)) =
+                                    parse_loop_dividers(data)
+                                {
+                                    if !config::timing::is_valid_loop_divider(speed_divider)
+                                        || !config::timing::is_valid_loop_divider(position_divider)
+                                    {
+                                        error!(
+                                            "Rejected loop dividers: speed={}, position={} (range 1-{})",
+                                            speed_divider,
+                                            position_divider,
+                                            config::timing::MAX_LOOP_DIVIDER
+                                        );
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.speed_loop_divider = speed_divider;
+                                        config.position_loop_divider = position_divider;
+                                        info!("Updated loop dividers: speed={}, position={} (applied when the motor is disabled)", speed_divider, position_divider);
+                                    }
+                                }
+                            }
+                            // === Speed Gain Schedule ===
+                            can_ids::SPEED_GAIN_SCHEDULE_POINT => {
+                                if let Some((index, points, speed)) = parse_gain_schedule_point(data) {
+                                    if !config::pid::is_valid_schedule_index(index)
+                                        || !config::pid::is_valid_schedule_points(points)
+                                    {
+                                        error!(
+                                            "Rejected speed gain schedule point: index={}, points={} (max {})",
+                                            index,
+                                            points,
+                                            config::pid::MAX_SCHEDULE_POINTS
+                                        );
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.speed_schedule_speeds[index as usize] = speed as f32;
+                                        config.speed_schedule_points = points;
+                                        info!("Updated speed gain schedule point {}: {} RPM ({} points in use)", index, speed, points);
+                                    }
+                                }
+                            }
+                            id if (can_ids::SPEED_GAIN_SCHEDULE_GAINS
+                                ..can_ids::SPEED_GAIN_SCHEDULE_GAINS
+                                    + config::pid::MAX_SCHEDULE_POINTS as u32)
+                                .contains(&id) =>
+                            {
+                                let index = (id - can_ids::SPEED_GAIN_SCHEDULE_GAINS) as usize;
+                                if let Some((kp, ki)) = parse_pi_gains(data) {
+                                    if !config::pid::is_valid_non_negative(kp)
+                                        || !config::pid::is_valid_non_negative(ki)
+                                    {
+                                        error!("Rejected speed gain schedule gains {}: Kp={}, Ki={}", index, kp, ki);
+                                    } else {
+                                        let mut config = RUNTIME_CONFIG.lock().await;
+                                        config.speed_schedule_kp[index] = kp;
+                                        config.speed_schedule_ki[index] = ki;
+                                        info!("Updated speed gain schedule gains {}: Kp={}, Ki={}", index, kp, ki);
+                                    }
                                 }
                             }
                             can_ids::EMERGENCY_STOP => {
                                 info!("Emergency stop received!");
-                                *MOTOR_ENABLE.lock().await = false;
+                                send_drive_request(DriveRequest::Stop);
                                 *TARGET_SPEED.lock().await = 0.0;
+                                *TARGET_CURRENT.lock().await = 0.0;
+                                *TARGET_VOLTAGE.lock().await = (0.0, 0.0);
                             }
                             _ => {
                                 debug!("Unknown CAN ID: 0x{:03X}", id_raw);
@@ -253,8 +881,10 @@ pub async fn can_task(
                         // error!("CAN RX Error: {:?}", _e);
                     }
                 }
-            },
-            async {
+            }
+        },
+        async {
+            loop {
                 // ステータス送信（100ms周期）
                 status_ticker.next().await;
 
@@ -269,6 +899,27 @@ pub async fn can_task(
                     }
                 }
 
+                // 電流ステータス送信 (ID 0x204)
+                let current_data = encode_current_status(status.id_current, status.iq_current);
+
+                if let Some(std_id) = StandardId::new(can_ids::CURRENT_STATUS as u16) {
+                    let id = Id::Standard(std_id);
+                    if let Ok(frame) = can::frame::Frame::new_data(id, &current_data) {
+                        let _ = tx.write(&frame).await;
+                    }
+                }
+
+                // 位置ステータス送信 (ID 0x205)
+                let target_position = *TARGET_POSITION.lock().await;
+                let position_data = encode_position_status(status.position, target_position);
+
+                if let Some(std_id) = StandardId::new(can_ids::POSITION_STATUS as u16) {
+                    let id = Id::Standard(std_id);
+                    if let Ok(frame) = can::frame::Frame::new_data(id, &position_data) {
+                        let _ = tx.write(&frame).await;
+                    }
+                }
+
                 // 電圧ステータス送信 (ID 0x201)
                 let voltage_state = *VOLTAGE_STATE.lock().await;
                 let voltage_data = encode_voltage_status(
@@ -310,8 +961,141 @@ pub async fn can_task(
                         let _ = tx.write(&frame).await;
                     }
                 }
-            },
-        )
-        .await;
+
+                // Hallセクター境界テーブル送信 (ID 0x206、3セクターずつ2フレーム)
+                for (page, angles) in calib_result.sector_angles.chunks(3).enumerate() {
+                    let table_data = encode_hall_sector_table_status(
+                        page as u8,
+                        angles,
+                        calib_result.sector_table_valid,
+                    );
+
+                    if let Some(std_id) = StandardId::new(can_ids::HALL_SECTOR_TABLE_STATUS as u16) {
+                        let id = Id::Standard(std_id);
+                        if let Ok(frame) = can::frame::Frame::new_data(id, &table_data) {
+                            let _ = tx.write(&frame).await;
+                        }
+                    }
+                }
+
+                // Hall診断ステータス送信 (ID 0x207)
+                let hall_diagnostics = *HALL_DIAGNOSTICS.lock().await;
+                let diagnostics_data = encode_hall_diagnostics_status(&hall_diagnostics);
+
+                if let Some(std_id) = StandardId::new(can_ids::HALL_DIAGNOSTICS_STATUS as u16) {
+                    let id = Id::Standard(std_id);
+                    if let Ok(frame) = can::frame::Frame::new_data(id, &diagnostics_data) {
+                        let _ = tx.write(&frame).await;
+                    }
+                }
+
+                // パラメータ同定ステータス送信 (ID 0x208)
+                let identification_state = *IDENTIFICATION_STATE.lock().await;
+                let identification = *IDENTIFICATION_RESULT.lock().await;
+                let identification_data = encode_identification_status(
+                    identification_state as u8,
+                    identification.electrical_valid,
+                    identification.mechanical_valid,
+                );
+
+                if let Some(std_id) = StandardId::new(can_ids::IDENTIFICATION_STATUS as u16) {
+                    let id = Id::Standard(std_id);
+                    if let Ok(frame) = can::frame::Frame::new_data(id, &identification_data) {
+                        let _ = tx.write(&frame).await;
+                    }
+                }
+
+                // パラメータ同定結果送信 (ID 0x209-0x20B)
+                let identified_params = [
+                    (
+                        can_ids::IDENTIFIED_ELECTRICAL_PARAMS,
+                        identification.resistance,
+                        identification.inductance,
+                    ),
+                    (
+                        can_ids::IDENTIFIED_FLUX_INERTIA,
+                        identification.flux_linkage,
+                        identification.inertia,
+                    ),
+                    (
+                        can_ids::IDENTIFIED_FRICTION,
+                        identification.friction_torque,
+                        identification.viscous_friction,
+                    ),
+                ];
+                for (can_id, first, second) in identified_params {
+                    let params_data = encode_identified_params(first, second);
+
+                    if let Some(std_id) = StandardId::new(can_id as u16) {
+                        let id = Id::Standard(std_id);
+                        if let Ok(frame) = can::frame::Frame::new_data(id, &params_data) {
+                            let _ = tx.write(&frame).await;
+                        }
+                    }
+                }
+
+                // 速度PI自動調整ステータス送信 (ID 0x20C)
+                let autotune_state = *AUTOTUNE_STATE.lock().await;
+                let autotune = *AUTOTUNE_RESULT.lock().await;
+                let autotune_data = encode_autotune_status(autotune_state as u8, autotune.valid);
+
+                if let Some(std_id) = StandardId::new(can_ids::AUTOTUNE_STATUS as u16) {
+                    let id = Id::Standard(std_id);
+                    if let Ok(frame) = can::frame::Frame::new_data(id, &autotune_data) {
+                        let _ = tx.write(&frame).await;
+                    }
+                }
+
+                // 自動調整した速度PIゲイン送信 (ID 0x20D、適用前に確認するため)
+                let gains_data = encode_autotune_gains(autotune.kp, autotune.ki);
+
+                if let Some(std_id) = StandardId::new(can_ids::AUTOTUNE_GAINS as u16) {
+                    let id = Id::Standard(std_id);
+                    if let Ok(frame) = can::frame::Frame::new_data(id, &gains_data) {
+                        let _ = tx.write(&frame).await;
+                    }
+                }
+
+                // 制御ループの周期送信 (ID 0x20E)
+                let rates = *CONTROL_RATES.lock().await;
+                let rates_data = encode_control_rates_status(&rates);
+
+                if let Some(std_id) = StandardId::new(can_ids::CONTROL_RATES_STATUS as u16) {
+                    let id = Id::Standard(std_id);
+                    if let Ok(frame) = can::frame::Frame::new_data(id, &rates_data) {
+                        let _ = tx.write(&frame).await;
+                    }
+                }
+
+                // 駆動状態送信 (ID 0x20F)
+                let drive = *DRIVE_STATUS.lock().await;
+                let drive_data =
+                    encode_drive_status(drive.state as u8, drive.control_mode as u8, drive.faults);
+
+                if let Some(std_id) = StandardId::new(can_ids::DRIVE_STATUS as u16) {
+                    let id = Id::Standard(std_id);
+                    if let Ok(frame) = can::frame::Frame::new_data(id, &drive_data) {
+                        let _ = tx.write(&frame).await;
+                    }
+                }
+            }
+        },
+    )
+    .await;
+}
+
+/// 駆動要求を制御割り込みに送信
+///
+/// 制御割り込みは毎周期すべての要求を受信するため、通常はチャネルが満杯になることはない。
+/// 満杯の場合（制御割り込みが停止している場合など）は要求を破棄する。
+///
+/// # 引数
+/// * `request` - 駆動要求
+fn send_drive_request(request: DriveRequest) {
+    if DRIVE_REQUESTS.try_send(request).is_err() {
+        error!(
+            "Drive request queue full, {} request dropped",
+            request.name()
+        );
     }
 }
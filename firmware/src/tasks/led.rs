@@ -1,15 +1,69 @@
 //! LED制御タスク
 //!
-//! 3つのLEDを順次点灯させて動作確認を行います。
+//! 駆動状態（`DRIVE_STATUS`）を3つのLEDで表示します。
+//!
+//! | 駆動状態 | LED1 | LED2 | LED3 |
+//! |---|---|---|---|
+//! | 起動直後 | 点灯 | 点灯 | 点灯 |
+//! | 待機 | 低速点滅 | 消灯 | 消灯 |
+//! | プリチャージ・始動 | 低速点滅 | 高速点滅 | 消灯 |
+//! | 運転 | 低速点滅 | 点灯 | 消灯 |
+//! | 停止処理 | 低速点滅 | 低速点滅（LED1と逆相） | 消灯 |
+//! | キャリブレーション | 低速点滅 | 高速点滅 | 高速点滅（LED2と逆相） |
+//! | 故障 | 消灯 | 消灯 | 高速点滅 |
 
 use embassy_stm32::gpio::Output;
-use embassy_time::{Duration, Timer};
+use embassy_time::{Duration, Ticker};
 
+use crate::drive_state::DriveState;
 use crate::fmt::*;
+use crate::state::DRIVE_STATUS;
+
+/// 表示の更新周期 [ms]（高速点滅の半周期）
+const TICK_MS: u64 = 100;
+
+/// 低速点滅の半周期（更新周期の数、500ms）
+const SLOW_BLINK_TICKS: u32 = 5;
+
+/// 駆動状態に対応するLEDの点灯状態を取得
+///
+/// # 引数
+/// * `state` - 駆動状態
+/// * `tick` - 更新周期のカウンタ
+///
+/// # 戻り値
+/// * `(led1, led2, led3)` - 点灯する場合true
+fn led_pattern(state: DriveState, tick: u32) -> (bool, bool, bool) {
+    let fast = tick.is_multiple_of(2);
+    let slow = (tick / SLOW_BLINK_TICKS).is_multiple_of(2);
+
+    match state {
+        DriveState::Init => (true, true, true),
+        DriveState::Idle => (slow, false, false),
+        DriveState::Precharge | DriveState::Starting => (slow, fast, false),
+        DriveState::Running => (slow, true, false),
+        DriveState::Stopping => (slow, !slow, false),
+        DriveState::Calibrating => (slow, fast, !fast),
+        DriveState::Fault => (false, false, fast),
+    }
+}
+
+/// LEDの点灯状態を設定
+///
+/// # 引数
+/// * `led` - LED出力
+/// * `on` - 点灯する場合true
+fn set_led(led: &mut Output<'static>, on: bool) {
+    if on {
+        led.set_high();
+    } else {
+        led.set_low();
+    }
+}
 
 /// LED制御タスク
 ///
-/// 3つのLEDを500msごとに順次点灯させます。
+/// 100msごとに駆動状態を読み出し、状態に応じたパターンでLEDを点灯させます。
 #[embassy_executor::task]
 pub async fn led_task(
     mut led1: Output<'static>,
@@ -18,20 +72,17 @@ pub async fn led_task(
 ) {
     info!("LED task started");
 
+    let mut ticker = Ticker::every(Duration::from_millis(TICK_MS));
+    let mut tick = 0u32;
+
     loop {
-        led1.set_high();
-        led2.set_low();
-        led3.set_low();
-        Timer::after(Duration::from_millis(500)).await;
-
-        led1.set_low();
-        led2.set_high();
-        led3.set_low();
-        Timer::after(Duration::from_millis(500)).await;
-
-        led1.set_low();
-        led2.set_low();
-        led3.set_high();
-        Timer::after(Duration::from_millis(500)).await;
+        let state = DRIVE_STATUS.lock().await.state;
+        let (on1, on2, on3) = led_pattern(state, tick);
+        set_led(&mut led1, on1);
+        set_led(&mut led2, on2);
+        set_led(&mut led3, on3);
+
+        tick = tick.wrapping_add(1);
+        ticker.next().await;
     }
 }
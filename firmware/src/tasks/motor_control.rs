@@ -1,189 +1,2256 @@
 //! モーター制御タスク
 //!
-//! 2.5kHz FOCループ + オープンループ始動制御を実行します。
+//! FOCループ + オープンループ始動制御を実行します。
 //! 各制御モードは独立したモジュールに分離されています。
+//!
+//! 制御ループはTIM1の更新割り込み（PWM周期）を固定の分周比で間引いた周期で実行します
+//! （デフォルト 50kHz / 2 = 25kHz）。制御周期はPWM周期の整数倍になり、相電流のサンプリングと同期します。
+//! 電流・角度ループは毎周期、速度ループはその分周比ごと（デフォルト 2.5kHz）、位置ループは
+//! さらに速度ループの分周比ごと（デフォルト 500Hz）に、それぞれの周期をdtとして実行します
+//! （`LoopScheduler`）。間の周期は速度・位置ループの前回の出力を保持します。
+//! タスクは制御ループを生成して割り込みに渡した後、`state`の共有状態と制御割り込みの間で
+//! 指令・ランタイム設定・制御結果をダブルバッファ（`exchange`）で受け渡します。
+//! 制御割り込みはログを出力せず、イベントを制御結果で返してタスクがログに出力します。
+//!
+//! 制御パラメータは`RUNTIME_CONFIG`から取得します。設定の変更はタスクが検出して検証し、
+//! 速度ループのフィルタ・ゲインスケジュールを設計してから制御割り込みに渡します。
+//! 制御割り込みは受け取った設定をループ先頭で、運転中に安全に反映できるもの（電圧制限・速度フィルタ等）は
+//! 即時に、極対数やオープンループ始動パラメータなど構造的な変更はモーター停止時に反映します。
+//!
+//! 駆動状態（待機・プリチャージ・始動・運転・停止処理・故障・キャリブレーション）は制御割り込みが
+//! `DriveStateMachine`で管理し、`DRIVE_STATUS`に公開します。始動・停止・制御モードなどの要求は
+//! 制御割り込みが`DRIVE_REQUESTS`から受信し、`DriveState::accepts`で受け付けたものだけを処理します。
+//! 運転要求（起動時に設定）が解除されると停止処理（PWM停止、Hallエッジが途切れるまで待機）を経て
+//! 待機に戻り、電圧異常やHallのみで運転中のHall故障では故障状態でPWMを停止して運転要求を解除します。
+//! 故障状態は要因が解消するまで維持し、その間の始動要求は拒否します。
+//!
+//! 運転要求を受けると、プリチャージとしてPWM出力を停止したまま相電流のゼロ点オフセットを校正してから
+//! 始動します。電流制御（d/q軸電流PI）を使うかどうか、センサレス角度推定を使うかどうかは
+//! この時点で決定します（オブザーバは相電流を使うため、校正に失敗した場合はHallに固定）。
+//! 角度キャリブレーションが成功済みの場合はオープンループ始動を省略し、Hallのセクター角度で
+//! 停止状態から直接FOCで始動します（回転しなければオープンループ始動にフォールバック）。
+//!
+//! パラメータ同定要求を受けると、電流センサーの校正後にモーター定数（抵抗・インダクタンス・
+//! 鎖交磁束、キャリブレーション済みなら慣性・摩擦も）を測定し、終了後にモーターを停止します。
+//! 速度PIゲイン自動調整要求は速度制御で運転中のみ受け付け、終了後は速度制御に戻ります
+//! （算出したゲインは適用コマンドを受けるまで速度PIに反映しません）。
 
 mod calibration_mode;
+mod exchange;
 mod foc_mode;
+mod identification_mode;
 mod openloop_mode;
 
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use cortex_m::peripheral::DWT;
+use embassy_stm32::interrupt;
 use embassy_stm32::{peripherals, timer::complementary_pwm::ComplementaryPwm};
+use embassy_sync::blocking_mutex::raw::ThreadModeRawMutex;
+use embassy_sync::mutex::Mutex;
 use embassy_time::{Duration, Timer};
 
+use crate::can_protocol::ControlRates;
 use crate::config::*;
+use crate::control_timer;
+use crate::current_sense;
+use crate::drive_state::{
+    voltage_faults, DriveRequest, DriveState, DriveStateMachine, DriveStatus, FAULT_HALL,
+};
 use crate::fmt::*;
-use crate::foc::{ControlMode, HallSensor, MotorCalibration, OpenLoopSixStep, PiController};
+#[cfg(not(feature = "fixed-point"))]
+use crate::foc::CurrentController;
+#[cfg(feature = "fixed-point")]
+use crate::foc::{fixed_point::PerUnit, FixedCurrentController};
+use crate::foc::{
+    AngleSource, ControlMode, CurrentSensor, DeadTimeCompensation, DeadTimeCompensator,
+    DirectStartMonitor, DirectStartStatus, FieldWeakening, FilterType, FluxObserver, GainSchedule,
+    HallDiagnostics, HallEstimator, HallSample, HallSensor, IdentificationState, LoopScheduler,
+    LoopTicks, Modulator, MotionProfile, MotorCalibration, MotorIdentification, OpenLoopMode,
+    OpenLoopSixStep, OpenLoopVf, PiController, SignalFilter, SpeedAutoTune, SpeedFeedforward,
+    StartupHandover,
+};
 use crate::hall_tim;
+use crate::hardware;
 use crate::motor_driver::MotorDriver;
-use crate::state::{CALIBRATION_REQUEST, CALIBRATION_TORQUE, CONTROL_MODE, MOTOR_ENABLE};
-use core::f32::consts::PI;
+use crate::state::{
+    AUTOTUNE_RESULT, AUTOTUNE_STATE, CALIBRATION_RESULT, CONTROL_RATES, DRIVE_REQUESTS,
+    DRIVE_STATUS, HALL_DIAGNOSTICS, HALL_FAULT_CLEAR_REQUEST, IDENTIFICATION_RESULT,
+    IDENTIFICATION_STATE, MOTOR_STATUS, RUNTIME_CONFIG, SPEED_PI_GAINS, TARGET_CURRENT,
+    TARGET_POSITION, TARGET_SPEED, TARGET_VOLTAGE, VOLTAGE_STATE,
+};
+use exchange::{
+    ControlCommand, ControlConfig, ControlEvent, ControlEvents, ControlReport, Writeback,
+    CONTROL_COMMAND, CONTROL_CONFIG, CONTROL_REPORT,
+};
 
-/// モーター制御タスク（2.5kHz FOC制御ループ）
-#[embassy_executor::task]
-pub async fn motor_control_task(uvw_pwm: ComplementaryPwm<'static, peripherals::TIM1>) {
-    info!("Motor control task started (OpenLoop + FOC mode)");
+/// 角度センサーの状態
+///
+/// Hallセンサーとセンサレス磁束オブザーバ、および角度の取得元をまとめて保持する。
+struct AngleSensor {
+    /// Hallセンサー
+    hall: HallSensor,
+    /// Hallセンサーの故障検出（故障はクリア要求までラッチされる）
+    diagnostics: HallDiagnostics,
+    /// 磁束オブザーバ（前周期の電圧指令と相電流から電気角・速度を推定）
+    observer: FluxObserver,
+    /// 角度の取得元（モーター有効化時に設定から決定）
+    source: AngleSource,
+    /// 前周期に出力したα/β軸電圧 [V]（オブザーバ入力）
+    applied_voltage: (f32, f32),
+    /// 前周期に出力したq軸電圧 [V]（故障検出の駆動レベル）
+    applied_vq: f32,
+    /// 現在オブザーバの角度で制御しているか
+    observer_in_use: bool,
+}
 
-    // モータードライバー初期化
-    let mut motor_driver = MotorDriver::new(uvw_pwm);
+impl AngleSensor {
+    /// Hallセンサーとオブザーバを停止状態にリセット
+    ///
+    /// ラッチされたHall故障はクリアしない（`HALL_FAULT_CLEAR_REQUEST`でのみクリア）。
+    fn reset(&mut self) {
+        self.hall.reset();
+        self.diagnostics.reset();
+        self.observer.reset();
+        self.applied_voltage = (0.0, 0.0);
+        self.applied_vq = 0.0;
+        self.observer_in_use = false;
+    }
+
+    /// Hallセンサーの故障検出を1周期分更新
+    ///
+    /// センサレス運転ではHallを使わないため検出しない。
+    ///
+    /// # 引数
+    /// * `drive` - 符号付き駆動レベル（q軸電圧 / 最大電圧、正 = 正転）
+    /// * `dt` - 制御周期 [s]
+    /// * `events` - タスクへのイベント（故障をラッチした場合に追加）
+    fn update_diagnostics(&mut self, drive: f32, dt: f32, events: &mut ControlEvents) {
+        if self.source == AngleSource::Sensorless {
+            return;
+        }
+
+        let sample = HallSample {
+            state: hall_tim::get_hall_state(),
+            edge_count: hall_tim::get_edge_count(),
+            glitch_count: hall_tim::get_glitch_count(),
+            period_cycles: hall_tim::get_period_cycles(),
+        };
+        if self.diagnostics.update(sample, drive, dt) != 0 {
+            events.push(ControlEvent::HallFaultLatched);
+        }
+    }
+
+    /// 現在制御に使っている角度の取得元から機械角速度 [RPM] を取得
+    fn speed_rpm(&self) -> f32 {
+        if self.observer_in_use {
+            self.observer.get_speed_rpm()
+        } else {
+            self.hall.get_speed_rpm()
+        }
+    }
+}
+
+/// 電流制御ループの状態
+///
+/// 相電流センサーとd/q軸電流PI、PWM出力段のデッドタイム補償、および電流制御で運転中かどうかを
+/// まとめて保持する。
+struct CurrentLoop {
+    /// 相電流センサー（オフセット校正・A換算）
+    sensor: CurrentSensor,
+    /// d/q軸電流PIコントローラー
+    #[cfg(not(feature = "fixed-point"))]
+    controller: CurrentController,
+    /// d/q軸電流PIコントローラー（固定小数点、電流・電圧は`per_unit()`の基準値による単位法）
+    #[cfg(feature = "fixed-point")]
+    controller: FixedCurrentController,
+    /// 弱め界磁（出力: 電流制御時はd軸電流指令 [A]、それ以外はd軸電圧指令 [V]）
+    field_weakening: FieldWeakening,
+    /// PWM変調（DCバス電圧による正規化・過変調）
+    modulator: Modulator,
+    /// 電圧ベクトルの制限 [V]（最大電圧設定と変調の上限の小さい方、制御周期ごとに更新）
+    voltage_limit: f32,
+    /// デッドタイム補償・最小パルス制限（SVPWMのデューティを補正）
+    dead_time: DeadTimeCompensator,
+    /// 電流制御で運転中か（有効化時のオフセット校正完了後に決定）
+    active: bool,
+}
+
+/// 始動制御の状態
+///
+/// オープンループ始動（6ステップ / 正弦波V/f）と、FOCへの切替時のハンドオーバーをまとめて保持する。
+struct Startup {
+    /// オープンループの駆動方式（モーター停止時に設定から決定）
+    mode: OpenLoopMode,
+    /// 6ステップ駆動コントローラー
+    openloop: OpenLoopSixStep,
+    /// 正弦波V/f駆動コントローラー
+    vf: OpenLoopVf,
+    /// 強制転流の電圧ベクトルからFOC出力への移行
+    handover: StartupHandover,
+    /// キャリブレーション済みの場合の停止状態からのFOC直接始動の監視
+    direct_start: DirectStartMonitor,
+}
+
+impl Startup {
+    /// オープンループ始動を最初からやり直す
+    fn reset(&mut self) {
+        self.openloop.reset();
+        self.vf.reset();
+        self.direct_start.cancel();
+    }
+
+    /// 現在の駆動方式の回転数 [RPM] を取得
+    fn current_rpm(&self) -> f32 {
+        match self.mode {
+            OpenLoopMode::SixStep => self.openloop.get_current_rpm(),
+            OpenLoopMode::SineVf => self.vf.get_current_rpm(),
+        }
+    }
+
+    /// 現在の駆動方式の電圧ベクトルの電気角 [rad] を取得
+    fn electrical_angle(&self) -> f32 {
+        match self.mode {
+            OpenLoopMode::SixStep => self.openloop.get_electrical_angle(),
+            OpenLoopMode::SineVf => self.vf.get_electrical_angle(),
+        }
+    }
+
+    /// 現在の駆動方式の電圧ベクトルの大きさ [V] を取得
+    ///
+    /// # 引数
+    /// * `v_dc_bus` - DCバス電圧 [V]（6ステップ駆動の電圧換算に使用）
+    fn voltage_amplitude(&self, v_dc_bus: f32) -> f32 {
+        match self.mode {
+            OpenLoopMode::SixStep => self.openloop.get_voltage_amplitude(v_dc_bus),
+            OpenLoopMode::SineVf => self.vf.get_voltage_amplitude(),
+        }
+    }
+}
+
+/// 速度制御ループの状態
+///
+/// 速度指令のプロファイル生成器と速度PI、速度フィードバック・速度PI出力のフィルタをまとめて保持する。
+struct SpeedLoop {
+    /// 速度指令のプロファイル生成器（加減速・ジャーク制限）
+    profile: MotionProfile,
+    /// 速度PIコントローラー（出力: q軸電流指令 [A] またはq軸電圧指令 [V]）
+    controller: PiController,
+    /// 速度PIのゲインスケジュール（空の場合はタスクからの速度PIゲインを使用）
+    schedule: GainSchedule,
+    /// 速度フィードフォワード（逆起電力・摩擦・加速トルク、速度PIの出力に加算）
+    feedforward: SpeedFeedforward,
+    /// 速度フィードバックのフィルタ（速度PIの入力）
+    feedback_filter: SignalFilter,
+    /// 速度PI出力のフィルタ（フィードフォワード加算前）
+    output_filter: SignalFilter,
+    /// プロファイル後の速度指令 [RPM]（以下、速度ループの周期で更新し、間の周期は保持）
+    reference: f32,
+    /// 速度PIの出力＋摩擦・加速のフィードフォワード（q軸電流指令 [A] またはq軸電圧指令 [V]）
+    output: f32,
+    /// 逆起電力のフィードフォワード [V]
+    back_emf: f32,
+    /// 目標速度0で停止しているか
+    stopped: bool,
+}
+
+impl SpeedLoop {
+    /// 速度PI・フィードフォワード・フィルタの状態と保持している出力をリセット（プロファイルはリセットしない）
+    fn reset(&mut self) {
+        self.controller.reset();
+        self.feedforward.reset();
+        self.feedback_filter.reset();
+        self.output_filter.reset();
+        self.output = 0.0;
+        self.back_emf = 0.0;
+        self.stopped = false;
+    }
+}
 
-    // ホールセンサ初期化（foc-simple互換の機械角ベース計算）
-    let mut hall_sensor = HallSensor::new(DEFAULT_POLE_PAIRS, DEFAULT_SPEED_FILTER_ALPHA);
-    hall_sensor.set_interpolation(true); // 角度補間を有効化（FOC制御の安定性向上）
-
-    // 電気オフセットを設定（キャリブレーション値）
-    let offset_rad = DEFAULT_HALL_ANGLE_OFFSET_DEG * PI / 180.0;
-    hall_sensor.set_electrical_offset(offset_rad);
-
-    // 速度PIコントローラ初期化
-    let mut speed_pi =
-        PiController::new_symmetric(DEFAULT_SPEED_KP, DEFAULT_SPEED_KI, DEFAULT_MAX_VOLTAGE);
-
-    // オープンループ始動コントローラ初期化
-    let mut openloop = OpenLoopSixStep::new(
-        openloop::DEFAULT_INITIAL_RPM,
-        openloop::DEFAULT_TARGET_RPM,
-        openloop::DEFAULT_ACCELERATION_RPM_PER_S,
-        openloop::DEFAULT_DUTY_RATIO,
-        DEFAULT_POLE_PAIRS,
+/// 位置制御ループの状態
+///
+/// 位置指令のプロファイル生成器と位置P制御をまとめて保持する。
+struct PositionLoop {
+    /// 位置指令のプロファイル生成器（加減速・ジャーク制限）
+    profile: MotionProfile,
+    /// 位置P制御（出力: 速度指令 [RPM]）
+    controller: PiController,
+    /// プロファイル後の位置指令 [rad]（プロファイルは速度ループの周期で更新し、間の周期は保持）
+    reference: f32,
+    /// プロファイルの速度（速度フィードフォワード）[RPM]
+    feedforward_speed: f32,
+    /// 位置P制御の補正速度 [RPM]（位置ループの周期で更新し、間の周期は保持）
+    correction_speed: f32,
+}
+
+/// 速度PIの出力制限を取得
+///
+/// 電流制御時は速度PIがq軸電流指令 [A] を、それ以外はq軸電圧指令 [V] を出力する。
+fn speed_output_limit(config: &StoredConfig, current_loop: &CurrentLoop) -> f32 {
+    if current_loop.active {
+        config.max_current
+    } else {
+        current_loop.voltage_limit
+    }
+}
+
+/// DCバス電圧を取得
+///
+/// 電圧監視タスクのフィルタ済み実測値を使用し、初回測定前は設定の公称値を使用する。
+fn bus_voltage(config: &StoredConfig, measured: f32) -> f32 {
+    if measured >= MIN_MEASURED_V_DC_BUS {
+        measured
+    } else {
+        config.v_dc_bus
+    }
+}
+
+/// 固定小数点の電流制御の単位法の基準値を取得
+///
+/// 電流は相電流センサーで測定できる最大値、電圧は最大電圧設定（電圧ベクトルの制限の上限）を1とする。
+#[cfg(feature = "fixed-point")]
+fn per_unit(config: &StoredConfig, sensor: &CurrentSensor) -> PerUnit {
+    PerUnit::new(sensor.full_scale(), config.max_voltage)
+}
+
+/// 電圧制限を更新
+///
+/// 電圧ベクトルの制限は最大電圧設定と、DCバス電圧から決まる変調の上限
+/// （線形変調: Vdc/√3、過変調: 6ステップの基本波 2Vdc/π）の小さい方とし、
+/// d/q軸電流PIと速度PI（電圧制御時）の出力制限に反映する。
+/// バス電圧が変動しても制御ループが同じ変調範囲で動作するよう、制御周期ごとに呼び出す。
+fn update_voltage_limit(
+    config: &StoredConfig,
+    speed_loop: &mut SpeedLoop,
+    current_loop: &mut CurrentLoop,
+) {
+    current_loop.voltage_limit = config
+        .max_voltage
+        .min(current_loop.modulator.voltage_limit());
+    current_loop
+        .controller
+        .set_voltage_limit(current_loop.voltage_limit);
+    speed_loop
+        .controller
+        .set_symmetric_limit(speed_output_limit(config, current_loop));
+}
+
+/// 速度フィードフォワードの係数を更新
+///
+/// 逆起電力はKv（0の場合は鎖交磁束から換算）から、摩擦・加速トルクはパラメータ同定の機械定数から求める。
+/// トルクはトルク定数（1.5 × 極対数 × 鎖交磁束）で速度PIの出力単位に換算し、電圧制御時は
+/// さらに巻線抵抗を掛けてq軸電圧とする。電流制御の有効/無効で単位が変わるため、有効化時にも呼び出す。
+fn update_feedforward(
+    config: &StoredConfig,
+    speed_loop: &mut SpeedLoop,
+    current_loop_active: bool,
+) {
+    const RPM_TO_RAD_PER_SEC: f32 = core::f32::consts::PI / 30.0;
+
+    let pole_pairs = config.pole_pairs as f32;
+    let flux_linkage = if config.motor_kv > 0.0 && pole_pairs > 0.0 {
+        1.0 / (config.motor_kv * RPM_TO_RAD_PER_SEC * pole_pairs)
+    } else {
+        config.motor_flux_linkage
+    };
+
+    // 機械角速度 [RPM] あたりの逆起電力 [V]
+    let back_emf = RPM_TO_RAD_PER_SEC * pole_pairs * flux_linkage;
+
+    // トルク [N·m] → 速度PIの出力（q軸電流 [A] またはq軸電圧 [V]）
+    let torque_constant = 1.5 * pole_pairs * flux_linkage;
+    let torque_scale = if torque_constant > 0.0 {
+        if current_loop_active {
+            1.0 / torque_constant
+        } else {
+            config.motor_resistance / torque_constant
+        }
+    } else {
+        0.0
+    };
+
+    let feedforward = &mut speed_loop.feedforward;
+    feedforward.set_back_emf(config.ff_back_emf_gain * back_emf);
+    feedforward.set_friction(
+        config.ff_friction_gain * config.motor_friction_torque * torque_scale,
+        config.ff_friction_gain * config.motor_viscous_friction * RPM_TO_RAD_PER_SEC * torque_scale,
+        config.ff_friction_band,
+    );
+    feedforward.set_acceleration(
+        config.ff_acceleration_gain * config.motor_inertia * RPM_TO_RAD_PER_SEC * torque_scale,
+    );
+}
+
+/// 速度PIの積分項をプリセット（バンプレス切替）
+///
+/// 速度PIの出力にはフィードフォワードを加算するため、引き継ぐ出力（q軸電流指令またはq軸電圧指令）から
+/// 現在速度でのフィードフォワードを差し引いた値を積分項とする。
+/// 電流制御時の逆起電力項はd/q軸電流PIの出力に加算するため差し引かない。
+fn preset_speed_output(
+    speed_loop: &mut SpeedLoop,
+    current_loop_active: bool,
+    output: f32,
+    speed_rpm: f32,
+) {
+    speed_loop.feedforward.reset();
+    let (torque, back_emf) = speed_loop.feedforward.update(speed_rpm, 0.0);
+    let feedforward = if current_loop_active {
+        torque
+    } else {
+        torque + back_emf
+    };
+    speed_loop.controller.set_integral(output - feedforward);
+}
+
+/// PIコントローラーの拡張機能を設定
+///
+/// 速度PIの逆算アンチワインドアップの追従ゲイン、D項（速度PI・位置制御）、
+/// 速度PIのセットポイント重み・出力レート制限を反映する。
+fn update_pid_extensions(
+    config: &StoredConfig,
+    speed_loop: &mut SpeedLoop,
+    position_loop: &mut PositionLoop,
+) {
+    let speed_pi = &mut speed_loop.controller;
+    speed_pi.set_tracking_gain(config.speed_tracking_gain);
+    speed_pi.set_derivative(config.speed_kd, config.speed_derivative_filter_time);
+    speed_pi.set_setpoint_weights(
+        config.speed_proportional_weight,
+        config.speed_derivative_weight,
     );
+    speed_pi.set_rate_limit(config.speed_rate_limit);
+    position_loop
+        .controller
+        .set_derivative(config.position_kd, config.position_derivative_filter_time);
+}
 
-    // キャリブレーション初期化（トルク0.1 = 10%、電力消費を抑える）
-    let mut calibration = MotorCalibration::new(DEFAULT_POLE_PAIRS, 0.1);
+/// 弱め界磁の出力制限を取得
+///
+/// 電流制御時はd軸電流指令 [A] を、それ以外はd軸電圧指令 [V] を制限する（0 = 弱め界磁なし）。
+fn field_weakening_limit(config: &StoredConfig, current_loop_active: bool) -> f32 {
+    if current_loop_active {
+        config.field_weakening_max_current
+    } else {
+        config.field_weakening_max_voltage
+    }
+}
 
-    // 制御モード
-    let mut control_mode = ControlMode::OpenLoop;
+/// デッドタイム補償方式を取得
+///
+/// 電流極性による補償は相電流が必要なため、電流センサーが未校正の場合は電圧指令の極性による補償に
+/// フォールバックする。
+fn dead_time_mode(config: &StoredConfig, current_sensing: bool) -> DeadTimeCompensation {
+    match DeadTimeCompensation::from_u8(config.pwm_dead_time_compensation) {
+        Some(DeadTimeCompensation::CurrentSign) if !current_sensing => {
+            DeadTimeCompensation::VoltageSign
+        }
+        Some(mode) => mode,
+        None => DeadTimeCompensation::Disabled,
+    }
+}
 
-    // 速度ランプ（加速度制限）用の現在指令速度
-    let mut ramped_target_speed: f32 = 0.0;
+/// 運転中に反映できないパラメータが変更されたかチェック
+///
+/// 極対数・Hallオフセット・角度の取得元・Hall推定方式・オープンループ始動パラメータは、運転中に変更すると
+/// 角度や転流が不連続になるため、モーター停止時にのみ反映する。
+/// 速度・位置ループの分周比は、速度ループのフィルタと保持している出力が周期に依存するため同様に扱う。
+fn requires_stop_to_apply(current: &StoredConfig, next: &StoredConfig) -> bool {
+    current.pole_pairs != next.pole_pairs
+        || current.speed_loop_divider != next.speed_loop_divider
+        || current.position_loop_divider != next.position_loop_divider
+        || current.hall_angle_offset != next.hall_angle_offset
+        || current.angle_source != next.angle_source
+        || current.hall_estimator != next.hall_estimator
+        || current.openloop_initial_rpm != next.openloop_initial_rpm
+        || current.openloop_target_rpm != next.openloop_target_rpm
+        || current.openloop_acceleration != next.openloop_acceleration
+        || current.openloop_duty_ratio != next.openloop_duty_ratio
+        || current.openloop_mode != next.openloop_mode
+        || current.openloop_vf_boost_voltage != next.openloop_vf_boost_voltage
+        || current.openloop_vf_gain != next.openloop_vf_gain
+}
 
-    // 制御周期
-    let dt = DEFAULT_CONTROL_PERIOD_US as f32 / 1_000_000.0; // 秒に変換
+/// 運転中に受け取った設定のうち、運転中に反映できるパラメータだけを反映した設定を取得
+///
+/// `requires_stop_to_apply`が対象とするパラメータは、モーター停止時まで適用中の値を保持する。
+fn with_stop_params(current: &StoredConfig, next: &StoredConfig) -> StoredConfig {
+    let mut config = *next;
+    config.pole_pairs = current.pole_pairs;
+    config.speed_loop_divider = current.speed_loop_divider;
+    config.position_loop_divider = current.position_loop_divider;
+    config.hall_angle_offset = current.hall_angle_offset;
+    config.angle_source = current.angle_source;
+    config.hall_estimator = current.hall_estimator;
+    config.openloop_initial_rpm = current.openloop_initial_rpm;
+    config.openloop_target_rpm = current.openloop_target_rpm;
+    config.openloop_acceleration = current.openloop_acceleration;
+    config.openloop_duty_ratio = current.openloop_duty_ratio;
+    config.openloop_mode = current.openloop_mode;
+    config.openloop_vf_boost_voltage = current.openloop_vf_boost_voltage;
+    config.openloop_vf_gain = current.openloop_vf_gain;
+    config
+}
 
-    info!(
-        "FOC parameters: Pole pairs={}, Control freq={}Hz, dt={}s",
-        DEFAULT_POLE_PAIRS,
-        1_000_000 / DEFAULT_CONTROL_PERIOD_US,
-        dt
+/// 運転中に安全に反映できるパラメータを適用
+///
+/// 電圧・電流制限、過変調の有無、電流PIゲイン、電流検出スケール、位置制御ゲイン・最大速度、
+/// PIコントローラーの拡張機能（追従ゲイン・D項・セットポイント重み・レート制限・ゲインスケジュール）、
+/// 加減速プロファイル、Hallセンサの速度フィルタ・角度補間・PLL帯域、オブザーバのモーター定数・PLL帯域は
+/// 制御周期ごとに参照されるだけなので、ループ先頭で切り替えても不連続にならない。
+/// ハンドオーバー時間は次回のFOC切替時に反映する。正弦波V/f駆動の電圧は最大電圧で制限する。
+/// デッドタイム補償方式・最小パルスはPWM周期ごとのデューティ補正にのみ使うため運転中に切り替える
+/// （デッドタイムとPWM周波数は起動時の値で固定）。
+/// 電流制御の有効/無効は次回のモーター有効化時に反映する。
+/// 速度ループのフィルタ・ゲインスケジュールはタスクが設計したものに置き換える
+/// （フィルタはパラメータが変わらなければ状態を保持する）。
+fn apply_live_config(
+    prepared: &ControlConfig,
+    angle_sensor: &mut AngleSensor,
+    speed_loop: &mut SpeedLoop,
+    position_loop: &mut PositionLoop,
+    current_loop: &mut CurrentLoop,
+    startup: &mut Startup,
+) {
+    let config = &prepared.config;
+    current_loop
+        .modulator
+        .set_overmodulation(config.pwm_overmodulation);
+    update_voltage_limit(config, speed_loop, current_loop);
+    update_feedforward(config, speed_loop, current_loop.active);
+    speed_loop
+        .feedback_filter
+        .adopt(&prepared.speed_feedback_filter);
+    speed_loop
+        .output_filter
+        .adopt(&prepared.speed_output_filter);
+    speed_loop.schedule = prepared.speed_schedule;
+    update_pid_extensions(config, speed_loop, position_loop);
+    speed_loop.profile.set_limits(
+        config.profile_acceleration,
+        config.profile_deceleration,
+        config.profile_jerk,
     );
-    info!(
-        "PWM configuration: Frequency={}Hz, Max duty={}",
-        pwm::DEFAULT_FREQUENCY.0,
-        motor_driver.max_duty()
+    position_loop.controller.set_gains(config.position_kp, 0.0);
+    position_loop
+        .controller
+        .set_symmetric_limit(config.position_max_speed);
+    position_loop.profile.set_limits(
+        config.profile_acceleration,
+        config.profile_deceleration,
+        config.profile_jerk,
+    );
+    current_loop
+        .controller
+        .set_gains(config.current_kp, config.current_ki);
+    // 固定小数点の電流PIは積分を出力制限でクランプするため追従ゲインはない
+    #[cfg(not(feature = "fixed-point"))]
+    current_loop
+        .controller
+        .set_tracking_gain(config.current_tracking_gain);
+    current_loop.field_weakening.set_params(
+        config.field_weakening_gain,
+        config.field_weakening_voltage_ratio,
     );
+    current_loop
+        .field_weakening
+        .set_max_output(field_weakening_limit(config, current_loop.active));
+    current_loop
+        .dead_time
+        .set_mode(dead_time_mode(config, current_loop.sensor.is_calibrated()));
+    current_loop.dead_time.set_min_pulse(config.pwm_min_pulse);
+    current_loop
+        .sensor
+        .set_scaling(config.current_shunt_resistance, config.current_amp_gain);
+    #[cfg(feature = "fixed-point")]
+    current_loop
+        .controller
+        .set_per_unit(per_unit(config, &current_loop.sensor));
+    angle_sensor
+        .hall
+        .set_filter_alpha(config.speed_filter_alpha);
+    angle_sensor
+        .hall
+        .set_interpolation(config.enable_angle_interpolation);
+    angle_sensor
+        .hall
+        .set_pll_bandwidth(config.hall_pll_bandwidth);
+    angle_sensor.observer.set_motor_params(
+        config.motor_resistance,
+        config.motor_inductance,
+        config.motor_flux_linkage,
+    );
+    angle_sensor
+        .observer
+        .set_pll_bandwidth(config.observer_pll_bandwidth);
+    startup.vf.set_max_voltage(config.max_voltage);
+    startup.handover.set_duration(config.openloop_handover_time);
+}
 
-    // モーター有効状態の追跡（PWMチャネル制御用）
-    let mut was_enabled = false;
+/// 1制御周期の入力
+///
+/// 制御モードの実行関数に、タスクからの指令・適用中の設定・制御周期をまとめて渡す。
+struct CycleInput<'a> {
+    /// 適用中のランタイム設定
+    config: &'a StoredConfig,
+    /// タスクからの指令（目標値・速度PIゲイン）
+    command: &'a ControlCommand,
+    /// 電流・角度ループの制御周期 [s]
+    dt: f32,
+    /// 今周期に実行する速度・位置ループ
+    ticks: LoopTicks,
+    /// 速度ループの制御周期 [s]
+    speed_dt: f32,
+    /// 位置ループの制御周期 [s]
+    position_dt: f32,
+}
 
-    loop {
-        // 1. モーター使能チェック
-        let motor_enabled = *MOTOR_ENABLE.lock().await;
-        if !motor_enabled {
-            if was_enabled {
-                info!("Motor control loop: Disabling PWM channels");
-                was_enabled = false;
+/// 要求の通し番号が変わったかチェックし、処理済みの番号を更新
+///
+/// # 引数
+/// * `handled` - 処理済みの通し番号
+/// * `requested` - タスクからの指令の通し番号
+///
+/// # 戻り値
+/// * `bool` - 未処理の要求があったか
+fn take_request(handled: &mut u32, requested: u32) -> bool {
+    if *handled == requested {
+        return false;
+    }
+    *handled = requested;
+    true
+}
+
+/// 制御ループの状態
+///
+/// モーター制御タスクが生成して制御割り込みに渡し、以降は制御割り込みのみが更新する。
+/// 共有状態との受け渡しは`exchange`のダブルバッファで行う。
+struct ControlLoop {
+    /// モータードライバー
+    motor_driver: MotorDriver,
+    /// 適用中のランタイム設定
+    active_config: StoredConfig,
+    /// タスクから受け取った最新のランタイム設定（検証・フィルタ設計済み）
+    latest_config: ControlConfig,
+    /// 受け取ったランタイム設定の書き込み回数（`CONTROL_CONFIG`の更新検出）
+    config_sequence: u32,
+    /// 受け取ったランタイム設定が未適用か
+    config_pending: bool,
+    /// 停止時に反映待ちの設定変更があるか
+    restart_pending: bool,
+    /// タスクからの最新の指令
+    command: ControlCommand,
+    /// タスクへの制御結果（制御周期の終わりに書き込む）
+    report: ControlReport,
+    /// 運転要求（`DriveRequest::Start`で設定、停止要求・故障・パラメータ同定の終了で解除）
+    run_requested: bool,
+    /// 外部指令で要求された制御モード（`DriveRequest::Mode`で設定）
+    command_mode: ControlMode,
+    /// 運転中に処理するキャリブレーション・パラメータ同定・自動調整の要求
+    pending_request: Option<DriveRequest>,
+    /// 処理済みのHall故障クリア要求の通し番号
+    hall_fault_clear_request: u32,
+    /// 角度センサー（Hallセンサー・オブザーバ）
+    angle_sensor: AngleSensor,
+    /// 速度制御ループ
+    speed_loop: SpeedLoop,
+    /// 位置制御ループ（P制御、出力は速度指令 [RPM]）
+    position_loop: PositionLoop,
+    /// 電流制御ループ
+    current_loop: CurrentLoop,
+    /// 始動制御（オープンループ始動・FOC切替ハンドオーバー）
+    startup: Startup,
+    /// キャリブレーション
+    calibration: MotorCalibration,
+    /// パラメータ同定（要求時に現在の極対数で作り直す）
+    identification: MotorIdentification,
+    /// 速度PIゲイン自動調整
+    autotune: SpeedAutoTune,
+    /// 制御モード
+    control_mode: ControlMode,
+    /// 前周期に速度・位置ループを実行した制御モード（切り替え時にスケジュールをやり直す）
+    scheduled_mode: ControlMode,
+    /// 駆動状態マシン（状態・故障要因）
+    drive: DriveStateMachine,
+    /// 停止処理中に最後に観測したHallエッジカウンタの値
+    stop_edge_count: u32,
+    /// 停止処理中にHallエッジが途切れている時間 [s]
+    stop_quiet_time: f32,
+    /// 電流・速度・位置ループのスケジューラ（電流ループの周期 = PWM周期 × 分周比）
+    scheduler: LoopScheduler,
+}
+
+impl ControlLoop {
+    /// 各コントローラをデフォルト値で生成し、ランタイム設定で上書きする
+    ///
+    /// # 引数
+    /// * `motor_driver` - モータードライバー
+    /// * `prepared` - 起動時のランタイム設定（`CONTROL_CONFIG`に書き込み済みのもの）
+    /// * `command` - 最初の指令（保存済みのキャリブレーション結果を使用）
+    /// * `dt` - 電流・角度ループの制御周期 [s]
+    fn new(
+        motor_driver: MotorDriver,
+        prepared: ControlConfig,
+        command: ControlCommand,
+        dt: f32,
+    ) -> Self {
+        let config = prepared.config;
+        let current_sensor =
+            CurrentSensor::new(current::DEFAULT_SHUNT_RESISTANCE, current::DEFAULT_AMP_GAIN);
+        #[cfg(feature = "fixed-point")]
+        let current_per_unit = per_unit(&config, &current_sensor);
+        let mut control_loop = Self {
+            motor_driver,
+            active_config: config,
+            latest_config: prepared,
+            config_sequence: CONTROL_CONFIG.sequence(),
+            config_pending: false,
+            restart_pending: false,
+            command,
+            report: ControlReport::new(),
+            run_requested: false,
+            command_mode: ControlMode::ClosedLoopFoc,
+            pending_request: None,
+            hall_fault_clear_request: command.hall_fault_clear_request,
+            angle_sensor: AngleSensor {
+                hall: HallSensor::new(DEFAULT_POLE_PAIRS, DEFAULT_SPEED_FILTER_ALPHA),
+                diagnostics: HallDiagnostics::new(),
+                observer: FluxObserver::new(
+                    sensorless::DEFAULT_RESISTANCE,
+                    sensorless::DEFAULT_INDUCTANCE,
+                    sensorless::DEFAULT_FLUX_LINKAGE,
+                    sensorless::DEFAULT_PLL_BANDWIDTH,
+                    DEFAULT_POLE_PAIRS,
+                ),
+                source: AngleSource::Hall,
+                applied_voltage: (0.0, 0.0),
+                applied_vq: 0.0,
+                observer_in_use: false,
+            },
+            speed_loop: SpeedLoop {
+                profile: MotionProfile::new(
+                    profile::DEFAULT_ACCELERATION,
+                    profile::DEFAULT_DECELERATION,
+                    profile::DEFAULT_JERK,
+                ),
+                controller: PiController::new_symmetric(
+                    config.speed_kp,
+                    config.speed_ki,
+                    DEFAULT_MAX_VOLTAGE,
+                ),
+                schedule: GainSchedule::default(),
+                feedforward: SpeedFeedforward::new(0.0, 0.0, 0.0, 0.0, 0.0),
+                feedback_filter: SignalFilter::disabled(),
+                output_filter: SignalFilter::disabled(),
+                reference: 0.0,
+                output: 0.0,
+                back_emf: 0.0,
+                stopped: false,
+            },
+            position_loop: PositionLoop {
+                profile: MotionProfile::new(
+                    profile::DEFAULT_ACCELERATION,
+                    profile::DEFAULT_DECELERATION,
+                    profile::DEFAULT_JERK,
+                ),
+                controller: PiController::new_symmetric(
+                    position::DEFAULT_KP,
+                    0.0,
+                    position::DEFAULT_MAX_SPEED,
+                ),
+                reference: 0.0,
+                feedforward_speed: 0.0,
+                correction_speed: 0.0,
+            },
+            current_loop: CurrentLoop {
+                sensor: current_sensor,
+                #[cfg(not(feature = "fixed-point"))]
+                controller: CurrentController::new(
+                    current::DEFAULT_KP,
+                    current::DEFAULT_KI,
+                    DEFAULT_MAX_VOLTAGE,
+                ),
+                #[cfg(feature = "fixed-point")]
+                controller: FixedCurrentController::new(
+                    current::DEFAULT_KP,
+                    current::DEFAULT_KI,
+                    DEFAULT_MAX_VOLTAGE,
+                    current_per_unit,
+                ),
+                field_weakening: FieldWeakening::new(
+                    field_weakening::DEFAULT_GAIN,
+                    field_weakening::DEFAULT_VOLTAGE_RATIO,
+                    0.0,
+                ),
+                modulator: Modulator::new(config.v_dc_bus),
+                voltage_limit: DEFAULT_MAX_VOLTAGE,
+                // デッドタイムとPWM周波数は起動時の設定値で固定（タイマー設定と同じ値）
+                dead_time: DeadTimeCompensator::new(
+                    config.pwm_dead_time,
+                    config.pwm_frequency,
+                    pwm::TIMER_CLOCK_HZ,
+                    pwm::DEAD_TIME_CURRENT_BAND,
+                ),
+                active: false,
+            },
+            startup: Startup {
+                mode: OpenLoopMode::SixStep,
+                openloop: OpenLoopSixStep::new(
+                    openloop::DEFAULT_INITIAL_RPM,
+                    openloop::DEFAULT_TARGET_RPM,
+                    openloop::DEFAULT_ACCELERATION_RPM_PER_S,
+                    openloop::DEFAULT_DUTY_RATIO,
+                    DEFAULT_POLE_PAIRS,
+                ),
+                vf: OpenLoopVf::new(
+                    openloop::DEFAULT_INITIAL_RPM,
+                    openloop::DEFAULT_TARGET_RPM,
+                    openloop::DEFAULT_ACCELERATION_RPM_PER_S,
+                    openloop::DEFAULT_VF_BOOST_VOLTAGE,
+                    openloop::DEFAULT_VF_GAIN,
+                    DEFAULT_MAX_VOLTAGE,
+                    DEFAULT_POLE_PAIRS,
+                ),
+                handover: StartupHandover::new(openloop::DEFAULT_HANDOVER_TIME),
+                direct_start: DirectStartMonitor::new(
+                    openloop::DIRECT_START_TIMEOUT,
+                    openloop::DIRECT_START_EDGES,
+                ),
+            },
+            // キャリブレーション初期化（トルク0.1 = 10%、電力消費を抑える）
+            calibration: MotorCalibration::new(DEFAULT_POLE_PAIRS, 0.1),
+            identification: MotorIdentification::new(DEFAULT_POLE_PAIRS),
+            autotune: SpeedAutoTune::new(),
+            control_mode: ControlMode::OpenLoop,
+            scheduled_mode: ControlMode::OpenLoop,
+            drive: DriveStateMachine::new(),
+            stop_edge_count: 0,
+            stop_quiet_time: 0.0,
+            scheduler: LoopScheduler::new(
+                dt,
+                timing::DEFAULT_SPEED_DIVIDER,
+                timing::DEFAULT_POSITION_DIVIDER,
+            ),
+        };
+
+        control_loop.apply_full_config();
+        control_loop
+    }
+
+    /// 受け取ったランタイム設定のすべてのパラメータを適用（モーター停止中のみ呼び出す）
+    ///
+    /// 設定はタスクが検証済みのため、そのまま適用する。
+    /// セクター境界テーブル・電気オフセットには、タスクからの指令の保存済みキャリブレーション結果を使用する。
+    fn apply_full_config(&mut self) {
+        let prepared = &self.latest_config;
+        let config = &prepared.config;
+        let angle_sensor = &mut self.angle_sensor;
+        let startup = &mut self.startup;
+
+        angle_sensor.hall.set_pole_pairs(config.pole_pairs);
+        angle_sensor.observer.set_pole_pairs(config.pole_pairs);
+        self.calibration.set_pole_pairs(config.pole_pairs);
+        if let Some(estimator) = HallEstimator::from_u8(config.hall_estimator) {
+            angle_sensor.hall.set_estimator(estimator);
+        }
+
+        // 速度・位置ループの分周比（速度ループのフィルタはタスクがこの周期で設計する）
+        self.scheduler
+            .set_dividers(config.speed_loop_divider, config.position_loop_divider);
+        self.report.rates = ControlRates {
+            current_loop_hz: (1.0 / self.scheduler.current_dt() + 0.5) as u32,
+            speed_divider: self.scheduler.speed_divider(),
+            position_divider: self.scheduler.position_divider(),
+        };
+
+        apply_live_config(
+            prepared,
+            angle_sensor,
+            &mut self.speed_loop,
+            &mut self.position_loop,
+            &mut self.current_loop,
+            startup,
+        );
+
+        // セクター境界テーブル・電気オフセットを設定（キャリブレーション結果があればそちらを優先）
+        let calib_result = self.command.calibration;
+        angle_sensor
+            .diagnostics
+            .set_direction_inversed(calib_result.success && calib_result.direction_inversed);
+        let sector_table_applied = calib_result.success
+            && calib_result.sector_table_valid
+            && angle_sensor
+                .hall
+                .set_sector_angles(&calib_result.sector_angles);
+        // テーブルを適用した場合はテーブルが電気オフセットを含む
+        if !sector_table_applied {
+            angle_sensor.hall.reset_sector_angles();
+            if calib_result.success {
+                angle_sensor
+                    .hall
+                    .set_electrical_offset(calib_result.electrical_offset);
+            } else {
+                angle_sensor
+                    .hall
+                    .set_electrical_offset(config.hall_angle_offset);
             }
+        }
 
-            // モーター停止：PWMチャネルを完全無効化
-            motor_driver.stop();
+        if let Some(mode) = OpenLoopMode::from_u8(config.openloop_mode) {
+            startup.mode = mode;
+        }
+        startup.openloop = OpenLoopSixStep::new(
+            config.openloop_initial_rpm,
+            config.openloop_target_rpm,
+            config.openloop_acceleration,
+            config.openloop_duty_ratio.min(100),
+            config.pole_pairs,
+        );
+        startup.vf = OpenLoopVf::new(
+            config.openloop_initial_rpm,
+            config.openloop_target_rpm,
+            config.openloop_acceleration,
+            config.openloop_vf_boost_voltage,
+            config.openloop_vf_gain,
+            config.max_voltage,
+            config.pole_pairs,
+        );
 
-            // 各コントローラとセンサーをリセット
-            speed_pi.reset();
-            hall_sensor.reset();
-            openloop.reset();
-            hall_tim::reset_state(); // TIM4の状態もリセット
-            ramped_target_speed = 0.0; // 速度ランプもリセット
-            control_mode = ControlMode::OpenLoop; // OpenLoopに戻す
+        self.active_config = *config;
+        self.report.events.push(ControlEvent::ConfigApplied);
+    }
 
-            Timer::after(Duration::from_micros(DEFAULT_CONTROL_PERIOD_US)).await;
-            continue;
+    /// 駆動状態を遷移させ、遷移時の処理を実行
+    ///
+    /// 未定義の遷移は拒否し、状態を変えない。
+    ///
+    /// # 引数
+    /// * `next` - 遷移先の状態
+    fn enter_drive_state(&mut self, next: DriveState) {
+        let previous = self.drive.state();
+        match self.drive.transition(next) {
+            Ok(true) => {}
+            Ok(false) => return,
+            Err(illegal) => {
+                self.report.events.push(ControlEvent::TransitionRejected {
+                    from: illegal.from,
+                    to: illegal.to,
+                });
+                return;
+            }
         }
+        self.report.events.push(ControlEvent::DriveStateChanged {
+            from: previous,
+            to: next,
+        });
 
-        // モーター有効化時の処理
-        if !was_enabled {
-            info!("Motor control loop: Starting with OpenLoop mode");
-            motor_driver.enable_all_channels();
-            was_enabled = true;
+        // 入場処理
+        match next {
+            DriveState::Precharge => {
+                // PWM停止中にゼロ電流オフセットを校正
+                self.motor_driver.stop();
+                self.current_loop
+                    .sensor
+                    .start_offset_calibration(current::OFFSET_CALIBRATION_SAMPLES);
+            }
+            DriveState::Stopping => {
+                // PWMを停止し、Hallエッジが途切れるまで待つ
+                self.motor_driver.stop();
+                self.stop_edge_count = hall_tim::get_edge_count();
+                self.stop_quiet_time = 0.0;
+            }
+            _ => {}
         }
+    }
 
-        // 2. キャリブレーションリクエストをチェック
+    /// 故障要因と運転要求から駆動状態を更新
+    ///
+    /// 故障要因（電圧異常、Hallのみで運転する場合のHall故障）があれば故障状態でPWMを停止して
+    /// 運転要求と処理待ちの要求を解除する。故障状態は要因が解消するまで維持する
+    /// （故障中の始動要求は`DriveState::accepts`で拒否される）。
+    ///
+    /// # 引数
+    /// * `dt` - 制御周期 [s]
+    fn update_drive_state(&mut self, dt: f32) {
+        // Hall故障がラッチされている間、Hallのみで運転する場合は故障とする
+        // （フォールバック付きの場合はオブザーバの角度で運転を継続）
+        let mut faults = self.command.voltage_faults;
+        if self.angle_sensor.diagnostics.is_faulted()
+            && self.angle_sensor.source == AngleSource::Hall
         {
-            let mut calibration_request = CALIBRATION_REQUEST.lock().await;
-            if *calibration_request {
-                info!("Calibration requested, switching to Calibration mode");
-                *calibration_request = false; // リクエストをクリア
+            faults |= FAULT_HALL;
+        }
+        if let Some(previous) = self.drive.set_faults(faults) {
+            self.report.events.push(ControlEvent::DriveFault {
+                state: previous,
+                faults,
+            });
+            self.motor_driver.stop();
+            self.run_requested = false;
+            self.pending_request = None;
+        }
+
+        let run_requested = self.run_requested;
+        match self.drive.state() {
+            DriveState::Init => self.enter_drive_state(DriveState::Idle),
+            DriveState::Idle => {
+                if run_requested {
+                    self.enter_drive_state(DriveState::Precharge);
+                }
+            }
+            DriveState::Stopping => {
+                if self.rotor_stopped(dt) {
+                    self.enter_drive_state(DriveState::Idle);
+                }
+            }
+            DriveState::Fault => {
+                if self.drive.faults() == 0 {
+                    self.enter_drive_state(DriveState::Idle);
+                }
+            }
+            DriveState::Precharge
+            | DriveState::Starting
+            | DriveState::Running
+            | DriveState::Calibrating => {
+                if !run_requested {
+                    self.enter_drive_state(DriveState::Stopping);
+                }
+            }
+        }
+    }
+
+    /// 駆動要求をすべて受信
+    ///
+    /// 現在の状態で受け付けない要求は拒否する。始動・停止・制御モードの要求はすぐに反映し、
+    /// キャリブレーション・パラメータ同定・自動調整の要求は運転中に処理する（後の要求で置き換え）。
+    fn receive_requests(&mut self) {
+        while let Ok(request) = DRIVE_REQUESTS.try_receive() {
+            let state = self.drive.state();
+            if !state.accepts(request) {
+                self.report.events.push(ControlEvent::RequestRejected {
+                    request,
+                    state,
+                    faults: self.drive.faults(),
+                });
+                continue;
+            }
+            match request {
+                DriveRequest::Start => self.run_requested = true,
+                DriveRequest::Stop => self.run_requested = false,
+                DriveRequest::Mode(mode) => self.command_mode = mode,
+                _ => self.pending_request = Some(request),
+            }
+        }
+    }
+
+    /// 停止処理中に回転が止まったか
+    ///
+    /// Hallエッジが一定時間途切れるか、停止処理の最大時間を超えたら停止とみなす
+    /// （Hallを使わない場合もエッジが来ないため、一定時間後に停止とみなす）。
+    ///
+    /// # 引数
+    /// * `dt` - 制御周期 [s]
+    fn rotor_stopped(&mut self, dt: f32) -> bool {
+        let edge_count = hall_tim::get_edge_count();
+        if edge_count != self.stop_edge_count {
+            self.stop_edge_count = edge_count;
+            self.stop_quiet_time = 0.0;
+        } else {
+            self.stop_quiet_time += dt;
+        }
+
+        self.stop_quiet_time >= drive::STOP_SETTLE_TIME
+            || self.drive.time_in_state() >= drive::STOP_TIMEOUT
+    }
+
+    /// 動作中の駆動状態を制御モードに合わせる
+    ///
+    /// プリチャージの完了後は始動へ、以降はオープンループ始動・直接始動の監視・FOCへの切替中を始動、
+    /// キャリブレーション・パラメータ同定をキャリブレーション、それ以外を運転とする。
+    fn sync_drive_state(&mut self) {
+        // 運転要求を解除した周期は次の周期で停止処理に移るため、状態を変えない
+        if !self.run_requested {
+            return;
+        }
+
+        let next = match self.drive.state() {
+            DriveState::Precharge if self.current_loop.sensor.is_calibrating() => return,
+            DriveState::Precharge => DriveState::Starting,
+            DriveState::Starting | DriveState::Running | DriveState::Calibrating => {
+                match self.control_mode {
+                    ControlMode::OpenLoop => DriveState::Starting,
+                    ControlMode::Calibration | ControlMode::Identification => {
+                        DriveState::Calibrating
+                    }
+                    ControlMode::ClosedLoopFoc
+                        if self.startup.direct_start.is_active()
+                            || self.startup.handover.is_active() =>
+                    {
+                        DriveState::Starting
+                    }
+                    _ => DriveState::Running,
+                }
+            }
+            _ => return,
+        };
+        self.enter_drive_state(next);
+    }
+
+    /// 制御周期を1回実行（制御割り込みから呼び出す）
+    ///
+    /// タスクからの指令・設定を読み出して制御を実行し、制御結果をタスクに渡す。
+    /// 処理時間（`record_load`で記録）は次の周期の制御結果で渡す。
+    fn step(&mut self) {
+        self.command = CONTROL_COMMAND.read();
+        self.report
+            .writeback
+            .acknowledge(self.command.writeback_ack);
+
+        // ランタイム設定は書き込み回数が変わった場合のみ読み出す（適用はループ先頭）
+        let config_sequence = CONTROL_CONFIG.sequence();
+        if config_sequence != self.config_sequence {
+            self.config_sequence = config_sequence;
+            self.latest_config = CONTROL_CONFIG.read();
+            self.config_pending = true;
+        }
+
+        self.run_cycle();
+        self.sync_drive_state();
+
+        self.report.drive = DriveStatus {
+            state: self.drive.state(),
+            control_mode: self.control_mode,
+            faults: self.drive.faults(),
+        };
+        CONTROL_REPORT.write(self.report);
+    }
+
+    /// 制御周期の処理時間を記録
+    ///
+    /// # 引数
+    /// * `cycles` - 制御割り込みの処理時間 [cycles]
+    fn record_load(&mut self, cycles: u32) {
+        self.report.load.record(cycles);
+    }
+
+    /// 制御周期の処理本体
+    fn run_cycle(&mut self) {
+        let dt = self.scheduler.current_dt();
+        self.drive.update(dt);
+
+        // Hall故障のクリア要求（停止中・運転中どちらでも受け付ける）
+        if take_request(
+            &mut self.hall_fault_clear_request,
+            self.command.hall_fault_clear_request,
+        ) {
+            self.angle_sensor.diagnostics.clear_fault();
+            self.report.events.push(ControlEvent::HallFaultCleared);
+        }
+
+        // 1. 駆動要求の受信と駆動状態の更新（故障・運転要求による始動と停止）
+        self.receive_requests();
+        self.update_drive_state(dt);
+        let motor_enabled = self.drive.state().is_active();
+
+        // 診断状態をグローバル状態に反映（CAN送信用）
+        self.report.hall_diagnostics = self.angle_sensor.diagnostics.status();
+
+        // 2. 受け取ったランタイム設定の適用（ループ先頭を唯一の適用ポイントとする）
+        if motor_enabled {
+            if self.config_pending {
+                apply_live_config(
+                    &self.latest_config,
+                    &mut self.angle_sensor,
+                    &mut self.speed_loop,
+                    &mut self.position_loop,
+                    &mut self.current_loop,
+                    &mut self.startup,
+                );
+                if self.latest_config.requires_stop && !self.restart_pending {
+                    self.report.events.push(ControlEvent::ConfigDeferred);
+                    self.restart_pending = true;
+                }
+                self.active_config =
+                    with_stop_params(&self.active_config, &self.latest_config.config);
+                self.config_pending = false;
+            }
+        } else if self.config_pending || self.restart_pending {
+            self.apply_full_config();
+            self.config_pending = false;
+            self.restart_pending = false;
+        }
+
+        let active_config = &self.active_config;
+        let angle_sensor = &mut self.angle_sensor;
+        let speed_loop = &mut self.speed_loop;
+        let position_loop = &mut self.position_loop;
+        let current_loop = &mut self.current_loop;
+        let startup = &mut self.startup;
+        let motor_driver = &mut self.motor_driver;
+        let autotune = &mut self.autotune;
+        let report = &mut self.report;
+
+        // DCバス電圧の変動に合わせて変調の正規化と電圧制限を更新
+        current_loop
+            .modulator
+            .set_bus_voltage(bus_voltage(active_config, self.command.measured_v_dc));
+        update_voltage_limit(active_config, speed_loop, current_loop);
+
+        if !motor_enabled {
+            // モーター停止中（待機・停止処理・故障）：PWMチャネルを完全無効化
+            motor_driver.stop();
+
+            // 各コントローラとセンサーをリセット
+            speed_loop.reset();
+            speed_loop.profile.reset_velocity(0.0); // 速度プロファイルもリセット
+            position_loop.controller.reset();
+            current_loop.controller.reset();
+            current_loop.field_weakening.reset();
+            current_loop.active = false;
+            angle_sensor.reset();
+            startup.reset();
+            startup.handover.cancel();
+            if autotune.is_running() {
+                autotune.abort();
+                report.autotune_state = autotune.get_state();
+            }
+            hall_tim::reset_state(); // TIM4の状態もリセット
+            self.control_mode = ControlMode::OpenLoop; // OpenLoopに戻す
+            return;
+        }
+
+        // プリチャージ：PWM停止中にゼロ電流オフセットを校正（校正の開始は状態の入場処理）
+        if current_loop.sensor.is_calibrating() {
+            if current_loop
+                .sensor
+                .feed_offset_sample(current_sense::read_raw())
+            {
+                report.events.push(if current_loop.sensor.is_calibrated() {
+                    ControlEvent::CurrentOffsets(current_loop.sensor.offsets())
+                } else {
+                    ControlEvent::CurrentOffsetsInvalid
+                });
+
+                // 電流制御の有効/無効を決定（校正失敗時は電圧制御にフォールバック）
+                current_loop.active =
+                    active_config.current_control_enabled && current_loop.sensor.is_calibrated();
+                update_voltage_limit(active_config, speed_loop, current_loop);
+                update_feedforward(active_config, speed_loop, current_loop.active);
+                current_loop
+                    .field_weakening
+                    .set_max_output(field_weakening_limit(active_config, current_loop.active));
+                current_loop.dead_time.set_mode(dead_time_mode(
+                    active_config,
+                    current_loop.sensor.is_calibrated(),
+                ));
+
+                // 角度の取得元を決定（オブザーバは相電流が必要なため、校正失敗時はHallに固定）
+                let configured_source =
+                    AngleSource::from_u8(active_config.angle_source).unwrap_or(AngleSource::Hall);
+                angle_sensor.source = if configured_source != AngleSource::Hall
+                    && !current_loop.sensor.is_calibrated()
+                {
+                    AngleSource::Hall
+                } else {
+                    configured_source
+                };
+
+                // キャリブレーション済みでHall状態が有効なら、オープンループ始動を省略して
+                // Hallのセクター角度で停止状態からFOCで直接始動（回転しなければオープンループに戻す）
+                let calibrated = self.command.calibration.success;
+                let hall_ready = angle_sensor.source != AngleSource::Sensorless
+                    && HallSensor::normalize_state(hall_tim::get_hall_state()).is_some()
+                    && !angle_sensor.diagnostics.is_faulted();
+                if calibrated && hall_ready {
+                    self.control_mode = ControlMode::ClosedLoopFoc;
+                    startup.direct_start.start(hall_tim::get_edge_count());
+                }
+
+                report.events.push(ControlEvent::MotorStarted {
+                    direct_foc: self.control_mode == ControlMode::ClosedLoopFoc,
+                    current_loop: current_loop.active,
+                    angle_source: angle_sensor.source,
+                });
+                motor_driver.enable_all_channels();
+            }
 
+            return;
+        }
+
+        // 3. キャリブレーション・パラメータ同定・自動調整の要求を処理（受け付けは`receive_requests`）
+        match self.pending_request.take() {
+            Some(DriveRequest::Calibrate { torque }) => {
                 // トルク値を取得（0-100 → 0.0-1.0に変換）
-                let torque_u8 = *CALIBRATION_TORQUE.lock().await;
-                let torque_f32 = torque_u8 as f32 / 100.0;
-                info!("Starting motor calibration...");
-                info!("  Pole pairs: {}", DEFAULT_POLE_PAIRS);
-                info!("  Torque: {}", torque_f32);
-                calibration.set_torque(torque_f32);
+                let torque_f32 = torque as f32 / 100.0;
+                report
+                    .events
+                    .push(ControlEvent::CalibrationStarted { torque: torque_f32 });
+                self.calibration.set_torque(torque_f32);
 
-                control_mode = ControlMode::Calibration;
-                calibration.start();
+                self.control_mode = ControlMode::Calibration;
+                self.calibration.start();
+            }
+            // パラメータ同定（相電流を使うため電流センサーの校正が必要）
+            Some(DriveRequest::Identify {
+                current: test_current,
+                speed: test_speed,
+            }) => {
+                if current_loop.sensor.is_calibrated() {
+                    // 慣性・摩擦の測定はHallの速度を使うため、角度キャリブレーション済みの場合のみ
+                    let mechanical = self.command.calibration.success
+                        && angle_sensor.source != AngleSource::Sensorless
+                        && !angle_sensor.diagnostics.is_faulted();
+                    report.events.push(ControlEvent::IdentificationStarted {
+                        current: test_current,
+                        speed: test_speed,
+                        mechanical,
+                    });
 
-                // 制御モードをグローバル状態に反映
-                let mut mode = CONTROL_MODE.lock().await;
-                *mode = ControlMode::Calibration;
+                    self.identification =
+                        MotorIdentification::new(angle_sensor.hall.get_pole_pairs());
+                    self.identification.start(
+                        test_current,
+                        test_speed,
+                        current_loop.voltage_limit,
+                        mechanical,
+                    );
+                    startup.handover.cancel();
+                    startup.direct_start.cancel();
+                    self.control_mode = ControlMode::Identification;
+                } else {
+                    report
+                        .events
+                        .push(ControlEvent::IdentificationNeedsCurrentSensing);
+                }
             }
+            // 速度PIゲイン自動調整（速度制御で運転中のみ、始動・切替中は拒否）
+            Some(DriveRequest::AutoTune {
+                speed: test_speed,
+                relay_ratio,
+            }) => {
+                if self.control_mode == ControlMode::ClosedLoopFoc
+                    && self.drive.state() == DriveState::Running
+                {
+                    let output_limit = speed_output_limit(active_config, current_loop);
+                    autotune.start(
+                        test_speed,
+                        relay_ratio * output_limit,
+                        output_limit,
+                        test_speed.abs() * autotune::MAX_SPEED_RATIO,
+                    );
+                    report.autotune_state = autotune.get_state();
+                    report.autotune_result = autotune.get_result();
+
+                    if autotune.is_running() {
+                        startup.handover.cancel();
+                        startup.direct_start.cancel();
+                        self.control_mode = ControlMode::AutoTune;
+                    } else if let Some(failure) = autotune.get_failure() {
+                        report.events.push(ControlEvent::AutoTuneFailed(failure));
+                    }
+                } else {
+                    report.events.push(ControlEvent::AutoTuneRejected);
+                }
+            }
+            _ => {}
         }
 
-        // 3. 制御モード別処理
-        match control_mode {
+        // 4. 外部指令による制御モード切り替え（キャリブレーション・パラメータ同定・自動調整中は無視）
+        if !matches!(
+            self.control_mode,
+            ControlMode::Calibration | ControlMode::Identification | ControlMode::AutoTune
+        ) {
+            let command_mode = self.command_mode;
+            let speed_mode_running = matches!(
+                self.control_mode,
+                ControlMode::OpenLoop | ControlMode::ClosedLoopFoc
+            );
+            let needs_switch = match command_mode {
+                ControlMode::ClosedLoopFoc => !speed_mode_running,
+                ControlMode::Torque | ControlMode::Voltage | ControlMode::Position => {
+                    command_mode != self.control_mode
+                }
+                _ => false,
+            };
+
+            if needs_switch {
+                // 電流制御なしではトルクを制御できず、停止中の位置はオブザーバで推定できないため、
+                // 現在のモードを維持
+                let rejected = (command_mode == ControlMode::Torque && !current_loop.active)
+                    || (command_mode == ControlMode::Position
+                        && angle_sensor.source == AngleSource::Sensorless);
+
+                if rejected {
+                    report.events.push(ControlEvent::ModeRejected(command_mode));
+                    self.command_mode = if speed_mode_running {
+                        ControlMode::ClosedLoopFoc
+                    } else {
+                        self.control_mode
+                    };
+                } else {
+                    let at = match command_mode {
+                        ControlMode::ClosedLoopFoc => {
+                            // 回転中の可能性があるため、現在速度からプロファイルを開始
+                            let current_rpm = angle_sensor.speed_rpm();
+                            speed_loop.reset();
+                            speed_loop.profile.reset_velocity(current_rpm);
+                            current_rpm
+                        }
+                        ControlMode::Position => {
+                            // 現在位置からプロファイルを開始
+                            let current_position = angle_sensor.hall.get_position();
+                            speed_loop.reset();
+                            position_loop.controller.reset();
+                            position_loop
+                                .profile
+                                .reset_position(current_position, angle_sensor.speed_rpm());
+                            current_position
+                        }
+                        _ => 0.0,
+                    };
+                    report.events.push(ControlEvent::ModeSwitched {
+                        mode: command_mode,
+                        at,
+                    });
+                    current_loop.controller.reset();
+                    startup.handover.cancel();
+                    startup.direct_start.cancel();
+                    self.control_mode = command_mode;
+                }
+            }
+        }
+
+        // 5. 制御モード別処理
+        // 制御モードが切り替わった周期は、前のモードの出力を保持しないよう速度・位置ループも実行する
+        if self.control_mode != self.scheduled_mode {
+            self.scheduler.reset();
+            self.scheduled_mode = self.control_mode;
+        }
+        let input = CycleInput {
+            config: active_config,
+            command: &self.command,
+            dt,
+            ticks: self.scheduler.tick(),
+            speed_dt: self.scheduler.speed_dt(),
+            position_dt: self.scheduler.position_dt(),
+        };
+        match self.control_mode {
             ControlMode::OpenLoop => {
-                // オープンループ制御を実行
-                let (should_switch, _hall_state) =
-                    openloop_mode::execute(&mut openloop, &hall_sensor, &mut motor_driver, dt)
-                        .await;
+                // オープンループは常に全駆動のため、Hallエッジが来なければ故障とみなす
+                angle_sensor.update_diagnostics(1.0, dt, &mut report.events);
+
+                // オープンループ制御を実行（センサレス運転ではHall状態を切替条件にしない）
+                let require_hall = angle_sensor.source != AngleSource::Sensorless;
+                let (should_switch, _hall_state) = openloop_mode::execute(
+                    startup,
+                    require_hall,
+                    motor_driver,
+                    current_loop.modulator.get_bus_voltage(),
+                    dt,
+                    &mut report.status,
+                );
 
                 // OpenLoopからFOCへの切り替え判定
                 if should_switch {
-                    control_mode = ControlMode::ClosedLoopFoc;
-                    info!("Switching to FOC mode: Hall state valid, target speed reached");
+                    self.control_mode = ControlMode::ClosedLoopFoc;
 
                     // Hall センサーの速度フィルタを現在の速度で初期化
-                    let current_rpm = openloop.get_current_rpm();
-                    hall_sensor.reset_speed_filter(current_rpm);
-                    ramped_target_speed = current_rpm;
-                    info!("FOC mode initialized with speed: {} RPM", current_rpm);
+                    let current_rpm = startup.current_rpm();
+                    angle_sensor.hall.reset_speed_filter(current_rpm);
+                    if angle_sensor.source == AngleSource::Sensorless {
+                        // オブザーバをオープンループの電圧ベクトルの角度と始動速度から開始
+                        angle_sensor
+                            .observer
+                            .reset_to(startup.electrical_angle(), current_rpm);
+                        angle_sensor.applied_voltage = (0.0, 0.0);
+                    }
+                    speed_loop.profile.reset_velocity(current_rpm);
+
+                    // オープンループの電圧ベクトルからFOC出力へ徐々に移行
+                    let forced_voltage =
+                        startup.voltage_amplitude(current_loop.modulator.get_bus_voltage());
+                    startup.handover.start(
+                        startup.electrical_angle(),
+                        forced_voltage,
+                        current_rpm * angle_sensor.hall.get_pole_pairs() as f32,
+                    );
+                    if startup.handover.is_active() && !current_loop.active {
+                        // 速度PIの出力（q軸電圧指令）を強制転流の電圧から開始
+                        preset_speed_output(speed_loop, false, forced_voltage, current_rpm);
+                    }
+                    report
+                        .events
+                        .push(ControlEvent::FocStarted { rpm: current_rpm });
                 }
             }
 
             ControlMode::ClosedLoopFoc => {
                 // FOC制御を実行
                 let success = foc_mode::execute(
-                    &mut hall_sensor,
-                    &mut speed_pi,
-                    &mut motor_driver,
-                    &mut ramped_target_speed,
-                    dt,
-                )
-                .await;
+                    angle_sensor,
+                    speed_loop,
+                    current_loop,
+                    &mut startup.handover,
+                    motor_driver,
+                    &input,
+                    report,
+                );
 
-                // Hall状態が無効な場合は処理をスキップ
+                // 角度が得られない場合は処理をスキップ
                 if !success {
-                    Timer::after(Duration::from_micros(DEFAULT_CONTROL_PERIOD_US)).await;
-                    continue;
+                    // センサレス運転で推定を失った場合、回転指令があればオープンループから再始動
+                    if angle_sensor.source == AngleSource::Sensorless
+                        && self.command.target_speed.abs() >= active_config.sensorless_min_speed
+                    {
+                        report.events.push(ControlEvent::SensorlessLost);
+                        startup.reset();
+                        self.control_mode = ControlMode::OpenLoop;
+                    }
+                    return;
+                }
+
+                // 直接始動の監視：回転指令があるのに回転しなければオープンループ始動にフォールバック
+                match startup.direct_start.update(
+                    self.command.target_speed,
+                    hall_tim::get_edge_count(),
+                    dt,
+                ) {
+                    DirectStartStatus::Succeeded => {
+                        report.events.push(ControlEvent::DirectStartSucceeded);
+                    }
+                    DirectStartStatus::Failed => {
+                        report.events.push(ControlEvent::DirectStartFailed);
+                        speed_loop.reset();
+                        speed_loop.profile.reset_velocity(0.0);
+                        current_loop.controller.reset();
+                        startup.reset();
+                        self.control_mode = ControlMode::OpenLoop;
+                    }
+                    _ => {}
                 }
             }
 
+            ControlMode::Torque => {
+                // トルク制御を実行（q軸電流指令）
+                foc_mode::execute_torque(angle_sensor, current_loop, motor_driver, &input, report);
+            }
+
+            ControlMode::Voltage => {
+                // 電圧制御を実行（d/q軸電圧指令）
+                foc_mode::execute_voltage(angle_sensor, current_loop, motor_driver, &input, report);
+            }
+
+            ControlMode::Position => {
+                // 位置制御を実行（位置P → 速度PI）
+                foc_mode::execute_position(
+                    angle_sensor,
+                    speed_loop,
+                    position_loop,
+                    current_loop,
+                    motor_driver,
+                    &input,
+                    report,
+                );
+            }
+
             ControlMode::Calibration => {
                 // キャリブレーション制御を実行
                 if let Some(next_mode) = calibration_mode::execute(
-                    &mut calibration,
-                    &mut hall_sensor,
-                    &mut motor_driver,
+                    &mut self.calibration,
+                    &mut angle_sensor.hall,
+                    current_loop,
+                    motor_driver,
                     dt,
-                )
-                .await
-                {
+                    report,
+                ) {
                     // キャリブレーション完了、次のモードに移行
-                    control_mode = next_mode;
+                    self.control_mode = next_mode;
                 }
             }
+
+            ControlMode::AutoTune => {
+                // 速度PIゲイン自動調整を実行（角度が得られない場合は中断）
+                foc_mode::execute_autotune(
+                    angle_sensor,
+                    current_loop,
+                    speed_loop,
+                    autotune,
+                    motor_driver,
+                    &input,
+                    report,
+                );
+                report.autotune_state = autotune.get_state();
+
+                if autotune.is_finished() {
+                    report.autotune_result = autotune.get_result();
+
+                    // 速度制御に戻る（現在速度からプロファイルを開始し、速度PIの積分項は
+                    // 試験速度を保っていた出力から開始。ゲインは適用コマンドまで変更しない）
+                    let current_rpm = angle_sensor.speed_rpm();
+                    speed_loop.reset();
+                    preset_speed_output(
+                        speed_loop,
+                        current_loop.active,
+                        autotune.get_bias(),
+                        current_rpm,
+                    );
+                    speed_loop.profile.reset_velocity(current_rpm);
+                    current_loop.controller.reset();
+                    report.events.push(ControlEvent::AutoTuneFinished {
+                        rpm: current_rpm,
+                        failure: autotune.get_failure(),
+                    });
+                    self.control_mode = ControlMode::ClosedLoopFoc;
+                }
+            }
+
+            ControlMode::Identification => {
+                // パラメータ同定を実行（終了時はPWMを停止済み、運転要求を解除する）
+                if let Some(next_mode) = identification_mode::execute(
+                    &mut self.identification,
+                    angle_sensor,
+                    current_loop,
+                    motor_driver,
+                    dt,
+                    report,
+                ) {
+                    self.control_mode = next_mode;
+                    self.run_requested = false;
+                }
+            }
+        }
+    }
+}
+
+/// 制御割り込みが所有する制御ループ
+///
+/// タスクが制御割り込みの有効化前に一度だけ設定し、以降は制御割り込みからのみアクセスする。
+/// 制御割り込みは設定後にしか有効化されないため、未初期化のまま参照されることはない。
+/// （`Option`で包むと`None`の初期値がゼロでないため`.data`としてフラッシュを消費する）
+struct ControlLoopCell(UnsafeCell<MaybeUninit<ControlLoop>>);
+
+// 安全性: 設定は制御割り込みの有効化前、以降のアクセスは制御割り込みのみ（同時アクセスなし）
+unsafe impl Sync for ControlLoopCell {}
+
+static CONTROL_LOOP: ControlLoopCell = ControlLoopCell(UnsafeCell::new(MaybeUninit::uninit()));
+
+/// TIM1更新割り込み
+///
+/// PWM周期ごとに呼び出され、分周比に達した周期で制御ループを1回実行する。
+/// 制御ループを実行した周期は処理時間をDWTサイクルカウンタで計測し、次の制御結果で渡す。
+#[interrupt]
+fn TIM1_UP_TIM16() {
+    let start = DWT::cycle_count();
+
+    // 安全性: 割り込みコンテキストから呼び出している。CONTROL_LOOPは割り込みの有効化前に設定済みで、
+    // 以降は割り込みからのみアクセスされる
+    unsafe {
+        if !control_timer::on_update() {
+            return;
+        }
+
+        let control_loop = (*CONTROL_LOOP.0.get()).assume_init_mut();
+        control_loop.step();
+        control_loop.record_load(DWT::cycle_count().wrapping_sub(start));
+    }
+}
+
+/// ランタイム設定を検証
+///
+/// NaN・無限大の値はデフォルト値に戻し、範囲外の値はエラーを出力して前回の値を使う
+/// （制御割り込みは検証済みの設定をそのまま適用する）。
+///
+/// # 引数
+/// * `next` - 共有状態のランタイム設定
+/// * `previous` - 前回制御割り込みに渡した検証済みの設定（起動時はデフォルト設定）
+///
+/// # 戻り値
+/// * `StoredConfig` - 検証済みの設定
+fn validate_config(next: &StoredConfig, previous: &StoredConfig) -> StoredConfig {
+    let mut config = *next;
+
+    let non_finite = config.sanitize_float_params();
+    if non_finite != 0 {
+        error!(
+            "{} non-finite values in runtime config, reset to defaults",
+            non_finite
+        );
+    }
+
+    if !is_valid_pole_pairs(config.pole_pairs) {
+        error!("Invalid pole_pairs=0 in runtime config, keeping previous value");
+        config.pole_pairs = previous.pole_pairs;
+    }
+
+    if !(is_valid_voltage(config.max_voltage) && is_valid_voltage(config.v_dc_bus)) {
+        error!(
+            "Invalid voltages (max={}V, vdc={}V) in runtime config, keeping previous values",
+            config.max_voltage, config.v_dc_bus
+        );
+        config.max_voltage = previous.max_voltage;
+        config.v_dc_bus = previous.v_dc_bus;
+    }
+
+    if !(is_valid_speed_gain(config.speed_kp) && is_valid_speed_gain(config.speed_ki)) {
+        error!(
+            "Invalid speed PI gains (Kp={}, Ki={}) in runtime config, keeping previous values",
+            config.speed_kp, config.speed_ki
+        );
+        config.speed_kp = previous.speed_kp;
+        config.speed_ki = previous.speed_ki;
+    }
+
+    // PIの出力制限（q軸電流指令・位置制御の速度指令）
+    if !current::is_valid_positive(config.max_current) {
+        error!(
+            "Invalid max_current={}A in runtime config, keeping previous value",
+            config.max_current
+        );
+        config.max_current = previous.max_current;
+    }
+    if !position::is_valid_max_speed(config.position_max_speed) {
+        error!(
+            "Invalid position_max_speed={} RPM in runtime config, keeping previous value",
+            config.position_max_speed
+        );
+        config.position_max_speed = previous.position_max_speed;
+    }
+
+    if !is_valid_speed_filter_alpha(config.speed_filter_alpha) {
+        error!(
+            "Invalid speed_filter_alpha={} in runtime config, keeping previous value",
+            config.speed_filter_alpha
+        );
+        config.speed_filter_alpha = previous.speed_filter_alpha;
+    }
+
+    if HallEstimator::from_u8(config.hall_estimator).is_none() {
+        error!(
+            "Invalid hall_estimator={} in runtime config, keeping previous value",
+            config.hall_estimator
+        );
+        config.hall_estimator = previous.hall_estimator;
+    }
+
+    if !(timing::is_valid_loop_divider(config.speed_loop_divider)
+        && timing::is_valid_loop_divider(config.position_loop_divider))
+    {
+        error!(
+            "Invalid loop dividers (speed={}, position={}) in runtime config, keeping previous values",
+            config.speed_loop_divider, config.position_loop_divider
+        );
+        config.speed_loop_divider = previous.speed_loop_divider;
+        config.position_loop_divider = previous.position_loop_divider;
+    }
+
+    if OpenLoopMode::from_u8(config.openloop_mode).is_none() {
+        error!(
+            "Invalid openloop_mode={} in runtime config, keeping previous value",
+            config.openloop_mode
+        );
+        config.openloop_mode = previous.openloop_mode;
+    }
+
+    // オープンループ始動パラメータ（0以下の回転数はステップ周期が発散するため拒否）
+    if !(openloop::is_valid_rpm_range(config.openloop_initial_rpm, config.openloop_target_rpm)
+        && openloop::is_valid_acceleration(config.openloop_acceleration)
+        && openloop::is_valid_duty_ratio(config.openloop_duty_ratio))
+    {
+        error!(
+            "Invalid openloop params (initial={}, target={}, accel={}, duty={}), keeping previous values",
+            config.openloop_initial_rpm,
+            config.openloop_target_rpm,
+            config.openloop_acceleration,
+            config.openloop_duty_ratio
+        );
+        config.openloop_initial_rpm = previous.openloop_initial_rpm;
+        config.openloop_target_rpm = previous.openloop_target_rpm;
+        config.openloop_acceleration = previous.openloop_acceleration;
+        config.openloop_duty_ratio = previous.openloop_duty_ratio;
+        config.openloop_vf_boost_voltage = previous.openloop_vf_boost_voltage;
+        config.openloop_vf_gain = previous.openloop_vf_gain;
+    }
+
+    config
+}
+
+/// 制御割り込みに渡す設定を作成
+///
+/// 速度ループのフィルタ（設定が不正な場合はフィルタなし）とゲインスケジュール（速度が昇順でない場合は
+/// スケジュールなし）を設計し、運転中に反映できない変更があるかを判定する。
+///
+/// # 引数
+/// * `config` - 検証済みの設定
+/// * `previous` - 前回制御割り込みに渡した検証済みの設定
+/// * `speed_dt` - 制御割り込みが使用中の速度ループの制御周期 [s]（フィルタの設計に使用）
+///
+/// # 戻り値
+/// * `ControlConfig` - 制御割り込みに渡す設定
+fn prepare_config(config: StoredConfig, previous: &StoredConfig, speed_dt: f32) -> ControlConfig {
+    let sample_rate = 1.0 / speed_dt;
+
+    let mut speed_feedback_filter = SignalFilter::disabled();
+    if !speed_feedback_filter.configure(
+        FilterType::from_u8(config.speed_feedback_filter).unwrap_or(FilterType::None),
+        config.speed_feedback_filter_frequency,
+        config.speed_feedback_filter_shape,
+        config.speed_feedback_filter_length as usize,
+        sample_rate,
+    ) {
+        error!(
+            "Invalid speed feedback filter (type={}, frequency={}Hz, shape={}), filter disabled",
+            config.speed_feedback_filter,
+            config.speed_feedback_filter_frequency,
+            config.speed_feedback_filter_shape
+        );
+    }
+
+    let mut speed_output_filter = SignalFilter::disabled();
+    if !speed_output_filter.configure(
+        FilterType::from_u8(config.speed_output_filter).unwrap_or(FilterType::None),
+        config.speed_output_filter_frequency,
+        config.speed_output_filter_shape,
+        config.speed_output_filter_length as usize,
+        sample_rate,
+    ) {
+        error!(
+            "Invalid speed output filter (type={}, frequency={}Hz, shape={}), filter disabled",
+            config.speed_output_filter,
+            config.speed_output_filter_frequency,
+            config.speed_output_filter_shape
+        );
+    }
+
+    let mut speed_schedule = GainSchedule::new();
+    let points = (config.speed_schedule_points as usize).min(pid::MAX_SCHEDULE_POINTS);
+    for ((&speed, &kp), &ki) in config
+        .speed_schedule_speeds
+        .iter()
+        .zip(&config.speed_schedule_kp)
+        .zip(&config.speed_schedule_ki)
+        .take(points)
+    {
+        if !speed_schedule.push(speed, kp, ki) {
+            error!(
+                "Invalid speed gain schedule (point at {} RPM is not above the previous point), schedule disabled",
+                speed
+            );
+            speed_schedule.clear();
+            break;
+        }
+    }
+
+    ControlConfig {
+        config,
+        speed_feedback_filter,
+        speed_output_filter,
+        speed_schedule,
+        requires_stop: requires_stop_to_apply(previous, &config),
+    }
+}
+
+/// 制御割り込みのイベントをログに出力
+///
+/// # 引数
+/// * `event` - イベント
+/// * `report` - イベントを受け取った制御結果（Hall診断・同定・自動調整の結果を参照）
+/// * `config` - 制御割り込みに渡した検証済みの設定
+fn log_event(event: ControlEvent, report: &ControlReport, config: &StoredConfig) {
+    match event {
+        ControlEvent::DriveStateChanged { from, to } => {
+            info!("Drive state: {} -> {}", from.name(), to.name());
+            if from == DriveState::Fault {
+                info!("Drive fault cleared");
+            }
+        }
+        ControlEvent::TransitionRejected { from, to } => error!(
+            "Illegal drive state transition rejected: {} -> {}",
+            from.name(),
+            to.name()
+        ),
+        ControlEvent::DriveFault { state, faults } => error!(
+            "Drive fault in {} state: flags={:#04x}, disabling motor",
+            state.name(),
+            faults
+        ),
+        ControlEvent::RequestRejected {
+            request,
+            state,
+            faults,
+        } => error!(
+            "{} request rejected in {} state: faults={:#04x}",
+            request.name(),
+            state.name(),
+            faults
+        ),
+        ControlEvent::HallFaultLatched => {
+            let status = report.hall_diagnostics;
+            error!(
+                "Hall sensor fault latched: flags={:#04x} (invalid={}, skipped={}, glitches={}, wrong_dir={})",
+                status.fault_flags,
+                status.invalid_states,
+                status.skipped_sectors,
+                status.glitches,
+                status.wrong_direction_edges
+            );
+        }
+        ControlEvent::HallFaultCleared => info!("Hall sensor fault cleared"),
+        ControlEvent::HallSignalLost => {
+            error!("Hall signal lost, continuing with sensorless angle")
+        }
+        ControlEvent::HallSignalRestored => {
+            info!("Hall signal restored, switching back to hall angle")
+        }
+        ControlEvent::CurrentOffsets(offsets) => info!(
+            "Current sense offsets: U={}, V={}, W={}",
+            offsets[0], offsets[1], offsets[2]
+        ),
+        ControlEvent::CurrentOffsetsInvalid => {
+            error!("Current sense offset calibration failed (offsets out of range)")
+        }
+        ControlEvent::MotorStarted {
+            direct_foc,
+            current_loop,
+            angle_source,
+        } => {
+            if config.current_control_enabled && !current_loop {
+                error!("Current control disabled: falling back to voltage mode");
+            }
+            if config.angle_source != AngleSource::Hall as u8 && angle_source == AngleSource::Hall
+            {
+                error!("Sensorless angle disabled: falling back to hall sensor");
+            }
+            info!(
+                "Motor control loop: Starting with {} mode (current loop: {}, angle source: {})",
+                if direct_foc { "direct FOC" } else { "OpenLoop" },
+                current_loop,
+                angle_source as u8
+            );
+        }
+        ControlEvent::CalibrationStarted { torque } => info!(
+            "Starting motor calibration: pole_pairs={}, torque={}",
+            config.pole_pairs, torque
+        ),
+        ControlEvent::CalibrationFailed => error!("Calibration failed, motor stopped"),
+        ControlEvent::IdentificationStarted {
+            current,
+            speed,
+            mechanical,
+        } => info!(
+            "Identification started: current={}A, speed={} RPM, mechanical={}",
+            current, speed, mechanical
+        ),
+        ControlEvent::IdentificationNeedsCurrentSensing => {
+            error!("Identification requires calibrated current sensing, request ignored")
+        }
+        ControlEvent::IdentificationFinished(failure) => {
+            let result = report.identification_result;
+            if report.identification_state != IdentificationState::Completed {
+                error!(
+                    "Identification failed: {}",
+                    failure.map_or("aborted", |failure| failure.name())
+                );
+                return;
+            }
+            info!(
+                "Identification completed: resistance={}ohm, inductance={}H, flux_linkage={}Wb",
+                result.resistance, result.inductance, result.flux_linkage
+            );
+            if result.mechanical_valid {
+                info!(
+                    "Identification: inertia={}kgm2, friction={}Nm, viscous={}Nms/rad",
+                    result.inertia, result.friction_torque, result.viscous_friction
+                );
+            } else if let Some(failure) = failure {
+                error!(
+                    "Identification: mechanical parameters skipped ({})",
+                    failure.name()
+                );
+            }
+        }
+        ControlEvent::AutoTuneRejected => {
+            error!("Auto-tune requires running in closed-loop speed mode, request ignored")
+        }
+        ControlEvent::AutoTuneFailed(failure) => error!("Auto-tune rejected: {}", failure.name()),
+        ControlEvent::AutoTuneFinished { rpm, failure } => {
+            let result = report.autotune_result;
+            match failure {
+                None => info!(
+                    "Auto-tune finished: Kp={}, Ki={} (Ku={}, Tu={}s)",
+                    result.kp, result.ki, result.ultimate_gain, result.ultimate_period
+                ),
+                Some(failure) => error!("Auto-tune failed: {}", failure.name()),
+            }
+            info!("Returning to speed mode at {} RPM", rpm);
+        }
+        ControlEvent::ModeRejected(ControlMode::Torque) => {
+            error!("Torque mode requires current control, request ignored")
+        }
+        ControlEvent::ModeRejected(_) => {
+            error!("Position mode requires hall sensor, request ignored")
+        }
+        ControlEvent::ModeSwitched { mode, at } => match mode {
+            ControlMode::ClosedLoopFoc => info!("Switching to speed mode at {} RPM", at),
+            ControlMode::Position => info!("Switching to position mode at {} rad", at),
+            ControlMode::Torque => info!("Switching to torque mode"),
+            _ => info!("Switching to voltage mode"),
+        },
+        ControlEvent::FocStarted { rpm } => info!(
+            "Switching to FOC mode at {} RPM: Hall state valid, target speed reached",
+            rpm
+        ),
+        ControlEvent::HandoverComplete { rpm } => {
+            info!("OpenLoop handover complete at {} RPM", rpm)
+        }
+        ControlEvent::SensorlessLost => {
+            info!("Sensorless angle lost, restarting with OpenLoop mode")
+        }
+        ControlEvent::DirectStartSucceeded => info!("Direct FOC start succeeded"),
+        ControlEvent::DirectStartFailed => {
+            error!("Direct FOC start failed, falling back to OpenLoop mode")
+        }
+        ControlEvent::ConfigApplied => info!(
+            "Runtime config applied: pole_pairs={}, max_voltage={}V, v_dc_bus={}V, alpha={}, interpolation={}",
+            config.pole_pairs,
+            config.max_voltage,
+            config.v_dc_bus,
+            config.speed_filter_alpha,
+            config.enable_angle_interpolation
+        ),
+        ControlEvent::ConfigDeferred => {
+            info!("Config change will be applied when the motor is disabled")
+        }
+    }
+}
+
+/// 制御ループの状態と制御割り込みの処理時間をログに出力（ステータスログの間隔ごと）
+fn log_status(report: &ControlReport) {
+    let status = &report.status;
+    debug!(
+        "[Status] State: {}, Mode: {}, Speed: {} RPM, Id: {} A, Iq: {} A, Position: {} rad",
+        report.drive.state.name(),
+        report.drive.control_mode as u8,
+        status.speed_rpm,
+        status.id_current,
+        status.iq_current,
+        status.position
+    );
+
+    // 処理時間を制御周期に対するCPU使用率 [%] に換算
+    let load = report.load;
+    let percent_per_cycle =
+        100.0 * report.rates.current_loop_hz as f32 / timing::CPU_CLOCK_HZ as f32;
+    debug!(
+        "[Status] Control ISR: {} cycles ({}%), max {} cycles ({}%)",
+        load.last_cycles,
+        load.last_cycles as f32 * percent_per_cycle,
+        load.max_cycles,
+        load.max_cycles as f32 * percent_per_cycle
+    );
+}
+
+/// 要求フラグを読み出してクリア
+async fn take_request_flag(flag: &Mutex<ThreadModeRawMutex, bool>) -> bool {
+    let mut request = flag.lock().await;
+    core::mem::replace(&mut *request, false)
+}
+
+/// 共有状態から制御割り込みへの指令を更新
+///
+/// Hall故障クリア要求フラグはクリアし、要求があれば通し番号を進める（制御割り込みは番号の変化で要求を検出する）。
+async fn update_command(command: &mut ControlCommand) {
+    command.target_speed = *TARGET_SPEED.lock().await;
+    command.target_current = *TARGET_CURRENT.lock().await;
+    command.target_voltage = *TARGET_VOLTAGE.lock().await;
+    command.target_position = *TARGET_POSITION.lock().await;
+    command.speed_gains = *SPEED_PI_GAINS.lock().await;
+    let voltage_state = *VOLTAGE_STATE.lock().await;
+    command.measured_v_dc = voltage_state.voltage;
+    command.voltage_faults = voltage_faults(voltage_state.overvoltage, voltage_state.undervoltage);
+    command.calibration = *CALIBRATION_RESULT.lock().await;
+
+    if take_request_flag(&HALL_FAULT_CLEAR_REQUEST).await {
+        command.hall_fault_clear_request = command.hall_fault_clear_request.wrapping_add(1);
+    }
+}
+
+/// 制御割り込みからの書き込み要求を共有状態に反映
+async fn apply_writeback(writeback: &Writeback) {
+    if let Some(result) = writeback.calibration {
+        info!("Calibration completed successfully!");
+        info!(
+            "  Electrical offset: {} rad ({} deg)",
+            result.electrical_offset,
+            result.electrical_offset * 180.0 / core::f32::consts::PI
+        );
+        info!("  Direction inversed: {}", result.direction_inversed);
+        if result.sector_table_valid {
+            info!("  Sector boundaries (rad): {}", result.sector_angles);
+        }
+        *CALIBRATION_RESULT.lock().await = result;
+    }
+    if let Some(result) = writeback.identification {
+        let mut runtime_config = RUNTIME_CONFIG.lock().await;
+        if result.electrical_valid {
+            runtime_config.motor_resistance = result.resistance;
+            runtime_config.motor_inductance = result.inductance;
+            runtime_config.motor_flux_linkage = result.flux_linkage;
+        }
+        if result.mechanical_valid {
+            runtime_config.motor_inertia = result.inertia;
+            runtime_config.motor_friction_torque = result.friction_torque;
+            runtime_config.motor_viscous_friction = result.viscous_friction;
+        }
+    }
+}
+
+/// 制御結果を共有状態に反映（CAN送信用、駆動状態は電圧監視・LEDも参照）
+async fn publish_report(report: &ControlReport) {
+    *MOTOR_STATUS.lock().await = report.status;
+    *HALL_DIAGNOSTICS.lock().await = report.hall_diagnostics;
+    *CONTROL_RATES.lock().await = report.rates;
+    *DRIVE_STATUS.lock().await = report.drive;
+    *AUTOTUNE_STATE.lock().await = report.autotune_state;
+    *AUTOTUNE_RESULT.lock().await = report.autotune_result;
+    *IDENTIFICATION_STATE.lock().await = report.identification_state;
+    *IDENTIFICATION_RESULT.lock().await = report.identification_result;
+}
+
+/// モーター制御タスク
+///
+/// 制御ループを生成してTIM1更新割り込み（PWM周波数の固定分周）に渡し、以降は
+/// 共有状態と制御割り込みの間で指令・ランタイム設定・制御結果を受け渡す。
+#[embassy_executor::task]
+pub async fn motor_control_task(uvw_pwm: ComplementaryPwm<'static, peripherals::TIM1>) {
+    info!("Motor control task started (OpenLoop + FOC mode)");
+
+    // モータードライバー初期化
+    let mut motor_driver = MotorDriver::new(uvw_pwm);
+    let max_duty = motor_driver.max_duty();
+
+    // ランタイム設定を取得（起動時にフラッシュから読み込まれた設定、またはCANで変更された設定）
+    // 以降の変更は変更回数で検出する
+    let (mut config_generation, runtime_config) = {
+        let runtime = RUNTIME_CONFIG.lock().await;
+        (runtime.generation(), **runtime)
+    };
+
+    // 3相とも相電流をサンプリングできるようにDuty比を制限
+    // （デッドタイムは起動時の設定値で固定、タイマー設定と同じ値）
+    let duty_limit = current_sense::max_sampled_duty(max_duty, runtime_config.pwm_dead_time);
+    motor_driver.set_duty_limit(duty_limit);
+
+    // 制御周期（起動時の設定値で固定、変更は再起動後に反映）
+    // PWM周期の整数倍に丸めるため、実際の周期は設定値と僅かに異なる場合がある
+    let divider = timing::control_divider(
+        runtime_config.pwm_frequency,
+        runtime_config.control_period_us,
+    );
+    let dt = divider as f32 / runtime_config.pwm_frequency as f32;
+
+    // 設定を検証して速度ループのフィルタを設計し、制御割り込みに渡す
+    let defaults = StoredConfig::default();
+    let validated = validate_config(&runtime_config, &defaults);
+    let mut design_divider = validated.speed_loop_divider;
+    let mut prepared = prepare_config(validated, &defaults, dt * design_divider as f32);
+    CONTROL_CONFIG.write(prepared);
+
+    // 最初の指令を書き込んでから制御ループを生成（保存済みのキャリブレーション結果を使用）
+    let mut command = ControlCommand::new();
+    update_command(&mut command).await;
+    CONTROL_COMMAND.write(command);
+    let control_loop = ControlLoop::new(motor_driver, prepared, command, dt);
+
+    let current_loop_hz = runtime_config.pwm_frequency / divider;
+    let speed_divider = control_loop.scheduler.speed_divider() as u32;
+    let position_divider = control_loop.scheduler.position_divider() as u32;
+    info!(
+        "FOC parameters: Pole pairs={}, Control freq={}Hz (PWM/{}), dt={}s",
+        control_loop.angle_sensor.hall.get_pole_pairs(),
+        current_loop_hz,
+        divider,
+        dt
+    );
+    info!(
+        "Loop rates: Current={}Hz, Speed={}Hz, Position={}Hz",
+        current_loop_hz,
+        current_loop_hz / speed_divider,
+        current_loop_hz / (speed_divider * position_divider)
+    );
+    info!(
+        "PWM configuration: Frequency={}Hz, Max duty={}, Sampled duty limit={}",
+        runtime_config.pwm_frequency, max_duty, duty_limit
+    );
+
+    // 制御ループを制御割り込みに渡してから割り込みを有効化
+    // 安全性: 割り込みの有効化前のため、制御割り込みと同時にアクセスすることはない
+    unsafe {
+        (*CONTROL_LOOP.0.get()).write(control_loop);
+        hardware::init_control_timer(divider);
+    }
+
+    let exchange_period = Duration::from_micros(timing::EXCHANGE_PERIOD_US);
+    let mut events_seen = 0;
+    let mut status_log_counter = 0;
+
+    loop {
+        // 1. 制御結果を受け取り、イベントをログに出力して書き込み要求と状態を共有状態に反映
+        let report = CONTROL_REPORT.read();
+        let (dropped, events) = report.events.since(events_seen);
+        if dropped > 0 {
+            error!("{} control events dropped", dropped);
+        }
+        for event in events {
+            log_event(event, &report, &prepared.config);
+        }
+        events_seen = report.events.count();
+
+        if report.writeback.sequence != command.writeback_ack {
+            apply_writeback(&report.writeback).await;
+            command.writeback_ack = report.writeback.sequence;
+        }
+        publish_report(&report).await;
+
+        status_log_counter += 1;
+        if status_log_counter >= timing::STATUS_LOG_INTERVAL {
+            status_log_counter = 0;
+            log_status(&report);
+        }
+
+        // 2. ランタイム設定の変更、または制御割り込みの速度ループの分周比の変更（停止時に反映）を
+        // 検出したら、検証・フィルタ設計をやり直して制御割り込みに渡す（適用は制御割り込みのループ先頭）
+        let latest_config = {
+            let runtime = RUNTIME_CONFIG.lock().await;
+            let changed = runtime.generation() != config_generation;
+            config_generation = runtime.generation();
+            changed.then(|| **runtime)
+        };
+        let divider_changed =
+            report.rates.current_loop_hz != 0 && report.rates.speed_divider != design_divider;
+        if latest_config.is_some() || divider_changed {
+            let previous = prepared.config;
+            let validated = match latest_config {
+                Some(latest_config) => validate_config(&latest_config, &previous),
+                None => previous,
+            };
+            // フィルタは制御割り込みが使用中の速度ループの周期で設計する
+            if report.rates.current_loop_hz != 0 {
+                design_divider = report.rates.speed_divider;
+            }
+            prepared = prepare_config(validated, &previous, dt * design_divider as f32);
+            CONTROL_CONFIG.write(prepared);
+        }
+
+        // 3. 指令を更新（書き込み要求の反映後に読み出すため、無効化等は同じ指令で返る）
+        let previous_gains = command.speed_gains;
+        update_command(&mut command).await;
+        if command.speed_gains != previous_gains {
+            info!(
+                "Speed PI gains updated: Kp={}, Ki={}",
+                command.speed_gains.0, command.speed_gains.1
+            );
         }
+        CONTROL_COMMAND.write(command);
 
-        Timer::after(Duration::from_micros(DEFAULT_CONTROL_PERIOD_US)).await;
+        Timer::after(exchange_period).await;
     }
 }
@@ -2,55 +2,40 @@
 //!
 //! モーターの電気角オフセットと回転方向を自動検出します。
 
-use core::f32::consts::PI;
-
-use crate::config::*;
-use crate::fmt::*;
-use crate::foc::{calculate_svpwm, inverse_park, ControlMode, HallSensor, MotorCalibration};
+use super::exchange::{ControlEvent, ControlReport};
+use super::CurrentLoop;
+use crate::foc::{inverse_park, ControlMode, HallSensor, MotorCalibration};
 use crate::motor_driver::MotorDriver;
-use crate::state::{CALIBRATION_RESULT, CONTROL_MODE};
 
 /// キャリブレーション制御の実行
 ///
 /// # 引数
 /// * `calibration` - キャリブレーションコントローラー
 /// * `hall_sensor` - Hallセンサー
+/// * `current_loop` - 電流制御ループ（電圧制限とPWM変調を使用）
 /// * `motor_driver` - モータードライバー
 /// * `dt` - 制御周期 [秒]
+/// * `report` - タスクへの制御結果（キャリブレーション結果の保存、失敗時はイベントを書き込む）
 ///
 /// # 戻り値
 /// * `Option<ControlMode>` - 完了時は次のモード（ClosedLoopFocまたはOpenLoop）、継続中はNone
-pub async fn execute(
+pub fn execute(
     calibration: &mut MotorCalibration,
     hall_sensor: &mut HallSensor,
+    current_loop: &CurrentLoop,
     motor_driver: &mut MotorDriver,
     dt: f32,
+    report: &mut ControlReport,
 ) -> Option<ControlMode> {
     // Hall センサーを更新して現在の角度を取得
     let (_electrical_angle, _speed_rpm) = hall_sensor.update(dt);
     let sensor_angle = hall_sensor.get_mechanical_angle();
 
-    // デバッグ：Hall状態と角度を定期的にログ出力（2500サイクルごと = 1秒）
-    static mut DEBUG_COUNTER: u32 = 0;
-    unsafe {
-        DEBUG_COUNTER += 1;
-        if DEBUG_COUNTER >= 2500 {
-            DEBUG_COUNTER = 0;
-            let hall_state = crate::hall_tim::get_hall_state();
-            info!(
-                "[Calibration Execute] Hall state={}, sensor_angle={} rad ({} deg)",
-                hall_state,
-                sensor_angle,
-                sensor_angle * 180.0 / PI
-            );
-        }
-    }
-
     // キャリブレーションステートマシンを更新
     match calibration.update(sensor_angle) {
         Ok((electrical_angle, torque)) => {
-            // トルクから電圧指令を計算（トルク 0.0～1.0 → 電圧 0～MAX_VOLTAGE）
-            let v_cmd = torque * DEFAULT_MAX_VOLTAGE;
+            // トルクから電圧指令を計算（トルク 0.0～1.0 → 電圧 0～電圧制限）
+            let v_cmd = torque * current_loop.voltage_limit;
 
             // d軸・q軸電圧（キャリブレーション中はシンプルにq軸のみ）
             let vd_cmd = 0.0;
@@ -62,7 +47,7 @@ pub async fn execute(
             // SVPWM計算（実際のPWM最大値を使用）
             let pwm_max_duty = motor_driver.max_duty();
             let (duty_u, duty_v, duty_w) =
-                calculate_svpwm(v_alpha, v_beta, DEFAULT_V_DC_BUS, pwm_max_duty);
+                current_loop.modulator.duties(v_alpha, v_beta, pwm_max_duty);
 
             // PWM出力
             motor_driver.set_duty_uvw(duty_u, duty_v, duty_w);
@@ -75,52 +60,36 @@ pub async fn execute(
                 let result = calibration.get_result();
 
                 if result.success {
-                    info!("Calibration completed successfully!");
-                    info!(
-                        "  Electrical offset: {} rad ({} deg)",
-                        result.electrical_offset,
-                        result.electrical_offset * 180.0 / PI
-                    );
-                    info!("  Direction inversed: {}", result.direction_inversed);
+                    // 結果をグローバル状態に保存（結果のログはタスクが出力）
+                    report.writeback.save_calibration(result);
 
-                    // 結果をグローバル状態に保存
+                    // Hall センサーに結果を適用（セクター境界テーブルが有効ならそちらを優先）
+                    if !(result.sector_table_valid
+                        && hall_sensor.set_sector_angles(&result.sector_angles))
                     {
-                        let mut saved_result = CALIBRATION_RESULT.lock().await;
-                        *saved_result = result;
+                        hall_sensor.reset_sector_angles();
+                        hall_sensor.set_electrical_offset(result.electrical_offset);
                     }
-
-                    // Hall センサーに結果を適用
-                    hall_sensor.set_electrical_offset(result.electrical_offset);
                     // TODO: 方向反転の適用（HallSensor に direction_inversed を追加する必要がある）
 
                     // ClosedLoopFocモードに切り替え
-                    let mut mode = CONTROL_MODE.lock().await;
-                    *mode = ControlMode::ClosedLoopFoc;
-
-                    info!("Switching to ClosedLoopFoc mode");
                     return Some(ControlMode::ClosedLoopFoc);
                 } else {
-                    error!("Calibration failed!");
+                    report.events.push(ControlEvent::CalibrationFailed);
                     // エラー時はモーターを停止
                     motor_driver.stop();
 
                     // OpenLoopモードに戻る
-                    let mut mode = CONTROL_MODE.lock().await;
-                    *mode = ControlMode::OpenLoop;
-
                     return Some(ControlMode::OpenLoop);
                 }
             }
         }
         Err(_) => {
-            error!("Calibration update error, stopping motor");
+            report.events.push(ControlEvent::CalibrationFailed);
             // エラー時はモーターを停止
             motor_driver.stop();
 
             // OpenLoopモードに戻る
-            let mut mode = CONTROL_MODE.lock().await;
-            *mode = ControlMode::OpenLoop;
-
             return Some(ControlMode::OpenLoop);
         }
     }
@@ -0,0 +1,422 @@
+//! 制御割り込みとモーター制御タスクの受け渡し
+//!
+//! 制御割り込みは`Mutex`を待てないため、モーター制御タスクが`state`の共有状態を読み出して
+//! 指令（`ControlCommand`）と設定を、制御割り込みが制御結果（`ControlReport`）を
+//! それぞれダブルバッファに書き込み、相手側が最新の値を読み出します。
+//!
+//! 駆動要求（始動・停止・制御モード・キャリブレーション・パラメータ同定・自動調整）は
+//! 制御割り込みが`state::DRIVE_REQUESTS`から直接受信し、運転要求・外部指令モードは制御割り込みが保持します。
+//! Hall故障クリア要求は通し番号で受け渡し、制御割り込みは前回処理した番号から変わった場合に1回だけ処理します。
+//! 制御割り込みから共有状態への書き込み（キャリブレーション結果・同定したモーター定数の保存）は
+//! `Writeback`で要求し、
+//! タスクが反映して通し番号を返すまで制御割り込みは要求した値を優先して使います。
+//!
+//! ランタイム設定はタスクが検証し、速度ループのフィルタ・ゲインスケジュールを設計してから
+//! （`ControlConfig`）変更時のみ書き込みます。制御割り込みは書き込み回数の変化で更新を検出します。
+//! 制御割り込みはログを出力せず、遷移・拒否・完了などのイベントを`ControlEvents`で返し、
+//! タスクがログに出力します。
+
+use crate::can_protocol::{ControlRates, MotorStatus};
+use crate::config::StoredConfig;
+use crate::double_buffer::DoubleBuffer;
+use crate::drive_state::{DriveRequest, DriveState, DriveStatus};
+use crate::foc::{
+    AngleSource, AutoTuneFailure, AutoTuneResult, AutoTuneState, CalibrationResult, ControlMode,
+    GainSchedule, HallDiagnosticsStatus, IdentificationFailure, IdentificationResult,
+    IdentificationState, SignalFilter,
+};
+
+/// タスクから制御割り込みへのランタイム設定
+///
+/// 検証・フィルタ設計などの重い処理はタスクで済ませ、制御割り込みは適用のみ行う。
+#[derive(Clone, Copy)]
+pub struct ControlConfig {
+    /// 検証済みのランタイム設定（不正な値は前回の値に置き換え済み）
+    pub config: StoredConfig,
+    /// 速度フィードバックのフィルタ（速度ループの周期で設計済み、不正な設定ではフィルタなし）
+    pub speed_feedback_filter: SignalFilter,
+    /// 速度PI出力のフィルタ（同上）
+    pub speed_output_filter: SignalFilter,
+    /// 速度PIのゲインスケジュール（速度が昇順でない場合は空）
+    pub speed_schedule: GainSchedule,
+    /// 前回の設定から運転中に反映できないパラメータが変わったか
+    pub requires_stop: bool,
+}
+
+impl ControlConfig {
+    /// デフォルト設定を作成（タスクが最初の設定を書き込むまで使用）
+    pub const fn new() -> Self {
+        Self {
+            config: StoredConfig::default(),
+            speed_feedback_filter: SignalFilter::disabled(),
+            speed_output_filter: SignalFilter::disabled(),
+            speed_schedule: GainSchedule::new(),
+            requires_stop: false,
+        }
+    }
+}
+
+/// 制御割り込みで発生したイベント（タスクがログに出力する）
+#[derive(Clone, Copy)]
+pub enum ControlEvent {
+    /// 駆動状態が遷移した
+    DriveStateChanged { from: DriveState, to: DriveState },
+    /// 未定義の駆動状態の遷移を拒否した
+    TransitionRejected { from: DriveState, to: DriveState },
+    /// 故障を検出して運転要求を解除した（`state`は故障前の状態）
+    DriveFault { state: DriveState, faults: u8 },
+    /// 駆動要求を拒否した（要求時の駆動状態・故障要因）
+    RequestRejected {
+        request: DriveRequest,
+        state: DriveState,
+        faults: u8,
+    },
+    /// Hallセンサーの故障をラッチした（詳細は`ControlReport::hall_diagnostics`）
+    HallFaultLatched,
+    /// Hallセンサーの故障をクリアした
+    HallFaultCleared,
+    /// Hall状態が無効になり、オブザーバの角度で運転を継続した
+    HallSignalLost,
+    /// Hall状態が復帰し、Hallの角度に戻した
+    HallSignalRestored,
+    /// 相電流のゼロ点オフセットを校正した（U, V, W）
+    CurrentOffsets([f32; 3]),
+    /// 相電流のゼロ点オフセットが範囲外で校正に失敗した
+    CurrentOffsetsInvalid,
+    /// 始動した（直接FOC始動か、電流制御の有無、角度の取得元）
+    MotorStarted {
+        direct_foc: bool,
+        current_loop: bool,
+        angle_source: AngleSource,
+    },
+    /// キャリブレーションを開始した（トルク 0.0-1.0）
+    CalibrationStarted { torque: f32 },
+    /// キャリブレーションに失敗した（成功時は`Writeback::calibration`で結果を返す）
+    CalibrationFailed,
+    /// パラメータ同定を開始した（試験電流 [A]、試験速度 [RPM]、機械定数を測定するか）
+    IdentificationStarted {
+        current: f32,
+        speed: f32,
+        mechanical: bool,
+    },
+    /// 電流センサーが未校正のためパラメータ同定要求を拒否した
+    IdentificationNeedsCurrentSensing,
+    /// パラメータ同定が終了した（結果は`ControlReport::identification_result`）
+    IdentificationFinished(Option<IdentificationFailure>),
+    /// 速度制御で運転中でないため自動調整要求を拒否した
+    AutoTuneRejected,
+    /// 自動調整を開始できなかった
+    AutoTuneFailed(AutoTuneFailure),
+    /// 自動調整が終了し、速度制御に戻った（結果は`ControlReport::autotune_result`）
+    AutoTuneFinished {
+        rpm: f32,
+        failure: Option<AutoTuneFailure>,
+    },
+    /// 外部指令の制御モードを拒否した
+    ModeRejected(ControlMode),
+    /// 外部指令で制御モードを切り替えた（速度制御は現在速度 [RPM]、位置制御は現在位置 [rad]）
+    ModeSwitched { mode: ControlMode, at: f32 },
+    /// オープンループ始動からFOCに切り替えた
+    FocStarted { rpm: f32 },
+    /// オープンループからのハンドオーバーが完了した
+    HandoverComplete { rpm: f32 },
+    /// センサレス運転で推定を失い、オープンループから再始動した
+    SensorlessLost,
+    /// 停止状態からのFOC直接始動に成功した
+    DirectStartSucceeded,
+    /// FOC直接始動で回転せず、オープンループ始動にフォールバックした
+    DirectStartFailed,
+    /// ランタイム設定をすべて適用した
+    ConfigApplied,
+    /// 運転中に反映できない設定変更を停止時まで保留した
+    ConfigDeferred,
+}
+
+/// 制御割り込みのイベントのリングバッファ
+///
+/// 制御結果とともに毎周期書き込まれ、タスクは前回読み出した件数以降のイベントを取り出す。
+/// タスクが読み出すまでに`EVENT_QUEUE_LEN`件を超えた古いイベントは上書きされる（件数は分かる）。
+#[derive(Clone, Copy)]
+pub struct ControlEvents {
+    /// イベント（件数 % `EVENT_QUEUE_LEN`の位置に書き込む）
+    entries: [ControlEvent; EVENT_QUEUE_LEN],
+    /// 追加したイベントの総数（ラップアラウンドあり）
+    count: u32,
+}
+
+/// イベントのリングバッファの容量（2のべき乗、件数のラップアラウンドと整合させる）
+pub const EVENT_QUEUE_LEN: usize = 8;
+
+impl ControlEvents {
+    /// 空のリングバッファを作成
+    pub const fn new() -> Self {
+        Self {
+            entries: [ControlEvent::ConfigApplied; EVENT_QUEUE_LEN],
+            count: 0,
+        }
+    }
+
+    /// イベントを追加（呼び出し箇所が多いためインライン展開しない）
+    #[inline(never)]
+    pub fn push(&mut self, event: ControlEvent) {
+        self.entries[self.count as usize % EVENT_QUEUE_LEN] = event;
+        self.count = self.count.wrapping_add(1);
+    }
+
+    /// 追加したイベントの総数を取得
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// 指定した件数以降に追加されたイベントを取得
+    ///
+    /// # 引数
+    /// * `seen` - 読み出し済みのイベントの総数（前回の`count()`）
+    ///
+    /// # 戻り値
+    /// * `(u32, impl Iterator)` - (上書きされて失われた件数, 古い順のイベント)
+    pub fn since(&self, seen: u32) -> (u32, impl Iterator<Item = ControlEvent> + '_) {
+        let pending = self.count.wrapping_sub(seen);
+        let available = pending.min(EVENT_QUEUE_LEN as u32);
+        let first = self.count.wrapping_sub(available);
+        let events = (0..available)
+            .map(move |i| self.entries[first.wrapping_add(i) as usize % EVENT_QUEUE_LEN]);
+        (pending - available, events)
+    }
+}
+
+/// 制御割り込みの処理時間（DWTサイクルカウンタ、CPUクロック単位）
+#[derive(Clone, Copy)]
+pub struct ControlLoad {
+    /// 前回の制御周期の処理時間 [cycles]
+    pub last_cycles: u32,
+    /// 起動後の最大処理時間 [cycles]
+    pub max_cycles: u32,
+}
+
+impl ControlLoad {
+    /// 未計測の状態を作成
+    pub const fn new() -> Self {
+        Self {
+            last_cycles: 0,
+            max_cycles: 0,
+        }
+    }
+
+    /// 制御周期の処理時間を記録
+    ///
+    /// # 引数
+    /// * `cycles` - 処理時間 [cycles]
+    pub fn record(&mut self, cycles: u32) {
+        self.last_cycles = cycles;
+        self.max_cycles = self.max_cycles.max(cycles);
+    }
+}
+
+/// タスクから制御割り込みへの指令
+#[derive(Clone, Copy)]
+pub struct ControlCommand {
+    /// 目標速度 [RPM]
+    pub target_speed: f32,
+    /// トルク（q軸電流）指令 [A]
+    pub target_current: f32,
+    /// 電圧指令 (Vd, Vq) [V]
+    pub target_voltage: (f32, f32),
+    /// 位置指令 [rad]
+    pub target_position: f32,
+    /// 速度PIコントローラのゲイン (Kp, Ki)
+    pub speed_gains: (f32, f32),
+    /// DCバス電圧の実測値 [V]（電圧監視タスクのフィルタ済み値、未測定時は0）
+    pub measured_v_dc: f32,
+    /// 電圧監視で検出中の故障要因（`FAULT_OVERVOLTAGE` / `FAULT_UNDERVOLTAGE`）
+    pub voltage_faults: u8,
+    /// 保存済みのキャリブレーション結果
+    pub calibration: CalibrationResult,
+    /// Hall故障クリア要求の通し番号
+    pub hall_fault_clear_request: u32,
+    /// タスクが反映済みの`Writeback`の通し番号
+    pub writeback_ack: u32,
+}
+
+impl ControlCommand {
+    /// 停止状態の指令を作成（タスクが最初の指令を書き込むまで使用）
+    pub const fn new() -> Self {
+        Self {
+            target_speed: 0.0,
+            target_current: 0.0,
+            target_voltage: (0.0, 0.0),
+            target_position: 0.0,
+            speed_gains: (0.0, 0.0),
+            measured_v_dc: 0.0,
+            voltage_faults: 0,
+            calibration: CalibrationResult {
+                electrical_offset: 0.0,
+                direction_inversed: false,
+                success: false,
+                sector_angles: [0.0; 6],
+                sector_table_valid: false,
+            },
+            hall_fault_clear_request: 0,
+            writeback_ack: 0,
+        }
+    }
+}
+
+/// 制御割り込みから共有状態への書き込み要求
+#[derive(Clone, Copy)]
+pub struct Writeback {
+    /// 通し番号（要求を追加するたびにインクリメント）
+    pub sequence: u32,
+    /// キャリブレーション結果を保存する（`CALIBRATION_RESULT`）
+    pub calibration: Option<CalibrationResult>,
+    /// 同定したモーター定数をランタイム設定に反映する（`RUNTIME_CONFIG`）
+    pub identification: Option<IdentificationResult>,
+}
+
+impl Writeback {
+    /// 要求なしの状態を作成
+    pub const fn new() -> Self {
+        Self {
+            sequence: 0,
+            calibration: None,
+            identification: None,
+        }
+    }
+
+    /// キャリブレーション結果の保存を要求
+    pub fn save_calibration(&mut self, result: CalibrationResult) {
+        self.calibration = Some(result);
+        self.sequence = self.sequence.wrapping_add(1);
+    }
+
+    /// 同定したモーター定数の反映を要求
+    pub fn save_identification(&mut self, result: IdentificationResult) {
+        self.identification = Some(result);
+        self.sequence = self.sequence.wrapping_add(1);
+    }
+
+    /// タスクが反映済みの通し番号を受け取り、反映済みなら要求をクリア
+    ///
+    /// # 引数
+    /// * `ack` - タスクが反映済みの通し番号（`ControlCommand::writeback_ack`）
+    pub fn acknowledge(&mut self, ack: u32) {
+        if ack == self.sequence {
+            self.calibration = None;
+            self.identification = None;
+        }
+    }
+}
+
+/// 制御割り込みからタスクへの制御結果
+#[derive(Clone, Copy)]
+pub struct ControlReport {
+    /// モーターステータス（CAN送信用）
+    pub status: MotorStatus,
+    /// Hallセンサー診断ステータス
+    pub hall_diagnostics: HallDiagnosticsStatus,
+    /// 使用中の制御ループの周期（`CONTROL_RATES`に反映）
+    pub rates: ControlRates,
+    /// 駆動状態・制御モード・故障要因（`DRIVE_STATUS`に反映）
+    pub drive: DriveStatus,
+    /// 速度PIゲイン自動調整の進行状態
+    pub autotune_state: AutoTuneState,
+    /// 速度PIゲイン自動調整結果
+    pub autotune_result: AutoTuneResult,
+    /// パラメータ同定の進行状態
+    pub identification_state: IdentificationState,
+    /// パラメータ同定結果
+    pub identification_result: IdentificationResult,
+    /// 共有状態への書き込み要求
+    pub writeback: Writeback,
+    /// 制御割り込みで発生したイベント
+    pub events: ControlEvents,
+    /// 制御割り込みの処理時間（前回の制御周期までの計測値）
+    pub load: ControlLoad,
+}
+
+impl ControlReport {
+    /// 初期状態の制御結果を作成（`state`の共有状態の初期値と同じ）
+    pub const fn new() -> Self {
+        Self {
+            status: MotorStatus::new(),
+            hall_diagnostics: HallDiagnosticsStatus::new(),
+            rates: ControlRates::new(),
+            drive: DriveStatus::new(),
+            autotune_state: AutoTuneState::Idle,
+            autotune_result: AutoTuneResult::new(),
+            identification_state: IdentificationState::Idle,
+            identification_result: IdentificationResult::new(),
+            writeback: Writeback::new(),
+            events: ControlEvents::new(),
+            load: ControlLoad::new(),
+        }
+    }
+}
+
+/// タスクから制御割り込みへの指令
+pub static CONTROL_COMMAND: DoubleBuffer<ControlCommand> = DoubleBuffer::new(ControlCommand::new());
+
+/// タスクから制御割り込みへのランタイム設定（変更時のみ書き込む、書き込み回数が変更の通し番号）
+pub static CONTROL_CONFIG: DoubleBuffer<ControlConfig> = DoubleBuffer::new(ControlConfig::new());
+
+/// 制御割り込みからタスクへの制御結果（制御周期ごとに書き込む）
+pub static CONTROL_REPORT: DoubleBuffer<ControlReport> = DoubleBuffer::new(ControlReport::new());
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// イベントをキャリブレーション開始時のトルクとして取り出す（順序の確認用）
+    fn torques(events: impl Iterator<Item = ControlEvent>) -> [f32; EVENT_QUEUE_LEN] {
+        let mut torques = [-1.0; EVENT_QUEUE_LEN];
+        for (slot, event) in torques.iter_mut().zip(events) {
+            if let ControlEvent::CalibrationStarted { torque } = event {
+                *slot = torque;
+            }
+        }
+        torques
+    }
+
+    #[test]
+    fn test_events_since() {
+        let mut events = ControlEvents::new();
+        let (dropped, mut pending) = events.since(0);
+        assert_eq!(dropped, 0);
+        assert!(pending.next().is_none());
+
+        events.push(ControlEvent::CalibrationStarted { torque: 0.1 });
+        events.push(ControlEvent::CalibrationStarted { torque: 0.2 });
+        let (dropped, pending) = events.since(0);
+        assert_eq!(dropped, 0);
+        assert_eq!(torques(pending)[..3], [0.1, 0.2, -1.0]);
+
+        // 読み出し済みの件数以降のみ
+        let (_, pending) = events.since(1);
+        assert_eq!(torques(pending)[..2], [0.2, -1.0]);
+        assert_eq!(events.count(), 2);
+    }
+
+    #[test]
+    fn test_events_overwrite_oldest() {
+        let mut events = ControlEvents::new();
+        for i in 0..EVENT_QUEUE_LEN + 2 {
+            events.push(ControlEvent::CalibrationStarted { torque: i as f32 });
+        }
+        let (dropped, pending) = events.since(0);
+        assert_eq!(dropped, 2);
+        let torques = torques(pending);
+        assert_eq!(torques[0], 2.0);
+        assert_eq!(torques[EVENT_QUEUE_LEN - 1], (EVENT_QUEUE_LEN + 1) as f32);
+    }
+
+    #[test]
+    fn test_events_count_wraps() {
+        let mut events = ControlEvents::new();
+        events.count = u32::MAX;
+        events.push(ControlEvent::CalibrationStarted { torque: 1.0 });
+        events.push(ControlEvent::CalibrationStarted { torque: 2.0 });
+        let (dropped, pending) = events.since(u32::MAX);
+        assert_eq!(dropped, 0);
+        assert_eq!(torques(pending)[..3], [1.0, 2.0, -1.0]);
+    }
+}
@@ -1,158 +1,759 @@
 //! FOC（Field Oriented Control）制御モード
 //!
-//! Hallセンサーベースのクローズドループ速度制御を実行します。
+//! Hallセンサーまたはセンサレス磁束オブザーバの角度でクローズドループ制御を実行します。
+//! - 速度制御: 速度PIの出力をq軸電圧指令（電流制御有効時はq軸電流指令）とする
+//! - トルク制御: 外部から与えたq軸電流指令をd/q軸電流PIで追従する（速度ループなし）
+//! - 電圧制御: 外部から与えたd/q軸電圧指令をそのまま出力する（電流・速度ループなし）
+//! - 位置制御: 位置P制御の出力を速度指令とし、速度制御と同じ速度PIに渡す（カスケード制御）
+//!
+//! 角度の取得元（`AngleSensor::source`）:
+//! - Hall: Hall状態が無効な場合はモーターを停止する
+//! - センサレス: オブザーバがロックしていない（最低速度未満等）場合はモーターを停止する
+//! - Hall＋フォールバック: Hall状態が無効な間、オブザーバがロックしていればその角度で運転を継続する
+//!   （この間、複数回転の位置は更新されない）
+//!
+//! Hall故障（セクター飛び・逆回転・固着・グリッチ）がラッチされている間はHall状態を無効として扱う。
+//!
+//! 速度・位置・トルク制御では、電圧ベクトルが飽和すると弱め界磁（`CurrentLoop::field_weakening`）が
+//! 負のd軸指令を注入し、基底速度を超えて回転できるようにする。
+//!
+//! 速度・位置制御では、速度PIの出力に速度指令から計算したフィードフォワード
+//! （`SpeedLoop::feedforward`: 逆起電力・クーロン摩擦・粘性摩擦・加速トルク）を加算し、
+//! 速度PIはモデル誤差の補正のみを受け持つ。
+//!
+//! 速度PIのゲインスケジュール（`SpeedLoop::schedule`）が有効な場合は、速度ループの周期ごとに
+//! 現在速度の絶対値からゲインを補間し、タスクからの速度PIゲインの代わりに使う。
+//!
+//! 速度PIゲイン自動調整中は、速度PIの代わりに`SpeedAutoTune`のリレー出力を同じ単位
+//! （q軸電流指令またはq軸電圧指令）で出力する。
+//!
+//! 角度推定とd/q軸電流PIは制御周期ごとに実行し、速度ループ（速度プロファイル・速度PI・
+//! フィードフォワード・リレー出力）は`CycleInput::ticks`の速度ループの周期のみ、
+//! 位置P制御は位置ループの周期のみ、それぞれの周期（`speed_dt`・`position_dt`）で更新する。
+//! 間の周期は前回の出力（`SpeedLoop::output`・`PositionLoop::correction_speed`）を保持する。
+//!
+//! 電圧ベクトルは`CurrentLoop::voltage_limit`（最大電圧設定と実測DCバス電圧による変調の上限の
+//! 小さい方）で制限し、過変調が有効な場合は`CurrentLoop::modulator`で6ステップまでの軌跡に整形する。
+//! SVPWMのデューティは`CurrentLoop::dead_time`でデッドタイム補償・最小パルス制限を
+//! 適用してからタイマーに設定する。
+//!
+//! `fixed-point`フィーチャ有効時は、Clarke・Park変換、d/q軸電流PI、Park逆変換、SVPWMを
+//! 単位法のQ15（電流はセンサーの測定範囲、電圧は最大電圧設定を1とする）で計算する。
+//! 速度ループ・弱め界磁・オブザーバ・デッドタイム補償との受け渡しはA・Vのf32のまま。
 
+use super::exchange::{ControlEvent, ControlEvents, ControlReport};
+use super::{AngleSensor, CurrentLoop, CycleInput, PositionLoop, SpeedLoop};
+use crate::can_protocol::MotorStatus;
 use crate::config::*;
-use crate::fmt::*;
-use crate::foc::{calculate_svpwm, inverse_park, limit_voltage, HallSensor, PiController};
+use crate::current_sense;
+#[cfg(feature = "fixed-point")]
+use crate::foc::fixed_point::{self, phase_from_rad, Q15};
+#[cfg(not(feature = "fixed-point"))]
+use crate::foc::{clarke, inverse_park, park};
+use crate::foc::{limit_voltage, AngleSource, SpeedAutoTune, StartupHandover};
 use crate::hall_tim;
 use crate::motor_driver::MotorDriver;
-use crate::state::{MOTOR_STATUS, SPEED_PI_GAINS, TARGET_SPEED};
 
-/// FOC制御の実行
-///
-/// # 引数
-/// * `hall_sensor` - Hallセンサー
-/// * `speed_pi` - 速度PIコントローラー
-/// * `motor_driver` - モータードライバー
-/// * `ramped_target_speed` - ランプ処理後の目標速度
-/// * `dt` - 制御周期 [s]
+/// 1制御周期分のフィードバック（電気角・速度・相電流・d/q軸電流）
+struct Feedback {
+    /// 電気角 [rad]
+    electrical_angle: f32,
+    /// 機械角速度 [RPM]
+    speed_rpm: f32,
+    /// 相電流 (U, V, W) [A]（デッドタイム補償の極性判定に使用）
+    phase_currents: (f32, f32, f32),
+    /// d軸電流 [A]
+    id: f32,
+    /// q軸電流 [A]
+    iq: f32,
+    /// d/q軸電流（単位法のQ15、d/q軸電流PIの入力）
+    #[cfg(feature = "fixed-point")]
+    current_dq: (Q15, Q15),
+    /// 複数回転の機械角位置 [rad]（補間後のHall角度から追跡）
+    position: f32,
+}
+
+/// Hallセンサー・オブザーバと相電流からフィードバックを取得
 ///
-/// # 戻り値
-/// * `(bool, f32)` - (Hall状態が有効か, 新しいランプ速度)
-pub async fn execute(
-    hall_sensor: &mut HallSensor,
-    speed_pi: &mut PiController,
+/// 角度の取得元から角度が得られない場合（Hall状態が無効、またはオブザーバが
+/// ロックしていない）はモーターを停止して電流PIをリセットし、`None`を返す。
+fn update_feedback(
+    angle_sensor: &mut AngleSensor,
+    current_loop: &mut CurrentLoop,
     motor_driver: &mut MotorDriver,
-    ramped_target_speed: &mut f32,
+    config: &StoredConfig,
     dt: f32,
-) -> bool {
-    // Hall状態の確認（有効な状態：1-6）
+    events: &mut ControlEvents,
+) -> Option<Feedback> {
+    // 相電流を取得してαβ軸に変換（abc → αβ）
+    let (i_u, i_v, i_w) = current_loop
+        .sensor
+        .phase_currents(current_sense::read_raw());
+    #[cfg(not(feature = "fixed-point"))]
+    let (i_alpha, i_beta) = clarke(i_u, i_v, i_w);
+    // 固定小数点では単位法のQ15で変換し、オブザーバにはAに戻して渡す
+    #[cfg(feature = "fixed-point")]
+    let per_unit = current_loop.controller.per_unit();
+    #[cfg(feature = "fixed-point")]
+    let (i_alpha_pu, i_beta_pu) = fixed_point::clarke(
+        per_unit.current(i_u),
+        per_unit.current(i_v),
+        per_unit.current(i_w),
+    );
+    #[cfg(feature = "fixed-point")]
+    let (i_alpha, i_beta) = (per_unit.amps(i_alpha_pu), per_unit.amps(i_beta_pu));
+
+    // オブザーバ更新（前周期に出力した電圧と今周期の電流を使用）
+    if angle_sensor.source != AngleSource::Hall {
+        let (v_alpha, v_beta) = angle_sensor.applied_voltage;
+        angle_sensor
+            .observer
+            .update(v_alpha, v_beta, i_alpha, i_beta, dt);
+    }
+
+    // Hall故障検出（前周期のq軸電圧を駆動レベルとする）
+    let drive = if current_loop.voltage_limit > 0.0 {
+        angle_sensor.applied_vq / current_loop.voltage_limit
+    } else {
+        0.0
+    };
+    angle_sensor.update_diagnostics(drive, dt, events);
+
+    // Hall状態の確認（有効な状態：1-6、故障ラッチ中は無効扱い）
     let hall_state = hall_tim::get_hall_state();
-    let is_valid_hall = (1..=6).contains(&hall_state);
+    let hall_valid = (1..=6).contains(&hall_state) && !angle_sensor.diagnostics.is_faulted();
+    let use_observer = match angle_sensor.source {
+        AngleSource::Hall => false,
+        AngleSource::Sensorless => true,
+        AngleSource::HallWithFallback => !hall_valid,
+    };
 
-    // Hallセンサが無効な場合の安全処理
-    if !is_valid_hall {
+    let angle_available = if use_observer {
+        angle_sensor.observer.is_locked(config.sensorless_min_speed)
+    } else {
+        hall_valid
+    };
+    if !angle_available {
         motor_driver.stop();
-        speed_pi.reset();
-        *ramped_target_speed = 0.0;
-        return false;
+        current_loop.controller.reset();
+        current_loop.field_weakening.reset();
+        angle_sensor.applied_voltage = (0.0, 0.0);
+        angle_sensor.applied_vq = 0.0;
+        return None;
     }
 
-    // 電気角と速度を取得（TIM4ハードウェアベース、foc-simple互換計算）
-    let (hall_electrical_angle, speed_rpm) = hall_sensor.update(dt);
+    if angle_sensor.source == AngleSource::HallWithFallback
+        && use_observer != angle_sensor.observer_in_use
+    {
+        events.push(if use_observer {
+            ControlEvent::HallSignalLost
+        } else {
+            ControlEvent::HallSignalRestored
+        });
+    }
+    angle_sensor.observer_in_use = use_observer;
+
+    // 電気角と速度を取得（Hall: TIM4ハードウェアベース、foc-simple互換計算）
+    let (electrical_angle, speed_rpm) = if use_observer {
+        (
+            angle_sensor.observer.get_electrical_angle(),
+            angle_sensor.observer.get_speed_rpm(),
+        )
+    } else {
+        angle_sensor.hall.update(dt)
+    };
+
+    // d/q軸電流に変換（αβ → dq）
+    #[cfg(not(feature = "fixed-point"))]
+    let (id, iq) = park(i_alpha, i_beta, electrical_angle);
+    #[cfg(feature = "fixed-point")]
+    let current_dq = fixed_point::park(i_alpha_pu, i_beta_pu, phase_from_rad(electrical_angle));
+    #[cfg(feature = "fixed-point")]
+    let (id, iq) = (per_unit.amps(current_dq.0), per_unit.amps(current_dq.1));
+
+    Some(Feedback {
+        electrical_angle,
+        speed_rpm,
+        phase_currents: (i_u, i_v, i_w),
+        id,
+        iq,
+        #[cfg(feature = "fixed-point")]
+        current_dq,
+        position: angle_sensor.hall.get_position(),
+    })
+}
+
+/// d/q軸電圧指令を制限してPWMに出力（dq → αβ → SVPWM → デッドタイム補償）
+///
+/// 出力したα/β軸電圧（過変調の整形後）は次周期のオブザーバ入力として`angle_sensor`に保持する。
+fn output_voltage(
+    vd_cmd: f32,
+    vq_cmd: f32,
+    feedback: &Feedback,
+    angle_sensor: &mut AngleSensor,
+    current_loop: &CurrentLoop,
+    motor_driver: &mut MotorDriver,
+) {
+    // 電圧ベクトル制限（最大電圧設定と変調の上限の小さい方）
+    let (vd_limited, vq_limited) = limit_voltage(vd_cmd, vq_cmd, current_loop.voltage_limit);
+
+    // Park逆変換（dq → αβ）、線形変調範囲を超える場合は過変調の軌跡に整形
+    #[cfg(not(feature = "fixed-point"))]
+    let (v_alpha, v_beta) = inverse_park(vd_limited, vq_limited, feedback.electrical_angle);
+    #[cfg(feature = "fixed-point")]
+    let (v_alpha, v_beta) = {
+        let per_unit = current_loop.controller.per_unit();
+        let (v_alpha, v_beta) = fixed_point::inverse_park(
+            per_unit.voltage(vd_limited),
+            per_unit.voltage(vq_limited),
+            phase_from_rad(feedback.electrical_angle),
+        );
+        (per_unit.volts(v_alpha), per_unit.volts(v_beta))
+    };
+    let (v_alpha, v_beta) = current_loop.modulator.shape(v_alpha, v_beta);
+    angle_sensor.applied_voltage = (v_alpha, v_beta);
+    angle_sensor.applied_vq = vq_limited;
+
+    // SVPWM計算（実測DCバス電圧で正規化、実際のPWM最大値を使用）
+    let pwm_max_duty = motor_driver.max_duty();
+    let duties = current_loop.modulator.duties(v_alpha, v_beta, pwm_max_duty);
+
+    // デッドタイム補償・最小パルス制限
+    let (duty_u, duty_v, duty_w) = current_loop.dead_time.apply(
+        duties,
+        feedback.phase_currents,
+        v_alpha,
+        v_beta,
+        current_loop.modulator.get_bus_voltage(),
+        pwm_max_duty,
+    );
+
+    // PWM出力
+    motor_driver.set_duty_uvw(duty_u, duty_v, duty_w);
+
+    // FOCモードではすべてのチャネルを有効化
+    motor_driver.enable_all_channels();
+}
 
-    // PIゲイン更新チェック（非同期で更新された場合）
+/// d/q軸電流PIを実行
+///
+/// 固定小数点では電流指令を単位法のQ15に変換し、フィードバックのQ15のd/q軸電流と比較して、
+/// 出力の電圧指令をVに戻す。
+///
+/// # 戻り値
+/// * `(vd, vq)` - d/q軸電圧指令 [V]
+fn current_control(
+    current_loop: &mut CurrentLoop,
+    id_ref: f32,
+    iq_ref: f32,
+    feedback: &Feedback,
+    dt: f32,
+) -> (f32, f32) {
+    #[cfg(not(feature = "fixed-point"))]
     {
-        let (kp, ki) = *SPEED_PI_GAINS.lock().await;
-        if kp != speed_pi.get_kp() || ki != speed_pi.get_ki() {
-            speed_pi.set_gains(kp, ki);
-            info!("PI gains updated: Kp={}, Ki={}", kp, ki);
-        }
+        current_loop
+            .controller
+            .update(id_ref, iq_ref, feedback.id, feedback.iq, dt)
+    }
+    #[cfg(feature = "fixed-point")]
+    {
+        let per_unit = current_loop.controller.per_unit();
+        let (id, iq) = feedback.current_dq;
+        let (vd, vq) = current_loop.controller.update(
+            per_unit.current(id_ref),
+            per_unit.current(iq_ref),
+            id,
+            iq,
+            dt,
+        );
+        (per_unit.volts(vd), per_unit.volts(vq))
     }
+}
 
-    // 目標速度取得
-    let target_speed = *TARGET_SPEED.lock().await;
+/// ステータス更新（CAN送信用）
+fn update_status(feedback: &Feedback, status: &mut MotorStatus) {
+    status.speed_rpm = feedback.speed_rpm;
+    status.electrical_angle = feedback.electrical_angle;
+    status.id_current = feedback.id;
+    status.iq_current = feedback.iq;
+    status.position = feedback.position;
+}
 
-    // 速度ランプ（加速度制限）を適用
-    let speed_error = target_speed - *ramped_target_speed;
-    let max_delta_speed = MAX_SPEED_ACCELERATION * dt; // 1制御周期で変化可能な最大速度
+/// 速度PIゲインの更新
+///
+/// ゲインスケジュールが有効な場合は速度の絶対値で補間したゲインを使い、
+/// それ以外はタスクからの速度PIゲイン（CANから非同期で更新された場合）を反映する。
+fn refresh_speed_gains(speed_loop: &mut SpeedLoop, gains: (f32, f32), speed_rpm: f32) {
+    let speed_pi = &mut speed_loop.controller;
+    if speed_pi.apply_schedule(&speed_loop.schedule, speed_rpm.abs()) {
+        return;
+    }
 
-    if speed_error.abs() > max_delta_speed {
-        // 加速度制限を適用
-        if speed_error > 0.0 {
-            *ramped_target_speed += max_delta_speed;
-        } else {
-            *ramped_target_speed -= max_delta_speed;
-        }
-    } else {
-        // 目標速度に到達
-        *ramped_target_speed = target_speed;
+    let (kp, ki) = gains;
+    if kp != speed_pi.get_kp() || ki != speed_pi.get_ki() {
+        speed_pi.set_gains(kp, ki);
     }
+}
+
+/// 速度PI制御でd/q軸電圧指令を計算
+///
+/// 電流制御時は速度PIの出力をq軸電流指令としてd/q軸電流PIに渡し、
+/// それ以外はq軸電圧指令として出力する。
+/// 速度PIの入力には速度フィードバックのフィルタ、出力には速度PI出力のフィルタを通す。
+/// 速度PIの出力には速度フィードフォワードの摩擦・加速項を加算して出力制限を適用し、
+/// 逆起電力項はq軸電圧指令（電流制御時はd/q軸電流PIの出力）に加算する。
+/// 速度PI・フィードフォワードは速度ループの周期のみ更新し、間の周期は保持した出力を使う。
+/// d軸指令は弱め界磁の出力（電流制御時はd軸電流指令、それ以外はd軸電圧指令）とし、
+/// 今周期の電圧指令から次周期の弱め界磁を更新する。
+///
+/// # 引数
+/// * `target_speed` - 目標速度 [RPM]
+/// * `feedforward_speed` - フィードフォワードの速度指令 [RPM]（プロファイルの速度、位置補正を含まない）
+/// * `feedback` - 今周期のフィードバック
+/// * `speed_loop` - 速度制御ループ（速度PI・速度フィードフォワード・フィルタ）
+/// * `current_loop` - 電流制御ループ
+/// * `idle_when_stopped` - 目標速度0で停止している場合に出力を0にするか
+///   （位置保持では停止中も保持トルクが必要なため`false`にする）
+/// * `input` - 今周期の入力（実行する速度ループ、電流・速度ループの制御周期）
+///
+/// # 戻り値
+/// * `(vd, vq)` - d/q軸電圧指令 [V]
+fn speed_control(
+    target_speed: f32,
+    feedforward_speed: f32,
+    feedback: &Feedback,
+    speed_loop: &mut SpeedLoop,
+    current_loop: &mut CurrentLoop,
+    idle_when_stopped: bool,
+    input: &CycleInput,
+) -> (f32, f32) {
+    let dt = input.dt;
 
-    // 速度PI制御（q軸電圧指令生成）- ランプ処理後の速度を使用
-    let mut vq_cmd = speed_pi.update(*ramped_target_speed, speed_rpm, dt);
-    let vd_cmd = 0.0; // SPMSM: d軸電流/電圧は0
+    if input.ticks.speed {
+        let speed_rpm = feedback.speed_rpm;
+        let speed_dt = input.speed_dt;
 
-    // 停止時の処理：目標速度が0で実際に停止している場合、PI積分項をリセット
-    if ramped_target_speed.abs() < 1.0 && speed_rpm.abs() < 1.0 {
-        speed_pi.reset();
-        vq_cmd = 0.0;
+        // 速度PI制御＋フィードフォワード（電流制御時はq軸電流指令、それ以外はq軸電圧指令）
+        // 速度フィードバックと速度PIの出力にはフィルタ（設定で選択、デフォルトはなし）を挿入
+        let (torque_ff, back_emf_ff) = speed_loop.feedforward.update(feedforward_speed, speed_dt);
+        let filtered_speed = speed_loop.feedback_filter.update(speed_rpm);
+        let pi_output = speed_loop
+            .output_filter
+            .update(
+                speed_loop
+                    .controller
+                    .update(target_speed, filtered_speed, speed_dt),
+            );
+
+        // 停止時の処理：目標速度が0で実際に停止している場合、PI積分項をリセット
+        if idle_when_stopped && target_speed.abs() < 1.0 && speed_rpm.abs() < 1.0 {
+            speed_loop.reset();
+            current_loop.field_weakening.reset();
+            speed_loop.stopped = true;
+        } else {
+            speed_loop.output = speed_loop.controller.limit(pi_output + torque_ff);
+            speed_loop.back_emf = back_emf_ff;
+            speed_loop.stopped = false;
+        }
     }
+    let stopped = speed_loop.stopped;
+    let speed_output = speed_loop.output;
+    let back_emf_ff = speed_loop.back_emf;
 
-    // 最小電圧適用（静止摩擦克服用）
-    let speed_error_abs = (*ramped_target_speed - speed_rpm).abs();
-    if speed_error_abs > MIN_VOLTAGE_ERROR_THRESHOLD && vq_cmd.abs() > 0.0 {
-        // 速度誤差が大きい場合、最小電圧を適用
-        if vq_cmd > 0.0 {
-            vq_cmd = vq_cmd.max(MIN_VOLTAGE);
+    // 弱め界磁のd軸指令（SPMSM: 基底速度以下では0）
+    let d_ref = current_loop.field_weakening.output();
+
+    let (vd_cmd, vq_cmd) = if current_loop.active {
+        if stopped {
+            current_loop.controller.reset();
+            (0.0, 0.0)
         } else {
-            vq_cmd = vq_cmd.min(-MIN_VOLTAGE);
+            // d/q軸電流PI制御（逆起電力項は電流PIの出力に加算）
+            let iq_ref = speed_output;
+            let (vd_cmd, vq_cmd) = current_control(current_loop, d_ref, iq_ref, feedback, dt);
+            (vd_cmd, vq_cmd + back_emf_ff)
         }
+    } else {
+        (d_ref, speed_output + back_emf_ff)
+    };
+
+    // 電圧ベクトルの飽和から次周期の弱め界磁を更新
+    if !stopped {
+        current_loop
+            .field_weakening
+            .update(vd_cmd, vq_cmd, current_loop.voltage_limit, dt);
     }
 
-    // 電圧ベクトル制限
-    let (vd_limited, vq_limited) = limit_voltage(vd_cmd, vq_cmd, DEFAULT_MAX_VOLTAGE);
+    (vd_cmd, vq_cmd)
+}
+
+/// FOC速度制御の実行
+///
+/// 目標速度は加減速プロファイル（`speed_loop.profile`）を通してから速度PIに渡す。
+/// オープンループからの切替直後は、出力する電気角とd/q軸電圧を強制転流の電圧ベクトルから
+/// 徐々に移行する（`handover`）。
+///
+/// # 引数
+/// * `angle_sensor` - 角度センサー（Hallセンサー・オブザーバ）
+/// * `speed_loop` - 速度制御ループ（速度プロファイル・速度PI・速度フィードフォワード）
+/// * `current_loop` - 電流制御ループ（相電流センサー・d/q軸電流PI）
+/// * `handover` - オープンループからのハンドオーバー
+/// * `motor_driver` - モータードライバー
+/// * `input` - 今周期の入力（目標速度・速度PIゲイン、センサレス運転の最低速度、
+///   実行する速度ループ・制御周期）
+/// * `report` - タスクへの制御結果（モーターステータス・イベントを書き込む）
+///
+/// # 戻り値
+/// * `bool` - 角度が得られたか（Hall状態が有効、またはオブザーバがロック中）
+pub fn execute(
+    angle_sensor: &mut AngleSensor,
+    speed_loop: &mut SpeedLoop,
+    current_loop: &mut CurrentLoop,
+    handover: &mut StartupHandover,
+    motor_driver: &mut MotorDriver,
+    input: &CycleInput,
+    report: &mut ControlReport,
+) -> bool {
+    let dt = input.dt;
+
+    // Hallセンサが無効な場合の安全処理
+    let Some(mut feedback) = update_feedback(
+        angle_sensor,
+        current_loop,
+        motor_driver,
+        input.config,
+        dt,
+        &mut report.events,
+    ) else {
+        speed_loop.reset();
+        speed_loop.profile.reset_velocity(0.0);
+        speed_loop.reference = 0.0;
+        handover.cancel();
+        return false;
+    };
+    let speed_rpm = feedback.speed_rpm;
 
-    // Park逆変換（dq → αβ）
-    let (v_alpha, v_beta) = inverse_park(vd_limited, vq_limited, hall_electrical_angle);
+    // 目標速度取得
+    let target_speed = input.command.target_speed;
 
-    // SVPWM計算（実際のPWM最大値を使用）
-    let pwm_max_duty = motor_driver.max_duty();
-    let (duty_u, duty_v, duty_w) = calculate_svpwm(v_alpha, v_beta, DEFAULT_V_DC_BUS, pwm_max_duty);
-
-    // デバッグ用：FOC制御の詳細ログ（10Hz = 250回に1回）
-    static mut FOC_LOG_COUNTER: u32 = 0;
-    unsafe {
-        FOC_LOG_COUNTER += 1;
-        if FOC_LOG_COUNTER >= 250 {
-            FOC_LOG_COUNTER = 0;
-            let angle_deg = hall_electrical_angle * 180.0 / core::f32::consts::PI;
-            trace!(
-                "[FOC Detail] Hall={}, Angle={}rad ({}°), Vq={}V, Valpha={}V, Vbeta={}V, DutyU={}, DutyV={}, DutyW={}",
-                hall_state, hall_electrical_angle, angle_deg, vq_limited, v_alpha, v_beta, duty_u, duty_v, duty_w
-            );
-        }
+    if input.ticks.speed {
+        // PIゲイン更新チェック（ゲインスケジュール、または非同期で更新された場合）
+        refresh_speed_gains(speed_loop, input.command.speed_gains, speed_rpm);
+
+        // 加減速プロファイルを適用
+        speed_loop.reference = speed_loop
+            .profile
+            .update_velocity(target_speed, input.speed_dt);
     }
+    let profiled_target_speed = speed_loop.reference;
 
-    // PWM出力
-    motor_driver.set_duty_uvw(duty_u, duty_v, duty_w);
+    // 速度PI制御 - プロファイル後の速度を使用（フィードフォワードも同じ速度から計算）
+    let (vd_cmd, vq_cmd) = speed_control(
+        profiled_target_speed,
+        profiled_target_speed,
+        &feedback,
+        speed_loop,
+        current_loop,
+        true,
+        input,
+    );
 
-    // FOCモードではすべてのチャネルを有効化
-    motor_driver.enable_all_channels();
+    // オープンループからのハンドオーバー中は強制転流の電圧ベクトルと合成
+    let handover_active = handover.is_active();
+    let (electrical_angle, vd_cmd, vq_cmd) =
+        handover.update(feedback.electrical_angle, vd_cmd, vq_cmd, dt);
+    if handover_active && !handover.is_active() {
+        report
+            .events
+            .push(ControlEvent::HandoverComplete { rpm: speed_rpm });
+    }
+    feedback.electrical_angle = electrical_angle;
 
-    // ステータス更新
-    {
-        let mut status = MOTOR_STATUS.lock().await;
-        status.speed_rpm = speed_rpm;
-        status.electrical_angle = hall_electrical_angle;
+    output_voltage(
+        vd_cmd,
+        vq_cmd,
+        &feedback,
+        angle_sensor,
+        current_loop,
+        motor_driver,
+    );
+    update_status(&feedback, &mut report.status);
+
+    true
+}
+
+/// FOCトルク制御の実行（q軸電流指令、速度ループなし）
+///
+/// 電流制御が有効（`current_loop.active`）な場合のみ呼び出すこと。
+///
+/// # 引数
+/// * `angle_sensor` - 角度センサー（Hallセンサー・オブザーバ）
+/// * `current_loop` - 電流制御ループ（相電流センサー・d/q軸電流PI）
+/// * `motor_driver` - モータードライバー
+/// * `input` - 今周期の入力（トルク指令、電流制限・センサレス運転の最低速度、制御周期）
+/// * `report` - タスクへの制御結果（モーターステータス・イベントを書き込む）
+///
+/// # 戻り値
+/// * `bool` - 角度が得られたか（Hall状態が有効、またはオブザーバがロック中）
+pub fn execute_torque(
+    angle_sensor: &mut AngleSensor,
+    current_loop: &mut CurrentLoop,
+    motor_driver: &mut MotorDriver,
+    input: &CycleInput,
+    report: &mut ControlReport,
+) -> bool {
+    let (config, dt) = (input.config, input.dt);
+    let Some(feedback) = update_feedback(
+        angle_sensor,
+        current_loop,
+        motor_driver,
+        config,
+        dt,
+        &mut report.events,
+    ) else {
+        return false;
+    };
+
+    // q軸電流指令（最大電流で制限）
+    let iq_ref = input
+        .command
+        .target_current
+        .max(-config.max_current)
+        .min(config.max_current);
+
+    // d/q軸電流PI制御（d軸電流指令は弱め界磁の出力、基底速度以下では0）
+    let id_ref = current_loop.field_weakening.output();
+    let (vd_cmd, vq_cmd) = current_control(current_loop, id_ref, iq_ref, &feedback, dt);
+    current_loop
+        .field_weakening
+        .update(vd_cmd, vq_cmd, current_loop.voltage_limit, dt);
+
+    output_voltage(
+        vd_cmd,
+        vq_cmd,
+        &feedback,
+        angle_sensor,
+        current_loop,
+        motor_driver,
+    );
+    update_status(&feedback, &mut report.status);
+
+    true
+}
+
+/// FOC電圧制御の実行（d/q軸電圧指令を直接出力、電流・速度ループなし）
+///
+/// # 引数
+/// * `angle_sensor` - 角度センサー（Hallセンサー・オブザーバ）
+/// * `current_loop` - 電流制御ループ（電流計測のみに使用）
+/// * `motor_driver` - モータードライバー
+/// * `input` - 今周期の入力（電圧指令、センサレス運転の最低速度、制御周期）
+/// * `report` - タスクへの制御結果（モーターステータス・イベントを書き込む）
+///
+/// # 戻り値
+/// * `bool` - 角度が得られたか（Hall状態が有効、またはオブザーバがロック中）
+pub fn execute_voltage(
+    angle_sensor: &mut AngleSensor,
+    current_loop: &mut CurrentLoop,
+    motor_driver: &mut MotorDriver,
+    input: &CycleInput,
+    report: &mut ControlReport,
+) -> bool {
+    let Some(feedback) = update_feedback(
+        angle_sensor,
+        current_loop,
+        motor_driver,
+        input.config,
+        input.dt,
+        &mut report.events,
+    ) else {
+        return false;
+    };
+
+    // d/q軸電圧指令（ベクトル制限はoutput_voltageで適用）
+    let (vd_cmd, vq_cmd) = input.command.target_voltage;
+
+    output_voltage(
+        vd_cmd,
+        vq_cmd,
+        &feedback,
+        angle_sensor,
+        current_loop,
+        motor_driver,
+    );
+    update_status(&feedback, &mut report.status);
+
+    true
+}
+
+/// FOC位置制御の実行（位置P → 速度PIのカスケード）
+///
+/// 位置指令を加減速プロファイルに通し、プロファイルの速度をフィードフォワード、
+/// プロファイル位置との偏差に比例した速度を補正として速度PIに渡す（最大速度で制限）。
+/// 速度PIの積分により、負荷トルクがあっても位置偏差は0に収束する。
+/// プロファイルはフィードフォワードの加速度が階段状にならないよう速度ループの周期で、
+/// 位置P制御は位置ループの周期で更新する。
+///
+/// # 引数
+/// * `angle_sensor` - 角度センサー（Hallセンサーで複数回転の位置を追跡）
+/// * `speed_loop` - 速度制御ループ（速度PI・速度フィードフォワードを使用）
+/// * `position_loop` - 位置制御ループ（位置プロファイル・位置P制御）
+/// * `current_loop` - 電流制御ループ（相電流センサー・d/q軸電流PI）
+/// * `motor_driver` - モータードライバー
+/// * `input` - 今周期の入力（位置指令・速度PIゲイン、位置制御の最大速度・センサレス運転の最低速度、
+///   実行する速度・位置ループ・制御周期）
+/// * `report` - タスクへの制御結果（モーターステータス・イベントを書き込む）
+///
+/// # 戻り値
+/// * `bool` - 角度が得られたか（Hall状態が有効、またはオブザーバがロック中）
+pub fn execute_position(
+    angle_sensor: &mut AngleSensor,
+    speed_loop: &mut SpeedLoop,
+    position_loop: &mut PositionLoop,
+    current_loop: &mut CurrentLoop,
+    motor_driver: &mut MotorDriver,
+    input: &CycleInput,
+    report: &mut ControlReport,
+) -> bool {
+    let (config, dt) = (input.config, input.dt);
+    let Some(feedback) = update_feedback(
+        angle_sensor,
+        current_loop,
+        motor_driver,
+        config,
+        dt,
+        &mut report.events,
+    ) else {
+        speed_loop.reset();
+        position_loop.controller.reset();
+        return false;
+    };
+
+    let target_position = input.command.target_position;
+    let max_speed = config.position_max_speed;
+
+    if input.ticks.speed {
+        refresh_speed_gains(speed_loop, input.command.speed_gains, feedback.speed_rpm);
+
+        // 位置プロファイル（出力: 位置指令 [rad]、速度フィードフォワード [RPM]）
+        (position_loop.reference, position_loop.feedforward_speed) = position_loop
+            .profile
+            .update_position(target_position, max_speed, input.speed_dt);
     }
+    if input.ticks.position {
+        // 位置制御（出力: 補正速度 [RPM]）
+        position_loop.correction_speed = position_loop.controller.update(
+            position_loop.reference,
+            feedback.position,
+            input.position_dt,
+        );
+    }
+    let feedforward_speed = position_loop.feedforward_speed;
 
-    // デバッグログ（低頻度）
-    static mut FOC_MODE_LOG_COUNTER: u32 = 0;
-    unsafe {
-        FOC_MODE_LOG_COUNTER += 1;
-        if FOC_MODE_LOG_COUNTER >= 2500 {
-            // 1秒ごと（2.5kHz / 2500 = 1Hz）
-            FOC_MODE_LOG_COUNTER = 0;
-
-            // TIM4ベースのHallセンサ値を取得（ログ用）
-            let period_cycles = hall_tim::get_period_cycles();
-
-            // 最新のステータスを取得
-            let status = *MOTOR_STATUS.lock().await;
-            let target_speed = *TARGET_SPEED.lock().await;
-            debug!(
-                "[FOC] Speed: {}/{} RPM (ramped: {}), Angle: {}rad, Hall: {}, Period: {} cycles",
-                status.speed_rpm,
-                target_speed,
-                *ramped_target_speed,
-                status.electrical_angle,
-                hall_state,
-                period_cycles
-            );
+    // 速度指令（最大速度で制限）
+    let target_speed = (feedforward_speed + position_loop.correction_speed)
+        .max(-max_speed)
+        .min(max_speed);
+
+    // 速度PI制御（停止中も位置を保持するため出力を止めない）
+    // フィードフォワードはプロファイルの速度から計算（位置補正のノイズを微分しない）
+    let (vd_cmd, vq_cmd) = speed_control(
+        target_speed,
+        feedforward_speed,
+        &feedback,
+        speed_loop,
+        current_loop,
+        false,
+        input,
+    );
+
+    output_voltage(
+        vd_cmd,
+        vq_cmd,
+        &feedback,
+        angle_sensor,
+        current_loop,
+        motor_driver,
+    );
+    update_status(&feedback, &mut report.status);
+
+    true
+}
+
+/// 速度PIゲイン自動調整の実行（速度PIの代わりにリレー出力）
+///
+/// リレー出力は電流制御時はq軸電流指令、それ以外はq軸電圧指令とする（速度PIの出力と同じ単位）。
+/// リレーは速度ループの周期で更新し、間の周期は`speed_loop.output`に保持した出力を使う。
+/// 角度が得られない場合、または調整が終了した後は出力を停止する（惰性で回転）。
+///
+/// # 引数
+/// * `angle_sensor` - 角度センサー（Hallセンサー・オブザーバ）
+/// * `current_loop` - 電流制御ループ（相電流センサー・d/q軸電流PI）
+/// * `speed_loop` - 速度制御ループ（リレー出力の保持に使用）
+/// * `autotune` - 速度PIゲイン自動調整
+/// * `motor_driver` - モータードライバー
+/// * `input` - 今周期の入力（センサレス運転の最低速度、実行する速度ループ・制御周期）
+/// * `report` - タスクへの制御結果（モーターステータス・イベントを書き込む）
+///
+/// # 戻り値
+/// * `bool` - 角度が得られたか（Hall状態が有効、またはオブザーバがロック中）
+pub fn execute_autotune(
+    angle_sensor: &mut AngleSensor,
+    current_loop: &mut CurrentLoop,
+    speed_loop: &mut SpeedLoop,
+    autotune: &mut SpeedAutoTune,
+    motor_driver: &mut MotorDriver,
+    input: &CycleInput,
+    report: &mut ControlReport,
+) -> bool {
+    let dt = input.dt;
+    let Some(feedback) = update_feedback(
+        angle_sensor,
+        current_loop,
+        motor_driver,
+        input.config,
+        dt,
+        &mut report.events,
+    ) else {
+        autotune.abort();
+        return false;
+    };
+
+    if input.ticks.speed {
+        if let Some(relay_output) = autotune.update(feedback.speed_rpm, input.speed_dt) {
+            speed_loop.output = relay_output;
         }
     }
+    if !autotune.is_running() {
+        motor_driver.stop();
+        current_loop.controller.reset();
+        angle_sensor.applied_voltage = (0.0, 0.0);
+        angle_sensor.applied_vq = 0.0;
+        update_status(&feedback, &mut report.status);
+        return true;
+    }
+    let relay_output = speed_loop.output;
+
+    // d軸指令は弱め界磁の出力（基底速度以下では0）
+    let d_ref = current_loop.field_weakening.output();
+    let (vd_cmd, vq_cmd) = if current_loop.active {
+        current_control(current_loop, d_ref, relay_output, &feedback, dt)
+    } else {
+        (d_ref, relay_output)
+    };
+    current_loop
+        .field_weakening
+        .update(vd_cmd, vq_cmd, current_loop.voltage_limit, dt);
+
+    output_voltage(
+        vd_cmd,
+        vq_cmd,
+        &feedback,
+        angle_sensor,
+        current_loop,
+        motor_driver,
+    );
+    update_status(&feedback, &mut report.status);
 
     true
 }
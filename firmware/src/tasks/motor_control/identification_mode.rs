@@ -0,0 +1,75 @@
+//! パラメータ同定制御モード
+//!
+//! 相抵抗・インダクタンス・鎖交磁束・慣性モーメント・摩擦を自動測定します。
+//! 同定が終了するとモーターを無効化し、成功した結果をランタイム設定に反映します
+//! （反映はモーター制御タスクが行い、フラッシュへの保存は設定保存コマンドで行う）。
+
+use super::exchange::{ControlEvent, ControlReport};
+use super::{AngleSensor, CurrentLoop};
+use crate::current_sense;
+use crate::foc::{clarke, ControlMode, IdentificationState, MotorIdentification};
+use crate::motor_driver::MotorDriver;
+
+/// パラメータ同定制御の実行
+///
+/// # 引数
+/// * `identification` - パラメータ同定コントローラー
+/// * `angle_sensor` - 角度センサー（Hallの電気角・速度を使用）
+/// * `current_loop` - 電流制御ループ（相電流センサー・PWM変調を使用）
+/// * `motor_driver` - モータードライバー
+/// * `dt` - 制御周期 [秒]
+/// * `report` - タスクへの制御結果（同定の進行状態・結果・終了イベント、運転要求の解除を書き込む）
+///
+/// # 戻り値
+/// * `Option<ControlMode>` - 終了時は次のモード（OpenLoop、PWMは停止済み）、継続中はNone
+pub fn execute(
+    identification: &mut MotorIdentification,
+    angle_sensor: &mut AngleSensor,
+    current_loop: &mut CurrentLoop,
+    motor_driver: &mut MotorDriver,
+    dt: f32,
+    report: &mut ControlReport,
+) -> Option<ControlMode> {
+    // 相電流を取得してαβ軸に変換（abc → αβ）
+    let (i_u, i_v, i_w) = current_loop
+        .sensor
+        .phase_currents(current_sense::read_raw());
+    let (i_alpha, i_beta) = clarke(i_u, i_v, i_w);
+
+    // Hallセンサーの電気角と速度（加速試験・回転子の追従確認に使用）
+    let (electrical_angle, speed_rpm) = angle_sensor.hall.update(dt);
+
+    match identification.update(i_alpha, i_beta, electrical_angle, speed_rpm, dt) {
+        Some((v_alpha, v_beta)) => {
+            let pwm_max_duty = motor_driver.max_duty();
+            let (duty_u, duty_v, duty_w) =
+                current_loop.modulator.duties(v_alpha, v_beta, pwm_max_duty);
+            motor_driver.set_duty_uvw(duty_u, duty_v, duty_w);
+            motor_driver.enable_all_channels();
+        }
+        None => motor_driver.stop(),
+    }
+
+    let state = identification.get_state();
+    report.identification_state = state;
+
+    if !identification.is_finished() {
+        return None; // 同定継続中
+    }
+
+    let result = identification.get_result();
+    report.identification_result = result;
+
+    if state == IdentificationState::Completed {
+        // 有効な測定値をランタイム設定に反映（タスクが`RUNTIME_CONFIG`に書き込む）
+        report.writeback.save_identification(result);
+    }
+    report.events.push(ControlEvent::IdentificationFinished(
+        identification.get_failure(),
+    ));
+
+    // 同定後はモーターを停止（呼び出し側が運転要求を解除し、再始動は外部からの有効化指令で行う）
+    motor_driver.stop();
+
+    Some(ControlMode::OpenLoop)
+}
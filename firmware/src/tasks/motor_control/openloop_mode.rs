@@ -1,36 +1,66 @@
 //! オープンループ制御モード
 //!
-//! 始動時に6ステップ駆動（台形波）でモーターを回転させます。
+//! 始動時に6ステップ駆動（台形波）または正弦波V/f駆動でモーターを回転させます。
 
-use crate::fmt::*;
-use crate::foc::{HallSensor, OpenLoopSixStep};
+use super::Startup;
+use crate::can_protocol::MotorStatus;
+use crate::foc::{calculate_sinusoidal_pwm, OpenLoopMode, OpenLoopSixStep, OpenLoopVf};
 use crate::hall_tim;
 use crate::motor_driver::MotorDriver;
-use crate::state::MOTOR_STATUS;
 
 /// オープンループ制御の実行
 ///
 /// # 引数
-/// * `openloop` - オープンループコントローラー
-/// * `hall_sensor` - Hallセンサー（Hall状態確認用）
+/// * `startup` - 始動制御（駆動方式に応じて6ステップ / 正弦波V/fを実行）
+/// * `require_hall` - FOC切替にHall状態が有効であることを要求するか（センサレス運転では`false`）
 /// * `motor_driver` - モータードライバー
+/// * `v_dc_bus` - DCバス電圧 [V]（正弦波V/f駆動のPWM変換に使用）
 /// * `dt` - 制御周期 [s]
+/// * `status` - モーターステータス（CAN送信用）
 ///
 /// # 戻り値
 /// * `(bool, u8)` - (目標速度に達したか, Hall状態)
-pub async fn execute(
-    openloop: &mut OpenLoopSixStep,
-    _hall_sensor: &HallSensor,
+pub fn execute(
+    startup: &mut Startup,
+    require_hall: bool,
     motor_driver: &mut MotorDriver,
+    v_dc_bus: f32,
     dt: f32,
+    status: &mut MotorStatus,
 ) -> (bool, u8) {
-    // オープンループ6ステップ駆動を更新
-    let step_state = openloop.update(dt);
+    let target_reached = match startup.mode {
+        OpenLoopMode::SixStep => execute_six_step(&mut startup.openloop, motor_driver, dt),
+        OpenLoopMode::SineVf => execute_vf(&mut startup.vf, motor_driver, v_dc_bus, dt),
+    };
 
     // Hall状態を取得（切替判定用）
     let hall_state = hall_tim::get_hall_state();
     let is_valid_hall = (1..=6).contains(&hall_state);
-    let target_reached = openloop.is_target_reached();
+
+    // ステータス更新
+    status.speed_rpm = startup.current_rpm();
+    status.electrical_angle = match startup.mode {
+        OpenLoopMode::SixStep => 0.0, // 6ステップでは電気角は不定
+        OpenLoopMode::SineVf => startup.vf.get_electrical_angle(),
+    };
+
+    (
+        target_reached && (is_valid_hall || !require_hall),
+        hall_state,
+    )
+}
+
+/// 6ステップ駆動を1周期分実行
+///
+/// # 戻り値
+/// * `bool` - 目標速度に達したか
+fn execute_six_step(
+    openloop: &mut OpenLoopSixStep,
+    motor_driver: &mut MotorDriver,
+    dt: f32,
+) -> bool {
+    // オープンループ6ステップ駆動を更新
+    let step_state = openloop.update(dt);
 
     // PWM出力（0-100の値を実際のPWM最大値にスケーリング）
     let pwm_max_duty = motor_driver.max_duty();
@@ -47,30 +77,22 @@ pub async fn execute(
         step_state.enable_w,
     );
 
-    // ステータス更新
-    {
-        let mut status = MOTOR_STATUS.lock().await;
-        status.speed_rpm = openloop.get_current_rpm();
-        status.electrical_angle = 0.0; // OpenLoopでは電気角は不定
-    }
+    openloop.is_target_reached()
+}
+
+/// 正弦波V/f駆動を1周期分実行
+///
+/// # 戻り値
+/// * `bool` - 目標速度に達したか
+fn execute_vf(vf: &mut OpenLoopVf, motor_driver: &mut MotorDriver, v_dc_bus: f32, dt: f32) -> bool {
+    // 電圧ベクトルを回転させ、正弦波PWMで出力
+    let (v_alpha, v_beta) = vf.update(dt);
+    let pwm_max_duty = motor_driver.max_duty();
+    let (duty_u, duty_v, duty_w) =
+        calculate_sinusoidal_pwm(v_alpha, v_beta, v_dc_bus, pwm_max_duty);
 
-    // デバッグログ（低頻度）
-    static mut OPENLOOP_LOG_COUNTER: u32 = 0;
-    unsafe {
-        OPENLOOP_LOG_COUNTER += 1;
-        if OPENLOOP_LOG_COUNTER >= 2500 {
-            OPENLOOP_LOG_COUNTER = 0;
-            debug!(
-                "[OpenLoop] Step: {}, RPM: {}, Hall: {}, DutyU/V/W: {}/{}/{}",
-                step_state.step,
-                openloop.get_current_rpm(),
-                hall_state,
-                step_state.duty_u,
-                step_state.duty_v,
-                step_state.duty_w
-            );
-        }
-    }
+    motor_driver.set_duty_uvw(duty_u, duty_v, duty_w);
+    motor_driver.enable_all_channels();
 
-    (is_valid_hall && target_reached, hall_state)
+    vf.is_target_reached()
 }
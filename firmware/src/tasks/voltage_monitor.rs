@@ -1,20 +1,22 @@
 //! 電圧監視タスク
 //!
 //! DCバス電圧を監視し、過電圧/低電圧を検出してモーターを保護します。
+//! 検出結果は`VOLTAGE_STATE`に書き込み、制御割り込みの駆動状態マシンが故障状態に遷移して
+//! PWMを停止します（運転要求の解除も駆動状態マシンが行う）。
+//!
+//! DCバス電圧はADC2の注入シーケンス（相電流と同じTIM1トリガー）の変換結果を読みます。
+//! ADC2の通常変換は注入変換の設定を書き換えるため使いません（`current_sense`を参照）。
 
-use embassy_stm32::{adc::Adc, peripherals};
-use embassy_time::{Duration, Ticker};
+use embassy_time::{Duration, Ticker, Timer};
 
+use crate::current_sense;
 use crate::fmt::*;
-use crate::state::{MOTOR_ENABLE, TARGET_SPEED, VOLTAGE_STATE};
+use crate::state::{DRIVE_STATUS, VOLTAGE_STATE};
 use crate::voltage_monitor::{VoltageMonitor, VoltageMonitorConfig};
 
 /// 電圧監視タスク - DCバス電圧を監視し、過電圧/低電圧を検出
 #[embassy_executor::task]
-pub async fn voltage_monitor_task(
-    mut adc: Adc<'static, peripherals::ADC2>,
-    mut voltage_pin: embassy_stm32::adc::AnyAdcChannel<peripherals::ADC2>,
-) {
+pub async fn voltage_monitor_task() {
     info!("Voltage monitor task started");
 
     // 電圧監視コントローラ初期化
@@ -30,8 +32,12 @@ pub async fn voltage_monitor_task(
 
     info!("Voltage monitor initialized: OV=30V, UV=10V");
 
-    // 初回ADC読み取りでフィルタを初期化（起動時のUNDERVOLTAGE誤検出を防ぐ）
-    let initial_adc = adc.blocking_read(&mut voltage_pin);
+    // 最初の注入変換を待ってからフィルタを初期化（起動時のUNDERVOLTAGE誤検出を防ぐ）
+    Timer::after(Duration::from_millis(1)).await;
+    if !current_sense::is_injected_sequence_armed() {
+        error!("ADC injected sequence not armed, bus voltage readings are invalid");
+    }
+    let initial_adc = current_sense::read_bus_voltage_raw();
     monitor.initialize_with_adc(initial_adc); // フィルタを実電圧で初期化
     let state = monitor.get_state();
     info!(
@@ -48,8 +54,8 @@ pub async fn voltage_monitor_task(
     loop {
         ticker.next().await;
 
-        // ADCから電圧を読み取り
-        let adc_raw = adc.blocking_read(&mut voltage_pin);
+        // 注入変換の最新の結果から電圧を読み取り
+        let adc_raw = current_sense::read_bus_voltage_raw();
 
         // デバッグ: ADC生値とADC電圧を計算（33.3kΩ + 3.3kΩ分圧）
         let v_adc = (adc_raw as f32 / 4096.0) * 3.3;
@@ -62,16 +68,14 @@ pub async fn voltage_monitor_task(
         // グローバル状態を更新（CAN送信用）
         *VOLTAGE_STATE.lock().await = state;
 
-        // 過電圧/低電圧時はモーターを自動停止
+        // 過電圧/低電圧時のモーター停止は駆動状態マシンが行う（ここでは指令を変更しない）
         if !state.is_voltage_ok() {
-            let was_enabled = *MOTOR_ENABLE.lock().await;
-            if was_enabled {
+            let drive_state = DRIVE_STATUS.lock().await.state;
+            if drive_state.is_active() {
                 error!(
-                    "Voltage fault detected! Disabling motor. Voltage: {}V, OV: {}, UV: {}",
-                    state.voltage, state.overvoltage, state.undervoltage
+                    "Voltage fault detected in {} state! Disabling motor. Voltage: {}V, OV: {}, UV: {}",
+                    drive_state.name(), state.voltage, state.overvoltage, state.undervoltage
                 );
-                *MOTOR_ENABLE.lock().await = false;
-                *TARGET_SPEED.lock().await = 0.0;
             }
         }
 